@@ -1,5 +1,6 @@
 MEMORY {
-    FLASH : ORIGIN = 0x10000000, LENGTH = 4096K
+    /* The last 4K sector holds the saved usage statistics */
+    FLASH : ORIGIN = 0x10000000, LENGTH = 4092K
     RAM   : ORIGIN = 0x20000000, LENGTH = 512K
     SRAM8 : ORIGIN = 0x20080000, LENGTH = 4K
     SRAM9 : ORIGIN = 0x20081000, LENGTH = 4K
@@ -0,0 +1,478 @@
+/*
+ * @file bargraph.rs
+ * @brief Bar-graph display
+ * @author Kevin Thomas
+ * @date 2025
+ *
+ * MIT License
+ *
+ * Copyright (c) 2025 Kevin Thomas
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+//! FILE: bargraph.rs
+//!
+//! DESCRIPTION:
+//! Bar-Graph Display for Progress and Level Values.
+//!
+//! BRIEF:
+//! Shows a 0 to 100 percent value as a bar across the LED row.
+//! Lights the last LED by the fraction it covers.
+//! Fills forwards, in reverse or from the centre out.
+//! Smooths value changes and can blink on overflow.
+//!
+//! AUTHOR: Kevin Thomas
+//! CREATION DATE: October 18, 2026
+//! UPDATE DATE: October 18, 2026
+
+use crate::config::{LED_COUNT, MAX_BRIGHTNESS};
+use crate::frame::Frame;
+
+/// Full-bar value in tenths of a percent.
+#[allow(dead_code)]
+pub const BAR_FULL: u16 = 1000;
+
+/// Fixed-point scale of the smoothed value.
+const SCALE: u32 = 1000;
+
+/// Fill direction enumeration.
+///
+/// # Variants
+/// * `Forward` - Fills from LED 0 upwards
+/// * `Reverse` - Fills from the last LED downwards
+/// * `CentreOut` - Fills from the centre towards both ends
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[allow(dead_code)]
+pub enum FillDirection {
+    Forward,
+    Reverse,
+    CentreOut,
+}
+
+/// Returns distance of an LED from the centre of the row.
+///
+/// # Details
+/// The two middle LEDs of an even row are both at distance 0.
+///
+/// # Arguments
+/// * `index` - LED index
+///
+/// # Returns
+/// * `usize` - Distance in LEDs
+fn centre_distance(index: usize) -> usize {
+    (2 * index).abs_diff(LED_COUNT - 1) / 2
+}
+
+/// Returns brightness of a bar position.
+///
+/// # Arguments
+/// * `fill` - Bar fill in brightness steps
+/// * `position` - Position along the bar
+///
+/// # Returns
+/// * `u8` - Full below the fill, the fraction covered at its end
+fn covered(fill: u32, position: usize) -> u8 {
+    let covered = fill.saturating_sub(position as u32 * MAX_BRIGHTNESS as u32);
+    covered.min(MAX_BRIGHTNESS as u32) as u8
+}
+
+/// Bar-graph display.
+///
+/// # Details
+/// Values are in tenths of a percent. The shown value follows the
+/// set value exponentially with the smoothing time constant. Values
+/// above BAR_FULL show a full bar, blinking if overflow blink is on.
+///
+/// # Fields
+/// * `direction` - Fill direction
+/// * `smoothing_ms` - Smoothing time constant, 0 for none
+/// * `blink_ms` - Overflow blink period, 0 for a steady bar
+/// * `target` - Value set in tenths of a percent
+/// * `shown` - Value shown, scaled by SCALE
+/// * `phase_ms` - Position within the blink period
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct BarGraph {
+    direction: FillDirection,
+    smoothing_ms: u32,
+    blink_ms: u32,
+    target: u16,
+    shown: u32,
+    phase_ms: u32,
+}
+
+impl BarGraph {
+    /// Creates new empty bar graph without overflow blink.
+    ///
+    /// # Arguments
+    /// * `direction` - Fill direction
+    /// * `smoothing_ms` - Smoothing time constant, 0 for none
+    ///
+    /// # Returns
+    /// * `Self` - New BarGraph instance
+    #[allow(dead_code)]
+    pub fn new(direction: FillDirection, smoothing_ms: u32) -> Self {
+        Self {
+            direction,
+            smoothing_ms,
+            blink_ms: 0,
+            target: 0,
+            shown: 0,
+            phase_ms: 0,
+        }
+    }
+
+    /// Returns bar graph that blinks on overflow.
+    ///
+    /// # Arguments
+    /// * `period_ms` - Blink period, 0 for a steady bar
+    ///
+    /// # Returns
+    /// * `Self` - Updated bar graph
+    #[allow(dead_code)]
+    pub fn with_overflow_blink(mut self, period_ms: u32) -> Self {
+        self.blink_ms = period_ms;
+        self
+    }
+
+    /// Returns fill direction.
+    ///
+    /// # Returns
+    /// * `FillDirection` - Fill direction
+    #[allow(dead_code)]
+    pub fn direction(&self) -> FillDirection {
+        self.direction
+    }
+
+    /// Sets fill direction.
+    ///
+    /// # Arguments
+    /// * `direction` - Fill direction
+    #[allow(dead_code)]
+    pub fn set_direction(&mut self, direction: FillDirection) {
+        self.direction = direction;
+    }
+
+    /// Returns value set.
+    ///
+    /// # Returns
+    /// * `u16` - Value in tenths of a percent
+    #[allow(dead_code)]
+    pub fn value(&self) -> u16 {
+        self.target
+    }
+
+    /// Sets value to move towards.
+    ///
+    /// # Arguments
+    /// * `value` - Value in tenths of a percent, above BAR_FULL overflows
+    #[allow(dead_code)]
+    pub fn set_value(&mut self, value: u16) {
+        self.target = value;
+    }
+
+    /// Returns value shown.
+    ///
+    /// # Returns
+    /// * `u16` - Smoothed value in tenths of a percent, at most BAR_FULL
+    #[allow(dead_code)]
+    pub fn shown(&self) -> u16 {
+        (self.shown / SCALE) as u16
+    }
+
+    /// Returns whether the value set overflows the bar.
+    ///
+    /// # Returns
+    /// * `bool` - true above BAR_FULL
+    #[allow(dead_code)]
+    pub fn is_overflow(&self) -> bool {
+        self.target > BAR_FULL
+    }
+
+    /// Advances smoothing and blink time.
+    ///
+    /// # Arguments
+    /// * `elapsed_ms` - Time since the previous tick
+    #[allow(dead_code)]
+    pub fn tick(&mut self, elapsed_ms: u32) {
+        let target = self.target.min(BAR_FULL) as u32 * SCALE;
+        if self.smoothing_ms == 0 || elapsed_ms >= self.smoothing_ms {
+            self.shown = target;
+        } else {
+            let difference = target as i64 - self.shown as i64;
+            let step = difference * elapsed_ms as i64 / self.smoothing_ms as i64;
+            self.shown = if step == 0 {
+                target
+            } else {
+                (self.shown as i64 + step) as u32
+            };
+        }
+        if self.blink_ms > 0 {
+            self.phase_ms = (self.phase_ms + elapsed_ms % self.blink_ms) % self.blink_ms;
+        }
+    }
+
+    /// Returns bar frame.
+    ///
+    /// # Returns
+    /// * `Frame` - Bar for the shown value, dark in the off half of an overflow blink
+    #[allow(dead_code)]
+    pub fn frame(&self) -> Frame {
+        let mut frame = Frame::new();
+        if self.is_overflow() && self.blink_ms > 0 && self.phase_ms >= self.blink_ms / 2 {
+            return frame;
+        }
+        let value = self.shown as u64;
+        let full = BAR_FULL as u64 * SCALE as u64;
+        for index in 0..LED_COUNT {
+            let level = match self.direction {
+                FillDirection::Forward => covered(bar_fill(value, full, LED_COUNT), index),
+                FillDirection::Reverse => {
+                    covered(bar_fill(value, full, LED_COUNT), LED_COUNT - 1 - index)
+                }
+                FillDirection::CentreOut => covered(
+                    bar_fill(value, full, LED_COUNT.div_ceil(2)),
+                    centre_distance(index),
+                ),
+            };
+            frame.set_level(index, level);
+        }
+        frame
+    }
+}
+
+/// Returns bar fill for a value.
+///
+/// # Arguments
+/// * `value` - Scaled value
+/// * `full` - Scaled full-bar value
+/// * `length` - Bar length in LEDs
+///
+/// # Returns
+/// * `u32` - Fill in brightness steps
+fn bar_fill(value: u64, full: u64, length: usize) -> u32 {
+    (value * length as u64 * MAX_BRIGHTNESS as u64 / full) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bar(direction: FillDirection, value: u16) -> BarGraph {
+        let mut bar = BarGraph::new(direction, 0);
+        bar.set_value(value);
+        bar.tick(0);
+        bar
+    }
+
+    // ==================== Helper Tests ====================
+
+    #[test]
+    fn test_centre_distance() {
+        let distances: Vec<usize> = (0..LED_COUNT).map(centre_distance).collect();
+        assert_eq!(distances, [1, 0, 0, 1]);
+    }
+
+    #[test]
+    fn test_covered() {
+        assert_eq!(covered(300, 0), 255);
+        assert_eq!(covered(300, 1), 45);
+        assert_eq!(covered(300, 2), 0);
+    }
+
+    // ==================== Construction Tests ====================
+
+    #[test]
+    fn test_new_is_empty() {
+        let bar = BarGraph::new(FillDirection::Forward, 100);
+        assert_eq!(bar.value(), 0);
+        assert_eq!(bar.shown(), 0);
+        assert_eq!(bar.frame(), Frame::new());
+    }
+
+    #[test]
+    fn test_set_direction() {
+        let mut bar = BarGraph::new(FillDirection::Forward, 0);
+        bar.set_direction(FillDirection::CentreOut);
+        assert_eq!(bar.direction(), FillDirection::CentreOut);
+    }
+
+    // ==================== Forward Tests ====================
+
+    #[test]
+    fn test_forward_full() {
+        assert_eq!(
+            bar(FillDirection::Forward, BAR_FULL).frame(),
+            Frame::filled(MAX_BRIGHTNESS)
+        );
+    }
+
+    #[test]
+    fn test_forward_half() {
+        assert_eq!(
+            bar(FillDirection::Forward, 500).frame(),
+            Frame::from_levels([255, 255, 0, 0])
+        );
+    }
+
+    #[test]
+    fn test_forward_fractional_last_led() {
+        assert_eq!(
+            bar(FillDirection::Forward, 375).frame(),
+            Frame::from_levels([255, 127, 0, 0])
+        );
+    }
+
+    #[test]
+    fn test_forward_small_value() {
+        assert_eq!(
+            bar(FillDirection::Forward, 10).frame(),
+            Frame::from_levels([10, 0, 0, 0])
+        );
+    }
+
+    // ==================== Reverse Tests ====================
+
+    #[test]
+    fn test_reverse_mirrors_forward() {
+        assert_eq!(
+            bar(FillDirection::Reverse, 375).frame(),
+            Frame::from_levels([0, 0, 127, 255])
+        );
+    }
+
+    // ==================== CentreOut Tests ====================
+
+    #[test]
+    fn test_centre_out_half() {
+        assert_eq!(
+            bar(FillDirection::CentreOut, 500).frame(),
+            Frame::from_levels([0, 255, 255, 0])
+        );
+    }
+
+    #[test]
+    fn test_centre_out_fraction() {
+        assert_eq!(
+            bar(FillDirection::CentreOut, 750).frame(),
+            Frame::from_levels([127, 255, 255, 127])
+        );
+    }
+
+    #[test]
+    fn test_centre_out_full() {
+        assert_eq!(
+            bar(FillDirection::CentreOut, BAR_FULL).frame(),
+            Frame::filled(MAX_BRIGHTNESS)
+        );
+    }
+
+    // ==================== Overflow Tests ====================
+
+    #[test]
+    fn test_overflow_shows_full_bar() {
+        let bar = bar(FillDirection::Forward, 1500);
+        assert!(bar.is_overflow());
+        assert_eq!(bar.shown(), BAR_FULL);
+        assert_eq!(bar.frame(), Frame::filled(MAX_BRIGHTNESS));
+    }
+
+    #[test]
+    fn test_overflow_blinks() {
+        let mut bar = BarGraph::new(FillDirection::Forward, 0).with_overflow_blink(500);
+        bar.set_value(1200);
+        bar.tick(0);
+        assert_eq!(bar.frame(), Frame::filled(MAX_BRIGHTNESS));
+        bar.tick(250);
+        assert_eq!(bar.frame(), Frame::new());
+        bar.tick(250);
+        assert_eq!(bar.frame(), Frame::filled(MAX_BRIGHTNESS));
+    }
+
+    #[test]
+    fn test_no_blink_without_overflow() {
+        let mut bar = BarGraph::new(FillDirection::Forward, 0).with_overflow_blink(500);
+        bar.set_value(BAR_FULL);
+        bar.tick(250);
+        assert_eq!(bar.frame(), Frame::filled(MAX_BRIGHTNESS));
+    }
+
+    #[test]
+    fn test_blink_long_tick_wraps() {
+        let mut bar = BarGraph::new(FillDirection::Forward, 0).with_overflow_blink(500);
+        bar.set_value(1200);
+        bar.tick(u32::MAX);
+        bar.tick(u32::MAX);
+        assert_eq!(bar.shown(), BAR_FULL);
+    }
+
+    // ==================== Smoothing Tests ====================
+
+    #[test]
+    fn test_smoothing_moves_gradually() {
+        let mut bar = BarGraph::new(FillDirection::Forward, 100);
+        bar.set_value(BAR_FULL);
+        bar.tick(10);
+        assert_eq!(bar.shown(), 100);
+        bar.tick(10);
+        assert_eq!(bar.shown(), 190);
+    }
+
+    #[test]
+    fn test_smoothing_settles() {
+        let mut bar = BarGraph::new(FillDirection::Forward, 100);
+        bar.set_value(640);
+        for _ in 0..200 {
+            bar.tick(10);
+        }
+        assert_eq!(bar.shown(), 640);
+    }
+
+    #[test]
+    fn test_smoothing_falls() {
+        let mut bar = BarGraph::new(FillDirection::Forward, 100);
+        bar.set_value(BAR_FULL);
+        bar.tick(100);
+        bar.set_value(0);
+        bar.tick(50);
+        assert_eq!(bar.shown(), 500);
+        for _ in 0..200 {
+            bar.tick(10);
+        }
+        assert_eq!(bar.shown(), 0);
+    }
+
+    #[test]
+    fn test_long_tick_jumps() {
+        let mut bar = BarGraph::new(FillDirection::Forward, 100);
+        bar.set_value(700);
+        bar.tick(100);
+        assert_eq!(bar.shown(), 700);
+    }
+
+    #[test]
+    fn test_value_kept_while_smoothing() {
+        let mut bar = BarGraph::new(FillDirection::Forward, 100);
+        bar.set_value(300);
+        bar.tick(10);
+        assert_eq!(bar.value(), 300);
+    }
+}
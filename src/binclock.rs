@@ -0,0 +1,301 @@
+/*
+ * @file binclock.rs
+ * @brief Binary and BCD clock display
+ * @author Kevin Thomas
+ * @date 2025
+ *
+ * MIT License
+ *
+ * Copyright (c) 2025 Kevin Thomas
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+//! FILE: binclock.rs
+//!
+//! DESCRIPTION:
+//! Binary and BCD Clock Display for the LED Row.
+//!
+//! BRIEF:
+//! Shows hours, minutes and seconds as binary or BCD bits.
+//! Fits the fields to LED_COUNT, down to seconds only.
+//! LED 0 is the least significant bit of the seconds.
+//!
+//! AUTHOR: Kevin Thomas
+//! CREATION DATE: October 18, 2026
+//! UPDATE DATE: October 18, 2026
+
+use crate::config::{LED_COUNT, MAX_BRIGHTNESS};
+use crate::frame::Frame;
+use crate::wallclock::TimeOfDay;
+
+/// Clock encoding enumeration.
+///
+/// # Variants
+/// * `Binary` - Each field as one binary number
+/// * `Bcd` - Each field as tens and units decimal digits
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[allow(dead_code)]
+pub enum ClockEncoding {
+    Binary,
+    Bcd,
+}
+
+/// Clock fields shown for a row length.
+///
+/// # Variants
+/// * `HoursMinutesSeconds` - Every field
+/// * `MinutesSeconds` - Minutes and seconds
+/// * `Seconds` - Seconds only
+/// * `SecondsLowBits` - As many low bits of the seconds as fit
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub enum ClockLayout {
+    HoursMinutesSeconds,
+    MinutesSeconds,
+    Seconds,
+    SecondsLowBits,
+}
+
+impl ClockEncoding {
+    /// Returns bits needed for a field.
+    ///
+    /// # Arguments
+    /// * `max` - Largest field value, 23 or 59
+    ///
+    /// # Returns
+    /// * `usize` - Bit count
+    const fn field_bits(self, max: u8) -> usize {
+        match self {
+            ClockEncoding::Binary => (u8::BITS - max.leading_zeros()) as usize,
+            ClockEncoding::Bcd => (u8::BITS - (max / 10).leading_zeros()) as usize + 4,
+        }
+    }
+
+    /// Encodes a field.
+    ///
+    /// # Arguments
+    /// * `value` - Field value
+    ///
+    /// # Returns
+    /// * `u32` - Binary value, or tens digit above the units digit
+    const fn encode(self, value: u8) -> u32 {
+        match self {
+            ClockEncoding::Binary => value as u32,
+            ClockEncoding::Bcd => ((value / 10) as u32) << 4 | (value % 10) as u32,
+        }
+    }
+
+    /// Returns layout for a row length.
+    ///
+    /// # Arguments
+    /// * `leds` - LEDs in the row
+    ///
+    /// # Returns
+    /// * `ClockLayout` - Most fields that fit
+    #[allow(dead_code)]
+    pub const fn layout(self, leds: usize) -> ClockLayout {
+        let seconds = self.field_bits(59);
+        let minutes = seconds + self.field_bits(59);
+        if leds >= minutes + self.field_bits(23) {
+            ClockLayout::HoursMinutesSeconds
+        } else if leds >= minutes {
+            ClockLayout::MinutesSeconds
+        } else if leds >= seconds {
+            ClockLayout::Seconds
+        } else {
+            ClockLayout::SecondsLowBits
+        }
+    }
+
+    /// Returns the bits shown for a time.
+    ///
+    /// # Details
+    /// Seconds take the lowest bits, then minutes, then hours.
+    ///
+    /// # Arguments
+    /// * `time` - Time of day
+    ///
+    /// # Returns
+    /// * `u32` - Bit n lights LED n
+    #[allow(dead_code)]
+    pub const fn bits(self, time: TimeOfDay) -> u32 {
+        self.bits_in(time, LED_COUNT)
+    }
+
+    /// Returns the bits shown for a time on a row length.
+    ///
+    /// # Arguments
+    /// * `time` - Time of day
+    /// * `leds` - LEDs in the row
+    ///
+    /// # Returns
+    /// * `u32` - Bit n lights LED n
+    const fn bits_in(self, time: TimeOfDay, leds: usize) -> u32 {
+        let seconds_bits = self.field_bits(59);
+        let minutes_bits = self.field_bits(59);
+        let seconds = self.encode(time.seconds());
+        let minutes = self.encode(time.minutes()) << seconds_bits;
+        let hours = self.encode(time.hours()) << (seconds_bits + minutes_bits);
+        match self.layout(leds) {
+            ClockLayout::HoursMinutesSeconds => hours | minutes | seconds,
+            ClockLayout::MinutesSeconds => minutes | seconds,
+            ClockLayout::Seconds => seconds,
+            ClockLayout::SecondsLowBits => seconds & ((1 << leds) - 1),
+        }
+    }
+
+    /// Returns clock frame.
+    ///
+    /// # Arguments
+    /// * `time` - Time of day
+    ///
+    /// # Returns
+    /// * `Frame` - Set bits at full brightness
+    #[allow(dead_code)]
+    pub fn frame(self, time: TimeOfDay) -> Frame {
+        let bits = self.bits(time);
+        let mut frame = Frame::new();
+        for index in 0..LED_COUNT.min(u32::BITS as usize) {
+            if bits & (1 << index) != 0 {
+                frame.set_level(index, MAX_BRIGHTNESS);
+            }
+        }
+        frame
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(hours: u8, minutes: u8, seconds: u8) -> TimeOfDay {
+        TimeOfDay::new(hours, minutes, seconds).unwrap()
+    }
+
+    // ==================== Field Tests ====================
+
+    #[test]
+    fn test_binary_field_bits() {
+        assert_eq!(ClockEncoding::Binary.field_bits(59), 6);
+        assert_eq!(ClockEncoding::Binary.field_bits(23), 5);
+    }
+
+    #[test]
+    fn test_bcd_field_bits() {
+        assert_eq!(ClockEncoding::Bcd.field_bits(59), 7);
+        assert_eq!(ClockEncoding::Bcd.field_bits(23), 6);
+    }
+
+    #[test]
+    fn test_bcd_encode() {
+        assert_eq!(ClockEncoding::Bcd.encode(47), 0x47);
+        assert_eq!(ClockEncoding::Bcd.encode(9), 0x09);
+    }
+
+    // ==================== Layout Tests ====================
+
+    #[test]
+    fn test_binary_layouts() {
+        let encoding = ClockEncoding::Binary;
+        assert_eq!(encoding.layout(4), ClockLayout::SecondsLowBits);
+        assert_eq!(encoding.layout(6), ClockLayout::Seconds);
+        assert_eq!(encoding.layout(12), ClockLayout::MinutesSeconds);
+        assert_eq!(encoding.layout(17), ClockLayout::HoursMinutesSeconds);
+    }
+
+    #[test]
+    fn test_bcd_layouts() {
+        let encoding = ClockEncoding::Bcd;
+        assert_eq!(encoding.layout(4), ClockLayout::SecondsLowBits);
+        assert_eq!(encoding.layout(7), ClockLayout::Seconds);
+        assert_eq!(encoding.layout(14), ClockLayout::MinutesSeconds);
+        assert_eq!(encoding.layout(19), ClockLayout::MinutesSeconds);
+        assert_eq!(encoding.layout(20), ClockLayout::HoursMinutesSeconds);
+    }
+
+    #[test]
+    fn test_configured_row_shows_seconds_only() {
+        assert_eq!(
+            ClockEncoding::Binary.layout(LED_COUNT),
+            ClockLayout::SecondsLowBits
+        );
+    }
+
+    // ==================== Frame Tests ====================
+
+    #[test]
+    fn test_binary_seconds_low_bits() {
+        assert_eq!(ClockEncoding::Binary.bits(time(12, 34, 45)), 45 & 0xF);
+        assert_eq!(
+            ClockEncoding::Binary.frame(time(12, 34, 5)),
+            Frame::from_levels([255, 0, 255, 0])
+        );
+    }
+
+    #[test]
+    fn test_bcd_shows_seconds_units() {
+        assert_eq!(ClockEncoding::Bcd.bits(time(0, 0, 47)), 7);
+        assert_eq!(
+            ClockEncoding::Bcd.frame(time(0, 0, 39)),
+            Frame::from_levels([255, 0, 0, 255])
+        );
+    }
+
+    #[test]
+    fn test_binary_full_row() {
+        let bits = ClockEncoding::Binary.bits_in(time(13, 37, 42), 17);
+        assert_eq!(bits, 13 << 12 | 37 << 6 | 42);
+    }
+
+    #[test]
+    fn test_binary_minutes_seconds() {
+        let bits = ClockEncoding::Binary.bits_in(time(13, 37, 42), 12);
+        assert_eq!(bits, 37 << 6 | 42);
+    }
+
+    #[test]
+    fn test_bcd_full_row() {
+        let bits = ClockEncoding::Bcd.bits_in(time(13, 37, 42), 20);
+        assert_eq!(bits, 0x13 << 14 | 0x37 << 7 | 0x42);
+    }
+
+    #[test]
+    fn test_bcd_seconds_only() {
+        assert_eq!(ClockEncoding::Bcd.bits_in(time(13, 37, 42), 8), 0x42);
+    }
+
+    #[test]
+    fn test_midnight_is_dark() {
+        assert_eq!(
+            ClockEncoding::Binary.frame(TimeOfDay::MIDNIGHT),
+            Frame::new()
+        );
+        assert_eq!(ClockEncoding::Bcd.frame(TimeOfDay::MIDNIGHT), Frame::new());
+    }
+
+    #[test]
+    fn test_frame_counts_up() {
+        let lit: Vec<usize> = (0..4)
+            .map(|second| ClockEncoding::Binary.frame(time(0, 0, second)).lit_count())
+            .collect();
+        assert_eq!(lit, [0, 1, 1, 2]);
+    }
+}
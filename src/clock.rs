@@ -0,0 +1,557 @@
+/*
+ * @file clock.rs
+ * @brief External clock input and clock output
+ * @author Kevin Thomas
+ * @date 2025
+ *
+ * MIT License
+ *
+ * Copyright (c) 2025 Kevin Thomas
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+//! FILE: clock.rs
+//!
+//! DESCRIPTION:
+//! External Trigger and Clock Synchronisation for the LED Sequencer.
+//!
+//! BRIEF:
+//! Turns filtered rising edges into sequencer steps with divide or multiply ratios.
+//! Falls back to internal timing when the external clock stops.
+//! Generates clock-out pulses so several boards can be daisy-chained.
+//!
+//! AUTHOR: Kevin Thomas
+//! CREATION DATE: October 18, 2026
+//! UPDATE DATE: October 18, 2026
+
+/// Clock ratio enumeration.
+///
+/// # Variants
+/// * `Divide` - One step every n accepted edges
+/// * `Multiply` - n steps per edge period
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub enum ClockRatio {
+    Divide(u8),
+    Multiply(u8),
+}
+
+/// Clock source enumeration.
+///
+/// # Variants
+/// * `Internal` - Sequencer steps at its own delay
+/// * `External` - Sequencer steps on clock edges
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[allow(dead_code)]
+pub enum ClockSource {
+    Internal,
+    External,
+}
+
+/// Clock configuration error enumeration.
+///
+/// # Variants
+/// * `ZeroRatio` - Ratio of zero
+/// * `ZeroTimeout` - Timeout of zero
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[allow(dead_code)]
+pub enum ClockError {
+    ZeroRatio,
+    ZeroTimeout,
+}
+
+/// External clock input.
+///
+/// # Details
+/// Edges closer than the holdoff to the previous accepted edge
+/// are rejected as glitches. Multiplied steps are spread evenly
+/// over the last measured edge period, and any still pending at
+/// the next edge are taken at once so no step is lost. With no
+/// accepted edge for the timeout the source returns to internal.
+///
+/// # Fields
+/// * `ratio` - Steps per accepted edge
+/// * `holdoff_ms` - Minimum time between accepted edges
+/// * `timeout_ms` - Time without edges before falling back
+/// * `last_edge_ms` - Time of the last accepted edge
+/// * `period_ms` - Measured edge period, 0 until known
+/// * `edge_count` - Accepted edges toward the next divided step
+/// * `pending` - Multiplied steps still due this period
+/// * `next_step_ms` - Time the next multiplied step is due
+/// * `source` - Current clock source
+/// * `rejected` - Edges rejected by the holdoff filter
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct ClockInput {
+    ratio: ClockRatio,
+    holdoff_ms: u64,
+    timeout_ms: u64,
+    last_edge_ms: Option<u64>,
+    period_ms: u64,
+    edge_count: u8,
+    pending: u8,
+    next_step_ms: u64,
+    source: ClockSource,
+    rejected: u32,
+}
+
+impl ClockInput {
+    /// Creates new clock input on internal timing.
+    ///
+    /// # Arguments
+    /// * `ratio` - Steps per accepted edge
+    /// * `holdoff_ms` - Minimum time between accepted edges
+    /// * `timeout_ms` - Time without edges before falling back
+    ///
+    /// # Returns
+    /// * `Result<Self, ClockError>` - New ClockInput
+    #[allow(dead_code)]
+    pub fn new(ratio: ClockRatio, holdoff_ms: u64, timeout_ms: u64) -> Result<Self, ClockError> {
+        if matches!(ratio, ClockRatio::Divide(0) | ClockRatio::Multiply(0)) {
+            return Err(ClockError::ZeroRatio);
+        }
+        if timeout_ms == 0 {
+            return Err(ClockError::ZeroTimeout);
+        }
+        Ok(Self {
+            ratio,
+            holdoff_ms,
+            timeout_ms,
+            last_edge_ms: None,
+            period_ms: 0,
+            edge_count: 0,
+            pending: 0,
+            next_step_ms: 0,
+            source: ClockSource::Internal,
+            rejected: 0,
+        })
+    }
+
+    /// Returns current clock source.
+    ///
+    /// # Returns
+    /// * `ClockSource` - Internal or external
+    #[allow(dead_code)]
+    pub fn source(&self) -> ClockSource {
+        self.source
+    }
+
+    /// Returns measured edge period.
+    ///
+    /// # Returns
+    /// * `u64` - Period in milliseconds, 0 until two edges are seen
+    #[allow(dead_code)]
+    pub fn period_ms(&self) -> u64 {
+        self.period_ms
+    }
+
+    /// Returns number of edges rejected by the holdoff filter.
+    ///
+    /// # Returns
+    /// * `u32` - Rejected edges
+    #[allow(dead_code)]
+    pub fn rejected_edges(&self) -> u32 {
+        self.rejected
+    }
+
+    /// Handles a rising edge.
+    ///
+    /// # Details
+    /// The first accepted edge switches the source to external.
+    /// A divided clock steps on the first of every n edges.
+    ///
+    /// # Arguments
+    /// * `now_ms` - Time of the edge
+    ///
+    /// # Returns
+    /// * `u32` - Steps to take now
+    #[allow(dead_code)]
+    pub fn edge(&mut self, now_ms: u64) -> u32 {
+        if let Some(last) = self.last_edge_ms {
+            if now_ms.saturating_sub(last) < self.holdoff_ms {
+                self.rejected = self.rejected.saturating_add(1);
+                return 0;
+            }
+            self.period_ms = now_ms - last;
+        }
+        self.last_edge_ms = Some(now_ms);
+        self.source = ClockSource::External;
+        match self.ratio {
+            ClockRatio::Divide(n) => {
+                let fire = self.edge_count == 0;
+                self.edge_count = (self.edge_count + 1) % n;
+                fire as u32
+            }
+            ClockRatio::Multiply(n) => {
+                let steps = 1 + self.pending as u32;
+                self.pending = if self.period_ms > 0 { n - 1 } else { 0 };
+                self.next_step_ms = now_ms + self.period_ms / n as u64;
+                steps
+            }
+        }
+    }
+
+    /// Checks timeouts and multiplied steps.
+    ///
+    /// # Details
+    /// Call regularly between edges. Falls back to internal timing
+    /// once no edge has been accepted for the timeout.
+    ///
+    /// # Arguments
+    /// * `now_ms` - Current time
+    ///
+    /// # Returns
+    /// * `u32` - Steps to take now
+    #[allow(dead_code)]
+    pub fn poll(&mut self, now_ms: u64) -> u32 {
+        let Some(last) = self.last_edge_ms else {
+            return 0;
+        };
+        if now_ms.saturating_sub(last) >= self.timeout_ms {
+            self.fall_back();
+            return 0;
+        }
+        let ClockRatio::Multiply(n) = self.ratio else {
+            return 0;
+        };
+        let interval = (self.period_ms / n as u64).max(1);
+        let mut steps = 0;
+        while self.pending > 0 && now_ms >= self.next_step_ms {
+            steps += 1;
+            self.pending -= 1;
+            self.next_step_ms += interval;
+        }
+        steps
+    }
+
+    /// Returns to internal timing and forgets the edge history.
+    fn fall_back(&mut self) {
+        self.source = ClockSource::Internal;
+        self.last_edge_ms = None;
+        self.period_ms = 0;
+        self.edge_count = 0;
+        self.pending = 0;
+    }
+}
+
+/// Clock output pulse generator.
+///
+/// # Details
+/// Raises the output for one pulse per step, so a follower
+/// board sees one rising edge per step. Steps that land while a
+/// pulse is out are queued and each gets its own pulse after at
+/// least one pulse width of low time.
+///
+/// # Fields
+/// * `pulse_ms` - Pulse width in milliseconds
+/// * `last_steps` - Step count at the last update
+/// * `pending` - Steps still waiting for a pulse
+/// * `high_until_ms` - End of the current pulse, if any
+/// * `low_until_ms` - End of the gap after a pulse, if any
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct ClockOut {
+    pulse_ms: u64,
+    last_steps: u32,
+    pending: u32,
+    high_until_ms: Option<u64>,
+    low_until_ms: Option<u64>,
+}
+
+impl ClockOut {
+    /// Creates new clock output.
+    ///
+    /// # Arguments
+    /// * `pulse_ms` - Pulse width in milliseconds
+    /// * `steps` - Current step count
+    ///
+    /// # Returns
+    /// * `Self` - New ClockOut with the output low
+    #[allow(dead_code)]
+    pub fn new(pulse_ms: u64, steps: u32) -> Self {
+        Self {
+            pulse_ms,
+            last_steps: steps,
+            pending: 0,
+            high_until_ms: None,
+            low_until_ms: None,
+        }
+    }
+
+    /// Updates the output for the latest step count.
+    ///
+    /// # Arguments
+    /// * `steps` - Step count, wrapping
+    /// * `now_ms` - Current time
+    ///
+    /// # Returns
+    /// * `bool` - true while the output should be high
+    #[allow(dead_code)]
+    pub fn update(&mut self, steps: u32, now_ms: u64) -> bool {
+        let taken = steps.wrapping_sub(self.last_steps);
+        self.pending = self.pending.saturating_add(taken);
+        self.last_steps = steps;
+        if let Some(until) = self.high_until_ms {
+            if now_ms < until {
+                return true;
+            }
+            self.high_until_ms = None;
+            self.low_until_ms = Some(now_ms + self.pulse_ms);
+        }
+        if let Some(until) = self.low_until_ms {
+            if now_ms < until {
+                return false;
+            }
+            self.low_until_ms = None;
+        }
+        if self.pending == 0 {
+            return false;
+        }
+        self.pending -= 1;
+        self.high_until_ms = Some(now_ms + self.pulse_ms);
+        true
+    }
+
+    /// Returns whether the output still has pulses to send.
+    ///
+    /// # Returns
+    /// * `bool` - true while a pulse, its gap or a queued step is pending
+    #[allow(dead_code)]
+    pub fn is_active(&self) -> bool {
+        self.pending > 0 || self.high_until_ms.is_some() || self.low_until_ms.is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn clock(ratio: ClockRatio) -> ClockInput {
+        ClockInput::new(ratio, 5, 1000).unwrap()
+    }
+
+    // ==================== Construction Tests ====================
+
+    #[test]
+    fn test_new_starts_internal() {
+        let clock = clock(ClockRatio::Divide(1));
+        assert_eq!(clock.source(), ClockSource::Internal);
+        assert_eq!(clock.period_ms(), 0);
+    }
+
+    #[test]
+    fn test_new_zero_divide() {
+        assert_eq!(
+            ClockInput::new(ClockRatio::Divide(0), 5, 1000),
+            Err(ClockError::ZeroRatio)
+        );
+    }
+
+    #[test]
+    fn test_new_zero_multiply() {
+        assert_eq!(
+            ClockInput::new(ClockRatio::Multiply(0), 5, 1000),
+            Err(ClockError::ZeroRatio)
+        );
+    }
+
+    #[test]
+    fn test_new_zero_timeout() {
+        assert_eq!(
+            ClockInput::new(ClockRatio::Divide(1), 5, 0),
+            Err(ClockError::ZeroTimeout)
+        );
+    }
+
+    // ==================== Edge Tests ====================
+
+    #[test]
+    fn test_edge_steps_once() {
+        let mut clock = clock(ClockRatio::Divide(1));
+        assert_eq!(clock.edge(100), 1);
+        assert_eq!(clock.edge(200), 1);
+        assert_eq!(clock.source(), ClockSource::External);
+    }
+
+    #[test]
+    fn test_edge_measures_period() {
+        let mut clock = clock(ClockRatio::Divide(1));
+        clock.edge(100);
+        clock.edge(250);
+        assert_eq!(clock.period_ms(), 150);
+    }
+
+    #[test]
+    fn test_holdoff_rejects_glitch() {
+        let mut clock = clock(ClockRatio::Divide(1));
+        clock.edge(100);
+        assert_eq!(clock.edge(103), 0);
+        assert_eq!(clock.rejected_edges(), 1);
+        assert_eq!(clock.edge(105), 1);
+        assert_eq!(clock.period_ms(), 5);
+    }
+
+    #[test]
+    fn test_divide_steps_every_nth_edge() {
+        let mut clock = clock(ClockRatio::Divide(3));
+        let steps: Vec<u32> = (1..=7).map(|n| clock.edge(n * 100)).collect();
+        assert_eq!(steps, [1, 0, 0, 1, 0, 0, 1]);
+    }
+
+    #[test]
+    fn test_multiply_first_edge_single_step() {
+        let mut clock = clock(ClockRatio::Multiply(4));
+        assert_eq!(clock.edge(100), 1);
+        assert_eq!(clock.poll(150), 0);
+    }
+
+    #[test]
+    fn test_multiply_spreads_steps() {
+        let mut clock = clock(ClockRatio::Multiply(4));
+        clock.edge(0);
+        assert_eq!(clock.edge(400), 1);
+        assert_eq!(clock.poll(499), 0);
+        assert_eq!(clock.poll(500), 1);
+        assert_eq!(clock.poll(600), 1);
+        assert_eq!(clock.poll(700), 1);
+        assert_eq!(clock.poll(750), 0);
+    }
+
+    #[test]
+    fn test_multiply_catches_up_at_edge() {
+        let mut clock = clock(ClockRatio::Multiply(4));
+        clock.edge(0);
+        clock.edge(400);
+        assert_eq!(clock.edge(600), 4);
+    }
+
+    #[test]
+    fn test_multiply_late_poll_takes_all_due() {
+        let mut clock = clock(ClockRatio::Multiply(4));
+        clock.edge(0);
+        clock.edge(400);
+        assert_eq!(clock.poll(790), 3);
+    }
+
+    // ==================== Timeout Tests ====================
+
+    #[test]
+    fn test_poll_without_edges() {
+        let mut clock = clock(ClockRatio::Divide(1));
+        assert_eq!(clock.poll(10_000), 0);
+        assert_eq!(clock.source(), ClockSource::Internal);
+    }
+
+    #[test]
+    fn test_timeout_falls_back() {
+        let mut clock = clock(ClockRatio::Divide(1));
+        clock.edge(100);
+        clock.poll(1099);
+        assert_eq!(clock.source(), ClockSource::External);
+        clock.poll(1100);
+        assert_eq!(clock.source(), ClockSource::Internal);
+        assert_eq!(clock.period_ms(), 0);
+    }
+
+    #[test]
+    fn test_timeout_drops_pending_steps() {
+        let mut clock = clock(ClockRatio::Multiply(4));
+        clock.edge(0);
+        clock.edge(400);
+        clock.poll(1400);
+        assert_eq!(clock.poll(1410), 0);
+    }
+
+    #[test]
+    fn test_edge_after_timeout_restarts() {
+        let mut clock = clock(ClockRatio::Divide(2));
+        clock.edge(0);
+        clock.poll(5000);
+        assert_eq!(clock.edge(5100), 1);
+        assert_eq!(clock.period_ms(), 0);
+        assert_eq!(clock.source(), ClockSource::External);
+    }
+
+    // ==================== ClockOut Tests ====================
+
+    #[test]
+    fn test_clock_out_starts_low() {
+        let mut out = ClockOut::new(10, 0);
+        assert!(!out.update(0, 0));
+    }
+
+    #[test]
+    fn test_clock_out_pulses_on_step() {
+        let mut out = ClockOut::new(10, 0);
+        assert!(out.update(1, 100));
+        assert!(out.update(1, 109));
+        assert!(!out.update(1, 110));
+    }
+
+    #[test]
+    fn test_clock_out_pulses_each_step() {
+        let mut out = ClockOut::new(10, 0);
+        out.update(1, 100);
+        out.update(1, 110);
+        assert!(out.update(2, 200));
+    }
+
+    #[test]
+    fn test_clock_out_pulses_per_step_in_one_tick() {
+        let mut out = ClockOut::new(10, 0);
+        assert!(out.update(2, 100));
+        assert!(!out.update(2, 110));
+        assert!(!out.update(2, 119));
+        assert!(out.update(2, 120));
+        assert!(!out.update(2, 130));
+        assert!(!out.update(2, 200));
+    }
+
+    #[test]
+    fn test_clock_out_low_time_from_pulse_end() {
+        let mut out = ClockOut::new(10, 0);
+        out.update(1, 100);
+        assert!(!out.update(3, 115));
+        assert!(!out.update(3, 124));
+        assert!(out.update(3, 125));
+    }
+
+    #[test]
+    fn test_clock_out_active_until_queue_sent() {
+        let mut out = ClockOut::new(10, 0);
+        assert!(!out.is_active());
+        out.update(2, 100);
+        out.update(2, 110);
+        assert!(out.is_active());
+        out.update(2, 120);
+        out.update(2, 130);
+        assert!(out.is_active());
+        out.update(2, 140);
+        assert!(!out.is_active());
+    }
+
+    #[test]
+    fn test_clock_out_handles_wrap() {
+        let mut out = ClockOut::new(10, u32::MAX);
+        assert!(out.update(0, 100));
+    }
+}
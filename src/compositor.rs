@@ -0,0 +1,719 @@
+/*
+ * @file compositor.rs
+ * @brief Layered pattern compositor
+ * @author Kevin Thomas
+ * @date 2025
+ *
+ * MIT License
+ *
+ * Copyright (c) 2025 Kevin Thomas
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+//! FILE: compositor.rs
+//!
+//! DESCRIPTION:
+//! Layered LED Pattern Compositor for RP2350.
+//!
+//! BRIEF:
+//! Runs several patterns at once, each with its own tempo.
+//! Combines layer frames bottom to top using blend modes.
+//!
+//! AUTHOR: Kevin Thomas
+//! CREATION DATE: October 18, 2026
+//! UPDATE DATE: October 18, 2026
+
+use crate::config::{LED_COUNT, MAX_BRIGHTNESS, MAX_LAYERS};
+use crate::frame::{Frame, scale_level};
+use crate::led::LedSequenceController;
+use crate::pattern::Pattern;
+
+/// Layer blend mode enumeration.
+///
+/// # Details
+/// Describes how a layer combines with the layers below it.
+/// OR and XOR operate bitwise on levels, which matches the
+/// logical operation for fully on or off LEDs.
+///
+/// # Variants
+/// * `Or` - Bitwise OR of levels
+/// * `Xor` - Bitwise XOR of levels
+/// * `Max` - Brighter of the two levels
+/// * `AddClamp` - Sum of levels clamped to full brightness
+/// * `Mask` - Lower levels scaled by this layer's level
+/// * `Override` - This layer's level wherever it is lit
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub enum BlendMode {
+    Or,
+    Xor,
+    Max,
+    AddClamp,
+    Mask,
+    Override,
+}
+
+/// Compositor error enumeration.
+///
+/// # Variants
+/// * `Full` - All layer slots are in use
+/// * `InvalidLayer` - Layer index does not exist
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[allow(dead_code)]
+pub enum CompositorError {
+    Full,
+    InvalidLayer,
+}
+
+/// Blends two brightness levels.
+///
+/// # Arguments
+/// * `base` - Level from the layers below
+/// * `top` - Level from the blended layer
+/// * `mode` - Blend mode to apply
+///
+/// # Returns
+/// * `u8` - Blended level
+#[allow(dead_code)]
+pub fn blend_level(base: u8, top: u8, mode: BlendMode) -> u8 {
+    match mode {
+        BlendMode::Or => base | top,
+        BlendMode::Xor => base ^ top,
+        BlendMode::Max => base.max(top),
+        BlendMode::AddClamp => base.saturating_add(top),
+        BlendMode::Mask => scale_level(base, top),
+        BlendMode::Override if top > 0 => top,
+        BlendMode::Override => base,
+    }
+}
+
+/// Blends two frames LED by LED.
+///
+/// # Arguments
+/// * `base` - Frame from the layers below
+/// * `top` - Frame from the blended layer
+/// * `mode` - Blend mode to apply
+///
+/// # Returns
+/// * `Frame` - Blended frame
+#[allow(dead_code)]
+pub fn blend_frames(base: &Frame, top: &Frame, mode: BlendMode) -> Frame {
+    let mut out = Frame::new();
+    for index in 0..LED_COUNT {
+        out.set_level(
+            index,
+            blend_level(base.level(index), top.level(index), mode),
+        );
+    }
+    out
+}
+
+/// Single compositor layer.
+///
+/// # Details
+/// Wraps a sequence controller that steps at its own delay.
+/// Elapsed time is accumulated so tempos need not match the
+/// output refresh interval.
+///
+/// # Fields
+/// * `controller` - Pattern state and step delay
+/// * `blend` - Blend mode against lower layers
+/// * `opacity` - Scale applied to the layer frame before blending
+/// * `enabled` - Whether the layer contributes to the output
+/// * `elapsed_ms` - Time accumulated toward the next step
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct Layer {
+    controller: LedSequenceController,
+    blend: BlendMode,
+    opacity: u8,
+    enabled: bool,
+    elapsed_ms: u64,
+}
+
+impl Layer {
+    /// Creates new layer for a pattern.
+    ///
+    /// # Arguments
+    /// * `pattern` - Pattern to run
+    /// * `step_ms` - Delay between pattern steps in milliseconds
+    /// * `blend` - Blend mode against lower layers
+    ///
+    /// # Returns
+    /// * `Self` - New Layer at full opacity
+    #[allow(dead_code)]
+    pub fn new(pattern: Pattern, step_ms: u64, blend: BlendMode) -> Self {
+        let mut controller = LedSequenceController::with_pattern(pattern);
+        controller.set_delay_ms(step_ms);
+        Self {
+            controller,
+            blend,
+            opacity: MAX_BRIGHTNESS,
+            enabled: true,
+            elapsed_ms: 0,
+        }
+    }
+
+    /// Returns layer with given opacity.
+    ///
+    /// # Arguments
+    /// * `opacity` - Scale factor (0 to MAX_BRIGHTNESS)
+    ///
+    /// # Returns
+    /// * `Self` - Updated layer
+    #[allow(dead_code)]
+    pub fn with_opacity(mut self, opacity: u8) -> Self {
+        self.opacity = opacity;
+        self
+    }
+
+    /// Advances layer time and steps the pattern when due.
+    ///
+    /// # Details
+    /// Takes as many steps as fit in the accumulated time.
+    ///
+    /// # Arguments
+    /// * `elapsed_ms` - Time since the previous tick
+    ///
+    /// # Returns
+    /// * `u32` - Steps taken
+    #[allow(dead_code)]
+    pub fn tick(&mut self, elapsed_ms: u64) -> u32 {
+        self.elapsed_ms += elapsed_ms;
+        let mut steps = 0;
+        while self.elapsed_ms >= self.controller.delay_ms() {
+            self.elapsed_ms -= self.controller.delay_ms();
+            self.controller.advance();
+            steps += 1;
+        }
+        steps
+    }
+
+    /// Returns time until the layer next changes its output.
+    ///
+    /// # Details
+    /// Disabled or paused layers never change on their own.
+    ///
+    /// # Returns
+    /// * `u64` - Milliseconds until the next step, u64::MAX if never
+    #[allow(dead_code)]
+    pub fn ms_until_step(&self) -> u64 {
+        if !self.enabled || self.controller.is_paused() {
+            return u64::MAX;
+        }
+        self.controller.delay_ms().saturating_sub(self.elapsed_ms)
+    }
+
+    /// Returns layer frame after opacity.
+    ///
+    /// # Returns
+    /// * `Frame` - Frame to blend
+    #[allow(dead_code)]
+    pub fn frame(&self) -> Frame {
+        self.controller.frame().scaled(self.opacity)
+    }
+
+    /// Returns blend mode.
+    ///
+    /// # Returns
+    /// * `BlendMode` - Blend mode against lower layers
+    #[allow(dead_code)]
+    pub fn blend(&self) -> BlendMode {
+        self.blend
+    }
+
+    /// Sets blend mode.
+    ///
+    /// # Arguments
+    /// * `blend` - New blend mode
+    #[allow(dead_code)]
+    pub fn set_blend(&mut self, blend: BlendMode) {
+        self.blend = blend;
+    }
+
+    /// Returns whether the layer is enabled.
+    ///
+    /// # Returns
+    /// * `bool` - true if the layer contributes to the output
+    #[allow(dead_code)]
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Enables or disables the layer.
+    ///
+    /// # Arguments
+    /// * `enabled` - true to include the layer in the output
+    #[allow(dead_code)]
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    /// Returns layer controller.
+    ///
+    /// # Returns
+    /// * `&LedSequenceController` - Pattern state and step delay
+    #[allow(dead_code)]
+    pub fn controller(&self) -> &LedSequenceController {
+        &self.controller
+    }
+
+    /// Returns mutable layer controller.
+    ///
+    /// # Returns
+    /// * `&mut LedSequenceController` - Pattern state and step delay
+    #[allow(dead_code)]
+    pub fn controller_mut(&mut self) -> &mut LedSequenceController {
+        &mut self.controller
+    }
+}
+
+/// Stack of pattern layers combined into one frame.
+///
+/// # Details
+/// Layer 0 is the bottom layer and is blended onto a blank frame.
+/// Each following layer is blended onto the result so far.
+///
+/// # Fields
+/// * `layers` - Layer slots, bottom first
+/// * `count` - Number of slots in use
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct Compositor {
+    layers: [Option<Layer>; MAX_LAYERS],
+    count: usize,
+}
+
+impl Default for Compositor {
+    /// Returns default Compositor instance.
+    ///
+    /// # Details
+    /// Delegates to new() for initialization.
+    ///
+    /// # Returns
+    /// * `Self` - New Compositor with no layers
+    #[allow(dead_code)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Compositor {
+    /// Creates new compositor with no layers.
+    ///
+    /// # Returns
+    /// * `Self` - New Compositor instance
+    #[allow(dead_code)]
+    pub fn new() -> Self {
+        Self {
+            layers: [None; MAX_LAYERS],
+            count: 0,
+        }
+    }
+
+    /// Adds a layer on top of the stack.
+    ///
+    /// # Arguments
+    /// * `layer` - Layer to add
+    ///
+    /// # Returns
+    /// * `Result<usize, CompositorError>` - Index of the new layer
+    #[allow(dead_code)]
+    pub fn push(&mut self, layer: Layer) -> Result<usize, CompositorError> {
+        if self.count == MAX_LAYERS {
+            return Err(CompositorError::Full);
+        }
+        self.layers[self.count] = Some(layer);
+        self.count += 1;
+        Ok(self.count - 1)
+    }
+
+    /// Returns number of layers.
+    ///
+    /// # Returns
+    /// * `usize` - Layers in the stack
+    #[allow(dead_code)]
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    /// Returns whether the stack has no layers.
+    ///
+    /// # Returns
+    /// * `bool` - true if empty
+    #[allow(dead_code)]
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Returns mutable access to a layer.
+    ///
+    /// # Arguments
+    /// * `index` - Layer index, 0 being the bottom
+    ///
+    /// # Returns
+    /// * `Result<&mut Layer, CompositorError>` - Layer reference
+    #[allow(dead_code)]
+    pub fn layer_mut(&mut self, index: usize) -> Result<&mut Layer, CompositorError> {
+        self.layers[..self.count]
+            .get_mut(index)
+            .and_then(Option::as_mut)
+            .ok_or(CompositorError::InvalidLayer)
+    }
+
+    /// Advances every layer and returns the composed frame.
+    ///
+    /// # Arguments
+    /// * `elapsed_ms` - Time since the previous tick
+    ///
+    /// # Returns
+    /// * `Frame` - Composed frame
+    #[allow(dead_code)]
+    pub fn tick(&mut self, elapsed_ms: u64) -> Frame {
+        for layer in self.layers.iter_mut().flatten() {
+            layer.tick(elapsed_ms);
+        }
+        self.frame()
+    }
+
+    /// Sets number of LEDs every layer's pattern runs on.
+    ///
+    /// # Arguments
+    /// * `led_count` - LEDs in the sequence
+    #[allow(dead_code)]
+    pub fn set_led_count(&mut self, led_count: usize) {
+        for layer in self.layers.iter_mut().flatten() {
+            layer.controller_mut().set_led_count(led_count);
+        }
+    }
+
+    /// Returns time until any layer next changes the output.
+    ///
+    /// # Returns
+    /// * `u64` - Milliseconds until the next step, u64::MAX if never
+    #[allow(dead_code)]
+    pub fn ms_until_step(&self) -> u64 {
+        self.layers
+            .iter()
+            .flatten()
+            .map(Layer::ms_until_step)
+            .min()
+            .unwrap_or(u64::MAX)
+    }
+
+    /// Returns composed frame without advancing time.
+    ///
+    /// # Returns
+    /// * `Frame` - Composed frame
+    #[allow(dead_code)]
+    pub fn frame(&self) -> Frame {
+        self.layers
+            .iter()
+            .flatten()
+            .filter(|layer| layer.is_enabled())
+            .fold(Frame::new(), |base, layer| {
+                blend_frames(&base, &layer.frame(), layer.blend())
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // ==================== blend_level() Tests ====================
+
+    #[test]
+    fn test_blend_or() {
+        assert_eq!(blend_level(0b1010, 0b0101, BlendMode::Or), 0b1111);
+    }
+
+    #[test]
+    fn test_blend_xor() {
+        assert_eq!(blend_level(0b1110, 0b0111, BlendMode::Xor), 0b1001);
+    }
+
+    #[test]
+    fn test_blend_xor_full_toggles() {
+        assert_eq!(
+            blend_level(MAX_BRIGHTNESS, MAX_BRIGHTNESS, BlendMode::Xor),
+            0
+        );
+        assert_eq!(
+            blend_level(0, MAX_BRIGHTNESS, BlendMode::Xor),
+            MAX_BRIGHTNESS
+        );
+    }
+
+    #[test]
+    fn test_blend_max() {
+        assert_eq!(blend_level(40, 90, BlendMode::Max), 90);
+        assert_eq!(blend_level(90, 40, BlendMode::Max), 90);
+    }
+
+    #[test]
+    fn test_blend_add_clamp() {
+        assert_eq!(blend_level(100, 50, BlendMode::AddClamp), 150);
+        assert_eq!(blend_level(200, 100, BlendMode::AddClamp), MAX_BRIGHTNESS);
+    }
+
+    #[test]
+    fn test_blend_mask() {
+        assert_eq!(blend_level(200, MAX_BRIGHTNESS, BlendMode::Mask), 200);
+        assert_eq!(blend_level(200, 0, BlendMode::Mask), 0);
+    }
+
+    #[test]
+    fn test_blend_override() {
+        assert_eq!(blend_level(200, 10, BlendMode::Override), 10);
+        assert_eq!(blend_level(200, 0, BlendMode::Override), 200);
+    }
+
+    // ==================== blend_frames() Tests ====================
+
+    #[test]
+    fn test_blend_frames_per_led() {
+        let base = Frame::from_levels([10, 20, 30, 40]);
+        let top = Frame::from_levels([40, 30, 20, 10]);
+        let out = blend_frames(&base, &top, BlendMode::Max);
+        assert_eq!(out, Frame::from_levels([40, 30, 30, 40]));
+    }
+
+    // ==================== Layer Tests ====================
+
+    #[test]
+    fn test_layer_new() {
+        let layer = Layer::new(Pattern::Chase, 100, BlendMode::Or);
+        assert_eq!(layer.controller().delay_ms(), 100);
+        assert_eq!(layer.blend(), BlendMode::Or);
+        assert!(layer.is_enabled());
+    }
+
+    #[test]
+    fn test_layer_waits_for_delay() {
+        let mut layer = Layer::new(Pattern::Chase, 100, BlendMode::Or);
+        layer.tick(99);
+        assert_eq!(layer.controller().current_index(), 0);
+        layer.tick(1);
+        assert_eq!(layer.controller().current_index(), 1);
+    }
+
+    #[test]
+    fn test_layer_catches_up() {
+        let mut layer = Layer::new(Pattern::Chase, 100, BlendMode::Or);
+        layer.tick(250);
+        assert_eq!(layer.controller().current_index(), 2);
+        layer.tick(50);
+        assert_eq!(layer.controller().current_index(), 3);
+    }
+
+    #[test]
+    fn test_layer_tick_counts_steps() {
+        let mut layer = Layer::new(Pattern::Chase, 100, BlendMode::Or);
+        assert_eq!(layer.tick(99), 0);
+        assert_eq!(layer.tick(201), 3);
+    }
+
+    #[test]
+    fn test_layer_ms_until_step() {
+        let mut layer = Layer::new(Pattern::Chase, 100, BlendMode::Or);
+        assert_eq!(layer.ms_until_step(), 100);
+        layer.tick(30);
+        assert_eq!(layer.ms_until_step(), 70);
+    }
+
+    #[test]
+    fn test_layer_ms_until_step_disabled() {
+        let mut layer = Layer::new(Pattern::Chase, 100, BlendMode::Or);
+        layer.set_enabled(false);
+        assert_eq!(layer.ms_until_step(), u64::MAX);
+    }
+
+    #[test]
+    fn test_layer_opacity() {
+        let layer = Layer::new(Pattern::Solid, 100, BlendMode::Or).with_opacity(0);
+        assert_eq!(layer.frame(), Frame::new());
+    }
+
+    #[test]
+    fn test_layer_set_blend() {
+        let mut layer = Layer::new(Pattern::Solid, 100, BlendMode::Or);
+        layer.set_blend(BlendMode::Mask);
+        assert_eq!(layer.blend(), BlendMode::Mask);
+    }
+
+    // ==================== Compositor Tests ====================
+
+    #[test]
+    fn test_new_compositor_empty() {
+        let compositor = Compositor::new();
+        assert!(compositor.is_empty());
+        assert_eq!(compositor.frame(), Frame::new());
+    }
+
+    #[test]
+    fn test_default_equals_new() {
+        assert_eq!(Compositor::default(), Compositor::new());
+    }
+
+    #[test]
+    fn test_push_returns_index() {
+        let mut compositor = Compositor::new();
+        let layer = Layer::new(Pattern::Chase, 100, BlendMode::Or);
+        assert_eq!(compositor.push(layer), Ok(0));
+        assert_eq!(compositor.push(layer), Ok(1));
+        assert_eq!(compositor.len(), 2);
+    }
+
+    #[test]
+    fn test_push_full() {
+        let mut compositor = Compositor::new();
+        let layer = Layer::new(Pattern::Chase, 100, BlendMode::Or);
+        for _ in 0..MAX_LAYERS {
+            compositor.push(layer).unwrap();
+        }
+        assert_eq!(compositor.push(layer), Err(CompositorError::Full));
+    }
+
+    #[test]
+    fn test_layer_mut_invalid() {
+        let mut compositor = Compositor::new();
+        assert_eq!(
+            compositor.layer_mut(0).err(),
+            Some(CompositorError::InvalidLayer)
+        );
+    }
+
+    #[test]
+    fn test_single_layer_passthrough() {
+        let mut compositor = Compositor::new();
+        compositor
+            .push(Layer::new(Pattern::Chase, 100, BlendMode::Or))
+            .unwrap();
+        assert_eq!(compositor.frame(), Pattern::Chase.frame(0));
+    }
+
+    #[test]
+    fn test_background_with_chase_override() {
+        let mut compositor = Compositor::new();
+        compositor
+            .push(Layer::new(Pattern::Solid, 100, BlendMode::Max).with_opacity(50))
+            .unwrap();
+        compositor
+            .push(Layer::new(Pattern::Chase, 100, BlendMode::Override))
+            .unwrap();
+        let frame = compositor.frame();
+        assert_eq!(frame.level(0), MAX_BRIGHTNESS);
+        assert_eq!(frame.level(1), 50);
+    }
+
+    #[test]
+    fn test_mask_layer_hides_lower() {
+        let mut compositor = Compositor::new();
+        compositor
+            .push(Layer::new(Pattern::Solid, 100, BlendMode::Max))
+            .unwrap();
+        compositor
+            .push(Layer::new(Pattern::Chase, 100, BlendMode::Mask))
+            .unwrap();
+        assert_eq!(compositor.frame(), Pattern::Chase.frame(0));
+    }
+
+    #[test]
+    fn test_disabled_layer_ignored() {
+        let mut compositor = Compositor::new();
+        compositor
+            .push(Layer::new(Pattern::Solid, 100, BlendMode::Max))
+            .unwrap();
+        compositor.layer_mut(0).unwrap().set_enabled(false);
+        assert_eq!(compositor.frame(), Frame::new());
+    }
+
+    #[test]
+    fn test_layers_keep_own_tempo() {
+        let mut compositor = Compositor::new();
+        compositor
+            .push(Layer::new(Pattern::Chase, 100, BlendMode::Or))
+            .unwrap();
+        compositor
+            .push(Layer::new(Pattern::Chase, 50, BlendMode::Or))
+            .unwrap();
+        compositor.tick(100);
+        assert_eq!(
+            compositor
+                .layer_mut(0)
+                .unwrap()
+                .controller()
+                .current_index(),
+            1
+        );
+        assert_eq!(
+            compositor
+                .layer_mut(1)
+                .unwrap()
+                .controller()
+                .current_index(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_tick_returns_frame() {
+        let mut compositor = Compositor::new();
+        compositor
+            .push(Layer::new(Pattern::Chase, 100, BlendMode::Or))
+            .unwrap();
+        assert_eq!(compositor.tick(100), Pattern::Chase.frame(1));
+    }
+
+    #[test]
+    fn test_compositor_ms_until_step_empty() {
+        assert_eq!(Compositor::new().ms_until_step(), u64::MAX);
+    }
+
+    #[test]
+    fn test_compositor_ms_until_step_soonest_layer() {
+        let mut compositor = Compositor::new();
+        compositor
+            .push(Layer::new(Pattern::Chase, 100, BlendMode::Or))
+            .unwrap();
+        compositor
+            .push(Layer::new(Pattern::Chase, 40, BlendMode::Or))
+            .unwrap();
+        compositor.tick(30);
+        assert_eq!(compositor.ms_until_step(), 10);
+    }
+
+    #[test]
+    fn test_set_led_count_applies_to_layers() {
+        let mut compositor = Compositor::new();
+        compositor
+            .push(Layer::new(Pattern::Solid, 100, BlendMode::Max))
+            .unwrap();
+        compositor.set_led_count(1);
+        assert_eq!(compositor.frame().lit_count(), 1);
+    }
+
+    #[test]
+    fn test_compositor_error_debug() {
+        assert_eq!(format!("{:?}", CompositorError::Full), "Full");
+    }
+}
@@ -38,7 +38,7 @@
 //!
 //! AUTHOR: Kevin Thomas
 //! CREATION DATE: December 7, 2025
-//! UPDATE DATE: December 7, 2025
+//! UPDATE DATE: October 18, 2026
 
 /// Number of LEDs in the sequence.
 ///
@@ -82,6 +82,519 @@ pub const MIN_SEQUENCE_DELAY_MS: u64 = 10;
 #[allow(dead_code)]
 pub const MAX_SEQUENCE_DELAY_MS: u64 = 5000;
 
+/// Output refresh interval in milliseconds.
+///
+/// # Details
+/// Period at which composed frames are pushed to the LED outputs.
+/// Independent of the per-pattern step delay.
+///
+/// # Value
+/// 10 milliseconds (100 Hz)
+#[allow(dead_code)]
+pub const FRAME_INTERVAL_MS: u64 = 10;
+
+/// Maximum LED brightness level.
+///
+/// # Details
+/// Full-scale value of a single LED level within a frame.
+/// A level of 0 is off and this value is fully on.
+///
+/// # Value
+/// 255
+#[allow(dead_code)]
+pub const MAX_BRIGHTNESS: u8 = 255;
+
+/// Number of steps in one breathing cycle.
+///
+/// # Details
+/// Steps taken to ramp all LEDs up to full brightness and back down.
+///
+/// # Value
+/// 32 steps
+#[allow(dead_code)]
+pub const BREATHE_STEPS: usize = 32;
+
+/// Maximum number of compositor layers.
+///
+/// # Details
+/// Upper bound on patterns that can run at once in the compositor.
+///
+/// # Value
+/// 4 layers
+#[allow(dead_code)]
+pub const MAX_LAYERS: usize = 4;
+
+/// PWM counter wrap value for LED outputs.
+///
+/// # Details
+/// Sets the PWM resolution used to render LED brightness levels.
+/// At the 150 MHz system clock this yields roughly 36.6 kHz.
+///
+/// # Value
+/// 4095 (12-bit resolution)
+#[allow(dead_code)]
+pub const PWM_TOP: u16 = 4095;
+
+/// Maximum number of playlist entries.
+///
+/// # Details
+/// Bounds the play order table used for sequential and shuffled playback.
+///
+/// # Value
+/// 16 entries
+#[allow(dead_code)]
+pub const MAX_PLAYLIST_ENTRIES: usize = 16;
+
+/// Maximum number of schedule entries.
+///
+/// # Value
+/// 8 entries
+#[allow(dead_code)]
+pub const MAX_SCHEDULE_ENTRIES: usize = 8;
+
+/// Maximum number of state machine states.
+///
+/// # Details
+/// Bounds the table walked by the reachability check.
+///
+/// # Value
+/// 16 states
+#[allow(dead_code)]
+pub const MAX_FSM_STATES: usize = 16;
+
+/// Maximum Morse message length in characters.
+///
+/// # Details
+/// Bounds the fixed buffer used to hold a queued Morse message.
+///
+/// # Value
+/// 32 characters
+#[allow(dead_code)]
+pub const MORSE_MESSAGE_LEN: usize = 32;
+
+/// Morse message queue capacity.
+///
+/// # Details
+/// Number of messages that can wait behind the one being played.
+///
+/// # Value
+/// 4 messages
+#[allow(dead_code)]
+pub const MORSE_QUEUE_LEN: usize = 4;
+
+/// Watchdog timeout in milliseconds.
+///
+/// # Details
+/// The chip resets if the sequencer fails to feed the watchdog
+/// within this time. Covers two of the longest idle sleeps, so one
+/// late wake that skips a feed does not reset the chip.
+///
+/// # Value
+/// 1000 milliseconds
+#[allow(dead_code)]
+pub const WATCHDOG_TIMEOUT_MS: u64 = 1000;
+
+/// Allowed step lateness in milliseconds.
+///
+/// # Details
+/// A step finishing later than its interval plus this slack is
+/// treated as off schedule and does not feed the watchdog.
+///
+/// # Value
+/// 20 milliseconds
+#[allow(dead_code)]
+pub const STEP_TOLERANCE_MS: u64 = 20;
+
+/// Step lateness counted as an overrun in microseconds.
+///
+/// # Details
+/// A step landing a whole frame after its scheduled time has
+/// missed a frame and is counted in the jitter histogram.
+///
+/// # Value
+/// 10000 microseconds, one frame
+#[allow(dead_code)]
+pub const JITTER_OVERRUN_US: i64 = FRAME_INTERVAL_MS as i64 * 1000;
+
+/// Watchdog recovery pattern duration in milliseconds.
+///
+/// # Details
+/// How long the startup pattern shows after a watchdog reset.
+///
+/// # Value
+/// 2000 milliseconds
+#[allow(dead_code)]
+pub const WATCHDOG_RECOVERY_MS: u64 = 2000;
+
+/// Sequencer command queue depth.
+///
+/// # Details
+/// Commands waiting for the sequencer before senders block.
+///
+/// # Value
+/// 8 commands
+#[allow(dead_code)]
+pub const COMMAND_QUEUE_LEN: usize = 8;
+
+/// Sequencer status queue depth.
+///
+/// # Details
+/// Status reports waiting for each subscriber. The oldest report
+/// is dropped rather than delaying the sequencer when it is full.
+///
+/// # Value
+/// 4 reports
+#[allow(dead_code)]
+pub const STATUS_QUEUE_LEN: usize = 4;
+
+/// Maximum sequencer status subscribers.
+///
+/// # Details
+/// Tasks such as inputs and telemetry that follow state changes.
+///
+/// # Value
+/// 4 subscribers
+#[allow(dead_code)]
+pub const MAX_STATUS_SUBSCRIBERS: usize = 4;
+
+/// Clock input holdoff in milliseconds.
+///
+/// # Details
+/// Edges closer than this to the previous accepted edge are
+/// rejected as glitches or contact bounce.
+///
+/// # Value
+/// 5 milliseconds
+#[allow(dead_code)]
+pub const CLOCK_HOLDOFF_MS: u64 = 5;
+
+/// Clock input timeout in milliseconds.
+///
+/// # Details
+/// The sequencer returns to internal timing after this long
+/// without an accepted edge.
+///
+/// # Value
+/// 2000 milliseconds
+#[allow(dead_code)]
+pub const CLOCK_TIMEOUT_MS: u64 = 2000;
+
+/// Clock output pulse width in milliseconds.
+///
+/// # Details
+/// One frame interval, well above the follower holdoff.
+///
+/// # Value
+/// 10 milliseconds
+#[allow(dead_code)]
+pub const CLOCK_PULSE_MS: u64 = 10;
+
+/// Boards in a synchronised row.
+///
+/// # Details
+/// Boards together run one chase across all their LEDs.
+///
+/// # Value
+/// 1 board
+#[allow(dead_code)]
+pub const SYNC_BOARD_COUNT: u8 = 1;
+
+/// Position of this board in the synchronised row.
+///
+/// # Details
+/// Board 0 is the leader and every other board follows it.
+///
+/// # Value
+/// Board 0
+#[allow(dead_code)]
+pub const SYNC_BOARD_INDEX: u8 = 0;
+
+/// Sync UART baud rate.
+///
+/// # Value
+/// 115200 baud
+#[allow(dead_code)]
+pub const SYNC_BAUD: u32 = 115_200;
+
+/// Time between sync beacons in milliseconds.
+///
+/// # Value
+/// 200 milliseconds
+#[allow(dead_code)]
+pub const SYNC_BEACON_INTERVAL_MS: u32 = 200;
+
+/// Beacon transmit delay in milliseconds.
+///
+/// # Details
+/// Twelve bytes of ten bits each take about 1 ms at SYNC_BAUD.
+///
+/// # Value
+/// 1 millisecond
+#[allow(dead_code)]
+pub const SYNC_LATENCY_MS: u32 = 1;
+
+/// DMX512 line rate.
+///
+/// # Value
+/// 250000 baud
+#[allow(dead_code)]
+pub const DMX_BAUD: u32 = 250_000;
+
+/// DMX512 start address of LED 0.
+///
+/// # Details
+/// 1-based as on a lighting desk. LED n takes the following slots.
+///
+/// # Value
+/// Slot 1
+#[allow(dead_code)]
+pub const DMX_START_ADDRESS: u16 = 1;
+
+/// DMX512 signal-loss timeout in milliseconds.
+///
+/// # Details
+/// The internal sequence takes over after this long without a frame.
+///
+/// # Value
+/// 1000 milliseconds
+#[allow(dead_code)]
+pub const DMX_TIMEOUT_MS: u32 = 1000;
+
+/// Current drawn by one LED at full brightness in milliamps.
+///
+/// # Value
+/// 20 milliamps
+#[allow(dead_code)]
+pub const LED_CURRENT_MA: u32 = 20;
+
+/// Total LED current budget in milliamps.
+///
+/// # Details
+/// Covers the four LEDs at full brightness with headroom. Lower it
+/// to suit the supply once the LED count grows.
+///
+/// # Value
+/// 100 milliamps
+#[allow(dead_code)]
+pub const POWER_BUDGET_MA: u32 = 100;
+
+/// Longest render loop sleep in milliseconds.
+///
+/// # Details
+/// Keeps the loop waking, and feeding the watchdog, often enough that
+/// WATCHDOG_TIMEOUT_MS survives a skipped feed even when the output
+/// is idle.
+///
+/// # Value
+/// 400 milliseconds
+#[allow(dead_code)]
+pub const IDLE_MAX_SLEEP_MS: u64 = 400;
+
+/// Board current while rendering in milliamps, LEDs excluded.
+///
+/// # Value
+/// 25 milliamps
+#[allow(dead_code)]
+pub const ACTIVE_CURRENT_MA: u32 = 25;
+
+/// Board current while sleeping in milliamps, LEDs excluded.
+///
+/// # Value
+/// 2 milliamps
+#[allow(dead_code)]
+pub const SLEEP_CURRENT_MA: u32 = 2;
+
+/// Battery capacity used for run time estimates in milliamp hours.
+///
+/// # Value
+/// 2000 milliamp hours
+#[allow(dead_code)]
+pub const BATTERY_CAPACITY_MAH: u32 = 2000;
+
+/// Sense reading rise below which an LED is open circuit.
+///
+/// # Details
+/// Raw 12-bit ADC counts over the all-off baseline across a
+/// 10 ohm sense resistor. A healthy LED at LED_CURRENT_MA reads
+/// about 250 counts.
+///
+/// # Value
+/// 60 counts, about 5 milliamps
+#[allow(dead_code)]
+pub const HEALTH_OPEN_BELOW: u16 = 60;
+
+/// Sense reading rise above which an LED is shorted.
+///
+/// # Value
+/// 600 counts, about 48 milliamps
+#[allow(dead_code)]
+pub const HEALTH_SHORT_ABOVE: u16 = 600;
+
+/// Time for the sense reading to settle after each self-test step.
+///
+/// # Value
+/// 20 milliseconds
+#[allow(dead_code)]
+pub const HEALTH_SETTLE_MS: u64 = 20;
+
+/// Sense readings averaged per self-test step.
+///
+/// # Value
+/// 16 readings
+#[allow(dead_code)]
+pub const HEALTH_SAMPLES: u32 = 16;
+
+/// Size of the on-board flash in bytes.
+///
+/// # Value
+/// 4 MiB, the Pico 2 flash
+#[allow(dead_code)]
+pub const FLASH_SIZE_BYTES: usize = 4 * 1024 * 1024;
+
+/// Flash offset of the saved usage statistics.
+///
+/// # Details
+/// The last 4 KiB erase sector, left out of the FLASH region in
+/// memory.x so the firmware image never reaches it.
+///
+/// # Value
+/// 4 MiB minus 4 KiB
+#[allow(dead_code)]
+pub const USAGE_FLASH_OFFSET: u32 = (FLASH_SIZE_BYTES - 4096) as u32;
+
+/// Interval between usage statistics saves in milliseconds.
+///
+/// # Details
+/// Hourly saves keep the sector within its erase endurance for
+/// many years of continuous running.
+///
+/// # Value
+/// 3600000 milliseconds, one hour
+#[allow(dead_code)]
+pub const USAGE_SAVE_INTERVAL_MS: u64 = 3_600_000;
+
+/// Text console UART baud rate.
+///
+/// # Value
+/// 115200 baud
+#[allow(dead_code)]
+pub const CONSOLE_BAUD: u32 = 115_200;
+
+/// Longest text console command line in bytes.
+///
+/// # Details
+/// Fits a Morse command for one LED with a full MORSE_MESSAGE_LEN message.
+///
+/// # Value
+/// 48 bytes
+#[allow(dead_code)]
+pub const CONSOLE_LINE_LEN: usize = 48;
+
+/// Console report queue depth.
+///
+/// # Details
+/// Reports that arrive while the queue is full are dropped.
+///
+/// # Value
+/// 4 reports
+#[allow(dead_code)]
+pub const REPORT_QUEUE_LEN: usize = 4;
+
+/// Push button debounce time in milliseconds.
+///
+/// # Details
+/// Presses closer together than this count once.
+///
+/// # Value
+/// 50 milliseconds
+#[allow(dead_code)]
+pub const BUTTON_DEBOUNCE_MS: u64 = 50;
+
+/// Bar-graph smoothing time constant in milliseconds.
+///
+/// # Value
+/// 250 milliseconds
+#[allow(dead_code)]
+pub const BAR_SMOOTHING_MS: u32 = 250;
+
+/// Bar-graph overflow blink period in milliseconds.
+///
+/// # Value
+/// 500 milliseconds
+#[allow(dead_code)]
+pub const BAR_BLINK_MS: u32 = 500;
+
+/// MIDI line rate.
+///
+/// # Value
+/// 31250 baud
+#[allow(dead_code)]
+pub const MIDI_BAUD: u32 = 31_250;
+
+/// MIDI note driving LED 0.
+///
+/// # Details
+/// Middle C. LED n follows the note n semitones above.
+///
+/// # Value
+/// Note 60
+#[allow(dead_code)]
+pub const MIDI_BASE_NOTE: u8 = 60;
+
+/// MIDI channel driving the LEDs.
+///
+/// # Details
+/// 0-based channel number, None to follow every channel.
+///
+/// # Value
+/// Every channel
+#[allow(dead_code)]
+pub const MIDI_CHANNEL: Option<u8> = None;
+
+/// MIDI clock pulses per sequence step.
+///
+/// # Details
+/// At 24 pulses per quarter note, 6 steps the sequence in sixteenth notes.
+///
+/// # Value
+/// 6 pulses
+#[allow(dead_code)]
+pub const MIDI_CLOCKS_PER_STEP: u8 = 6;
+
+/// VU meter microphone sample rate.
+///
+/// # Details
+/// Covers speech and the beat-carrying low end of music.
+///
+/// # Value
+/// 8000 Hz
+#[allow(dead_code)]
+pub const VU_SAMPLE_RATE_HZ: u32 = 8000;
+
+/// VU meter level shown as an empty bar.
+///
+/// # Value
+/// -48 dB below full scale
+#[allow(dead_code)]
+pub const VU_FLOOR_DB: i16 = -48;
+
+/// Whether detected beats step the chase instead of showing the meter.
+///
+/// # Value
+/// false, show the level meter
+#[allow(dead_code)]
+pub const VU_BEAT_STEPS: bool = false;
+
+/// Core 1 stack size in bytes.
+///
+/// # Details
+/// Stack for the executor running the sequencer on core 1.
+///
+/// # Value
+/// 4096 bytes
+#[allow(dead_code)]
+pub const CORE1_STACK_SIZE: usize = 4096;
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -98,16 +611,10 @@ mod tests {
         let _: usize = LED_COUNT;
     }
 
-    #[test]
-    fn test_led_count_non_zero() {
-        assert!(LED_COUNT > 0);
-    }
+    const _: () = assert!(LED_COUNT > 0);
 
-    #[test]
-    fn test_led_count_reasonable() {
-        assert!(LED_COUNT >= 2);
-        assert!(LED_COUNT <= 24);
-    }
+    const _: () = assert!(LED_COUNT >= 2);
+    const _: () = assert!(LED_COUNT <= 24);
 
     // ==================== SEQUENCE_DELAY_MS Tests ====================
 
@@ -121,16 +628,10 @@ mod tests {
         let _: u64 = SEQUENCE_DELAY_MS;
     }
 
-    #[test]
-    fn test_sequence_delay_non_zero() {
-        assert!(SEQUENCE_DELAY_MS > 0);
-    }
+    const _: () = assert!(SEQUENCE_DELAY_MS > 0);
 
-    #[test]
-    fn test_sequence_delay_reasonable_range() {
-        assert!(SEQUENCE_DELAY_MS >= 50);
-        assert!(SEQUENCE_DELAY_MS <= 2000);
-    }
+    const _: () = assert!(SEQUENCE_DELAY_MS >= 50);
+    const _: () = assert!(SEQUENCE_DELAY_MS <= 2000);
 
     // ==================== MIN_SEQUENCE_DELAY_MS Tests ====================
 
@@ -144,20 +645,11 @@ mod tests {
         let _: u64 = MIN_SEQUENCE_DELAY_MS;
     }
 
-    #[test]
-    fn test_min_delay_non_zero() {
-        assert!(MIN_SEQUENCE_DELAY_MS > 0);
-    }
+    const _: () = assert!(MIN_SEQUENCE_DELAY_MS > 0);
 
-    #[test]
-    fn test_min_delay_less_than_default() {
-        assert!(MIN_SEQUENCE_DELAY_MS < SEQUENCE_DELAY_MS);
-    }
+    const _: () = assert!(MIN_SEQUENCE_DELAY_MS < SEQUENCE_DELAY_MS);
 
-    #[test]
-    fn test_min_delay_practical_minimum() {
-        assert!(MIN_SEQUENCE_DELAY_MS >= 1);
-    }
+    const _: () = assert!(MIN_SEQUENCE_DELAY_MS >= 1);
 
     // ==================== MAX_SEQUENCE_DELAY_MS Tests ====================
 
@@ -171,10 +663,7 @@ mod tests {
         let _: u64 = MAX_SEQUENCE_DELAY_MS;
     }
 
-    #[test]
-    fn test_max_delay_greater_than_default() {
-        assert!(MAX_SEQUENCE_DELAY_MS > SEQUENCE_DELAY_MS);
-    }
+    const _: () = assert!(MAX_SEQUENCE_DELAY_MS > SEQUENCE_DELAY_MS);
 
     #[test]
     fn test_max_delay_is_5_seconds() {
@@ -183,16 +672,10 @@ mod tests {
 
     // ==================== Range Relationship Tests ====================
 
-    #[test]
-    fn test_delay_range_valid() {
-        assert!(MIN_SEQUENCE_DELAY_MS < MAX_SEQUENCE_DELAY_MS);
-    }
+    const _: () = assert!(MIN_SEQUENCE_DELAY_MS < MAX_SEQUENCE_DELAY_MS);
 
-    #[test]
-    fn test_default_within_range() {
-        assert!(SEQUENCE_DELAY_MS >= MIN_SEQUENCE_DELAY_MS);
-        assert!(SEQUENCE_DELAY_MS <= MAX_SEQUENCE_DELAY_MS);
-    }
+    const _: () = assert!(SEQUENCE_DELAY_MS >= MIN_SEQUENCE_DELAY_MS);
+    const _: () = assert!(SEQUENCE_DELAY_MS <= MAX_SEQUENCE_DELAY_MS);
 
     #[test]
     fn test_range_span() {
@@ -268,4 +751,356 @@ mod tests {
     fn test_led_count_fits_in_u8() {
         assert!(LED_COUNT <= u8::MAX as usize);
     }
+
+    // ==================== FRAME_INTERVAL_MS Tests ====================
+
+    #[test]
+    fn test_frame_interval_value() {
+        assert_eq!(FRAME_INTERVAL_MS, 10);
+    }
+
+    const _: () = assert!(FRAME_INTERVAL_MS <= MIN_SEQUENCE_DELAY_MS);
+
+    // ==================== MAX_BRIGHTNESS Tests ====================
+
+    #[test]
+    fn test_max_brightness_value() {
+        assert_eq!(MAX_BRIGHTNESS, u8::MAX);
+    }
+
+    // ==================== BREATHE_STEPS Tests ====================
+
+    #[test]
+    fn test_breathe_steps_value() {
+        assert_eq!(BREATHE_STEPS, 32);
+    }
+
+    #[test]
+    fn test_breathe_steps_even() {
+        assert_eq!(BREATHE_STEPS % 2, 0);
+    }
+
+    // ==================== MAX_LAYERS Tests ====================
+
+    #[test]
+    fn test_max_layers_value() {
+        assert_eq!(MAX_LAYERS, 4);
+    }
+
+    const _: () = assert!(MAX_LAYERS >= 2);
+
+    // ==================== PWM_TOP Tests ====================
+
+    #[test]
+    fn test_pwm_top_value() {
+        assert_eq!(PWM_TOP, 4095);
+    }
+
+    #[test]
+    fn test_pwm_top_full_scale_fits_in_u16() {
+        assert!((PWM_TOP as u32) < u16::MAX as u32);
+    }
+
+    // ==================== MAX_PLAYLIST_ENTRIES Tests ====================
+
+    #[test]
+    fn test_max_playlist_entries_value() {
+        assert_eq!(MAX_PLAYLIST_ENTRIES, 16);
+    }
+
+    #[test]
+    fn test_max_playlist_entries_fits_in_u8() {
+        assert!(MAX_PLAYLIST_ENTRIES <= u8::MAX as usize);
+    }
+
+    #[test]
+    fn test_max_schedule_entries_value() {
+        assert_eq!(MAX_SCHEDULE_ENTRIES, 8);
+    }
+
+    #[test]
+    fn test_max_fsm_states_value() {
+        assert_eq!(MAX_FSM_STATES, 16);
+    }
+
+    // ==================== Morse Tests ====================
+
+    #[test]
+    fn test_morse_message_len_value() {
+        assert_eq!(MORSE_MESSAGE_LEN, 32);
+    }
+
+    #[test]
+    fn test_morse_queue_len_value() {
+        assert_eq!(MORSE_QUEUE_LEN, 4);
+    }
+
+    // ==================== Watchdog Tests ====================
+
+    #[test]
+    fn test_watchdog_timeout_value() {
+        assert_eq!(WATCHDOG_TIMEOUT_MS, 1000);
+    }
+
+    const _: () = assert!(WATCHDOG_TIMEOUT_MS > FRAME_INTERVAL_MS + STEP_TOLERANCE_MS);
+
+    #[test]
+    fn test_step_tolerance_value() {
+        assert_eq!(STEP_TOLERANCE_MS, 20);
+    }
+
+    #[test]
+    fn test_watchdog_recovery_value() {
+        assert_eq!(WATCHDOG_RECOVERY_MS, 2000);
+    }
+
+    // ==================== Sequencer Tests ====================
+
+    #[test]
+    fn test_command_queue_len_value() {
+        assert_eq!(COMMAND_QUEUE_LEN, 8);
+    }
+
+    #[test]
+    fn test_status_queue_len_value() {
+        assert_eq!(STATUS_QUEUE_LEN, 4);
+    }
+
+    #[test]
+    fn test_max_status_subscribers_value() {
+        assert_eq!(MAX_STATUS_SUBSCRIBERS, 4);
+    }
+
+    // ==================== Clock Tests ====================
+
+    #[test]
+    fn test_clock_holdoff_value() {
+        assert_eq!(CLOCK_HOLDOFF_MS, 5);
+    }
+
+    #[test]
+    fn test_clock_timeout_value() {
+        assert_eq!(CLOCK_TIMEOUT_MS, 2000);
+    }
+
+    #[test]
+    fn test_clock_pulse_value() {
+        assert_eq!(CLOCK_PULSE_MS, 10);
+    }
+
+    const _: () = assert!(CLOCK_PULSE_MS >= CLOCK_HOLDOFF_MS);
+
+    // ==================== Sync Tests ====================
+
+    const _: () = assert!(SYNC_BOARD_INDEX < SYNC_BOARD_COUNT);
+
+    #[test]
+    fn test_sync_baud_value() {
+        assert_eq!(SYNC_BAUD, 115_200);
+    }
+
+    #[test]
+    fn test_sync_beacon_interval_value() {
+        assert_eq!(SYNC_BEACON_INTERVAL_MS, 200);
+    }
+
+    #[test]
+    fn test_sync_latency_value() {
+        assert_eq!(SYNC_LATENCY_MS, 1);
+    }
+
+    // ==================== DMX Tests ====================
+
+    #[test]
+    fn test_dmx_baud_value() {
+        assert_eq!(DMX_BAUD, 250_000);
+    }
+
+    #[test]
+    fn test_dmx_start_address_value() {
+        assert_eq!(DMX_START_ADDRESS, 1);
+    }
+
+    #[test]
+    fn test_dmx_timeout_value() {
+        assert_eq!(DMX_TIMEOUT_MS, 1000);
+    }
+
+    // ==================== Power Tests ====================
+
+    #[test]
+    fn test_led_current_value() {
+        assert_eq!(LED_CURRENT_MA, 20);
+    }
+
+    #[test]
+    fn test_power_budget_value() {
+        assert_eq!(POWER_BUDGET_MA, 100);
+    }
+
+    #[test]
+    fn test_power_budget_covers_full_row() {
+        assert!(POWER_BUDGET_MA > LED_CURRENT_MA * LED_COUNT as u32);
+    }
+
+    // ==================== Idle Tests ====================
+
+    #[test]
+    fn test_idle_max_sleep_value() {
+        assert_eq!(IDLE_MAX_SLEEP_MS, 400);
+    }
+
+    const _: () = assert!(IDLE_MAX_SLEEP_MS + STEP_TOLERANCE_MS < WATCHDOG_TIMEOUT_MS);
+
+    const _: () = assert!(2 * (IDLE_MAX_SLEEP_MS + STEP_TOLERANCE_MS) < WATCHDOG_TIMEOUT_MS);
+
+    #[test]
+    fn test_active_current_value() {
+        assert_eq!(ACTIVE_CURRENT_MA, 25);
+    }
+
+    #[test]
+    fn test_sleep_current_value() {
+        assert_eq!(SLEEP_CURRENT_MA, 2);
+    }
+
+    #[test]
+    fn test_battery_capacity_value() {
+        assert_eq!(BATTERY_CAPACITY_MAH, 2000);
+    }
+
+    // ==================== Health Tests ====================
+
+    #[test]
+    fn test_health_open_below_value() {
+        assert_eq!(HEALTH_OPEN_BELOW, 60);
+    }
+
+    #[test]
+    fn test_health_short_above_value() {
+        assert_eq!(HEALTH_SHORT_ABOVE, 600);
+    }
+
+    #[test]
+    fn test_health_settle_value() {
+        assert_eq!(HEALTH_SETTLE_MS, 20);
+    }
+
+    #[test]
+    fn test_health_samples_value() {
+        assert_eq!(HEALTH_SAMPLES, 16);
+    }
+
+    // ==================== Usage Tests ====================
+
+    #[test]
+    fn test_flash_size_value() {
+        assert_eq!(FLASH_SIZE_BYTES, 4 * 1024 * 1024);
+    }
+
+    #[test]
+    fn test_usage_flash_offset_value() {
+        assert_eq!(USAGE_FLASH_OFFSET, 0x003F_F000);
+    }
+
+    #[test]
+    fn test_usage_save_interval_value() {
+        assert_eq!(USAGE_SAVE_INTERVAL_MS, 3_600_000);
+    }
+
+    // ==================== JITTER_OVERRUN_US Tests ====================
+
+    #[test]
+    fn test_jitter_overrun_is_one_frame() {
+        assert_eq!(JITTER_OVERRUN_US, 10_000);
+    }
+
+    #[test]
+    fn test_jitter_overrun_within_step_tolerance() {
+        assert!(JITTER_OVERRUN_US <= STEP_TOLERANCE_MS as i64 * 1000);
+    }
+
+    // ==================== Console Tests ====================
+
+    #[test]
+    fn test_console_baud_value() {
+        assert_eq!(CONSOLE_BAUD, 115_200);
+    }
+
+    #[test]
+    fn test_console_line_len_value() {
+        assert_eq!(CONSOLE_LINE_LEN, 48);
+    }
+
+    const _: () = assert!(CONSOLE_LINE_LEN >= "morse 3 ".len() + MORSE_MESSAGE_LEN);
+
+    #[test]
+    fn test_report_queue_len_value() {
+        assert_eq!(REPORT_QUEUE_LEN, 4);
+    }
+
+    // ==================== Button Tests ====================
+
+    #[test]
+    fn test_button_debounce_value() {
+        assert_eq!(BUTTON_DEBOUNCE_MS, 50);
+    }
+
+    // ==================== Bar-Graph Tests ====================
+
+    #[test]
+    fn test_bar_smoothing_value() {
+        assert_eq!(BAR_SMOOTHING_MS, 250);
+    }
+
+    #[test]
+    fn test_bar_blink_value() {
+        assert_eq!(BAR_BLINK_MS, 500);
+    }
+
+    // ==================== MIDI Tests ====================
+
+    #[test]
+    fn test_midi_baud_value() {
+        assert_eq!(MIDI_BAUD, 31_250);
+    }
+
+    #[test]
+    fn test_midi_base_note_value() {
+        assert_eq!(MIDI_BASE_NOTE, 60);
+    }
+
+    #[test]
+    fn test_midi_channel_value() {
+        assert_eq!(MIDI_CHANNEL, None);
+    }
+
+    #[test]
+    fn test_midi_clocks_per_step_value() {
+        assert_eq!(MIDI_CLOCKS_PER_STEP, 6);
+    }
+
+    #[test]
+    fn test_midi_clocks_per_step_divides_quarter() {
+        assert_eq!(24 % MIDI_CLOCKS_PER_STEP, 0);
+    }
+
+    // ==================== VU Meter Tests ====================
+
+    #[test]
+    fn test_vu_sample_rate_value() {
+        assert_eq!(VU_SAMPLE_RATE_HZ, 8000);
+    }
+
+    #[test]
+    fn test_vu_floor_value() {
+        assert_eq!(VU_FLOOR_DB, -48);
+    }
+
+    const _: () = assert!(!VU_BEAT_STEPS);
+
+    #[test]
+    fn test_core1_stack_size_value() {
+        assert_eq!(CORE1_STACK_SIZE, 4096);
+    }
 }
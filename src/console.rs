@@ -0,0 +1,1180 @@
+/*
+ * @file console.rs
+ * @brief Text console command parser and report formatter
+ * @author Kevin Thomas
+ * @date 2025
+ *
+ * MIT License
+ *
+ * Copyright (c) 2025 Kevin Thomas
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+//! FILE: console.rs
+//!
+//! DESCRIPTION:
+//! Text Console Commands and Reports for the Control Plane.
+//!
+//! BRIEF:
+//! Collects received bytes into lines and parses them into sequencer commands.
+//! Formats status changes and rejected lines as single text lines.
+//! Skips status changes that only move the step so the console stays readable.
+//! Writes into fixed buffers so it needs no allocator.
+//!
+//! AUTHOR: Kevin Thomas
+//! CREATION DATE: October 18, 2026
+//! UPDATE DATE: October 18, 2026
+
+use core::fmt::{self, Write};
+
+use crate::bargraph::FillDirection;
+use crate::binclock::ClockEncoding;
+use crate::config::{CONSOLE_LINE_LEN, LED_COUNT};
+use crate::counter::{Counter, CounterEncoding};
+use crate::effects::EffectKind;
+use crate::idle::IdleReport;
+use crate::jitter::JitterHistogram;
+use crate::led::Direction;
+use crate::morse::{MorseMessage, MorseTarget};
+use crate::pattern::Pattern;
+use crate::power::PowerStats;
+use crate::sequencer::{Command, Status};
+use crate::timeline::Playback;
+use crate::usage::UsageStats;
+use crate::wallclock::TimeOfDay;
+
+/// Longest report line in bytes, line ending included.
+///
+/// # Details
+/// Fits a usage report with the largest totals for every LED.
+#[allow(dead_code)]
+pub const REPORT_LINE_LEN: usize = 48 + 64 * LED_COUNT;
+
+/// Console error enumeration.
+///
+/// # Variants
+/// * `Empty` - Line holds only spaces
+/// * `Unknown` - Command word not recognised
+/// * `BadArgument` - Missing, extra or invalid argument
+/// * `TooLong` - Line longer than CONSOLE_LINE_LEN
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[allow(dead_code)]
+pub enum ConsoleError {
+    Empty,
+    Unknown,
+    BadArgument,
+    TooLong,
+}
+
+/// Parses one console line into a command.
+///
+/// # Details
+/// A command word is followed by its arguments. Words are lower
+/// case: pause, resume, step, back, reset, forward, reverse,
+/// playlist, selftest, jump INDEX, delay MS, pattern NAME, morse [LED] TEXT,
+/// counter binary|gray [START END], effect twinkle|walk|candle,
+/// timeline play|seek MS|once|loop|pingpong,
+/// bar VALUE|forward|reverse|centre, time HH:MM[:SS],
+/// clock binary|bcd, and report or clear followed by usage,
+/// jitter, power or idle. Bar
+/// values are in tenths of a percent and Morse text runs to the
+/// end of the line.
+///
+/// # Arguments
+/// * `line` - Line without its line ending
+///
+/// # Returns
+/// * `Result<Command, ConsoleError>` - Parsed command
+#[allow(dead_code)]
+pub fn parse_line(line: &str) -> Result<Command, ConsoleError> {
+    let line = line.trim();
+    let (word, argument) = match line.split_once(' ') {
+        Some((word, argument)) => (word, argument.trim()),
+        None => (line, ""),
+    };
+    match word {
+        "" => Err(ConsoleError::Empty),
+        "jump" => parse_number(argument).map(Command::Jump),
+        "delay" => parse_number(argument).map(Command::SetDelay),
+        "pattern" => parse_pattern(argument).map(Command::SetPattern),
+        "morse" => parse_morse(argument).map(Command::Morse),
+        "counter" => parse_counter(argument).map(Command::ShowCounter),
+        "effect" => parse_effect(argument).map(Command::ShowEffect),
+        "timeline" => parse_timeline(argument),
+        "bar" => parse_bar(argument),
+        "time" => parse_time(argument).map(Command::SetTime),
+        "clock" => parse_clock(argument).map(Command::ShowClock),
+        "report" => parse_report(argument),
+        "clear" => parse_clear(argument),
+        _ => {
+            let command = parse_word(word).ok_or(ConsoleError::Unknown)?;
+            if argument.is_empty() {
+                Ok(command)
+            } else {
+                Err(ConsoleError::BadArgument)
+            }
+        }
+    }
+}
+
+/// Returns command for a word without an argument.
+///
+/// # Arguments
+/// * `word` - Command word
+///
+/// # Returns
+/// * `Option<Command>` - Command, None if the word needs an argument or is unknown
+fn parse_word(word: &str) -> Option<Command> {
+    let command = match word {
+        "pause" => Command::Pause,
+        "resume" => Command::Resume,
+        "step" => Command::Step,
+        "back" => Command::StepBack,
+        "reset" => Command::Reset,
+        "forward" => Command::SetDirection(Direction::Forward),
+        "reverse" => Command::SetDirection(Direction::Reverse),
+        "playlist" => Command::RunPlaylist,
+        "selftest" => Command::SelfTest,
+        _ => return None,
+    };
+    Some(command)
+}
+
+/// Returns number given as an argument.
+///
+/// # Arguments
+/// * `text` - Decimal digits
+///
+/// # Returns
+/// * `Result<T, ConsoleError>` - Parsed number
+fn parse_number<T: core::str::FromStr>(text: &str) -> Result<T, ConsoleError> {
+    text.parse().map_err(|_| ConsoleError::BadArgument)
+}
+
+/// Returns pattern named by an argument.
+///
+/// # Arguments
+/// * `name` - Lower-case pattern name
+///
+/// # Returns
+/// * `Result<Pattern, ConsoleError>` - Named pattern
+fn parse_pattern(name: &str) -> Result<Pattern, ConsoleError> {
+    match name {
+        "chase" => Ok(Pattern::Chase),
+        "bounce" => Ok(Pattern::Bounce),
+        "fill" => Ok(Pattern::Fill),
+        "breathe" => Ok(Pattern::Breathe),
+        "blink" => Ok(Pattern::Blink),
+        "alternate" => Ok(Pattern::Alternate),
+        "solid" => Ok(Pattern::Solid),
+        "blank" => Ok(Pattern::Blank),
+        _ => Err(ConsoleError::BadArgument),
+    }
+}
+
+/// Returns effect named by an argument.
+///
+/// # Arguments
+/// * `name` - twinkle, walk or candle
+///
+/// # Returns
+/// * `Result<EffectKind, ConsoleError>` - Named effect
+fn parse_effect(name: &str) -> Result<EffectKind, ConsoleError> {
+    match name {
+        "twinkle" => Ok(EffectKind::Twinkle),
+        "walk" => Ok(EffectKind::RandomWalk),
+        "candle" => Ok(EffectKind::Candle),
+        _ => Err(ConsoleError::BadArgument),
+    }
+}
+
+/// Returns bar graph command given as an argument.
+///
+/// # Arguments
+/// * `argument` - Value in tenths of a percent, or a fill direction
+///
+/// # Returns
+/// * `Result<Command, ConsoleError>` - Bar graph command
+fn parse_bar(argument: &str) -> Result<Command, ConsoleError> {
+    match argument {
+        "forward" => Ok(Command::SetBarDirection(FillDirection::Forward)),
+        "reverse" => Ok(Command::SetBarDirection(FillDirection::Reverse)),
+        "centre" => Ok(Command::SetBarDirection(FillDirection::CentreOut)),
+        _ => parse_number(argument).map(Command::SetBar),
+    }
+}
+
+/// Returns time of day given as an argument.
+///
+/// # Arguments
+/// * `text` - Time as HH:MM or HH:MM:SS on a 24-hour clock
+///
+/// # Returns
+/// * `Result<TimeOfDay, ConsoleError>` - Parsed time of day
+fn parse_time(text: &str) -> Result<TimeOfDay, ConsoleError> {
+    let mut fields = text.split(':');
+    let (Some(hours), Some(minutes), seconds, None) =
+        (fields.next(), fields.next(), fields.next(), fields.next())
+    else {
+        return Err(ConsoleError::BadArgument);
+    };
+    let seconds = match seconds {
+        Some(seconds) => parse_number(seconds)?,
+        None => 0,
+    };
+    TimeOfDay::new(parse_number(hours)?, parse_number(minutes)?, seconds)
+        .ok_or(ConsoleError::BadArgument)
+}
+
+/// Returns clock encoding named by an argument.
+///
+/// # Arguments
+/// * `name` - binary or bcd
+///
+/// # Returns
+/// * `Result<ClockEncoding, ConsoleError>` - Named encoding
+fn parse_clock(name: &str) -> Result<ClockEncoding, ConsoleError> {
+    match name {
+        "binary" => Ok(ClockEncoding::Binary),
+        "bcd" => Ok(ClockEncoding::Bcd),
+        _ => Err(ConsoleError::BadArgument),
+    }
+}
+
+/// Returns timeline command given as an argument.
+///
+/// # Details
+/// The sequencer ignores these until a timeline is loaded.
+///
+/// # Arguments
+/// * `argument` - play, seek MS, once, loop or pingpong
+///
+/// # Returns
+/// * `Result<Command, ConsoleError>` - Timeline command
+fn parse_timeline(argument: &str) -> Result<Command, ConsoleError> {
+    let (word, position) = match argument.split_once(' ') {
+        Some((word, position)) => (word, position.trim()),
+        None => (argument, ""),
+    };
+    match word {
+        "seek" => parse_number(position).map(Command::SeekTimeline),
+        _ if !position.is_empty() => Err(ConsoleError::BadArgument),
+        "play" => Ok(Command::PlayTimeline),
+        "once" => Ok(Command::SetPlayback(Playback::Once)),
+        "loop" => Ok(Command::SetPlayback(Playback::Loop)),
+        "pingpong" => Ok(Command::SetPlayback(Playback::PingPong)),
+        _ => Err(ConsoleError::BadArgument),
+    }
+}
+
+/// Returns statistics report request named by an argument.
+///
+/// # Arguments
+/// * `name` - Statistics to report
+///
+/// # Returns
+/// * `Result<Command, ConsoleError>` - Report command
+fn parse_report(name: &str) -> Result<Command, ConsoleError> {
+    match name {
+        "usage" => Ok(Command::ReportUsage),
+        "jitter" => Ok(Command::ReportJitter),
+        "power" => Ok(Command::ReportPower),
+        "idle" => Ok(Command::ReportIdle),
+        _ => Err(ConsoleError::BadArgument),
+    }
+}
+
+/// Returns statistics reset named by an argument.
+///
+/// # Arguments
+/// * `name` - Statistics to clear
+///
+/// # Returns
+/// * `Result<Command, ConsoleError>` - Reset command
+fn parse_clear(name: &str) -> Result<Command, ConsoleError> {
+    match name {
+        "usage" => Ok(Command::ResetUsage),
+        "jitter" => Ok(Command::ResetJitter),
+        "power" => Ok(Command::ResetPower),
+        "idle" => Ok(Command::ResetIdle),
+        _ => Err(ConsoleError::BadArgument),
+    }
+}
+
+/// Returns Morse message given as an argument.
+///
+/// # Details
+/// A leading number followed by text picks a single LED, so a
+/// lone number is sent as text on every LED.
+///
+/// # Arguments
+/// * `argument` - Optional LED index and message text
+///
+/// # Returns
+/// * `Result<MorseMessage, ConsoleError>` - Message and its target
+fn parse_morse(argument: &str) -> Result<MorseMessage, ConsoleError> {
+    let (target, text) = match argument.split_once(' ') {
+        Some((index, text)) if index.bytes().all(|b| b.is_ascii_digit()) => {
+            let index: usize = parse_number(index)?;
+            if index >= LED_COUNT {
+                return Err(ConsoleError::BadArgument);
+            }
+            (MorseTarget::Led(index), text.trim())
+        }
+        _ => (MorseTarget::All, argument),
+    };
+    if text.is_empty() {
+        return Err(ConsoleError::BadArgument);
+    }
+    MorseMessage::new(text, target).map_err(|_| ConsoleError::BadArgument)
+}
+
+/// Returns counter given as an argument.
+///
+/// # Details
+/// Without a range the counter runs over every value the LEDs
+/// can show.
+///
+/// # Arguments
+/// * `argument` - Encoding, then an optional start and end
+///
+/// # Returns
+/// * `Result<Counter, ConsoleError>` - Counter at its start
+fn parse_counter(argument: &str) -> Result<Counter, ConsoleError> {
+    let mut words = argument.split_whitespace();
+    let encoding = match words.next() {
+        Some("binary") => CounterEncoding::Binary,
+        Some("gray") => CounterEncoding::Gray,
+        _ => return Err(ConsoleError::BadArgument),
+    };
+    let counter = match (words.next(), words.next(), words.next()) {
+        (None, _, _) => Counter::full_range(),
+        (Some(start), Some(end), None) => Counter::new(parse_number(start)?, parse_number(end)?)
+            .map_err(|_| ConsoleError::BadArgument)?,
+        _ => return Err(ConsoleError::BadArgument),
+    };
+    Ok(counter.with_encoding(encoding))
+}
+
+/// Console line collector.
+///
+/// # Details
+/// Either CR or LF ends a line, so CRLF line endings work and
+/// blank lines are skipped. A line too long for the buffer is
+/// dropped whole and reported once it ends.
+///
+/// # Fields
+/// * `line` - Bytes received on the current line
+/// * `len` - Bytes in use
+/// * `overflow` - Whether the current line outgrew the buffer
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct LineReader {
+    line: [u8; CONSOLE_LINE_LEN],
+    len: usize,
+    overflow: bool,
+}
+
+impl Default for LineReader {
+    /// Returns default LineReader instance.
+    ///
+    /// # Details
+    /// Delegates to new() for initialization.
+    ///
+    /// # Returns
+    /// * `Self` - Reader with an empty line
+    #[allow(dead_code)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LineReader {
+    /// Creates new empty reader.
+    ///
+    /// # Returns
+    /// * `Self` - New LineReader instance
+    #[allow(dead_code)]
+    pub fn new() -> Self {
+        Self {
+            line: [0; CONSOLE_LINE_LEN],
+            len: 0,
+            overflow: false,
+        }
+    }
+
+    /// Feeds one received byte.
+    ///
+    /// # Arguments
+    /// * `byte` - Received byte
+    ///
+    /// # Returns
+    /// * `Option<Result<Command, ConsoleError>>` - Line ended by this byte, if any
+    #[allow(dead_code)]
+    pub fn push(&mut self, byte: u8) -> Option<Result<Command, ConsoleError>> {
+        if byte != b'\r' && byte != b'\n' {
+            if self.len == CONSOLE_LINE_LEN {
+                self.overflow = true;
+            } else {
+                self.line[self.len] = byte;
+                self.len += 1;
+            }
+            return None;
+        }
+        let len = core::mem::take(&mut self.len);
+        if core::mem::take(&mut self.overflow) {
+            return Some(Err(ConsoleError::TooLong));
+        }
+        if len == 0 {
+            return None;
+        }
+        let line = core::str::from_utf8(&self.line[..len]).map_err(|_| ConsoleError::Unknown);
+        Some(line.and_then(parse_line))
+    }
+}
+
+/// Console report enumeration.
+///
+/// # Variants
+/// * `Status` - Sequencer status change
+/// * `Usage` - Per-LED usage statistics
+/// * `Jitter` - Step timing histogram
+/// * `Power` - Power limiter statistics
+/// * `Idle` - Idle time and battery estimate
+/// * `Error` - Console line that could not be applied
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub enum Report {
+    Status(Status),
+    Usage(UsageStats),
+    Jitter(JitterHistogram),
+    Power(PowerStats),
+    Idle(IdleReport),
+    Error(ConsoleError),
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Status(status) => {
+                write!(
+                    f,
+                    "status {:?} {:?} step {} delay {} ms",
+                    status.mode, status.pattern, status.step, status.delay_ms
+                )?;
+                if status.paused {
+                    f.write_str(" paused")?;
+                }
+                if status.power_limited {
+                    f.write_str(" limited")?;
+                }
+                if status.self_test.is_some() {
+                    f.write_str(" testing")?;
+                }
+                let faulty = status.health.faulty_mask();
+                if faulty != 0 {
+                    write!(f, " faulty {:#06b}", faulty)?;
+                }
+                Ok(())
+            }
+            Self::Usage(usage) => {
+                write!(
+                    f,
+                    "usage {} ms balance {}",
+                    usage.total_ms(),
+                    usage.balance_permille()
+                )?;
+                for index in 0..LED_COUNT {
+                    let led = usage.led(index);
+                    write!(
+                        f,
+                        " led{} on {} ms switches {} duty {}",
+                        index,
+                        led.on_ms,
+                        led.switches,
+                        usage.duty_permille(index)
+                    )?;
+                }
+                Ok(())
+            }
+            Self::Jitter(jitter) => {
+                f.write_str("jitter min ")?;
+                write_optional(f, jitter.min_us())?;
+                f.write_str(" max ")?;
+                write_optional(f, jitter.max_us())?;
+                f.write_str(" mean ")?;
+                write_optional(f, jitter.mean_us())?;
+                f.write_str(" p99 ")?;
+                write_optional(f, jitter.p99_us())?;
+                write!(f, " us overruns {}", jitter.overruns())
+            }
+            Self::Power(power) => write!(
+                f,
+                "power frames {} scaled {} refused {} peak {} mA",
+                power.frames, power.scaled, power.refused, power.peak_ma
+            ),
+            Self::Idle(idle) => {
+                write!(
+                    f,
+                    "idle {} permille average {} uA battery ",
+                    idle.idle_permille, idle.average_ua
+                )?;
+                write_optional(f, idle.battery_hours)?;
+                f.write_str(" h")
+            }
+            Self::Error(error) => write!(f, "error {:?}", error),
+        }
+    }
+}
+
+/// Returns whether a status is worth writing to the console.
+///
+/// # Details
+/// Every step publishes a status, so a status that differs from
+/// the last one written only in its step is skipped.
+///
+/// # Arguments
+/// * `last` - Status written before, if any
+/// * `status` - New status
+///
+/// # Returns
+/// * `bool` - true if anything but the step changed
+#[allow(dead_code)]
+pub fn status_worth_reporting(last: Option<&Status>, status: &Status) -> bool {
+    last.is_none_or(|last| {
+        Status {
+            step: status.step,
+            ..*last
+        } != *status
+    })
+}
+
+/// Writes a value, or a dash when there is none.
+///
+/// # Arguments
+/// * `f` - Formatter to write to
+/// * `value` - Value to write, if any
+///
+/// # Returns
+/// * `fmt::Result` - Formatter result
+fn write_optional<T: fmt::Display>(f: &mut fmt::Formatter<'_>, value: Option<T>) -> fmt::Result {
+    match value {
+        Some(value) => write!(f, "{}", value),
+        None => f.write_str("-"),
+    }
+}
+
+/// One formatted report line.
+///
+/// # Details
+/// Ends in CRLF. Text that does not fit is cut short so the line
+/// ending always fits.
+///
+/// # Fields
+/// * `bytes` - Line text
+/// * `len` - Bytes in use
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct ReportLine {
+    bytes: [u8; REPORT_LINE_LEN],
+    len: usize,
+}
+
+impl ReportLine {
+    /// Formats a report into a line.
+    ///
+    /// # Arguments
+    /// * `report` - Report to format
+    ///
+    /// # Returns
+    /// * `Self` - Formatted line
+    #[allow(dead_code)]
+    pub fn new(report: &Report) -> Self {
+        let mut line = Self {
+            bytes: [0; REPORT_LINE_LEN],
+            len: 0,
+        };
+        let _ = write!(line, "{}", report);
+        line.bytes[line.len..line.len + 2].copy_from_slice(b"\r\n");
+        line.len += 2;
+        line
+    }
+
+    /// Returns line bytes.
+    ///
+    /// # Returns
+    /// * `&[u8]` - Text and line ending
+    #[allow(dead_code)]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes[..self.len]
+    }
+}
+
+impl Write for ReportLine {
+    fn write_str(&mut self, text: &str) -> fmt::Result {
+        let room = REPORT_LINE_LEN - 2 - self.len;
+        let take = text.len().min(room);
+        self.bytes[self.len..self.len + take].copy_from_slice(&text.as_bytes()[..take]);
+        self.len += take;
+        if take < text.len() {
+            return Err(fmt::Error);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ClockSource;
+    use crate::compositor::Compositor;
+    use crate::config::{MAX_BRIGHTNESS, MORSE_MESSAGE_LEN};
+    use crate::frame::Frame;
+    use crate::health::{HealthReport, LedHealth, TestStep};
+    use crate::idle::IdleBudget;
+    use crate::playlist::{EntryLength, Playlist, PlaylistEntry};
+    use crate::schedule::{Schedule, ScheduleEntry};
+    use crate::sequencer::{Mode, Sequencer};
+
+    fn line(report: &Report) -> String {
+        let line = ReportLine::new(report);
+        String::from_utf8(line.as_bytes().to_vec()).unwrap()
+    }
+
+    fn status() -> Status {
+        Status {
+            mode: Mode::Manual,
+            paused: false,
+            clock: ClockSource::Internal,
+            pattern: Pattern::Chase,
+            step: 2,
+            delay_ms: 100,
+            power_limited: false,
+            health: HealthReport::new(),
+            self_test: None,
+        }
+    }
+
+    // ==================== parse_line() Tests ====================
+
+    #[test]
+    fn test_parse_words() {
+        assert_eq!(parse_line("pause"), Ok(Command::Pause));
+        assert_eq!(parse_line("resume"), Ok(Command::Resume));
+        assert_eq!(parse_line("step"), Ok(Command::Step));
+        assert_eq!(parse_line("playlist"), Ok(Command::RunPlaylist));
+        assert_eq!(parse_line("selftest"), Ok(Command::SelfTest));
+    }
+
+    #[test]
+    fn test_parse_stepping_words() {
+        assert_eq!(parse_line("back"), Ok(Command::StepBack));
+        assert_eq!(parse_line("reset"), Ok(Command::Reset));
+        assert_eq!(
+            parse_line("forward"),
+            Ok(Command::SetDirection(Direction::Forward))
+        );
+        assert_eq!(
+            parse_line("reverse"),
+            Ok(Command::SetDirection(Direction::Reverse))
+        );
+    }
+
+    #[test]
+    fn test_parse_trims_spaces() {
+        assert_eq!(parse_line("  jump   3 "), Ok(Command::Jump(3)));
+    }
+
+    #[test]
+    fn test_parse_numbers() {
+        assert_eq!(parse_line("jump 5"), Ok(Command::Jump(5)));
+        assert_eq!(parse_line("delay 250"), Ok(Command::SetDelay(250)));
+    }
+
+    #[test]
+    fn test_parse_bad_number() {
+        assert_eq!(parse_line("jump"), Err(ConsoleError::BadArgument));
+        assert_eq!(parse_line("delay -1"), Err(ConsoleError::BadArgument));
+    }
+
+    #[test]
+    fn test_parse_pattern() {
+        assert_eq!(
+            parse_line("pattern bounce"),
+            Ok(Command::SetPattern(Pattern::Bounce))
+        );
+        assert_eq!(parse_line("pattern zigzag"), Err(ConsoleError::BadArgument));
+    }
+
+    #[test]
+    fn test_parse_effect() {
+        assert_eq!(
+            parse_line("effect twinkle"),
+            Ok(Command::ShowEffect(EffectKind::Twinkle))
+        );
+        assert_eq!(
+            parse_line("effect walk"),
+            Ok(Command::ShowEffect(EffectKind::RandomWalk))
+        );
+        assert_eq!(
+            parse_line("effect candle"),
+            Ok(Command::ShowEffect(EffectKind::Candle))
+        );
+        assert_eq!(parse_line("effect fire"), Err(ConsoleError::BadArgument));
+    }
+
+    #[test]
+    fn test_parse_bar_value() {
+        assert_eq!(parse_line("bar 750"), Ok(Command::SetBar(750)));
+        assert_eq!(parse_line("bar"), Err(ConsoleError::BadArgument));
+        assert_eq!(parse_line("bar 70000"), Err(ConsoleError::BadArgument));
+    }
+
+    #[test]
+    fn test_parse_bar_direction() {
+        assert_eq!(
+            parse_line("bar forward"),
+            Ok(Command::SetBarDirection(FillDirection::Forward))
+        );
+        assert_eq!(
+            parse_line("bar reverse"),
+            Ok(Command::SetBarDirection(FillDirection::Reverse))
+        );
+        assert_eq!(
+            parse_line("bar centre"),
+            Ok(Command::SetBarDirection(FillDirection::CentreOut))
+        );
+        assert_eq!(parse_line("bar sideways"), Err(ConsoleError::BadArgument));
+    }
+
+    #[test]
+    fn test_parse_time() {
+        let time = TimeOfDay::new(21, 45, 30).unwrap();
+        assert_eq!(parse_line("time 21:45:30"), Ok(Command::SetTime(time)));
+    }
+
+    #[test]
+    fn test_parse_time_without_seconds() {
+        assert_eq!(
+            parse_line("time 7:30"),
+            Ok(Command::SetTime(TimeOfDay::hm(7, 30)))
+        );
+    }
+
+    const ENTRIES: [PlaylistEntry; 1] = [PlaylistEntry::new(
+        Pattern::Chase,
+        100,
+        EntryLength::Repeats(2),
+    )];
+
+    const NIGHT: [ScheduleEntry; 1] = [ScheduleEntry::new(
+        TimeOfDay::hm(22, 0),
+        TimeOfDay::hm(7, 0),
+        Pattern::Breathe,
+        32,
+        1000,
+    )];
+
+    #[test]
+    fn test_time_line_starts_schedule() {
+        let mut sequencer = Sequencer::new(Compositor::new(), Playlist::new(&ENTRIES).unwrap())
+            .with_schedule(Schedule::new(&NIGHT).unwrap());
+        sequencer.tick(10);
+        assert_eq!(sequencer.scheduled(), None);
+        sequencer.apply(parse_line("time 23:15").unwrap());
+        sequencer.tick(10);
+        assert_eq!(sequencer.scheduled(), Some(0));
+        assert_eq!(sequencer.status().pattern, Pattern::Breathe);
+    }
+
+    #[test]
+    fn test_parse_time_rejects_bad_times() {
+        assert_eq!(parse_line("time"), Err(ConsoleError::BadArgument));
+        assert_eq!(parse_line("time 24:00:00"), Err(ConsoleError::BadArgument));
+        assert_eq!(parse_line("time 12:60:00"), Err(ConsoleError::BadArgument));
+        assert_eq!(
+            parse_line("time 12:00:00:00"),
+            Err(ConsoleError::BadArgument)
+        );
+        assert_eq!(parse_line("time noon"), Err(ConsoleError::BadArgument));
+        assert_eq!(parse_line("time 12"), Err(ConsoleError::BadArgument));
+    }
+
+    #[test]
+    fn test_parse_clock() {
+        assert_eq!(
+            parse_line("clock binary"),
+            Ok(Command::ShowClock(ClockEncoding::Binary))
+        );
+        assert_eq!(
+            parse_line("clock bcd"),
+            Ok(Command::ShowClock(ClockEncoding::Bcd))
+        );
+        assert_eq!(parse_line("clock roman"), Err(ConsoleError::BadArgument));
+    }
+
+    #[test]
+    fn test_parse_timeline_play_and_seek() {
+        assert_eq!(parse_line("timeline play"), Ok(Command::PlayTimeline));
+        assert_eq!(
+            parse_line("timeline seek 1500"),
+            Ok(Command::SeekTimeline(1500))
+        );
+    }
+
+    #[test]
+    fn test_parse_timeline_playback() {
+        assert_eq!(
+            parse_line("timeline once"),
+            Ok(Command::SetPlayback(Playback::Once))
+        );
+        assert_eq!(
+            parse_line("timeline loop"),
+            Ok(Command::SetPlayback(Playback::Loop))
+        );
+        assert_eq!(
+            parse_line("timeline pingpong"),
+            Ok(Command::SetPlayback(Playback::PingPong))
+        );
+    }
+
+    #[test]
+    fn test_parse_timeline_rejects_bad_arguments() {
+        assert_eq!(parse_line("timeline"), Err(ConsoleError::BadArgument));
+        assert_eq!(parse_line("timeline seek"), Err(ConsoleError::BadArgument));
+        assert_eq!(
+            parse_line("timeline play 5"),
+            Err(ConsoleError::BadArgument)
+        );
+        assert_eq!(
+            parse_line("timeline rewind"),
+            Err(ConsoleError::BadArgument)
+        );
+    }
+
+    #[test]
+    fn test_parse_morse() {
+        let message = MorseMessage::new("SOS NOW", MorseTarget::All).unwrap();
+        assert_eq!(parse_line("morse SOS NOW"), Ok(Command::Morse(message)));
+    }
+
+    #[test]
+    fn test_parse_morse_on_one_led() {
+        let message = MorseMessage::new("SOS", MorseTarget::Led(3)).unwrap();
+        assert_eq!(parse_line("morse 3 SOS"), Ok(Command::Morse(message)));
+    }
+
+    #[test]
+    fn test_parse_morse_lone_number_is_text() {
+        let message = MorseMessage::new("73", MorseTarget::All).unwrap();
+        assert_eq!(parse_line("morse 73"), Ok(Command::Morse(message)));
+    }
+
+    #[test]
+    fn test_parse_morse_rejects_led_out_of_range() {
+        let line = format!("morse {} SOS", LED_COUNT);
+        assert_eq!(parse_line(&line), Err(ConsoleError::BadArgument));
+    }
+
+    #[test]
+    fn test_parse_morse_rejects_empty_and_long() {
+        let long = format!("morse {}", "E".repeat(MORSE_MESSAGE_LEN + 1));
+        assert_eq!(parse_line("morse"), Err(ConsoleError::BadArgument));
+        assert_eq!(parse_line(&long), Err(ConsoleError::BadArgument));
+    }
+
+    #[test]
+    fn test_parse_counter_full_range() {
+        assert_eq!(
+            parse_line("counter binary"),
+            Ok(Command::ShowCounter(Counter::full_range()))
+        );
+    }
+
+    #[test]
+    fn test_parse_counter_gray_range() {
+        let counter = Counter::new(2, 9)
+            .unwrap()
+            .with_encoding(CounterEncoding::Gray);
+        assert_eq!(
+            parse_line("counter gray 2 9"),
+            Ok(Command::ShowCounter(counter))
+        );
+    }
+
+    #[test]
+    fn test_parse_counter_rejects_bad_arguments() {
+        assert_eq!(parse_line("counter"), Err(ConsoleError::BadArgument));
+        assert_eq!(parse_line("counter octal"), Err(ConsoleError::BadArgument));
+        assert_eq!(
+            parse_line("counter binary 2"),
+            Err(ConsoleError::BadArgument)
+        );
+        assert_eq!(
+            parse_line("counter binary 9 2"),
+            Err(ConsoleError::BadArgument)
+        );
+        assert_eq!(
+            parse_line("counter binary 0 99"),
+            Err(ConsoleError::BadArgument)
+        );
+        assert_eq!(
+            parse_line("counter binary 0 1 2"),
+            Err(ConsoleError::BadArgument)
+        );
+    }
+
+    #[test]
+    fn test_parse_report_and_clear() {
+        assert_eq!(parse_line("report power"), Ok(Command::ReportPower));
+        assert_eq!(parse_line("clear power"), Ok(Command::ResetPower));
+        assert_eq!(parse_line("report usage"), Ok(Command::ReportUsage));
+        assert_eq!(parse_line("clear usage"), Ok(Command::ResetUsage));
+        assert_eq!(parse_line("report jitter"), Ok(Command::ReportJitter));
+        assert_eq!(parse_line("clear jitter"), Ok(Command::ResetJitter));
+        assert_eq!(parse_line("report idle"), Ok(Command::ReportIdle));
+        assert_eq!(parse_line("clear idle"), Ok(Command::ResetIdle));
+        assert_eq!(parse_line("report"), Err(ConsoleError::BadArgument));
+        assert_eq!(parse_line("clear heat"), Err(ConsoleError::BadArgument));
+    }
+
+    #[test]
+    fn test_parse_extra_argument() {
+        assert_eq!(parse_line("pause now"), Err(ConsoleError::BadArgument));
+    }
+
+    #[test]
+    fn test_parse_unknown_and_empty() {
+        assert_eq!(parse_line("dance"), Err(ConsoleError::Unknown));
+        assert_eq!(parse_line("   "), Err(ConsoleError::Empty));
+    }
+
+    // ==================== LineReader Tests ====================
+
+    fn feed(reader: &mut LineReader, text: &[u8]) -> Option<Result<Command, ConsoleError>> {
+        let mut last = None;
+        for &byte in text {
+            if let Some(result) = reader.push(byte) {
+                last = Some(result);
+            }
+        }
+        last
+    }
+
+    #[test]
+    fn test_reader_line_feed() {
+        let mut reader = LineReader::new();
+        assert_eq!(feed(&mut reader, b"step\n"), Some(Ok(Command::Step)));
+    }
+
+    #[test]
+    fn test_reader_crlf_once() {
+        let mut reader = LineReader::new();
+        assert_eq!(reader.push(b'x'), None);
+        assert_eq!(reader.push(b'\r'), Some(Err(ConsoleError::Unknown)));
+        assert_eq!(reader.push(b'\n'), None);
+    }
+
+    #[test]
+    fn test_reader_blank_lines_skipped() {
+        let mut reader = LineReader::new();
+        assert_eq!(feed(&mut reader, b"\r\n\r\n"), None);
+    }
+
+    #[test]
+    fn test_reader_too_long() {
+        let mut reader = LineReader::new();
+        let long = [b'a'; CONSOLE_LINE_LEN + 1];
+        assert_eq!(feed(&mut reader, &long), None);
+        assert_eq!(reader.push(b'\n'), Some(Err(ConsoleError::TooLong)));
+        assert_eq!(feed(&mut reader, b"pause\n"), Some(Ok(Command::Pause)));
+    }
+
+    #[test]
+    fn test_reader_full_line_fits() {
+        let mut reader = LineReader::new();
+        let mut text = [b' '; CONSOLE_LINE_LEN];
+        text[..4].copy_from_slice(b"step");
+        assert_eq!(feed(&mut reader, &text), None);
+        assert_eq!(reader.push(b'\n'), Some(Ok(Command::Step)));
+    }
+
+    #[test]
+    fn test_reader_invalid_utf8() {
+        let mut reader = LineReader::new();
+        assert_eq!(
+            feed(&mut reader, &[0xFF, b'\n']),
+            Some(Err(ConsoleError::Unknown))
+        );
+    }
+
+    // ==================== Report Tests ====================
+
+    #[test]
+    fn test_report_status() {
+        assert_eq!(
+            line(&Report::Status(status())),
+            "status Manual Chase step 2 delay 100 ms\r\n"
+        );
+    }
+
+    #[test]
+    fn test_report_status_flags() {
+        let mut status = status();
+        status.paused = true;
+        status.power_limited = true;
+        assert_eq!(
+            line(&Report::Status(status)),
+            "status Manual Chase step 2 delay 100 ms paused limited\r\n"
+        );
+    }
+
+    #[test]
+    fn test_report_status_self_test() {
+        let mut status = status();
+        status.self_test = Some(TestStep::Led(1));
+        status.health.set(0, LedHealth::Open);
+        assert_eq!(
+            line(&Report::Status(status)),
+            "status Manual Chase step 2 delay 100 ms testing faulty 0b0001\r\n"
+        );
+    }
+
+    #[test]
+    fn test_report_usage() {
+        let mut usage = UsageStats::new();
+        usage.record(&Frame::from_levels([255, 0, 0, 0]), 1000);
+        usage.record(&Frame::from_levels([0, 0, 0, 0]), 500);
+        usage.record(&Frame::from_levels([255, 0, 0, 0]), 500);
+        assert_eq!(
+            line(&Report::Usage(usage)),
+            "usage 2000 ms balance 0 \
+             led0 on 1500 ms switches 2 duty 750 \
+             led1 on 0 ms switches 0 duty 0 \
+             led2 on 0 ms switches 0 duty 0 \
+             led3 on 0 ms switches 0 duty 0\r\n"
+        );
+    }
+
+    #[test]
+    fn test_report_usage_largest_totals_fit() {
+        let mut usage = UsageStats::new();
+        for _ in 0..=MAX_BRIGHTNESS {
+            usage.record(&Frame::filled(MAX_BRIGHTNESS), u64::MAX / 255);
+        }
+        let last = format!(
+            " led{} on {} ms switches 1 duty {}\r\n",
+            LED_COUNT - 1,
+            u64::MAX,
+            usage.duty_permille(LED_COUNT - 1)
+        );
+        assert!(line(&Report::Usage(usage)).ends_with(&last));
+    }
+
+    #[test]
+    fn test_report_empty_jitter() {
+        let jitter = JitterHistogram::new(10_000);
+        assert_eq!(
+            line(&Report::Jitter(jitter)),
+            "jitter min - max - mean - p99 - us overruns 0\r\n"
+        );
+    }
+
+    #[test]
+    fn test_report_jitter() {
+        let mut jitter = JitterHistogram::new(10_000);
+        jitter.record(100);
+        jitter.record(300);
+        assert_eq!(
+            line(&Report::Jitter(jitter)),
+            "jitter min 100 max 300 mean 200 p99 300 us overruns 0\r\n"
+        );
+    }
+
+    #[test]
+    fn test_report_power() {
+        let power = PowerStats {
+            frames: 10,
+            scaled: 2,
+            refused: 1,
+            peak_ma: 80,
+        };
+        assert_eq!(
+            line(&Report::Power(power)),
+            "power frames 10 scaled 2 refused 1 peak 80 mA\r\n"
+        );
+    }
+
+    #[test]
+    fn test_report_idle() {
+        let mut budget = IdleBudget::new();
+        budget.record(100, 900);
+        assert_eq!(
+            line(&Report::Idle(budget.report(20, 2, 2000))),
+            "idle 900 permille average 3800 uA battery 526 h\r\n"
+        );
+        assert_eq!(
+            line(&Report::Idle(IdleBudget::new().report(20, 2, 2000))),
+            "idle 0 permille average 0 uA battery - h\r\n"
+        );
+    }
+
+    #[test]
+    fn test_report_error() {
+        assert_eq!(
+            line(&Report::Error(ConsoleError::TooLong)),
+            "error TooLong\r\n"
+        );
+    }
+
+    // ==================== status_worth_reporting() Tests ====================
+
+    #[test]
+    fn test_first_status_reported() {
+        assert!(status_worth_reporting(None, &status()));
+    }
+
+    #[test]
+    fn test_step_only_change_skipped() {
+        let mut next = status();
+        next.step = 3;
+        assert!(!status_worth_reporting(Some(&status()), &next));
+    }
+
+    #[test]
+    fn test_other_change_reported() {
+        let mut next = status();
+        next.step = 3;
+        next.paused = true;
+        assert!(status_worth_reporting(Some(&status()), &next));
+    }
+
+    // ==================== ReportLine Tests ====================
+
+    #[test]
+    fn test_report_line_truncates_before_line_ending() {
+        let mut line = ReportLine::new(&Report::Error(ConsoleError::Empty));
+        let long = "x".repeat(REPORT_LINE_LEN);
+        line.len = 0;
+        assert!(line.write_str(&long).is_err());
+        assert_eq!(line.len, REPORT_LINE_LEN - 2);
+    }
+}
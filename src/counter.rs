@@ -0,0 +1,551 @@
+/*
+ * @file counter.rs
+ * @brief Binary and Gray-code counter display
+ * @author Kevin Thomas
+ * @date 2025
+ *
+ * MIT License
+ *
+ * Copyright (c) 2025 Kevin Thomas
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+//! FILE: counter.rs
+//!
+//! DESCRIPTION:
+//! Binary and Gray-Code Counter Display for RP2350.
+//!
+//! BRIEF:
+//! Shows an N-bit counter value on the LEDs, one bit per LED.
+//! Supports start, end, step, direction and Gray-code output.
+//!
+//! AUTHOR: Kevin Thomas
+//! CREATION DATE: October 18, 2026
+//! UPDATE DATE: October 18, 2026
+
+use crate::config::{LED_COUNT, MAX_BRIGHTNESS};
+use crate::frame::Frame;
+
+/// Counter display encoding enumeration.
+///
+/// # Variants
+/// * `Binary` - Plain binary value
+/// * `Gray` - Reflected Gray code, one bit changes per increment
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[allow(dead_code)]
+pub enum CounterEncoding {
+    Binary,
+    Gray,
+}
+
+/// Counter direction enumeration.
+///
+/// # Variants
+/// * `Up` - Count from start toward end
+/// * `Down` - Count from end toward start
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[allow(dead_code)]
+pub enum CountDirection {
+    Up,
+    Down,
+}
+
+/// Counter error enumeration.
+///
+/// # Variants
+/// * `InvalidRange` - Start is greater than end
+/// * `RangeTooWide` - End does not fit in LED_COUNT bits
+/// * `ZeroStep` - Step is zero
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[allow(dead_code)]
+pub enum CounterError {
+    InvalidRange,
+    RangeTooWide,
+    ZeroStep,
+}
+
+/// Converts a binary value to reflected Gray code.
+///
+/// # Arguments
+/// * `value` - Binary value
+///
+/// # Returns
+/// * `u32` - Gray-coded value
+#[allow(dead_code)]
+pub fn gray_encode(value: u32) -> u32 {
+    value ^ (value >> 1)
+}
+
+/// Converts a reflected Gray code back to binary.
+///
+/// # Arguments
+/// * `gray` - Gray-coded value
+///
+/// # Returns
+/// * `u32` - Binary value
+#[allow(dead_code)]
+pub fn gray_decode(gray: u32) -> u32 {
+    let mut value = gray;
+    let mut shift = gray >> 1;
+    while shift != 0 {
+        value ^= shift;
+        shift >>= 1;
+    }
+    value
+}
+
+/// Returns frame showing a value as bits.
+///
+/// # Details
+/// LED 0 shows the least significant bit.
+///
+/// # Arguments
+/// * `bits` - Value to display
+///
+/// # Returns
+/// * `Frame` - LEDs lit for set bits
+#[allow(dead_code)]
+pub fn bits_frame(bits: u32) -> Frame {
+    let mut frame = Frame::new();
+    for index in 0..LED_COUNT.min(32) {
+        if bits & (1 << index) != 0 {
+            frame.set_level(index, MAX_BRIGHTNESS);
+        }
+    }
+    frame
+}
+
+/// Step counter shown on the LED row.
+///
+/// # Details
+/// Wraps to the opposite end of the range when a step would
+/// leave it. With a step of 1 over the full 2^LED_COUNT range,
+/// Gray encoding changes exactly one LED per step, wrap included.
+///
+/// # Fields
+/// * `value` - Current counter value
+/// * `start` - Lowest value of the range
+/// * `end` - Highest value of the range
+/// * `step` - Amount added or removed per step
+/// * `direction` - Count direction
+/// * `encoding` - Display encoding
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[allow(dead_code)]
+pub struct Counter {
+    value: u32,
+    start: u32,
+    end: u32,
+    step: u32,
+    direction: CountDirection,
+    encoding: CounterEncoding,
+}
+
+impl Counter {
+    /// Creates new binary up-counter over a range.
+    ///
+    /// # Arguments
+    /// * `start` - Lowest value of the range
+    /// * `end` - Highest value of the range
+    ///
+    /// # Returns
+    /// * `Result<Self, CounterError>` - Counter at start
+    #[allow(dead_code)]
+    pub fn new(start: u32, end: u32) -> Result<Self, CounterError> {
+        if start > end {
+            return Err(CounterError::InvalidRange);
+        }
+        if LED_COUNT < 32 && end >= 1 << LED_COUNT {
+            return Err(CounterError::RangeTooWide);
+        }
+        Ok(Self {
+            value: start,
+            start,
+            end,
+            step: 1,
+            direction: CountDirection::Up,
+            encoding: CounterEncoding::Binary,
+        })
+    }
+
+    /// Creates counter over every value the LEDs can show.
+    ///
+    /// # Returns
+    /// * `Self` - Binary up-counter from 0 to 2^LED_COUNT - 1
+    #[allow(dead_code)]
+    pub fn full_range() -> Self {
+        let end = if LED_COUNT >= 32 {
+            u32::MAX
+        } else {
+            (1 << LED_COUNT) - 1
+        };
+        Self {
+            value: 0,
+            start: 0,
+            end,
+            step: 1,
+            direction: CountDirection::Up,
+            encoding: CounterEncoding::Binary,
+        }
+    }
+
+    /// Returns counter with given step.
+    ///
+    /// # Arguments
+    /// * `step` - Amount added or removed per step
+    ///
+    /// # Returns
+    /// * `Result<Self, CounterError>` - Updated counter
+    #[allow(dead_code)]
+    pub fn with_step(mut self, step: u32) -> Result<Self, CounterError> {
+        if step == 0 {
+            return Err(CounterError::ZeroStep);
+        }
+        self.step = step;
+        Ok(self)
+    }
+
+    /// Returns counter with given direction.
+    ///
+    /// # Details
+    /// Down-counters restart from the end of the range.
+    ///
+    /// # Arguments
+    /// * `direction` - Count direction
+    ///
+    /// # Returns
+    /// * `Self` - Updated counter
+    #[allow(dead_code)]
+    pub fn with_direction(mut self, direction: CountDirection) -> Self {
+        self.direction = direction;
+        self.reset();
+        self
+    }
+
+    /// Returns counter with given encoding.
+    ///
+    /// # Arguments
+    /// * `encoding` - Display encoding
+    ///
+    /// # Returns
+    /// * `Self` - Updated counter
+    #[allow(dead_code)]
+    pub fn with_encoding(mut self, encoding: CounterEncoding) -> Self {
+        self.encoding = encoding;
+        self
+    }
+
+    /// Returns current counter value.
+    ///
+    /// # Returns
+    /// * `u32` - Value before encoding
+    #[allow(dead_code)]
+    pub fn value(&self) -> u32 {
+        self.value
+    }
+
+    /// Returns value as shown on the LEDs.
+    ///
+    /// # Returns
+    /// * `u32` - Value after encoding
+    #[allow(dead_code)]
+    pub fn display_bits(&self) -> u32 {
+        match self.encoding {
+            CounterEncoding::Binary => self.value,
+            CounterEncoding::Gray => gray_encode(self.value),
+        }
+    }
+
+    /// Returns counter to its first value.
+    ///
+    /// # Details
+    /// Start for up-counters, end for down-counters.
+    #[allow(dead_code)]
+    pub fn reset(&mut self) {
+        self.value = match self.direction {
+            CountDirection::Up => self.start,
+            CountDirection::Down => self.end,
+        };
+    }
+
+    /// Advances counter by one step and returns new value.
+    ///
+    /// # Returns
+    /// * `u32` - New counter value
+    #[allow(dead_code)]
+    pub fn advance(&mut self) -> u32 {
+        self.value = self.moved(self.direction);
+        self.value
+    }
+
+    /// Moves counter back by one step and returns new value.
+    ///
+    /// # Details
+    /// Counts against the direction, wrapping to the other end
+    /// of the range the same way advance does.
+    ///
+    /// # Returns
+    /// * `u32` - New counter value
+    #[allow(dead_code)]
+    pub fn retreat(&mut self) -> u32 {
+        let direction = match self.direction {
+            CountDirection::Up => CountDirection::Down,
+            CountDirection::Down => CountDirection::Up,
+        };
+        self.value = self.moved(direction);
+        self.value
+    }
+
+    /// Returns value one step away in a direction.
+    ///
+    /// # Arguments
+    /// * `direction` - Direction to count
+    ///
+    /// # Returns
+    /// * `u32` - Next value, wrapped into the range
+    fn moved(&self, direction: CountDirection) -> u32 {
+        match direction {
+            CountDirection::Up => match self.value.checked_add(self.step) {
+                Some(next) if next <= self.end => next,
+                _ => self.start,
+            },
+            CountDirection::Down => match self.value.checked_sub(self.step) {
+                Some(next) if next >= self.start => next,
+                _ => self.end,
+            },
+        }
+    }
+
+    /// Returns frame for the current value.
+    ///
+    /// # Returns
+    /// * `Frame` - LEDs lit for set bits
+    #[allow(dead_code)]
+    pub fn frame(&self) -> Frame {
+        bits_frame(self.display_bits())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // ==================== Gray Code Tests ====================
+
+    #[test]
+    fn test_gray_encode_known_values() {
+        let expected = [0, 1, 3, 2, 6, 7, 5, 4];
+        for (value, gray) in expected.iter().enumerate() {
+            assert_eq!(gray_encode(value as u32), *gray);
+        }
+    }
+
+    #[test]
+    fn test_gray_round_trip() {
+        for value in 0..1024 {
+            assert_eq!(gray_decode(gray_encode(value)), value);
+        }
+    }
+
+    #[test]
+    fn test_gray_single_bit_change() {
+        for value in 0..1023u32 {
+            let diff = gray_encode(value) ^ gray_encode(value + 1);
+            assert_eq!(diff.count_ones(), 1);
+        }
+    }
+
+    // ==================== bits_frame() Tests ====================
+
+    #[test]
+    fn test_bits_frame_lsb_first() {
+        assert_eq!(bits_frame(0b0001), Frame::single(0, MAX_BRIGHTNESS));
+        assert_eq!(bits_frame(0b1000), Frame::single(3, MAX_BRIGHTNESS));
+    }
+
+    #[test]
+    fn test_bits_frame_all() {
+        assert_eq!(bits_frame(0b1111), Frame::filled(MAX_BRIGHTNESS));
+    }
+
+    #[test]
+    fn test_bits_frame_ignores_high_bits() {
+        assert_eq!(bits_frame(1 << LED_COUNT), Frame::new());
+    }
+
+    // ==================== Construction Tests ====================
+
+    #[test]
+    fn test_new_starts_at_start() {
+        let counter = Counter::new(3, 9).unwrap();
+        assert_eq!(counter.value(), 3);
+    }
+
+    #[test]
+    fn test_new_invalid_range() {
+        assert_eq!(Counter::new(5, 4), Err(CounterError::InvalidRange));
+    }
+
+    #[test]
+    fn test_new_range_too_wide() {
+        assert_eq!(Counter::new(0, 16), Err(CounterError::RangeTooWide));
+    }
+
+    #[test]
+    fn test_zero_step_rejected() {
+        let counter = Counter::new(0, 15).unwrap();
+        assert_eq!(counter.with_step(0), Err(CounterError::ZeroStep));
+    }
+
+    #[test]
+    fn test_full_range() {
+        let counter = Counter::full_range();
+        assert_eq!(counter, Counter::new(0, 15).unwrap());
+    }
+
+    // ==================== Up Counting Tests ====================
+
+    #[test]
+    fn test_up_increments() {
+        let mut counter = Counter::new(0, 15).unwrap();
+        assert_eq!(counter.advance(), 1);
+        assert_eq!(counter.advance(), 2);
+    }
+
+    #[test]
+    fn test_up_wraps_to_start() {
+        let mut counter = Counter::new(2, 4).unwrap();
+        counter.advance();
+        counter.advance();
+        assert_eq!(counter.advance(), 2);
+    }
+
+    #[test]
+    fn test_up_step() {
+        let mut counter = Counter::new(0, 10).unwrap().with_step(4).unwrap();
+        assert_eq!(counter.advance(), 4);
+        assert_eq!(counter.advance(), 8);
+        assert_eq!(counter.advance(), 0);
+    }
+
+    // ==================== Down Counting Tests ====================
+
+    #[test]
+    fn test_down_starts_at_end() {
+        let counter = Counter::new(2, 9)
+            .unwrap()
+            .with_direction(CountDirection::Down);
+        assert_eq!(counter.value(), 9);
+    }
+
+    #[test]
+    fn test_down_decrements_and_wraps() {
+        let mut counter = Counter::new(1, 3)
+            .unwrap()
+            .with_direction(CountDirection::Down);
+        assert_eq!(counter.advance(), 2);
+        assert_eq!(counter.advance(), 1);
+        assert_eq!(counter.advance(), 3);
+    }
+
+    #[test]
+    fn test_down_step_below_zero_wraps() {
+        let mut counter = Counter::new(0, 15)
+            .unwrap()
+            .with_step(5)
+            .unwrap()
+            .with_direction(CountDirection::Down);
+        assert_eq!(counter.advance(), 10);
+        assert_eq!(counter.advance(), 5);
+        assert_eq!(counter.advance(), 0);
+        assert_eq!(counter.advance(), 15);
+    }
+
+    #[test]
+    fn test_retreat_undoes_advance() {
+        let mut counter = Counter::new(2, 9).unwrap();
+        counter.advance();
+        counter.advance();
+        assert_eq!(counter.retreat(), 3);
+        assert_eq!(counter.retreat(), 2);
+    }
+
+    #[test]
+    fn test_retreat_wraps_to_end() {
+        let mut counter = Counter::new(2, 9).unwrap();
+        assert_eq!(counter.retreat(), 9);
+    }
+
+    #[test]
+    fn test_retreat_down_counts_up() {
+        let mut counter = Counter::new(0, 15)
+            .unwrap()
+            .with_direction(CountDirection::Down);
+        assert_eq!(counter.retreat(), 0);
+        assert_eq!(counter.retreat(), 1);
+    }
+
+    // ==================== Display Tests ====================
+
+    #[test]
+    fn test_binary_frame() {
+        let mut counter = Counter::new(0, 15).unwrap();
+        counter.advance();
+        counter.advance();
+        counter.advance();
+        assert_eq!(counter.frame(), bits_frame(0b0011));
+    }
+
+    #[test]
+    fn test_gray_frame() {
+        let mut counter = Counter::new(0, 15)
+            .unwrap()
+            .with_encoding(CounterEncoding::Gray);
+        counter.advance();
+        counter.advance();
+        assert_eq!(counter.display_bits(), 0b0011);
+        assert_eq!(counter.frame(), bits_frame(0b0011));
+    }
+
+    #[test]
+    fn test_gray_full_range_one_led_per_step() {
+        let mut counter = Counter::full_range().with_encoding(CounterEncoding::Gray);
+        for _ in 0..64 {
+            let before = counter.frame();
+            counter.advance();
+            let after = counter.frame();
+            let changed = (0..LED_COUNT)
+                .filter(|&i| before.level(i) != after.level(i))
+                .count();
+            assert_eq!(changed, 1);
+        }
+    }
+
+    #[test]
+    fn test_reset() {
+        let mut counter = Counter::new(4, 12).unwrap();
+        counter.advance();
+        counter.reset();
+        assert_eq!(counter.value(), 4);
+    }
+}
@@ -0,0 +1,416 @@
+/*
+ * @file dmx.rs
+ * @brief DMX512 receiver
+ * @author Kevin Thomas
+ * @date 2025
+ *
+ * MIT License
+ *
+ * Copyright (c) 2025 Kevin Thomas
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+//! FILE: dmx.rs
+//!
+//! DESCRIPTION:
+//! DMX512 Receiver for Lighting Desk Control of the LEDs.
+//!
+//! BRIEF:
+//! Parses DMX512 packets from a stream of UART bytes and breaks.
+//! Maps channels from a start address onto per-LED brightness.
+//! Reports signal loss so the internal sequence can take over.
+//!
+//! AUTHOR: Kevin Thomas
+//! CREATION DATE: October 18, 2026
+//! UPDATE DATE: October 18, 2026
+
+use crate::config::LED_COUNT;
+use crate::frame::Frame;
+
+/// Number of data slots in a full DMX512 packet.
+#[allow(dead_code)]
+pub const DMX_SLOTS: usize = 512;
+
+/// Start code of dimmer data packets.
+#[allow(dead_code)]
+pub const DMX_START_CODE: u8 = 0x00;
+
+/// Line event received from the UART.
+///
+/// # Variants
+/// * `Break` - Break marking the start of a packet
+/// * `Byte` - Received byte
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub enum DmxSignal {
+    Break,
+    Byte(u8),
+}
+
+/// DMX error enumeration.
+///
+/// # Variants
+/// * `InvalidAddress` - Start address leaves no room for every LED
+/// * `ZeroTimeout` - Signal-loss timeout of zero
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[allow(dead_code)]
+pub enum DmxError {
+    InvalidAddress,
+    ZeroTimeout,
+}
+
+/// Packet parser state.
+///
+/// # Variants
+/// * `Idle` - Waiting for a break
+/// * `StartCode` - Waiting for the start code after a break
+/// * `Data` - Reading dimmer slots, holding the next slot number
+/// * `Ignore` - Skipping a packet with another start code
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum State {
+    Idle,
+    StartCode,
+    Data(usize),
+    Ignore,
+}
+
+/// DMX512 receiver.
+///
+/// # Details
+/// Addresses are 1-based as on a lighting desk, so LED 0 takes
+/// the slot at the start address. A frame is produced as soon as
+/// the last LED slot arrives. Packets with a start code other
+/// than zero, such as RDM or text packets, are skipped. The
+/// signal counts as lost once no frame has arrived for the
+/// timeout.
+///
+/// # Fields
+/// * `start_address` - Slot of LED 0, from 1
+/// * `timeout_ms` - Time without a frame before the signal is lost
+/// * `state` - Packet parser state
+/// * `levels` - LED levels collected from the current packet
+/// * `since_frame_ms` - Time since the last frame
+/// * `live` - Whether a frame arrived within the timeout
+/// * `frames` - Frames received, wrapping
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct DmxReceiver {
+    start_address: u16,
+    timeout_ms: u32,
+    state: State,
+    levels: [u8; LED_COUNT],
+    since_frame_ms: u32,
+    live: bool,
+    frames: u32,
+}
+
+impl DmxReceiver {
+    /// Creates new receiver with no signal.
+    ///
+    /// # Arguments
+    /// * `start_address` - Slot of LED 0, from 1
+    /// * `timeout_ms` - Time without a frame before the signal is lost
+    ///
+    /// # Returns
+    /// * `Result<Self, DmxError>` - New DmxReceiver
+    #[allow(dead_code)]
+    pub fn new(start_address: u16, timeout_ms: u32) -> Result<Self, DmxError> {
+        if start_address == 0 || start_address as usize + LED_COUNT - 1 > DMX_SLOTS {
+            return Err(DmxError::InvalidAddress);
+        }
+        if timeout_ms == 0 {
+            return Err(DmxError::ZeroTimeout);
+        }
+        Ok(Self {
+            start_address,
+            timeout_ms,
+            state: State::Idle,
+            levels: [0; LED_COUNT],
+            since_frame_ms: 0,
+            live: false,
+            frames: 0,
+        })
+    }
+
+    /// Returns slot of LED 0.
+    ///
+    /// # Returns
+    /// * `u16` - Start address, from 1
+    #[allow(dead_code)]
+    pub fn start_address(&self) -> u16 {
+        self.start_address
+    }
+
+    /// Returns whether a frame arrived within the timeout.
+    ///
+    /// # Returns
+    /// * `bool` - true while the signal is live
+    #[allow(dead_code)]
+    pub fn is_live(&self) -> bool {
+        self.live
+    }
+
+    /// Returns number of frames received.
+    ///
+    /// # Returns
+    /// * `u32` - Frames received, wrapping
+    #[allow(dead_code)]
+    pub fn frames(&self) -> u32 {
+        self.frames
+    }
+
+    /// Feeds one line event.
+    ///
+    /// # Arguments
+    /// * `signal` - Break or received byte
+    ///
+    /// # Returns
+    /// * `Option<Frame>` - Frame completed by this event, if any
+    #[allow(dead_code)]
+    pub fn push(&mut self, signal: DmxSignal) -> Option<Frame> {
+        let byte = match signal {
+            DmxSignal::Break => {
+                self.state = State::StartCode;
+                return None;
+            }
+            DmxSignal::Byte(byte) => byte,
+        };
+        match self.state {
+            State::Idle | State::Ignore => None,
+            State::StartCode => {
+                self.state = if byte == DMX_START_CODE {
+                    State::Data(1)
+                } else {
+                    State::Ignore
+                };
+                None
+            }
+            State::Data(slot) => self.slot(slot, byte),
+        }
+    }
+
+    /// Advances signal-loss timing.
+    ///
+    /// # Arguments
+    /// * `elapsed_ms` - Time since the previous tick
+    ///
+    /// # Returns
+    /// * `bool` - true while the signal is live
+    #[allow(dead_code)]
+    pub fn tick(&mut self, elapsed_ms: u32) -> bool {
+        self.since_frame_ms = self.since_frame_ms.saturating_add(elapsed_ms);
+        if self.since_frame_ms >= self.timeout_ms {
+            self.live = false;
+        }
+        self.live
+    }
+
+    /// Stores a dimmer slot.
+    ///
+    /// # Arguments
+    /// * `slot` - Slot number, from 1
+    /// * `byte` - Slot value
+    ///
+    /// # Returns
+    /// * `Option<Frame>` - Frame once the last LED slot is stored
+    fn slot(&mut self, slot: usize, byte: u8) -> Option<Frame> {
+        let first = self.start_address as usize;
+        let last = first + LED_COUNT - 1;
+        self.state = if slot < last {
+            State::Data(slot + 1)
+        } else {
+            State::Idle
+        };
+        if slot < first {
+            return None;
+        }
+        self.levels[slot - first] = byte;
+        if slot < last {
+            return None;
+        }
+        self.since_frame_ms = 0;
+        self.live = true;
+        self.frames = self.frames.wrapping_add(1);
+        Some(Frame::from_levels(self.levels))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns a recorded packet: break, start code and slots.
+    fn packet(start_code: u8, slots: &[u8]) -> Vec<DmxSignal> {
+        let mut signals = vec![DmxSignal::Break, DmxSignal::Byte(start_code)];
+        signals.extend(slots.iter().map(|&slot| DmxSignal::Byte(slot)));
+        signals
+    }
+
+    fn feed(receiver: &mut DmxReceiver, signals: &[DmxSignal]) -> Vec<Frame> {
+        signals
+            .iter()
+            .filter_map(|&signal| receiver.push(signal))
+            .collect()
+    }
+
+    // ==================== Construction Tests ====================
+
+    #[test]
+    fn test_new_starts_without_signal() {
+        let receiver = DmxReceiver::new(1, 1000).unwrap();
+        assert!(!receiver.is_live());
+        assert_eq!(receiver.frames(), 0);
+        assert_eq!(receiver.start_address(), 1);
+    }
+
+    #[test]
+    fn test_new_address_zero() {
+        assert_eq!(DmxReceiver::new(0, 1000), Err(DmxError::InvalidAddress));
+    }
+
+    #[test]
+    fn test_new_last_address_fits() {
+        let last = (DMX_SLOTS - LED_COUNT + 1) as u16;
+        assert!(DmxReceiver::new(last, 1000).is_ok());
+        assert_eq!(
+            DmxReceiver::new(last + 1, 1000),
+            Err(DmxError::InvalidAddress)
+        );
+    }
+
+    #[test]
+    fn test_new_zero_timeout() {
+        assert_eq!(DmxReceiver::new(1, 0), Err(DmxError::ZeroTimeout));
+    }
+
+    // ==================== Packet Tests ====================
+
+    #[test]
+    fn test_packet_from_address_one() {
+        let mut receiver = DmxReceiver::new(1, 1000).unwrap();
+        let frames = feed(&mut receiver, &packet(0, &[10, 20, 30, 40, 50]));
+        assert_eq!(frames, [Frame::from_levels([10, 20, 30, 40])]);
+        assert!(receiver.is_live());
+    }
+
+    #[test]
+    fn test_packet_from_offset_address() {
+        let mut receiver = DmxReceiver::new(3, 1000).unwrap();
+        let frames = feed(&mut receiver, &packet(0, &[1, 2, 3, 4, 5, 6, 7]));
+        assert_eq!(frames, [Frame::from_levels([3, 4, 5, 6])]);
+    }
+
+    #[test]
+    fn test_short_packet_gives_no_frame() {
+        let mut receiver = DmxReceiver::new(1, 1000).unwrap();
+        assert!(feed(&mut receiver, &packet(0, &[1, 2, 3])).is_empty());
+        assert!(!receiver.is_live());
+    }
+
+    #[test]
+    fn test_full_universe() {
+        let mut receiver = DmxReceiver::new(509, 1000).unwrap();
+        let slots: Vec<u8> = (1..=DMX_SLOTS).map(|slot| slot as u8).collect();
+        let frames = feed(&mut receiver, &packet(0, &slots));
+        assert_eq!(frames, [Frame::from_levels([253, 254, 255, 0])]);
+    }
+
+    #[test]
+    fn test_bytes_before_first_break_ignored() {
+        let mut receiver = DmxReceiver::new(1, 1000).unwrap();
+        let mut signals = vec![DmxSignal::Byte(0); 8];
+        signals.extend(packet(0, &[9, 9, 9, 9]));
+        assert_eq!(feed(&mut receiver, &signals), [Frame::filled(9)]);
+    }
+
+    #[test]
+    fn test_non_zero_start_code_skipped() {
+        let mut receiver = DmxReceiver::new(1, 1000).unwrap();
+        assert!(feed(&mut receiver, &packet(0xCC, &[1, 2, 3, 4])).is_empty());
+        assert_eq!(receiver.frames(), 0);
+    }
+
+    #[test]
+    fn test_break_restarts_packet() {
+        let mut receiver = DmxReceiver::new(1, 1000).unwrap();
+        let mut signals = packet(0, &[1, 2]);
+        signals.extend(packet(0, &[5, 6, 7, 8]));
+        assert_eq!(
+            feed(&mut receiver, &signals),
+            [Frame::from_levels([5, 6, 7, 8])]
+        );
+    }
+
+    #[test]
+    fn test_recorded_stream() {
+        let mut receiver = DmxReceiver::new(2, 1000).unwrap();
+        let mut signals = packet(0, &[0, 255, 128, 64, 32, 0]);
+        signals.extend(packet(0xCC, &[1, 1, 1, 1, 1]));
+        signals.extend(packet(0, &[0, 0, 0, 0, 255]));
+        signals.extend(packet(0, &[0, 1, 2]));
+        let frames = feed(&mut receiver, &signals);
+        assert_eq!(
+            frames,
+            [
+                Frame::from_levels([255, 128, 64, 32]),
+                Frame::from_levels([0, 0, 0, 255]),
+            ]
+        );
+        assert_eq!(receiver.frames(), 2);
+    }
+
+    // ==================== Signal Loss Tests ====================
+
+    #[test]
+    fn test_signal_lost_after_timeout() {
+        let mut receiver = DmxReceiver::new(1, 1000).unwrap();
+        feed(&mut receiver, &packet(0, &[1, 2, 3, 4]));
+        assert!(receiver.tick(999));
+        assert!(!receiver.tick(1));
+        assert!(!receiver.is_live());
+    }
+
+    #[test]
+    fn test_frame_resets_timeout() {
+        let mut receiver = DmxReceiver::new(1, 1000).unwrap();
+        feed(&mut receiver, &packet(0, &[1, 2, 3, 4]));
+        receiver.tick(900);
+        feed(&mut receiver, &packet(0, &[1, 2, 3, 4]));
+        assert!(receiver.tick(900));
+    }
+
+    #[test]
+    fn test_skipped_packets_do_not_keep_live() {
+        let mut receiver = DmxReceiver::new(1, 1000).unwrap();
+        feed(&mut receiver, &packet(0, &[1, 2, 3, 4]));
+        receiver.tick(900);
+        feed(&mut receiver, &packet(0xCC, &[1, 2, 3, 4]));
+        assert!(!receiver.tick(100));
+    }
+
+    #[test]
+    fn test_signal_returns() {
+        let mut receiver = DmxReceiver::new(1, 1000).unwrap();
+        receiver.tick(5000);
+        feed(&mut receiver, &packet(0, &[1, 2, 3, 4]));
+        assert!(receiver.is_live());
+    }
+}
@@ -0,0 +1,515 @@
+/*
+ * @file effects.rs
+ * @brief Random twinkle, walk and candle effects
+ * @author Kevin Thomas
+ * @date 2025
+ *
+ * MIT License
+ *
+ * Copyright (c) 2025 Kevin Thomas
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+//! FILE: effects.rs
+//!
+//! DESCRIPTION:
+//! Random LED Effects for RP2350.
+//!
+//! BRIEF:
+//! Provides twinkle, random walk and candle flicker effects.
+//! Each effect is driven by a seeded Rng so output is reproducible.
+//! Wraps the effects in one selectable type for the sequencer.
+//!
+//! AUTHOR: Kevin Thomas
+//! CREATION DATE: October 18, 2026
+//! UPDATE DATE: October 18, 2026
+
+use crate::config::{LED_COUNT, MAX_BRIGHTNESS};
+use crate::frame::{Frame, scale_level};
+use crate::rng::Rng;
+
+/// Chance out of 256 that a twinkle starts on a step.
+#[allow(dead_code)]
+pub const TWINKLE_CHANCE: u8 = 96;
+
+/// Fraction of brightness kept per twinkle step, out of MAX_BRIGHTNESS.
+#[allow(dead_code)]
+pub const TWINKLE_FADE: u8 = 160;
+
+/// Lowest steady candle brightness.
+#[allow(dead_code)]
+pub const CANDLE_MIN: u8 = 120;
+
+/// Highest candle brightness.
+#[allow(dead_code)]
+pub const CANDLE_MAX: u8 = 255;
+
+/// Chance out of 256 that a candle gutters on a step.
+#[allow(dead_code)]
+pub const CANDLE_GUTTER_CHANCE: u8 = 12;
+
+/// Random twinkle effect.
+///
+/// # Details
+/// Lit LEDs fade out each step while new LEDs light at random.
+///
+/// # Fields
+/// * `rng` - Random source
+/// * `frame` - Current LED levels
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct Twinkle {
+    rng: Rng,
+    frame: Frame,
+}
+
+impl Twinkle {
+    /// Creates new twinkle effect with all LEDs off.
+    ///
+    /// # Arguments
+    /// * `seed` - Random seed
+    ///
+    /// # Returns
+    /// * `Self` - New Twinkle instance
+    #[allow(dead_code)]
+    pub fn new(seed: u32) -> Self {
+        Self {
+            rng: Rng::new(seed),
+            frame: Frame::new(),
+        }
+    }
+
+    /// Advances the effect by one step.
+    ///
+    /// # Returns
+    /// * `Frame` - New LED frame
+    #[allow(dead_code)]
+    pub fn step(&mut self) -> Frame {
+        self.frame = self.frame.scaled(TWINKLE_FADE);
+        if self.rng.chance(TWINKLE_CHANCE) {
+            let index = self.rng.below(LED_COUNT as u32) as usize;
+            self.frame.set_level(index, MAX_BRIGHTNESS);
+        }
+        self.frame
+    }
+
+    /// Returns current frame.
+    ///
+    /// # Returns
+    /// * `Frame` - Current LED levels
+    #[allow(dead_code)]
+    pub fn frame(&self) -> Frame {
+        self.frame
+    }
+}
+
+/// Random walk of a single lit LED.
+///
+/// # Details
+/// Each step the lit LED moves one place left or right,
+/// bouncing off the ends of the row.
+///
+/// # Fields
+/// * `rng` - Random source
+/// * `position` - Index of the lit LED
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct RandomWalk {
+    rng: Rng,
+    position: usize,
+}
+
+impl RandomWalk {
+    /// Creates new random walk starting at a random LED.
+    ///
+    /// # Arguments
+    /// * `seed` - Random seed
+    ///
+    /// # Returns
+    /// * `Self` - New RandomWalk instance
+    #[allow(dead_code)]
+    pub fn new(seed: u32) -> Self {
+        let mut rng = Rng::new(seed);
+        let position = rng.below(LED_COUNT as u32) as usize;
+        Self { rng, position }
+    }
+
+    /// Returns index of the lit LED.
+    ///
+    /// # Returns
+    /// * `usize` - LED index
+    #[allow(dead_code)]
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Advances the walk by one step.
+    ///
+    /// # Returns
+    /// * `Frame` - New LED frame
+    #[allow(dead_code)]
+    pub fn step(&mut self) -> Frame {
+        if LED_COUNT > 1 {
+            let left = self.rng.chance(128);
+            self.position = match (left, self.position) {
+                (true, 0) => 1,
+                (true, position) => position - 1,
+                (false, position) if position + 1 == LED_COUNT => position - 1,
+                (false, position) => position + 1,
+            };
+        }
+        self.frame()
+    }
+
+    /// Returns current frame.
+    ///
+    /// # Returns
+    /// * `Frame` - Frame with the walker lit
+    #[allow(dead_code)]
+    pub fn frame(&self) -> Frame {
+        Frame::single(self.position, MAX_BRIGHTNESS)
+    }
+}
+
+/// Candle flicker on every LED.
+///
+/// # Details
+/// Each LED drifts toward a random target in the steady band
+/// and occasionally gutters toward a low level. Drift smooths
+/// changes so the flame wavers rather than blinks.
+///
+/// # Fields
+/// * `rng` - Random source
+/// * `frame` - Current LED levels
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct Candle {
+    rng: Rng,
+    frame: Frame,
+}
+
+impl Candle {
+    /// Creates new candle effect at full brightness.
+    ///
+    /// # Arguments
+    /// * `seed` - Random seed
+    ///
+    /// # Returns
+    /// * `Self` - New Candle instance
+    #[allow(dead_code)]
+    pub fn new(seed: u32) -> Self {
+        Self {
+            rng: Rng::new(seed),
+            frame: Frame::filled(CANDLE_MAX),
+        }
+    }
+
+    /// Advances the effect by one step.
+    ///
+    /// # Returns
+    /// * `Frame` - New LED frame
+    #[allow(dead_code)]
+    pub fn step(&mut self) -> Frame {
+        for index in 0..LED_COUNT {
+            let target = if self.rng.chance(CANDLE_GUTTER_CHANCE) {
+                scale_level(CANDLE_MIN, MAX_BRIGHTNESS / 2)
+            } else {
+                self.rng.range_u8(CANDLE_MIN, CANDLE_MAX)
+            };
+            let current = self.frame.level(index) as u16;
+            let next = (current * 3 + target as u16) / 4;
+            self.frame.set_level(index, next as u8);
+        }
+        self.frame
+    }
+
+    /// Returns current frame.
+    ///
+    /// # Returns
+    /// * `Frame` - Current LED levels
+    #[allow(dead_code)]
+    pub fn frame(&self) -> Frame {
+        self.frame
+    }
+}
+
+/// Effect selection enumeration.
+///
+/// # Variants
+/// * `Twinkle` - Random twinkle
+/// * `RandomWalk` - Random walk of a single lit LED
+/// * `Candle` - Candle flicker on every LED
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[allow(dead_code)]
+pub enum EffectKind {
+    Twinkle,
+    RandomWalk,
+    Candle,
+}
+
+/// Any random effect.
+///
+/// # Variants
+/// * `Twinkle` - Random twinkle
+/// * `RandomWalk` - Random walk of a single lit LED
+/// * `Candle` - Candle flicker on every LED
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub enum Effect {
+    Twinkle(Twinkle),
+    RandomWalk(RandomWalk),
+    Candle(Candle),
+}
+
+impl Effect {
+    /// Creates new effect of a kind.
+    ///
+    /// # Arguments
+    /// * `kind` - Effect to create
+    /// * `seed` - Random seed
+    ///
+    /// # Returns
+    /// * `Self` - New Effect instance
+    #[allow(dead_code)]
+    pub fn new(kind: EffectKind, seed: u32) -> Self {
+        match kind {
+            EffectKind::Twinkle => Self::Twinkle(Twinkle::new(seed)),
+            EffectKind::RandomWalk => Self::RandomWalk(RandomWalk::new(seed)),
+            EffectKind::Candle => Self::Candle(Candle::new(seed)),
+        }
+    }
+
+    /// Returns effect kind.
+    ///
+    /// # Returns
+    /// * `EffectKind` - Which effect this is
+    #[allow(dead_code)]
+    pub fn kind(&self) -> EffectKind {
+        match self {
+            Self::Twinkle(_) => EffectKind::Twinkle,
+            Self::RandomWalk(_) => EffectKind::RandomWalk,
+            Self::Candle(_) => EffectKind::Candle,
+        }
+    }
+
+    /// Advances the effect by one step.
+    ///
+    /// # Returns
+    /// * `Frame` - New LED frame
+    #[allow(dead_code)]
+    pub fn step(&mut self) -> Frame {
+        match self {
+            Self::Twinkle(effect) => effect.step(),
+            Self::RandomWalk(effect) => effect.step(),
+            Self::Candle(effect) => effect.step(),
+        }
+    }
+
+    /// Returns current frame.
+    ///
+    /// # Returns
+    /// * `Frame` - Current LED levels
+    #[allow(dead_code)]
+    pub fn frame(&self) -> Frame {
+        match self {
+            Self::Twinkle(effect) => effect.frame(),
+            Self::RandomWalk(effect) => effect.frame(),
+            Self::Candle(effect) => effect.frame(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // ==================== Twinkle Tests ====================
+
+    #[test]
+    fn test_twinkle_starts_dark() {
+        assert_eq!(Twinkle::new(1).frame(), Frame::new());
+    }
+
+    #[test]
+    fn test_twinkle_reproducible() {
+        let mut a = Twinkle::new(42);
+        let mut b = Twinkle::new(42);
+        for _ in 0..50 {
+            assert_eq!(a.step(), b.step());
+        }
+    }
+
+    #[test]
+    fn test_twinkle_lights_something() {
+        let mut twinkle = Twinkle::new(42);
+        let lit = (0..50).any(|_| twinkle.step().lit_count() > 0);
+        assert!(lit);
+    }
+
+    #[test]
+    fn test_twinkle_fades() {
+        let mut twinkle = Twinkle::new(42);
+        twinkle.frame = Frame::filled(MAX_BRIGHTNESS);
+        twinkle.rng = Rng::new(1);
+        let frame = twinkle.step();
+        let faded = (0..LED_COUNT)
+            .filter(|&i| frame.level(i) < MAX_BRIGHTNESS)
+            .count();
+        assert!(faded >= LED_COUNT - 1);
+    }
+
+    // ==================== RandomWalk Tests ====================
+
+    #[test]
+    fn test_walk_reproducible() {
+        let mut a = RandomWalk::new(9);
+        let mut b = RandomWalk::new(9);
+        for _ in 0..50 {
+            assert_eq!(a.step(), b.step());
+        }
+    }
+
+    #[test]
+    fn test_walk_moves_one_place() {
+        let mut walk = RandomWalk::new(9);
+        for _ in 0..200 {
+            let before = walk.position();
+            walk.step();
+            assert_eq!(before.abs_diff(walk.position()), 1);
+        }
+    }
+
+    #[test]
+    fn test_walk_stays_in_bounds() {
+        let mut walk = RandomWalk::new(123);
+        for _ in 0..1000 {
+            walk.step();
+            assert!(walk.position() < LED_COUNT);
+        }
+    }
+
+    #[test]
+    fn test_walk_single_led_lit() {
+        let mut walk = RandomWalk::new(5);
+        assert_eq!(walk.step().lit_count(), 1);
+    }
+
+    #[test]
+    fn test_walk_visits_every_led() {
+        let mut walk = RandomWalk::new(77);
+        let mut seen = [false; LED_COUNT];
+        for _ in 0..500 {
+            walk.step();
+            seen[walk.position()] = true;
+        }
+        assert!(seen.iter().all(|&s| s));
+    }
+
+    // ==================== Candle Tests ====================
+
+    #[test]
+    fn test_candle_starts_bright() {
+        assert_eq!(Candle::new(1).frame(), Frame::filled(CANDLE_MAX));
+    }
+
+    #[test]
+    fn test_candle_reproducible() {
+        let mut a = Candle::new(8);
+        let mut b = Candle::new(8);
+        for _ in 0..50 {
+            assert_eq!(a.step(), b.step());
+        }
+    }
+
+    #[test]
+    fn test_candle_never_dark() {
+        let mut candle = Candle::new(8);
+        for _ in 0..1000 {
+            let frame = candle.step();
+            for i in 0..LED_COUNT {
+                assert!(frame.level(i) >= CANDLE_MIN / 2);
+            }
+        }
+    }
+
+    #[test]
+    fn test_candle_flickers() {
+        let mut candle = Candle::new(8);
+        let first = candle.step();
+        let changed = (0..20).any(|_| candle.step() != first);
+        assert!(changed);
+    }
+
+    #[test]
+    fn test_candle_leds_independent() {
+        let mut candle = Candle::new(8);
+        let differs = (0..50).any(|_| {
+            let frame = candle.step();
+            (1..LED_COUNT).any(|i| frame.level(i) != frame.level(0))
+        });
+        assert!(differs);
+    }
+
+    #[test]
+    fn test_candle_change_is_smoothed() {
+        let mut candle = Candle::new(8);
+        let mut last = candle.frame();
+        for _ in 0..200 {
+            let frame = candle.step();
+            for i in 0..LED_COUNT {
+                assert!(last.level(i).abs_diff(frame.level(i)) <= 64);
+            }
+            last = frame;
+        }
+    }
+
+    // ==================== Effect Tests ====================
+
+    #[test]
+    fn test_effect_kind() {
+        for kind in [
+            EffectKind::Twinkle,
+            EffectKind::RandomWalk,
+            EffectKind::Candle,
+        ] {
+            assert_eq!(Effect::new(kind, 3).kind(), kind);
+        }
+    }
+
+    #[test]
+    fn test_effect_matches_wrapped() {
+        let mut effect = Effect::new(EffectKind::Candle, 5);
+        let mut candle = Candle::new(5);
+        for _ in 0..10 {
+            assert_eq!(effect.step(), candle.step());
+        }
+        assert_eq!(effect.frame(), candle.frame());
+    }
+
+    #[test]
+    fn test_effect_reproducible() {
+        let mut first = Effect::new(EffectKind::Twinkle, 11);
+        let mut second = Effect::new(EffectKind::Twinkle, 11);
+        for _ in 0..20 {
+            assert_eq!(first.step(), second.step());
+        }
+    }
+}
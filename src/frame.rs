@@ -0,0 +1,458 @@
+/*
+ * @file frame.rs
+ * @brief LED frame representation
+ * @author Kevin Thomas
+ * @date 2025
+ *
+ * MIT License
+ *
+ * Copyright (c) 2025 Kevin Thomas
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+//! FILE: frame.rs
+//!
+//! DESCRIPTION:
+//! LED Frame Representation for RP2350.
+//!
+//! BRIEF:
+//! Provides a per-LED brightness frame pushed to the LED outputs.
+//! Converts brightness levels to PWM duty values.
+//!
+//! AUTHOR: Kevin Thomas
+//! CREATION DATE: October 18, 2026
+//! UPDATE DATE: October 18, 2026
+
+use crate::config::{LED_COUNT, MAX_BRIGHTNESS};
+use crate::led::LedState;
+
+/// Brightness levels for every LED in the sequence.
+///
+/// # Details
+/// Holds one brightness level per LED, 0 being off and
+/// MAX_BRIGHTNESS being fully on. Indices outside the LED
+/// range read as off and ignore writes.
+///
+/// # Fields
+/// * `levels` - Brightness level per LED index
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[allow(dead_code)]
+pub struct Frame {
+    levels: [u8; LED_COUNT],
+}
+
+impl Default for Frame {
+    /// Returns default Frame instance.
+    ///
+    /// # Details
+    /// Delegates to new() for initialization.
+    ///
+    /// # Returns
+    /// * `Self` - New Frame with all LEDs off
+    #[allow(dead_code)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Frame {
+    /// Creates new frame with all LEDs off.
+    ///
+    /// # Returns
+    /// * `Self` - New Frame instance
+    #[allow(dead_code)]
+    pub const fn new() -> Self {
+        Self {
+            levels: [0; LED_COUNT],
+        }
+    }
+
+    /// Creates frame with every LED at the same level.
+    ///
+    /// # Arguments
+    /// * `level` - Brightness level for all LEDs
+    ///
+    /// # Returns
+    /// * `Self` - New Frame instance
+    #[allow(dead_code)]
+    pub const fn filled(level: u8) -> Self {
+        Self {
+            levels: [level; LED_COUNT],
+        }
+    }
+
+    /// Creates frame from explicit per-LED levels.
+    ///
+    /// # Arguments
+    /// * `levels` - Brightness level per LED index
+    ///
+    /// # Returns
+    /// * `Self` - New Frame instance
+    #[allow(dead_code)]
+    pub const fn from_levels(levels: [u8; LED_COUNT]) -> Self {
+        Self { levels }
+    }
+
+    /// Creates frame with a single LED lit.
+    ///
+    /// # Details
+    /// All other LEDs are off. An out-of-range index yields a blank frame.
+    ///
+    /// # Arguments
+    /// * `index` - LED index to light
+    /// * `level` - Brightness level for that LED
+    ///
+    /// # Returns
+    /// * `Self` - New Frame instance
+    #[allow(dead_code)]
+    pub fn single(index: usize, level: u8) -> Self {
+        let mut frame = Self::new();
+        frame.set_level(index, level);
+        frame
+    }
+
+    /// Returns brightness level for given index.
+    ///
+    /// # Arguments
+    /// * `index` - LED index to read
+    ///
+    /// # Returns
+    /// * `u8` - Level, or 0 if index is out of range
+    #[allow(dead_code)]
+    pub fn level(&self, index: usize) -> u8 {
+        self.levels.get(index).copied().unwrap_or(0)
+    }
+
+    /// Sets brightness level for given index.
+    ///
+    /// # Details
+    /// Out-of-range indices are ignored.
+    ///
+    /// # Arguments
+    /// * `index` - LED index to write
+    /// * `level` - New brightness level
+    #[allow(dead_code)]
+    pub fn set_level(&mut self, index: usize, level: u8) {
+        if let Some(slot) = self.levels.get_mut(index) {
+            *slot = level;
+        }
+    }
+
+    /// Returns all brightness levels.
+    ///
+    /// # Returns
+    /// * `[u8; LED_COUNT]` - Copy of the per-LED levels
+    #[allow(dead_code)]
+    pub fn levels(&self) -> [u8; LED_COUNT] {
+        self.levels
+    }
+
+    /// Returns LED state for given index.
+    ///
+    /// # Details
+    /// Any non-zero level counts as On.
+    ///
+    /// # Arguments
+    /// * `index` - LED index to check
+    ///
+    /// # Returns
+    /// * `LedState` - On if lit, Off otherwise
+    #[allow(dead_code)]
+    pub fn led_state(&self, index: usize) -> LedState {
+        if self.level(index) > 0 {
+            LedState::On
+        } else {
+            LedState::Off
+        }
+    }
+
+    /// Returns number of lit LEDs.
+    ///
+    /// # Returns
+    /// * `usize` - Count of LEDs with a non-zero level
+    #[allow(dead_code)]
+    pub fn lit_count(&self) -> usize {
+        self.levels.iter().filter(|&&level| level > 0).count()
+    }
+
+    /// Returns frame with every level scaled by a factor.
+    ///
+    /// # Details
+    /// Scale is a fraction of MAX_BRIGHTNESS, so MAX_BRIGHTNESS
+    /// leaves the frame unchanged and 0 blanks it.
+    ///
+    /// # Arguments
+    /// * `scale` - Scale factor (0 to MAX_BRIGHTNESS)
+    ///
+    /// # Returns
+    /// * `Frame` - Scaled frame
+    #[allow(dead_code)]
+    pub fn scaled(&self, scale: u8) -> Frame {
+        let mut out = *self;
+        for level in out.levels.iter_mut() {
+            *level = scale_level(*level, scale);
+        }
+        out
+    }
+
+    /// Returns crossfade between this frame and another.
+    ///
+    /// # Details
+    /// Amount 0 returns this frame and MAX_BRIGHTNESS returns the other.
+    ///
+    /// # Arguments
+    /// * `other` - Frame to fade toward
+    /// * `amount` - Fade amount (0 to MAX_BRIGHTNESS)
+    ///
+    /// # Returns
+    /// * `Frame` - Mixed frame
+    #[allow(dead_code)]
+    pub fn mix(&self, other: &Frame, amount: u8) -> Frame {
+        let mut out = Frame::new();
+        for index in 0..LED_COUNT {
+            let from = scale_level(self.level(index), MAX_BRIGHTNESS - amount);
+            let to = scale_level(other.level(index), amount);
+            out.set_level(index, from.saturating_add(to));
+        }
+        out
+    }
+}
+
+/// Scales a brightness level by a factor.
+///
+/// # Details
+/// Computes level * scale / MAX_BRIGHTNESS with rounding.
+///
+/// # Arguments
+/// * `level` - Brightness level to scale
+/// * `scale` - Scale factor (0 to MAX_BRIGHTNESS)
+///
+/// # Returns
+/// * `u8` - Scaled level
+#[allow(dead_code)]
+pub fn scale_level(level: u8, scale: u8) -> u8 {
+    let max = MAX_BRIGHTNESS as u32;
+    ((level as u32 * scale as u32 + max / 2) / max) as u8
+}
+
+/// Converts a brightness level to a PWM compare value.
+///
+/// # Details
+/// Maps 0 to 0 and MAX_BRIGHTNESS to top + 1 so that
+/// full brightness holds the output permanently high.
+///
+/// # Arguments
+/// * `level` - Brightness level to convert
+/// * `top` - PWM counter wrap value
+///
+/// # Returns
+/// * `u16` - PWM compare value
+#[allow(dead_code)]
+pub fn level_to_duty(level: u8, top: u16) -> u16 {
+    let span = top as u32 + 1;
+    (level as u32 * span / MAX_BRIGHTNESS as u32).min(u16::MAX as u32) as u16
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // ==================== Frame Constructor Tests ====================
+
+    #[test]
+    fn test_new_frame_all_off() {
+        let frame = Frame::new();
+        assert_eq!(frame.levels(), [0; LED_COUNT]);
+    }
+
+    #[test]
+    fn test_default_equals_new() {
+        assert_eq!(Frame::default(), Frame::new());
+    }
+
+    #[test]
+    fn test_filled_frame() {
+        let frame = Frame::filled(100);
+        assert_eq!(frame.levels(), [100; LED_COUNT]);
+    }
+
+    #[test]
+    fn test_from_levels() {
+        let frame = Frame::from_levels([1, 2, 3, 4]);
+        assert_eq!(frame.level(0), 1);
+        assert_eq!(frame.level(3), 4);
+    }
+
+    #[test]
+    fn test_single_lights_one() {
+        let frame = Frame::single(2, MAX_BRIGHTNESS);
+        assert_eq!(frame.level(2), MAX_BRIGHTNESS);
+        assert_eq!(frame.lit_count(), 1);
+    }
+
+    #[test]
+    fn test_single_out_of_range_is_blank() {
+        assert_eq!(Frame::single(LED_COUNT, MAX_BRIGHTNESS), Frame::new());
+    }
+
+    // ==================== Frame Access Tests ====================
+
+    #[test]
+    fn test_level_out_of_range_is_zero() {
+        let frame = Frame::filled(MAX_BRIGHTNESS);
+        assert_eq!(frame.level(LED_COUNT), 0);
+    }
+
+    #[test]
+    fn test_set_level() {
+        let mut frame = Frame::new();
+        frame.set_level(1, 42);
+        assert_eq!(frame.level(1), 42);
+    }
+
+    #[test]
+    fn test_set_level_out_of_range_ignored() {
+        let mut frame = Frame::new();
+        frame.set_level(LED_COUNT + 5, 42);
+        assert_eq!(frame, Frame::new());
+    }
+
+    #[test]
+    fn test_led_state_on_when_lit() {
+        let frame = Frame::single(0, 1);
+        assert_eq!(frame.led_state(0), LedState::On);
+        assert_eq!(frame.led_state(1), LedState::Off);
+    }
+
+    #[test]
+    fn test_led_state_out_of_range_off() {
+        let frame = Frame::filled(MAX_BRIGHTNESS);
+        assert_eq!(frame.led_state(LED_COUNT), LedState::Off);
+    }
+
+    #[test]
+    fn test_lit_count() {
+        let frame = Frame::from_levels([0, 5, 0, 255]);
+        assert_eq!(frame.lit_count(), 2);
+    }
+
+    // ==================== Scaling Tests ====================
+
+    #[test]
+    fn test_scale_level_full() {
+        assert_eq!(scale_level(200, MAX_BRIGHTNESS), 200);
+    }
+
+    #[test]
+    fn test_scale_level_zero() {
+        assert_eq!(scale_level(200, 0), 0);
+    }
+
+    #[test]
+    fn test_scale_level_half() {
+        assert_eq!(scale_level(MAX_BRIGHTNESS, 128), 128);
+    }
+
+    #[test]
+    fn test_scaled_frame() {
+        let frame = Frame::from_levels([255, 0, 100, 50]).scaled(0);
+        assert_eq!(frame, Frame::new());
+    }
+
+    #[test]
+    fn test_scaled_frame_identity() {
+        let frame = Frame::from_levels([255, 0, 100, 50]);
+        assert_eq!(frame.scaled(MAX_BRIGHTNESS), frame);
+    }
+
+    // ==================== mix() Tests ====================
+
+    #[test]
+    fn test_mix_endpoints() {
+        let a = Frame::from_levels([255, 0, 100, 50]);
+        let b = Frame::from_levels([0, 255, 50, 100]);
+        assert_eq!(a.mix(&b, 0), a);
+        assert_eq!(a.mix(&b, MAX_BRIGHTNESS), b);
+    }
+
+    #[test]
+    fn test_mix_halfway() {
+        let a = Frame::filled(MAX_BRIGHTNESS);
+        let b = Frame::new();
+        assert_eq!(a.mix(&b, 128).level(0), 127);
+    }
+
+    #[test]
+    fn test_mix_same_frame_stable() {
+        let a = Frame::filled(200);
+        for amount in [0, 64, 128, 192, 255] {
+            let level = a.mix(&a, amount).level(0);
+            assert!((199..=201).contains(&level));
+        }
+    }
+
+    // ==================== level_to_duty Tests ====================
+
+    #[test]
+    fn test_duty_off() {
+        assert_eq!(level_to_duty(0, 4095), 0);
+    }
+
+    #[test]
+    fn test_duty_full_exceeds_top() {
+        assert_eq!(level_to_duty(MAX_BRIGHTNESS, 4095), 4096);
+    }
+
+    #[test]
+    fn test_duty_half() {
+        assert_eq!(level_to_duty(128, 4095), 2056);
+    }
+
+    #[test]
+    fn test_duty_saturates_at_u16_max() {
+        assert_eq!(level_to_duty(MAX_BRIGHTNESS, u16::MAX), u16::MAX);
+    }
+
+    #[test]
+    fn test_duty_monotonic() {
+        let mut last = 0;
+        for level in 0..=MAX_BRIGHTNESS {
+            let duty = level_to_duty(level, 4095);
+            assert!(duty >= last);
+            last = duty;
+        }
+    }
+
+    // ==================== Trait Implementation Tests ====================
+
+    #[test]
+    fn test_frame_copy() {
+        let frame = Frame::filled(7);
+        let copy = frame;
+        assert_eq!(frame, copy);
+    }
+
+    #[test]
+    fn test_frame_debug() {
+        let debug_str = format!("{:?}", Frame::new());
+        assert!(debug_str.contains("Frame"));
+    }
+}
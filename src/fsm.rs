@@ -0,0 +1,596 @@
+/*
+ * @file fsm.rs
+ * @brief Table-driven LED state machine
+ * @author Kevin Thomas
+ * @date 2025
+ *
+ * MIT License
+ *
+ * Copyright (c) 2025 Kevin Thomas
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+//! FILE: fsm.rs
+//!
+//! DESCRIPTION:
+//! Table-Driven LED State Machine with Guarded Transitions.
+//!
+//! BRIEF:
+//! Runs explicit state machines such as traffic lights.
+//! Each state shows its own frame for a dwell time.
+//! Transitions are guarded by timers and latched inputs.
+//! Validates the tables, including unreachable states, up front.
+//!
+//! AUTHOR: Kevin Thomas
+//! CREATION DATE: October 18, 2026
+//! UPDATE DATE: October 18, 2026
+
+use crate::config::MAX_FSM_STATES;
+use crate::frame::Frame;
+
+/// Transition guard enumeration.
+///
+/// # Details
+/// Inputs are bit masks over latched input lines. A guard is met
+/// by any of its input bits.
+///
+/// # Variants
+/// * `After` - The state's dwell time has elapsed
+/// * `Input` - An input is latched, whatever the dwell time
+/// * `AfterWithInput` - The dwell time has elapsed and an input is latched
+/// * `AfterWithoutInput` - The dwell time has elapsed and no input is latched
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub enum Guard {
+    After,
+    Input(u8),
+    AfterWithInput(u8),
+    AfterWithoutInput(u8),
+}
+
+/// State machine error enumeration.
+///
+/// # Variants
+/// * `NoStates` - The state table is empty
+/// * `TooManyStates` - The state table exceeds MAX_FSM_STATES
+/// * `InvalidInitial` - The initial state is not in the table
+/// * `InvalidTransition` - A transition names a state not in the table
+/// * `Unreachable` - A state cannot be reached from the initial state
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[allow(dead_code)]
+pub enum FsmError {
+    NoStates,
+    TooManyStates,
+    InvalidInitial,
+    InvalidTransition(usize),
+    Unreachable(usize),
+}
+
+/// State table entry.
+///
+/// # Fields
+/// * `frame` - Frame shown in this state
+/// * `dwell_ms` - Time before timed guards are met
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct FsmState {
+    pub frame: Frame,
+    pub dwell_ms: u32,
+}
+
+impl FsmState {
+    /// Creates new state.
+    ///
+    /// # Arguments
+    /// * `frame` - Frame shown in this state
+    /// * `dwell_ms` - Time before timed guards are met
+    ///
+    /// # Returns
+    /// * `Self` - New FsmState instance
+    #[allow(dead_code)]
+    pub const fn new(frame: Frame, dwell_ms: u32) -> Self {
+        Self { frame, dwell_ms }
+    }
+}
+
+/// Transition table entry.
+///
+/// # Fields
+/// * `from` - State the transition leaves
+/// * `to` - State the transition enters
+/// * `guard` - Condition for taking the transition
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct FsmTransition {
+    pub from: usize,
+    pub to: usize,
+    pub guard: Guard,
+}
+
+impl FsmTransition {
+    /// Creates new transition.
+    ///
+    /// # Arguments
+    /// * `from` - State the transition leaves
+    /// * `to` - State the transition enters
+    /// * `guard` - Condition for taking the transition
+    ///
+    /// # Returns
+    /// * `Self` - New FsmTransition instance
+    #[allow(dead_code)]
+    pub const fn new(from: usize, to: usize, guard: Guard) -> Self {
+        Self { from, to, guard }
+    }
+}
+
+/// Table-driven state machine.
+///
+/// # Details
+/// Transitions are tried in table order and the first one whose
+/// guard is met is taken, at most one per tick. Inputs stay
+/// latched until a transition guarded on them is taken.
+///
+/// # Fields
+/// * `states` - State table
+/// * `transitions` - Transition table
+/// * `initial` - State entered on reset
+/// * `current` - Current state
+/// * `in_state_ms` - Time spent in the current state
+/// * `inputs` - Latched input bits
+/// * `transitions_taken` - Transitions taken so far, wrapping
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct Fsm<'a> {
+    states: &'a [FsmState],
+    transitions: &'a [FsmTransition],
+    initial: usize,
+    current: usize,
+    in_state_ms: u32,
+    inputs: u8,
+    transitions_taken: u32,
+}
+
+impl<'a> Fsm<'a> {
+    /// Creates new state machine after validating its tables.
+    ///
+    /// # Arguments
+    /// * `states` - State table
+    /// * `transitions` - Transition table
+    /// * `initial` - State entered first
+    ///
+    /// # Returns
+    /// * `Result<Self, FsmError>` - New Fsm in its initial state
+    #[allow(dead_code)]
+    pub fn new(
+        states: &'a [FsmState],
+        transitions: &'a [FsmTransition],
+        initial: usize,
+    ) -> Result<Self, FsmError> {
+        validate(states, transitions, initial)?;
+        Ok(Self {
+            states,
+            transitions,
+            initial,
+            current: initial,
+            in_state_ms: 0,
+            inputs: 0,
+            transitions_taken: 0,
+        })
+    }
+
+    /// Returns current state.
+    ///
+    /// # Returns
+    /// * `usize` - Index into the state table
+    #[allow(dead_code)]
+    pub fn state(&self) -> usize {
+        self.current
+    }
+
+    /// Returns latched inputs.
+    ///
+    /// # Returns
+    /// * `u8` - Input bits waiting for a transition
+    #[allow(dead_code)]
+    pub fn inputs(&self) -> u8 {
+        self.inputs
+    }
+
+    /// Returns number of transitions taken.
+    ///
+    /// # Returns
+    /// * `u32` - Transition count
+    #[allow(dead_code)]
+    pub fn transitions_taken(&self) -> u32 {
+        self.transitions_taken
+    }
+
+    /// Latches inputs.
+    ///
+    /// # Arguments
+    /// * `mask` - Input bits to set
+    #[allow(dead_code)]
+    pub fn input(&mut self, mask: u8) {
+        self.inputs |= mask;
+    }
+
+    /// Returns to the initial state with no inputs latched.
+    #[allow(dead_code)]
+    pub fn reset(&mut self) {
+        self.current = self.initial;
+        self.in_state_ms = 0;
+        self.inputs = 0;
+    }
+
+    /// Returns current frame.
+    ///
+    /// # Returns
+    /// * `Frame` - Frame of the current state
+    #[allow(dead_code)]
+    pub fn frame(&self) -> Frame {
+        self.states[self.current].frame
+    }
+
+    /// Advances time and takes the first transition whose guard is met.
+    ///
+    /// # Arguments
+    /// * `elapsed_ms` - Time since the previous tick
+    ///
+    /// # Returns
+    /// * `bool` - true if the state changed
+    #[allow(dead_code)]
+    pub fn tick(&mut self, elapsed_ms: u32) -> bool {
+        self.in_state_ms = self.in_state_ms.saturating_add(elapsed_ms);
+        let dwelt = self.in_state_ms >= self.states[self.current].dwell_ms;
+        let inputs = self.inputs;
+        let taken = self.transitions.iter().find(|transition| {
+            transition.from == self.current
+                && match transition.guard {
+                    Guard::After => dwelt,
+                    Guard::Input(mask) => inputs & mask != 0,
+                    Guard::AfterWithInput(mask) => dwelt && inputs & mask != 0,
+                    Guard::AfterWithoutInput(mask) => dwelt && inputs & mask == 0,
+                }
+        });
+        let Some(transition) = taken else {
+            return false;
+        };
+        if let Guard::Input(mask) | Guard::AfterWithInput(mask) = transition.guard {
+            self.inputs &= !mask;
+        }
+        self.current = transition.to;
+        self.in_state_ms = 0;
+        self.transitions_taken = self.transitions_taken.wrapping_add(1);
+        true
+    }
+}
+
+/// Validates state machine tables.
+///
+/// # Arguments
+/// * `states` - State table
+/// * `transitions` - Transition table
+/// * `initial` - State entered first
+///
+/// # Returns
+/// * `Result<(), FsmError>` - First problem found, if any
+#[allow(dead_code)]
+pub fn validate(
+    states: &[FsmState],
+    transitions: &[FsmTransition],
+    initial: usize,
+) -> Result<(), FsmError> {
+    if states.is_empty() {
+        return Err(FsmError::NoStates);
+    }
+    if states.len() > MAX_FSM_STATES {
+        return Err(FsmError::TooManyStates);
+    }
+    if initial >= states.len() {
+        return Err(FsmError::InvalidInitial);
+    }
+    if let Some(index) = transitions
+        .iter()
+        .position(|transition| transition.from >= states.len() || transition.to >= states.len())
+    {
+        return Err(FsmError::InvalidTransition(index));
+    }
+    let mut reached = [false; MAX_FSM_STATES];
+    reached[initial] = true;
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for transition in transitions {
+            if reached[transition.from] && !reached[transition.to] {
+                reached[transition.to] = true;
+                changed = true;
+            }
+        }
+    }
+    match reached[..states.len()].iter().position(|&reached| !reached) {
+        Some(state) => Err(FsmError::Unreachable(state)),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RED: usize = 0;
+    const RED_AMBER: usize = 1;
+    const GREEN: usize = 2;
+    const AMBER: usize = 3;
+    const WALK: usize = 4;
+    const BUTTON: u8 = 0b01;
+
+    /// Traffic light with a pedestrian crossing on LED 3.
+    const STATES: [FsmState; 5] = [
+        FsmState::new(Frame::from_levels([255, 0, 0, 0]), 2000),
+        FsmState::new(Frame::from_levels([255, 255, 0, 0]), 500),
+        FsmState::new(Frame::from_levels([0, 0, 255, 0]), 3000),
+        FsmState::new(Frame::from_levels([0, 255, 0, 0]), 500),
+        FsmState::new(Frame::from_levels([255, 0, 0, 255]), 4000),
+    ];
+
+    const TRANSITIONS: [FsmTransition; 6] = [
+        FsmTransition::new(RED, RED_AMBER, Guard::After),
+        FsmTransition::new(RED_AMBER, GREEN, Guard::After),
+        FsmTransition::new(GREEN, AMBER, Guard::AfterWithInput(BUTTON)),
+        FsmTransition::new(AMBER, WALK, Guard::AfterWithInput(BUTTON)),
+        FsmTransition::new(AMBER, RED, Guard::After),
+        FsmTransition::new(WALK, RED, Guard::After),
+    ];
+
+    fn traffic_light() -> Fsm<'static> {
+        Fsm::new(&STATES, &TRANSITIONS, RED).unwrap()
+    }
+
+    /// Step of a host test script.
+    enum Event {
+        Wait(u32),
+        Press(u8),
+    }
+
+    /// Runs a script in 10 ms ticks and records every state entered.
+    fn run(fsm: &mut Fsm, script: &[Event]) -> Vec<usize> {
+        let mut visited = vec![fsm.state()];
+        for event in script {
+            match *event {
+                Event::Wait(ms) => {
+                    for _ in 0..ms / 10 {
+                        if fsm.tick(10) {
+                            visited.push(fsm.state());
+                        }
+                    }
+                }
+                Event::Press(mask) => fsm.input(mask),
+            }
+        }
+        visited
+    }
+
+    // ==================== Validation Tests ====================
+
+    #[test]
+    fn test_traffic_light_is_valid() {
+        assert_eq!(validate(&STATES, &TRANSITIONS, RED), Ok(()));
+    }
+
+    #[test]
+    fn test_no_states() {
+        assert_eq!(Fsm::new(&[], &[], 0), Err(FsmError::NoStates));
+    }
+
+    #[test]
+    fn test_too_many_states() {
+        let states = [FsmState::new(Frame::new(), 10); MAX_FSM_STATES + 1];
+        assert_eq!(Fsm::new(&states, &[], 0), Err(FsmError::TooManyStates));
+    }
+
+    #[test]
+    fn test_invalid_initial() {
+        assert_eq!(
+            Fsm::new(&STATES, &TRANSITIONS, STATES.len()),
+            Err(FsmError::InvalidInitial)
+        );
+    }
+
+    #[test]
+    fn test_invalid_transition() {
+        let transitions = [
+            FsmTransition::new(0, 1, Guard::After),
+            FsmTransition::new(1, 7, Guard::After),
+        ];
+        assert_eq!(
+            validate(&STATES[..2], &transitions, 0),
+            Err(FsmError::InvalidTransition(1))
+        );
+    }
+
+    #[test]
+    fn test_unreachable_state() {
+        let transitions = [
+            FsmTransition::new(RED, RED_AMBER, Guard::After),
+            FsmTransition::new(RED_AMBER, RED, Guard::After),
+            FsmTransition::new(GREEN, RED, Guard::After),
+        ];
+        assert_eq!(
+            validate(&STATES[..3], &transitions, RED),
+            Err(FsmError::Unreachable(GREEN))
+        );
+    }
+
+    #[test]
+    fn test_reachable_through_chain() {
+        let transitions = [
+            FsmTransition::new(2, 0, Guard::After),
+            FsmTransition::new(1, 2, Guard::After),
+            FsmTransition::new(0, 1, Guard::After),
+        ];
+        assert_eq!(validate(&STATES[..3], &transitions, 0), Ok(()));
+    }
+
+    #[test]
+    fn test_single_state_needs_no_transitions() {
+        assert!(Fsm::new(&STATES[..1], &[], 0).is_ok());
+    }
+
+    // ==================== Timer Tests ====================
+
+    #[test]
+    fn test_starts_in_initial_state() {
+        let fsm = traffic_light();
+        assert_eq!(fsm.state(), RED);
+        assert_eq!(fsm.frame(), STATES[RED].frame);
+    }
+
+    #[test]
+    fn test_dwell_time() {
+        let mut fsm = traffic_light();
+        assert!(!fsm.tick(1990));
+        assert!(fsm.tick(10));
+        assert_eq!(fsm.state(), RED_AMBER);
+    }
+
+    #[test]
+    fn test_one_transition_per_tick() {
+        let mut fsm = traffic_light();
+        assert!(fsm.tick(60_000));
+        assert_eq!(fsm.state(), RED_AMBER);
+    }
+
+    #[test]
+    fn test_cycle_without_pedestrian() {
+        let mut fsm = traffic_light();
+        let visited = run(&mut fsm, &[Event::Wait(20_000)]);
+        assert_eq!(visited, [RED, RED_AMBER, GREEN]);
+    }
+
+    #[test]
+    fn test_green_waits_for_button() {
+        let mut fsm = traffic_light();
+        run(&mut fsm, &[Event::Wait(60_000)]);
+        assert_eq!(fsm.state(), GREEN);
+        assert_eq!(fsm.transitions_taken(), 2);
+    }
+
+    // ==================== Input Tests ====================
+
+    #[test]
+    fn test_pedestrian_crossing() {
+        let mut fsm = traffic_light();
+        let script = [Event::Wait(2500), Event::Press(BUTTON), Event::Wait(3000)];
+        assert_eq!(run(&mut fsm, &script), [RED, RED_AMBER, GREEN, AMBER]);
+        assert_eq!(fsm.inputs(), 0);
+    }
+
+    #[test]
+    fn test_walk_phase() {
+        let mut fsm = traffic_light();
+        let script = [
+            Event::Wait(2500),
+            Event::Press(BUTTON),
+            Event::Wait(3000),
+            Event::Press(BUTTON),
+            Event::Wait(5000),
+        ];
+        assert_eq!(
+            run(&mut fsm, &script),
+            [RED, RED_AMBER, GREEN, AMBER, WALK, RED]
+        );
+        assert_eq!(STATES[WALK].frame.level(3), 255);
+    }
+
+    #[test]
+    fn test_amber_without_button_returns_to_red() {
+        let transitions = [
+            FsmTransition::new(GREEN, AMBER, Guard::After),
+            FsmTransition::new(AMBER, WALK, Guard::AfterWithInput(BUTTON)),
+            FsmTransition::new(AMBER, RED, Guard::After),
+            FsmTransition::new(RED, GREEN, Guard::After),
+            FsmTransition::new(WALK, RED, Guard::After),
+            FsmTransition::new(RED, RED_AMBER, Guard::Input(0b10)),
+            FsmTransition::new(RED_AMBER, RED, Guard::After),
+        ];
+        let mut fsm = Fsm::new(&STATES, &transitions, GREEN).unwrap();
+        assert_eq!(run(&mut fsm, &[Event::Wait(3500)]), [GREEN, AMBER, RED]);
+    }
+
+    #[test]
+    fn test_input_guard_ignores_dwell() {
+        let transitions = [
+            FsmTransition::new(0, 1, Guard::Input(BUTTON)),
+            FsmTransition::new(1, 0, Guard::After),
+        ];
+        let mut fsm = Fsm::new(&STATES[..2], &transitions, 0).unwrap();
+        fsm.input(BUTTON);
+        assert!(fsm.tick(0));
+        assert_eq!(fsm.state(), 1);
+        assert_eq!(fsm.inputs(), 0);
+    }
+
+    #[test]
+    fn test_without_input_guard() {
+        let transitions = [
+            FsmTransition::new(0, 1, Guard::AfterWithoutInput(BUTTON)),
+            FsmTransition::new(0, 0, Guard::AfterWithInput(BUTTON)),
+            FsmTransition::new(1, 0, Guard::After),
+        ];
+        let mut fsm = Fsm::new(&STATES[..2], &transitions, 0).unwrap();
+        fsm.input(BUTTON);
+        fsm.tick(2000);
+        assert_eq!(fsm.state(), 0);
+        fsm.tick(2000);
+        assert_eq!(fsm.state(), 1);
+    }
+
+    #[test]
+    fn test_unrelated_input_stays_latched() {
+        let mut fsm = traffic_light();
+        fsm.input(0b10);
+        run(&mut fsm, &[Event::Wait(3000)]);
+        assert_eq!(fsm.inputs(), 0b10);
+    }
+
+    #[test]
+    fn test_table_order_decides() {
+        let transitions = [
+            FsmTransition::new(0, 2, Guard::After),
+            FsmTransition::new(0, 1, Guard::After),
+            FsmTransition::new(1, 0, Guard::After),
+            FsmTransition::new(2, 0, Guard::After),
+        ];
+        let mut fsm = Fsm::new(&STATES[..3], &transitions, 0).unwrap();
+        fsm.tick(2000);
+        assert_eq!(fsm.state(), 2);
+    }
+
+    #[test]
+    fn test_reset() {
+        let mut fsm = traffic_light();
+        run(&mut fsm, &[Event::Wait(3000), Event::Press(BUTTON)]);
+        fsm.reset();
+        assert_eq!(fsm.state(), RED);
+        assert_eq!(fsm.inputs(), 0);
+        assert!(!fsm.tick(1990));
+    }
+}
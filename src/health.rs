@@ -0,0 +1,513 @@
+/*
+ * @file health.rs
+ * @brief LED health self-test and report
+ * @author Kevin Thomas
+ * @date 2025
+ *
+ * MIT License
+ *
+ * Copyright (c) 2025 Kevin Thomas
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+//! FILE: health.rs
+//!
+//! DESCRIPTION:
+//! LED Health Self-Test with Open and Short Detection.
+//!
+//! BRIEF:
+//! Lights each LED alone while a sense resistor reading is taken.
+//! Compares each reading against an all-off baseline.
+//! Classifies each LED as ok, open circuit or shorted.
+//! Spreads frames over the usable LEDs so sequences skip faulty ones.
+//!
+//! AUTHOR: Kevin Thomas
+//! CREATION DATE: October 18, 2026
+//! UPDATE DATE: October 18, 2026
+
+use crate::config::{LED_COUNT, MAX_BRIGHTNESS};
+use crate::frame::Frame;
+
+/// LED health enumeration.
+///
+/// # Variants
+/// * `Untested` - No self-test result yet
+/// * `Ok` - Drew the expected current
+/// * `Open` - Drew too little current, open circuit or dead LED
+/// * `Short` - Drew too much current, shorted LED
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[allow(dead_code)]
+pub enum LedHealth {
+    Untested,
+    Ok,
+    Open,
+    Short,
+}
+
+impl LedHealth {
+    /// Returns whether sequences may use the LED.
+    ///
+    /// # Details
+    /// Untested LEDs are used until a self-test says otherwise.
+    ///
+    /// # Returns
+    /// * `bool` - true unless the LED is open or shorted
+    #[allow(dead_code)]
+    pub fn is_usable(self) -> bool {
+        matches!(self, LedHealth::Untested | LedHealth::Ok)
+    }
+}
+
+/// Health self-test error enumeration.
+///
+/// # Variants
+/// * `InvalidThresholds` - Open threshold not below short threshold
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[allow(dead_code)]
+pub enum HealthError {
+    InvalidThresholds,
+}
+
+/// Sense reading thresholds.
+///
+/// # Details
+/// Thresholds apply to the rise of a reading over the all-off
+/// baseline, in raw ADC counts.
+///
+/// # Fields
+/// * `open_below` - Rise below which the LED is open
+/// * `short_above` - Rise above which the LED is shorted
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct SenseThresholds {
+    open_below: u16,
+    short_above: u16,
+}
+
+impl SenseThresholds {
+    /// Creates new thresholds.
+    ///
+    /// # Arguments
+    /// * `open_below` - Rise below which the LED is open
+    /// * `short_above` - Rise above which the LED is shorted
+    ///
+    /// # Returns
+    /// * `Result<Self, HealthError>` - New SenseThresholds or error
+    #[allow(dead_code)]
+    pub fn new(open_below: u16, short_above: u16) -> Result<Self, HealthError> {
+        if open_below >= short_above {
+            return Err(HealthError::InvalidThresholds);
+        }
+        Ok(Self {
+            open_below,
+            short_above,
+        })
+    }
+
+    /// Classifies a sense reading.
+    ///
+    /// # Arguments
+    /// * `sample` - Reading with the LED lit
+    /// * `baseline` - Reading with every LED off
+    ///
+    /// # Returns
+    /// * `LedHealth` - Ok, Open or Short
+    #[allow(dead_code)]
+    pub fn classify(&self, sample: u16, baseline: u16) -> LedHealth {
+        let rise = sample.saturating_sub(baseline);
+        if rise < self.open_below {
+            LedHealth::Open
+        } else if rise > self.short_above {
+            LedHealth::Short
+        } else {
+            LedHealth::Ok
+        }
+    }
+}
+
+/// Per-LED health report.
+///
+/// # Fields
+/// * `leds` - Health of each LED
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[allow(dead_code)]
+pub struct HealthReport {
+    leds: [LedHealth; LED_COUNT],
+}
+
+impl Default for HealthReport {
+    /// Returns default HealthReport instance.
+    ///
+    /// # Details
+    /// Delegates to new() for initialization.
+    ///
+    /// # Returns
+    /// * `Self` - Report with every LED untested
+    #[allow(dead_code)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HealthReport {
+    /// Creates new report with every LED untested.
+    ///
+    /// # Returns
+    /// * `Self` - New HealthReport instance
+    #[allow(dead_code)]
+    pub const fn new() -> Self {
+        Self {
+            leds: [LedHealth::Untested; LED_COUNT],
+        }
+    }
+
+    /// Returns health of one LED.
+    ///
+    /// # Arguments
+    /// * `index` - LED index
+    ///
+    /// # Returns
+    /// * `LedHealth` - LED health, Untested if out of range
+    #[allow(dead_code)]
+    pub fn get(&self, index: usize) -> LedHealth {
+        self.leds.get(index).copied().unwrap_or(LedHealth::Untested)
+    }
+
+    /// Sets health of one LED.
+    ///
+    /// # Details
+    /// Out-of-range indices are ignored.
+    ///
+    /// # Arguments
+    /// * `index` - LED index
+    /// * `health` - LED health
+    #[allow(dead_code)]
+    pub fn set(&mut self, index: usize, health: LedHealth) {
+        if let Some(slot) = self.leds.get_mut(index) {
+            *slot = health;
+        }
+    }
+
+    /// Returns number of LEDs sequences may use.
+    ///
+    /// # Returns
+    /// * `usize` - Usable LEDs
+    #[allow(dead_code)]
+    pub fn usable_count(&self) -> usize {
+        self.leds.iter().filter(|health| health.is_usable()).count()
+    }
+
+    /// Returns faulty LEDs as a bit mask.
+    ///
+    /// # Returns
+    /// * `u8` - Bit n set if LED n is open or shorted
+    #[allow(dead_code)]
+    pub fn faulty_mask(&self) -> u8 {
+        self.leds
+            .iter()
+            .enumerate()
+            .filter(|(_, health)| !health.is_usable())
+            .fold(0, |mask, (index, _)| mask | 1 << index)
+    }
+
+    /// Spreads a frame rendered for the usable LEDs over the row.
+    ///
+    /// # Details
+    /// Logical LED n lands on the nth usable LED, so a pattern run
+    /// on usable_count() LEDs skips faulty ones without a gap.
+    ///
+    /// # Arguments
+    /// * `frame` - Frame for the usable LEDs
+    ///
+    /// # Returns
+    /// * `Frame` - Frame for the physical LEDs
+    #[allow(dead_code)]
+    pub fn spread(&self, frame: &Frame) -> Frame {
+        let mut out = Frame::new();
+        let usable = (0..LED_COUNT).filter(|&index| self.leds[index].is_usable());
+        for (logical, physical) in usable.enumerate() {
+            out.set_level(physical, frame.level(logical));
+        }
+        out
+    }
+
+    /// Turns off faulty LEDs in a frame.
+    ///
+    /// # Arguments
+    /// * `frame` - Frame for the physical LEDs
+    ///
+    /// # Returns
+    /// * `Frame` - Frame with faulty LEDs dark
+    #[allow(dead_code)]
+    pub fn mask(&self, frame: &Frame) -> Frame {
+        let mut out = *frame;
+        for index in (0..LED_COUNT).filter(|&index| !self.leds[index].is_usable()) {
+            out.set_level(index, 0);
+        }
+        out
+    }
+}
+
+/// Self-test step enumeration.
+///
+/// # Variants
+/// * `Baseline` - Every LED off
+/// * `Led` - One LED lit alone
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[allow(dead_code)]
+pub enum TestStep {
+    Baseline,
+    Led(usize),
+}
+
+/// LED health self-test runner.
+///
+/// # Details
+/// Holds no hardware. The caller shows frame(), lets the sense
+/// reading settle and passes it to record(), which moves to the
+/// next step.
+///
+/// # Fields
+/// * `thresholds` - Sense reading thresholds
+/// * `step` - Step being measured
+/// * `baseline` - Reading with every LED off
+/// * `report` - Results so far
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct SelfTest {
+    thresholds: SenseThresholds,
+    step: TestStep,
+    baseline: u16,
+    report: HealthReport,
+}
+
+impl SelfTest {
+    /// Creates new self-test at the baseline step.
+    ///
+    /// # Arguments
+    /// * `thresholds` - Sense reading thresholds
+    ///
+    /// # Returns
+    /// * `Self` - New SelfTest instance
+    #[allow(dead_code)]
+    pub fn new(thresholds: SenseThresholds) -> Self {
+        Self {
+            thresholds,
+            step: TestStep::Baseline,
+            baseline: 0,
+            report: HealthReport::new(),
+        }
+    }
+
+    /// Returns step being measured.
+    ///
+    /// # Returns
+    /// * `TestStep` - Current step
+    #[allow(dead_code)]
+    pub fn step(&self) -> TestStep {
+        self.step
+    }
+
+    /// Returns frame to show for the current step.
+    ///
+    /// # Returns
+    /// * `Frame` - Blank for the baseline, else one LED at full brightness
+    #[allow(dead_code)]
+    pub fn frame(&self) -> Frame {
+        match self.step {
+            TestStep::Baseline => Frame::new(),
+            TestStep::Led(index) => Frame::single(index, MAX_BRIGHTNESS),
+        }
+    }
+
+    /// Records the sense reading for the current step.
+    ///
+    /// # Arguments
+    /// * `sample` - Settled sense reading
+    ///
+    /// # Returns
+    /// * `Option<HealthReport>` - Report once the last LED is measured
+    #[allow(dead_code)]
+    pub fn record(&mut self, sample: u16) -> Option<HealthReport> {
+        match self.step {
+            TestStep::Baseline => self.baseline = sample,
+            TestStep::Led(index) => {
+                let health = self.thresholds.classify(sample, self.baseline);
+                self.report.set(index, health);
+            }
+        }
+        let next = match self.step {
+            TestStep::Baseline => 0,
+            TestStep::Led(index) => index + 1,
+        };
+        if next == LED_COUNT {
+            return Some(self.report);
+        }
+        self.step = TestStep::Led(next);
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn thresholds() -> SenseThresholds {
+        SenseThresholds::new(60, 600).unwrap()
+    }
+
+    // ==================== SenseThresholds Tests ====================
+
+    #[test]
+    fn test_thresholds_reject_overlap() {
+        assert_eq!(
+            SenseThresholds::new(600, 600),
+            Err(HealthError::InvalidThresholds)
+        );
+    }
+
+    #[test]
+    fn test_classify_ok() {
+        assert_eq!(thresholds().classify(300, 50), LedHealth::Ok);
+    }
+
+    #[test]
+    fn test_classify_open() {
+        assert_eq!(thresholds().classify(100, 50), LedHealth::Open);
+    }
+
+    #[test]
+    fn test_classify_short() {
+        assert_eq!(thresholds().classify(700, 50), LedHealth::Short);
+    }
+
+    #[test]
+    fn test_classify_below_baseline_is_open() {
+        assert_eq!(thresholds().classify(10, 50), LedHealth::Open);
+    }
+
+    #[test]
+    fn test_classify_bounds_ok() {
+        assert_eq!(thresholds().classify(60, 0), LedHealth::Ok);
+        assert_eq!(thresholds().classify(600, 0), LedHealth::Ok);
+    }
+
+    // ==================== HealthReport Tests ====================
+
+    #[test]
+    fn test_new_report_untested_and_usable() {
+        let report = HealthReport::new();
+        assert_eq!(report.get(0), LedHealth::Untested);
+        assert_eq!(report.usable_count(), LED_COUNT);
+        assert_eq!(report.faulty_mask(), 0);
+    }
+
+    #[test]
+    fn test_default_equals_new() {
+        assert_eq!(HealthReport::default(), HealthReport::new());
+    }
+
+    #[test]
+    fn test_set_out_of_range_ignored() {
+        let mut report = HealthReport::new();
+        report.set(LED_COUNT, LedHealth::Open);
+        assert_eq!(report, HealthReport::new());
+        assert_eq!(report.get(LED_COUNT), LedHealth::Untested);
+    }
+
+    #[test]
+    fn test_faulty_mask() {
+        let mut report = HealthReport::new();
+        report.set(1, LedHealth::Open);
+        report.set(3, LedHealth::Short);
+        assert_eq!(report.faulty_mask(), 0b1010);
+        assert_eq!(report.usable_count(), 2);
+    }
+
+    #[test]
+    fn test_spread_skips_faulty() {
+        let mut report = HealthReport::new();
+        report.set(1, LedHealth::Open);
+        let frame = Frame::from_levels([10, 20, 30, 40]);
+        assert_eq!(report.spread(&frame), Frame::from_levels([10, 0, 20, 30]));
+    }
+
+    #[test]
+    fn test_spread_all_healthy_unchanged() {
+        let frame = Frame::from_levels([10, 20, 30, 40]);
+        assert_eq!(HealthReport::new().spread(&frame), frame);
+    }
+
+    #[test]
+    fn test_mask_darkens_faulty() {
+        let mut report = HealthReport::new();
+        report.set(2, LedHealth::Short);
+        let frame = Frame::filled(MAX_BRIGHTNESS);
+        assert_eq!(
+            report.mask(&frame),
+            Frame::from_levels([MAX_BRIGHTNESS, MAX_BRIGHTNESS, 0, MAX_BRIGHTNESS])
+        );
+    }
+
+    // ==================== SelfTest Tests ====================
+
+    #[test]
+    fn test_self_test_starts_with_baseline() {
+        let test = SelfTest::new(thresholds());
+        assert_eq!(test.step(), TestStep::Baseline);
+        assert_eq!(test.frame(), Frame::new());
+    }
+
+    #[test]
+    fn test_self_test_lights_one_led_at_a_time() {
+        let mut test = SelfTest::new(thresholds());
+        test.record(50);
+        assert_eq!(test.step(), TestStep::Led(0));
+        assert_eq!(test.frame(), Frame::single(0, MAX_BRIGHTNESS));
+        test.record(300);
+        assert_eq!(test.frame(), Frame::single(1, MAX_BRIGHTNESS));
+    }
+
+    #[test]
+    fn test_self_test_report() {
+        let mut test = SelfTest::new(thresholds());
+        assert_eq!(test.record(50), None);
+        assert_eq!(test.record(300), None);
+        assert_eq!(test.record(60), None);
+        assert_eq!(test.record(900), None);
+        let report = test.record(310).unwrap();
+        assert_eq!(report.get(0), LedHealth::Ok);
+        assert_eq!(report.get(1), LedHealth::Open);
+        assert_eq!(report.get(2), LedHealth::Short);
+        assert_eq!(report.get(3), LedHealth::Ok);
+    }
+
+    #[test]
+    fn test_health_error_debug() {
+        assert_eq!(
+            format!("{:?}", HealthError::InvalidThresholds),
+            "InvalidThresholds"
+        );
+    }
+}
@@ -0,0 +1,444 @@
+/*
+ * @file idle.rs
+ * @brief Idle sleep planning and battery budget
+ * @author Kevin Thomas
+ * @date 2025
+ *
+ * MIT License
+ *
+ * Copyright (c) 2025 Kevin Thomas
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+//! FILE: idle.rs
+//!
+//! DESCRIPTION:
+//! Low-Power Idle Planning and Battery Budget for the Render Loop.
+//!
+//! BRIEF:
+//! Chooses how long the render loop sleeps from the sequencer idle time.
+//! Sleeps one frame at a time or until the output next changes.
+//! Tracks awake and asleep time to estimate average current.
+//! Estimates battery run time from the average current.
+//! Summarises both in an idle report.
+//!
+//! AUTHOR: Kevin Thomas
+//! CREATION DATE: October 18, 2026
+//! UPDATE DATE: October 18, 2026
+
+/// Sleep strategy enumeration.
+///
+/// # Variants
+/// * `EveryFrame` - Wake every frame interval
+/// * `UntilChange` - Sleep until the output next changes
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub enum SleepStrategy {
+    EveryFrame,
+    UntilChange,
+}
+
+/// Idle planner error enumeration.
+///
+/// # Variants
+/// * `ZeroFrame` - Frame interval of zero
+/// * `SleepBelowFrame` - Longest sleep shorter than one frame
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[allow(dead_code)]
+pub enum IdleError {
+    ZeroFrame,
+    SleepBelowFrame,
+}
+
+/// Render loop sleep planner.
+///
+/// # Details
+/// Sleeps are whole frame intervals so the loop stays on the
+/// frame grid and never oversleeps a pattern step.
+///
+/// # Fields
+/// * `strategy` - Sleep strategy
+/// * `frame_ms` - Frame interval in milliseconds
+/// * `max_sleep_ms` - Longest single sleep in milliseconds
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct IdlePlanner {
+    strategy: SleepStrategy,
+    frame_ms: u64,
+    max_sleep_ms: u64,
+}
+
+impl IdlePlanner {
+    /// Creates new planner.
+    ///
+    /// # Arguments
+    /// * `strategy` - Sleep strategy
+    /// * `frame_ms` - Frame interval in milliseconds
+    /// * `max_sleep_ms` - Longest single sleep in milliseconds
+    ///
+    /// # Returns
+    /// * `Result<Self, IdleError>` - New IdlePlanner or error
+    #[allow(dead_code)]
+    pub fn new(
+        strategy: SleepStrategy,
+        frame_ms: u64,
+        max_sleep_ms: u64,
+    ) -> Result<Self, IdleError> {
+        if frame_ms == 0 {
+            return Err(IdleError::ZeroFrame);
+        }
+        if max_sleep_ms < frame_ms {
+            return Err(IdleError::SleepBelowFrame);
+        }
+        Ok(Self {
+            strategy,
+            frame_ms,
+            max_sleep_ms,
+        })
+    }
+
+    /// Returns sleep strategy.
+    ///
+    /// # Returns
+    /// * `SleepStrategy` - Sleep strategy
+    #[allow(dead_code)]
+    pub fn strategy(&self) -> SleepStrategy {
+        self.strategy
+    }
+
+    /// Returns how long to sleep before the next frame.
+    ///
+    /// # Details
+    /// Rounds the idle time down to whole frames, at least one and
+    /// at most the longest sleep.
+    ///
+    /// # Arguments
+    /// * `idle_ms` - Time the output stays unchanged
+    ///
+    /// # Returns
+    /// * `u64` - Sleep time in milliseconds
+    #[allow(dead_code)]
+    pub fn plan(&self, idle_ms: u64) -> u64 {
+        match self.strategy {
+            SleepStrategy::EveryFrame => self.frame_ms,
+            SleepStrategy::UntilChange => {
+                let limit = self.max_sleep_ms - self.max_sleep_ms % self.frame_ms;
+                let frames = idle_ms - idle_ms % self.frame_ms;
+                frames.clamp(self.frame_ms, limit)
+            }
+        }
+    }
+}
+
+/// Awake and asleep time totals.
+///
+/// # Fields
+/// * `awake_us` - Time spent rendering in microseconds
+/// * `asleep_us` - Time spent sleeping in microseconds
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct IdleBudget {
+    awake_us: u64,
+    asleep_us: u64,
+}
+
+impl IdleBudget {
+    /// Creates new empty budget.
+    ///
+    /// # Returns
+    /// * `Self` - New IdleBudget instance
+    #[allow(dead_code)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records one awake and sleep cycle.
+    ///
+    /// # Arguments
+    /// * `awake_us` - Time spent rendering in microseconds
+    /// * `asleep_us` - Time spent sleeping in microseconds
+    #[allow(dead_code)]
+    pub fn record(&mut self, awake_us: u64, asleep_us: u64) {
+        self.awake_us = self.awake_us.saturating_add(awake_us);
+        self.asleep_us = self.asleep_us.saturating_add(asleep_us);
+    }
+
+    /// Clears the totals.
+    #[allow(dead_code)]
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Returns time spent rendering.
+    ///
+    /// # Returns
+    /// * `u64` - Awake time in microseconds
+    #[allow(dead_code)]
+    pub fn awake_us(&self) -> u64 {
+        self.awake_us
+    }
+
+    /// Returns time spent sleeping.
+    ///
+    /// # Returns
+    /// * `u64` - Asleep time in microseconds
+    #[allow(dead_code)]
+    pub fn asleep_us(&self) -> u64 {
+        self.asleep_us
+    }
+
+    /// Returns share of time spent sleeping.
+    ///
+    /// # Returns
+    /// * `u16` - Asleep share in tenths of a percent, 0 if nothing recorded
+    #[allow(dead_code)]
+    pub fn idle_permille(&self) -> u16 {
+        let total = u128::from(self.awake_us) + u128::from(self.asleep_us);
+        if total == 0 {
+            return 0;
+        }
+        (u128::from(self.asleep_us) * 1000 / total) as u16
+    }
+
+    /// Returns time-weighted average current.
+    ///
+    /// # Arguments
+    /// * `active_ma` - Current while rendering in milliamps
+    /// * `sleep_ma` - Current while sleeping in milliamps
+    ///
+    /// # Returns
+    /// * `u32` - Average current in microamps, 0 if nothing recorded
+    #[allow(dead_code)]
+    pub fn average_current_ua(&self, active_ma: u32, sleep_ma: u32) -> u32 {
+        let total = u128::from(self.awake_us) + u128::from(self.asleep_us);
+        if total == 0 {
+            return 0;
+        }
+        let charge = u128::from(self.awake_us) * u128::from(active_ma)
+            + u128::from(self.asleep_us) * u128::from(sleep_ma);
+        (charge * 1000 / total) as u32
+    }
+
+    /// Returns idle report for a board and battery.
+    ///
+    /// # Arguments
+    /// * `active_ma` - Current while rendering in milliamps
+    /// * `sleep_ma` - Current while sleeping in milliamps
+    /// * `capacity_mah` - Battery capacity in milliamp hours
+    ///
+    /// # Returns
+    /// * `IdleReport` - Asleep share, average current and run time
+    #[allow(dead_code)]
+    pub fn report(&self, active_ma: u32, sleep_ma: u32, capacity_mah: u32) -> IdleReport {
+        let average_ua = self.average_current_ua(active_ma, sleep_ma);
+        IdleReport {
+            idle_permille: self.idle_permille(),
+            average_ua,
+            battery_hours: battery_hours(capacity_mah, average_ua),
+        }
+    }
+}
+
+/// Idle time and battery estimate.
+///
+/// # Fields
+/// * `idle_permille` - Asleep share in tenths of a percent
+/// * `average_ua` - Average current in microamps
+/// * `battery_hours` - Run time in hours, None if nothing recorded
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct IdleReport {
+    pub idle_permille: u16,
+    pub average_ua: u32,
+    pub battery_hours: Option<u32>,
+}
+
+/// Estimates battery run time.
+///
+/// # Arguments
+/// * `capacity_mah` - Battery capacity in milliamp hours
+/// * `current_ua` - Average current in microamps
+///
+/// # Returns
+/// * `Option<u32>` - Run time in hours, None if no current is drawn
+#[allow(dead_code)]
+pub fn battery_hours(capacity_mah: u32, current_ua: u32) -> Option<u32> {
+    if current_ua == 0 {
+        return None;
+    }
+    Some((u64::from(capacity_mah) * 1000 / u64::from(current_ua)) as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // ==================== IdlePlanner Tests ====================
+
+    #[test]
+    fn test_new_rejects_zero_frame() {
+        assert_eq!(
+            IdlePlanner::new(SleepStrategy::UntilChange, 0, 400),
+            Err(IdleError::ZeroFrame)
+        );
+    }
+
+    #[test]
+    fn test_new_rejects_short_max_sleep() {
+        assert_eq!(
+            IdlePlanner::new(SleepStrategy::UntilChange, 10, 5),
+            Err(IdleError::SleepBelowFrame)
+        );
+    }
+
+    #[test]
+    fn test_strategy() {
+        let planner = IdlePlanner::new(SleepStrategy::EveryFrame, 10, 400).unwrap();
+        assert_eq!(planner.strategy(), SleepStrategy::EveryFrame);
+    }
+
+    #[test]
+    fn test_every_frame_ignores_idle() {
+        let planner = IdlePlanner::new(SleepStrategy::EveryFrame, 10, 400).unwrap();
+        assert_eq!(planner.plan(0), 10);
+        assert_eq!(planner.plan(u64::MAX), 10);
+    }
+
+    #[test]
+    fn test_until_change_at_least_one_frame() {
+        let planner = IdlePlanner::new(SleepStrategy::UntilChange, 10, 400).unwrap();
+        assert_eq!(planner.plan(0), 10);
+        assert_eq!(planner.plan(7), 10);
+    }
+
+    #[test]
+    fn test_until_change_rounds_down_to_frames() {
+        let planner = IdlePlanner::new(SleepStrategy::UntilChange, 10, 400).unwrap();
+        assert_eq!(planner.plan(95), 90);
+        assert_eq!(planner.plan(100), 100);
+    }
+
+    #[test]
+    fn test_until_change_capped() {
+        let planner = IdlePlanner::new(SleepStrategy::UntilChange, 10, 400).unwrap();
+        assert_eq!(planner.plan(u64::MAX), 400);
+    }
+
+    #[test]
+    fn test_until_change_cap_on_frame_grid() {
+        let planner = IdlePlanner::new(SleepStrategy::UntilChange, 30, 100).unwrap();
+        assert_eq!(planner.plan(1000), 90);
+    }
+
+    // ==================== IdleBudget Tests ====================
+
+    #[test]
+    fn test_new_budget_empty() {
+        let budget = IdleBudget::new();
+        assert_eq!(budget.awake_us(), 0);
+        assert_eq!(budget.asleep_us(), 0);
+        assert_eq!(budget.idle_permille(), 0);
+        assert_eq!(budget.average_current_ua(25, 2), 0);
+    }
+
+    #[test]
+    fn test_record_accumulates() {
+        let mut budget = IdleBudget::new();
+        budget.record(100, 900);
+        budget.record(100, 900);
+        assert_eq!(budget.awake_us(), 200);
+        assert_eq!(budget.asleep_us(), 1800);
+    }
+
+    #[test]
+    fn test_idle_permille() {
+        let mut budget = IdleBudget::new();
+        budget.record(250, 750);
+        assert_eq!(budget.idle_permille(), 750);
+    }
+
+    #[test]
+    fn test_average_current_weighted() {
+        let mut budget = IdleBudget::new();
+        budget.record(100, 900);
+        assert_eq!(budget.average_current_ua(20, 2), 3800);
+    }
+
+    #[test]
+    fn test_average_current_always_awake() {
+        let mut budget = IdleBudget::new();
+        budget.record(1000, 0);
+        assert_eq!(budget.average_current_ua(25, 2), 25_000);
+    }
+
+    #[test]
+    fn test_reset_clears() {
+        let mut budget = IdleBudget::new();
+        budget.record(1, 2);
+        budget.reset();
+        assert_eq!(budget, IdleBudget::new());
+    }
+
+    #[test]
+    fn test_record_saturates() {
+        let mut budget = IdleBudget::new();
+        budget.record(u64::MAX, u64::MAX);
+        budget.record(1, 1);
+        assert_eq!(budget.awake_us(), u64::MAX);
+        assert_eq!(budget.idle_permille(), 500);
+    }
+
+    // ==================== battery_hours() Tests ====================
+
+    #[test]
+    fn test_battery_hours() {
+        assert_eq!(battery_hours(2000, 4000), Some(500));
+    }
+
+    #[test]
+    fn test_battery_hours_no_current() {
+        assert_eq!(battery_hours(2000, 0), None);
+    }
+
+    // ==================== IdleReport Tests ====================
+
+    #[test]
+    fn test_report_combines_estimates() {
+        let mut budget = IdleBudget::new();
+        budget.record(100, 900);
+        let report = budget.report(20, 2, 2000);
+        assert_eq!(report.idle_permille, 900);
+        assert_eq!(report.average_ua, 3800);
+        assert_eq!(report.battery_hours, Some(526));
+    }
+
+    #[test]
+    fn test_report_empty_budget() {
+        let report = IdleBudget::new().report(25, 2, 2000);
+        assert_eq!(report.average_ua, 0);
+        assert_eq!(report.battery_hours, None);
+    }
+
+    #[test]
+    fn test_idle_error_debug() {
+        assert_eq!(format!("{:?}", IdleError::ZeroFrame), "ZeroFrame");
+    }
+}
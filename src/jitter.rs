@@ -0,0 +1,352 @@
+/*
+ * @file jitter.rs
+ * @brief Step timing jitter and overrun histogram
+ * @author Kevin Thomas
+ * @date 2025
+ *
+ * MIT License
+ *
+ * Copyright (c) 2025 Kevin Thomas
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+//! FILE: jitter.rs
+//!
+//! DESCRIPTION:
+//! Step Timing Jitter and Overrun Histogram.
+//!
+//! BRIEF:
+//! Records how late each step lands against its scheduled time.
+//! Sorts the lateness into fixed-width buckets to estimate the 99th percentile.
+//! Tracks min, max, mean and the count of steps over an overrun limit.
+//!
+//! AUTHOR: Kevin Thomas
+//! CREATION DATE: October 18, 2026
+//! UPDATE DATE: October 18, 2026
+
+/// Width of each histogram bucket in microseconds.
+#[allow(dead_code)]
+pub const JITTER_BUCKET_US: i64 = 250;
+
+/// Number of histogram buckets.
+///
+/// # Details
+/// The buckets cover 0 to 10 ms late. Early steps count in the
+/// first bucket and anything later in the last.
+#[allow(dead_code)]
+pub const JITTER_BUCKETS: usize = 40;
+
+/// Step lateness histogram.
+///
+/// # Details
+/// Lateness is the actual minus the scheduled time of a step in
+/// microseconds, negative when a step lands early. Min, max and
+/// mean are exact; the 99th percentile is the upper edge of its
+/// bucket.
+///
+/// # Fields
+/// * `buckets` - Step count per bucket
+/// * `count` - Steps recorded
+/// * `sum_us` - Sum of every lateness
+/// * `min_us` - Earliest step
+/// * `max_us` - Latest step
+/// * `overruns` - Steps later than the overrun limit
+/// * `overrun_us` - Lateness above which a step is an overrun
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[allow(dead_code)]
+pub struct JitterHistogram {
+    buckets: [u32; JITTER_BUCKETS],
+    count: u32,
+    sum_us: i64,
+    min_us: i64,
+    max_us: i64,
+    overruns: u32,
+    overrun_us: i64,
+}
+
+impl JitterHistogram {
+    /// Creates a new empty histogram.
+    ///
+    /// # Arguments
+    /// * `overrun_us` - Lateness above which a step is an overrun
+    ///
+    /// # Returns
+    /// * `Self` - New JitterHistogram instance
+    #[allow(dead_code)]
+    pub fn new(overrun_us: i64) -> Self {
+        Self {
+            buckets: [0; JITTER_BUCKETS],
+            count: 0,
+            sum_us: 0,
+            min_us: 0,
+            max_us: 0,
+            overruns: 0,
+            overrun_us,
+        }
+    }
+
+    /// Records one step.
+    ///
+    /// # Arguments
+    /// * `lateness_us` - Actual minus scheduled step time
+    #[allow(dead_code)]
+    pub fn record(&mut self, lateness_us: i64) {
+        if self.count == 0 {
+            self.min_us = lateness_us;
+            self.max_us = lateness_us;
+        } else {
+            self.min_us = self.min_us.min(lateness_us);
+            self.max_us = self.max_us.max(lateness_us);
+        }
+        self.count = self.count.saturating_add(1);
+        self.sum_us = self.sum_us.saturating_add(lateness_us);
+        let bucket = (lateness_us.max(0) / JITTER_BUCKET_US) as usize;
+        let bucket = bucket.min(JITTER_BUCKETS - 1);
+        self.buckets[bucket] = self.buckets[bucket].saturating_add(1);
+        if lateness_us > self.overrun_us {
+            self.overruns = self.overruns.saturating_add(1);
+        }
+    }
+
+    /// Clears every step, keeping the overrun limit.
+    #[allow(dead_code)]
+    pub fn reset(&mut self) {
+        *self = Self::new(self.overrun_us);
+    }
+
+    /// Returns steps recorded.
+    ///
+    /// # Returns
+    /// * `u32` - Step count
+    #[allow(dead_code)]
+    pub fn count(&self) -> u32 {
+        self.count
+    }
+
+    /// Returns the step count in one bucket.
+    ///
+    /// # Arguments
+    /// * `index` - Bucket index
+    ///
+    /// # Returns
+    /// * `u32` - Steps in the bucket, 0 if out of range
+    #[allow(dead_code)]
+    pub fn bucket(&self, index: usize) -> u32 {
+        self.buckets.get(index).copied().unwrap_or(0)
+    }
+
+    /// Returns the earliest step.
+    ///
+    /// # Returns
+    /// * `Option<i64>` - Lateness in microseconds, None before any step
+    #[allow(dead_code)]
+    pub fn min_us(&self) -> Option<i64> {
+        (self.count > 0).then_some(self.min_us)
+    }
+
+    /// Returns the latest step.
+    ///
+    /// # Returns
+    /// * `Option<i64>` - Lateness in microseconds, None before any step
+    #[allow(dead_code)]
+    pub fn max_us(&self) -> Option<i64> {
+        (self.count > 0).then_some(self.max_us)
+    }
+
+    /// Returns the mean lateness.
+    ///
+    /// # Returns
+    /// * `Option<i64>` - Lateness in microseconds, None before any step
+    #[allow(dead_code)]
+    pub fn mean_us(&self) -> Option<i64> {
+        (self.count > 0).then(|| self.sum_us / i64::from(self.count))
+    }
+
+    /// Returns the 99th percentile lateness.
+    ///
+    /// # Details
+    /// Finds the bucket holding the 99th percentile step and returns
+    /// its upper edge, capped at the latest step so a lone bucket
+    /// never reads later than anything recorded. The open-ended
+    /// last bucket reads as the latest step.
+    ///
+    /// # Returns
+    /// * `Option<i64>` - Lateness in microseconds, None before any step
+    #[allow(dead_code)]
+    pub fn p99_us(&self) -> Option<i64> {
+        if self.count == 0 {
+            return None;
+        }
+        let rank = (u64::from(self.count) * 99).div_ceil(100);
+        let mut seen = 0u64;
+        for (index, &steps) in self.buckets.iter().enumerate() {
+            seen += u64::from(steps);
+            if seen >= rank && index + 1 < JITTER_BUCKETS {
+                let upper = (index as i64 + 1) * JITTER_BUCKET_US;
+                return Some(upper.min(self.max_us));
+            }
+        }
+        Some(self.max_us)
+    }
+
+    /// Returns steps later than the overrun limit.
+    ///
+    /// # Returns
+    /// * `u32` - Overrun count
+    #[allow(dead_code)]
+    pub fn overruns(&self) -> u32 {
+        self.overruns
+    }
+
+    /// Returns the overrun limit.
+    ///
+    /// # Returns
+    /// * `i64` - Lateness in microseconds above which a step is an overrun
+    #[allow(dead_code)]
+    pub fn overrun_us(&self) -> i64 {
+        self.overrun_us
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn histogram() -> JitterHistogram {
+        JitterHistogram::new(10_000)
+    }
+
+    // ==================== record() Tests ====================
+
+    #[test]
+    fn test_new_empty() {
+        let jitter = histogram();
+        assert_eq!(jitter.count(), 0);
+        assert_eq!(jitter.min_us(), None);
+        assert_eq!(jitter.max_us(), None);
+        assert_eq!(jitter.mean_us(), None);
+        assert_eq!(jitter.p99_us(), None);
+        assert_eq!(jitter.overruns(), 0);
+    }
+
+    #[test]
+    fn test_record_min_max_mean() {
+        let mut jitter = histogram();
+        for lateness in [100, 300, 500] {
+            jitter.record(lateness);
+        }
+        assert_eq!(jitter.count(), 3);
+        assert_eq!(jitter.min_us(), Some(100));
+        assert_eq!(jitter.max_us(), Some(500));
+        assert_eq!(jitter.mean_us(), Some(300));
+    }
+
+    #[test]
+    fn test_record_early_step() {
+        let mut jitter = histogram();
+        jitter.record(-40);
+        jitter.record(60);
+        assert_eq!(jitter.min_us(), Some(-40));
+        assert_eq!(jitter.mean_us(), Some(10));
+        assert_eq!(jitter.bucket(0), 2);
+    }
+
+    #[test]
+    fn test_record_buckets() {
+        let mut jitter = histogram();
+        jitter.record(0);
+        jitter.record(249);
+        jitter.record(250);
+        jitter.record(1_000);
+        assert_eq!(jitter.bucket(0), 2);
+        assert_eq!(jitter.bucket(1), 1);
+        assert_eq!(jitter.bucket(4), 1);
+    }
+
+    #[test]
+    fn test_record_late_step_in_last_bucket() {
+        let mut jitter = histogram();
+        jitter.record(1_000_000);
+        assert_eq!(jitter.bucket(JITTER_BUCKETS - 1), 1);
+    }
+
+    #[test]
+    fn test_bucket_out_of_range() {
+        assert_eq!(histogram().bucket(JITTER_BUCKETS), 0);
+    }
+
+    // ==================== Overrun Tests ====================
+
+    #[test]
+    fn test_overrun_counted_above_limit() {
+        let mut jitter = histogram();
+        jitter.record(10_000);
+        jitter.record(10_001);
+        assert_eq!(jitter.overruns(), 1);
+    }
+
+    #[test]
+    fn test_reset_keeps_limit() {
+        let mut jitter = histogram();
+        jitter.record(20_000);
+        jitter.reset();
+        assert_eq!(jitter, histogram());
+        assert_eq!(jitter.overrun_us(), 10_000);
+    }
+
+    // ==================== p99_us() Tests ====================
+
+    #[test]
+    fn test_p99_ignores_rare_outlier() {
+        let mut jitter = histogram();
+        for _ in 0..199 {
+            jitter.record(100);
+        }
+        jitter.record(5_000);
+        assert_eq!(jitter.p99_us(), Some(250));
+        assert_eq!(jitter.max_us(), Some(5_000));
+    }
+
+    #[test]
+    fn test_p99_catches_one_percent() {
+        let mut jitter = histogram();
+        for _ in 0..98 {
+            jitter.record(100);
+        }
+        jitter.record(3_100);
+        jitter.record(3_100);
+        assert_eq!(jitter.p99_us(), Some(3_100));
+    }
+
+    #[test]
+    fn test_p99_capped_at_max() {
+        let mut jitter = histogram();
+        jitter.record(10);
+        assert_eq!(jitter.p99_us(), Some(10));
+    }
+
+    #[test]
+    fn test_p99_in_last_bucket_is_max() {
+        let mut jitter = histogram();
+        jitter.record(50_000);
+        assert_eq!(jitter.p99_us(), Some(50_000));
+    }
+}
@@ -35,12 +35,15 @@
 //! BRIEF:
 //! Provides LED sequence controller for blinking multiple LEDs.
 //! Manages sequential LED activation with configurable timing.
+//! Supports pause, reverse playback, single steps and seeking.
 //!
 //! AUTHOR: Kevin Thomas
 //! CREATION DATE: December 7, 2025
-//! UPDATE DATE: December 7, 2025
+//! UPDATE DATE: October 18, 2026
 
-use crate::config::{LED_COUNT, SEQUENCE_DELAY_MS};
+use crate::config::{LED_COUNT, MAX_SEQUENCE_DELAY_MS, MIN_SEQUENCE_DELAY_MS, SEQUENCE_DELAY_MS};
+use crate::frame::Frame;
+use crate::pattern::Pattern;
 
 /// LED state enumeration.
 ///
@@ -52,28 +55,52 @@ use crate::config::{LED_COUNT, SEQUENCE_DELAY_MS};
 /// * `On` - LED is currently on (high)
 /// * `Off` - LED is currently off (low)
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[allow(dead_code)]
 pub enum LedState {
     On,
     Off,
 }
 
+/// Playback direction enumeration.
+///
+/// # Variants
+/// * `Forward` - Steps count up through the pattern cycle
+/// * `Reverse` - Steps count down through the pattern cycle
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[allow(dead_code)]
+pub enum Direction {
+    Forward,
+    Reverse,
+}
+
 /// LED sequence controller with state tracking.
 ///
 /// # Details
 /// Maintains LED sequence state and timing configuration.
 /// Provides methods for advancing through LED sequence.
+/// Timed playback goes through advance(), which honours pause and
+/// direction. Manual moves such as step_back() and seek() work
+/// even while paused, so a frozen sequence can be scrubbed.
 ///
 /// # Fields
-/// * `current_index` - Index of currently active LED (0 to LED_COUNT-1)
+/// * `current_index` - Current step within the pattern cycle
 /// * `led_count` - Total number of LEDs in sequence
 /// * `delay_ms` - Delay between LED transitions in milliseconds
+/// * `pattern` - Pattern rendered at each step
+/// * `direction` - Direction of timed playback
+/// * `paused` - Whether timed playback is frozen
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[allow(dead_code)]
 pub struct LedSequenceController {
     current_index: usize,
     led_count: usize,
     delay_ms: u64,
+    pattern: Pattern,
+    direction: Direction,
+    paused: bool,
 }
 
 impl Default for LedSequenceController {
@@ -94,33 +121,152 @@ impl LedSequenceController {
     /// Creates new LED sequence controller with default settings.
     ///
     /// # Details
-    /// Initializes controller starting at first LED (index 0).
+    /// Initializes controller starting at first LED (index 0)
+    /// running the chase pattern.
     ///
     /// # Returns
     /// * `Self` - New LedSequenceController instance
     #[allow(dead_code)]
     pub fn new() -> Self {
+        Self::with_pattern(Pattern::Chase)
+    }
+
+    /// Creates new LED sequence controller running given pattern.
+    ///
+    /// # Details
+    /// Uses default delay and starts at the first step.
+    ///
+    /// # Arguments
+    /// * `pattern` - Pattern to render
+    ///
+    /// # Returns
+    /// * `Self` - New LedSequenceController instance
+    #[allow(dead_code)]
+    pub fn with_pattern(pattern: Pattern) -> Self {
         Self {
             current_index: 0,
             led_count: LED_COUNT,
             delay_ms: SEQUENCE_DELAY_MS,
+            pattern,
+            direction: Direction::Forward,
+            paused: false,
         }
     }
 
     /// Advances to next LED in sequence and returns new index.
     ///
     /// # Details
-    /// Moves to next LED, wrapping around to first LED after last.
+    /// Moves one step in the playback direction, wrapping around at
+    /// either end of the pattern cycle. Does nothing while paused.
     /// Implements circular sequence behavior.
     ///
     /// # Returns
     /// * `usize` - New LED index after advancement
     #[allow(dead_code)]
     pub fn advance(&mut self) -> usize {
-        self.current_index = (self.current_index + 1) % self.led_count;
+        if self.paused {
+            return self.current_index;
+        }
+        match self.direction {
+            Direction::Forward => self.step_forward(),
+            Direction::Reverse => self.step_back(),
+        }
+    }
+
+    /// Moves one step forward and returns new index.
+    ///
+    /// # Details
+    /// Ignores pause and direction. Wraps to the first step after
+    /// the last step of the pattern cycle.
+    ///
+    /// # Returns
+    /// * `usize` - New LED index after the step
+    #[allow(dead_code)]
+    pub fn step_forward(&mut self) -> usize {
+        self.seek(self.current_index + 1)
+    }
+
+    /// Moves one step back and returns new index.
+    ///
+    /// # Details
+    /// Ignores pause and direction. Wraps to the last step of the
+    /// pattern cycle before the first step.
+    ///
+    /// # Returns
+    /// * `usize` - New LED index after the step
+    #[allow(dead_code)]
+    pub fn step_back(&mut self) -> usize {
+        let cycle_len = self.pattern.cycle_len_on(self.led_count);
+        self.seek(self.current_index + cycle_len - 1)
+    }
+
+    /// Moves to a step and returns new index.
+    ///
+    /// # Details
+    /// Ignores pause and direction. Wraps the index to the pattern cycle.
+    ///
+    /// # Arguments
+    /// * `index` - Requested step
+    ///
+    /// # Returns
+    /// * `usize` - New LED index after the seek
+    #[allow(dead_code)]
+    pub fn seek(&mut self, index: usize) -> usize {
+        self.current_index = index % self.pattern.cycle_len_on(self.led_count);
         self.current_index
     }
 
+    /// Restarts playback.
+    ///
+    /// # Details
+    /// Returns to the first step, playing forward and unpaused.
+    /// Keeps the pattern and delay.
+    #[allow(dead_code)]
+    pub fn reset(&mut self) {
+        self.current_index = 0;
+        self.direction = Direction::Forward;
+        self.paused = false;
+    }
+
+    /// Freezes timed playback on the current step.
+    #[allow(dead_code)]
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    /// Continues timed playback from the current step.
+    #[allow(dead_code)]
+    pub fn resume(&mut self) {
+        self.paused = false;
+    }
+
+    /// Returns whether timed playback is frozen.
+    ///
+    /// # Returns
+    /// * `bool` - true while paused
+    #[allow(dead_code)]
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Returns playback direction.
+    ///
+    /// # Returns
+    /// * `Direction` - Direction of timed playback
+    #[allow(dead_code)]
+    pub fn direction(&self) -> Direction {
+        self.direction
+    }
+
+    /// Sets playback direction.
+    ///
+    /// # Arguments
+    /// * `direction` - Direction of timed playback
+    #[allow(dead_code)]
+    pub fn set_direction(&mut self, direction: Direction) {
+        self.direction = direction;
+    }
+
     /// Returns current LED index.
     ///
     /// # Details
@@ -145,6 +291,20 @@ impl LedSequenceController {
         self.led_count
     }
 
+    /// Sets number of LEDs the pattern runs on.
+    ///
+    /// # Details
+    /// Lets patterns skip LEDs that cannot be used. The count is
+    /// capped at LED_COUNT and the step wraps to the new cycle.
+    ///
+    /// # Arguments
+    /// * `led_count` - LEDs in the sequence
+    #[allow(dead_code)]
+    pub fn set_led_count(&mut self, led_count: usize) {
+        self.led_count = led_count.min(LED_COUNT);
+        self.seek(self.current_index);
+    }
+
     /// Returns current sequence delay.
     ///
     /// # Details
@@ -157,23 +317,64 @@ impl LedSequenceController {
         self.delay_ms
     }
 
+    /// Sets sequence delay.
+    ///
+    /// # Details
+    /// Clamps the delay to MIN_SEQUENCE_DELAY_MS..=MAX_SEQUENCE_DELAY_MS.
+    ///
+    /// # Arguments
+    /// * `delay_ms` - Requested delay in milliseconds
+    #[allow(dead_code)]
+    pub fn set_delay_ms(&mut self, delay_ms: u64) {
+        self.delay_ms = delay_ms.clamp(MIN_SEQUENCE_DELAY_MS, MAX_SEQUENCE_DELAY_MS);
+    }
+
+    /// Returns active pattern.
+    ///
+    /// # Returns
+    /// * `Pattern` - Pattern rendered at each step
+    #[allow(dead_code)]
+    pub fn pattern(&self) -> Pattern {
+        self.pattern
+    }
+
+    /// Switches to a new pattern.
+    ///
+    /// # Details
+    /// Restarts the sequence from the first step.
+    /// Keeps the direction and pause state.
+    ///
+    /// # Arguments
+    /// * `pattern` - Pattern to render
+    #[allow(dead_code)]
+    pub fn set_pattern(&mut self, pattern: Pattern) {
+        self.pattern = pattern;
+        self.current_index = 0;
+    }
+
+    /// Returns frame for the current step.
+    ///
+    /// # Returns
+    /// * `Frame` - Rendered LED frame
+    #[allow(dead_code)]
+    pub fn frame(&self) -> Frame {
+        self.pattern.frame_on(self.current_index, self.led_count)
+    }
+
     /// Returns LED state for given index.
     ///
     /// # Details
-    /// Returns On if index matches current, Off otherwise.
+    /// Returns On if the LED is lit in the current frame, Off otherwise.
+    /// For the chase pattern only the current index is On.
     ///
     /// # Arguments
     /// * `index` - LED index to check
     ///
     /// # Returns
-    /// * `LedState` - On if current, Off otherwise
+    /// * `LedState` - On if lit, Off otherwise
     #[allow(dead_code)]
     pub fn led_state(&self, index: usize) -> LedState {
-        if index == self.current_index {
-            LedState::On
-        } else {
-            LedState::Off
-        }
+        self.frame().led_state(index)
     }
 }
 
@@ -272,12 +473,12 @@ mod tests {
 
     #[test]
     fn test_led_state_to_level_on_returns_true() {
-        assert_eq!(led_state_to_level(LedState::On), true);
+        assert!(led_state_to_level(LedState::On));
     }
 
     #[test]
     fn test_led_state_to_level_off_returns_false() {
-        assert_eq!(led_state_to_level(LedState::Off), false);
+        assert!(!led_state_to_level(LedState::Off));
     }
 
     #[test]
@@ -315,6 +516,9 @@ mod tests {
             current_index: 0,
             led_count: LED_COUNT,
             delay_ms: SEQUENCE_DELAY_MS,
+            pattern: Pattern::Chase,
+            direction: Direction::Forward,
+            paused: false,
         };
         assert_eq!(ctrl, expected);
     }
@@ -454,6 +658,30 @@ mod tests {
         assert_eq!(ctrl.led_count(), 4);
     }
 
+    #[test]
+    fn test_set_led_count_shortens_cycle() {
+        let mut ctrl = LedSequenceController::new();
+        ctrl.set_led_count(3);
+        ctrl.seek(2);
+        assert_eq!(ctrl.advance(), 0);
+        assert_eq!(ctrl.led_count(), 3);
+    }
+
+    #[test]
+    fn test_set_led_count_wraps_step() {
+        let mut ctrl = LedSequenceController::new();
+        ctrl.seek(3);
+        ctrl.set_led_count(2);
+        assert_eq!(ctrl.current_index(), 1);
+    }
+
+    #[test]
+    fn test_set_led_count_capped() {
+        let mut ctrl = LedSequenceController::new();
+        ctrl.set_led_count(LED_COUNT + 1);
+        assert_eq!(ctrl.led_count(), LED_COUNT);
+    }
+
     #[test]
     fn test_led_count_immutable_after_advance() {
         let mut ctrl = LedSequenceController::new();
@@ -540,6 +768,9 @@ mod tests {
             current_index: 0,
             led_count: LED_COUNT,
             delay_ms: SEQUENCE_DELAY_MS,
+            pattern: Pattern::Chase,
+            direction: Direction::Forward,
+            paused: false,
         };
         assert_eq!(ctrl, expected);
     }
@@ -650,10 +881,10 @@ mod tests {
         let mut ctrl = LedSequenceController::new();
         for _ in 0..LED_COUNT * 3 {
             let current = ctrl.current_index();
-            assert_eq!(led_state_to_level(ctrl.led_state(current)), true);
+            assert!(led_state_to_level(ctrl.led_state(current)));
             for i in 0..LED_COUNT {
                 if i != current {
-                    assert_eq!(led_state_to_level(ctrl.led_state(i)), false);
+                    assert!(!led_state_to_level(ctrl.led_state(i)));
                 }
             }
             ctrl.advance();
@@ -674,15 +905,247 @@ mod tests {
     #[test]
     fn test_controller_in_option() {
         let maybe_ctrl: Option<LedSequenceController> = Some(LedSequenceController::new());
-        assert!(maybe_ctrl.is_some());
-        assert_eq!(maybe_ctrl.unwrap().delay_ms(), SEQUENCE_DELAY_MS);
+        assert_eq!(
+            maybe_ctrl.map(|ctrl| ctrl.delay_ms()),
+            Some(SEQUENCE_DELAY_MS)
+        );
     }
 
     #[test]
     fn test_controller_in_result() {
         let result: Result<LedSequenceController, ()> = Ok(LedSequenceController::new());
-        assert!(result.is_ok());
-        assert_eq!(result.unwrap().delay_ms(), SEQUENCE_DELAY_MS);
+        assert_eq!(result.map(|ctrl| ctrl.delay_ms()), Ok(SEQUENCE_DELAY_MS));
+    }
+
+    // ==================== Pattern Tests ====================
+
+    #[test]
+    fn test_new_uses_chase() {
+        let ctrl = LedSequenceController::new();
+        assert_eq!(ctrl.pattern(), Pattern::Chase);
+    }
+
+    #[test]
+    fn test_with_pattern() {
+        let ctrl = LedSequenceController::with_pattern(Pattern::Breathe);
+        assert_eq!(ctrl.pattern(), Pattern::Breathe);
+        assert_eq!(ctrl.current_index(), 0);
+        assert_eq!(ctrl.delay_ms(), SEQUENCE_DELAY_MS);
+    }
+
+    #[test]
+    fn test_set_pattern_restarts() {
+        let mut ctrl = LedSequenceController::new();
+        ctrl.advance();
+        ctrl.set_pattern(Pattern::Bounce);
+        assert_eq!(ctrl.current_index(), 0);
+        assert_eq!(ctrl.pattern(), Pattern::Bounce);
+    }
+
+    #[test]
+    fn test_advance_wraps_at_pattern_cycle() {
+        let mut ctrl = LedSequenceController::with_pattern(Pattern::Bounce);
+        for _ in 0..Pattern::Bounce.cycle_len() {
+            ctrl.advance();
+        }
+        assert_eq!(ctrl.current_index(), 0);
+    }
+
+    #[test]
+    fn test_frame_matches_pattern() {
+        let mut ctrl = LedSequenceController::with_pattern(Pattern::Fill);
+        ctrl.advance();
+        ctrl.advance();
+        assert_eq!(ctrl.frame(), Pattern::Fill.frame(2));
+    }
+
+    #[test]
+    fn test_led_state_follows_frame() {
+        let mut ctrl = LedSequenceController::with_pattern(Pattern::Fill);
+        ctrl.advance();
+        ctrl.advance();
+        assert_eq!(ctrl.led_state(1), LedState::On);
+        assert_eq!(ctrl.led_state(2), LedState::Off);
+    }
+
+    // ==================== Playback Control Tests ====================
+
+    const ALL_PATTERNS: [Pattern; 8] = [
+        Pattern::Chase,
+        Pattern::Bounce,
+        Pattern::Fill,
+        Pattern::Breathe,
+        Pattern::Blink,
+        Pattern::Alternate,
+        Pattern::Solid,
+        Pattern::Blank,
+    ];
+
+    #[test]
+    fn test_new_plays_forward() {
+        let ctrl = LedSequenceController::new();
+        assert_eq!(ctrl.direction(), Direction::Forward);
+        assert!(!ctrl.is_paused());
+    }
+
+    #[test]
+    fn test_pause_freezes_advance() {
+        for pattern in ALL_PATTERNS {
+            let mut ctrl = LedSequenceController::with_pattern(pattern);
+            ctrl.seek(1);
+            let index = ctrl.current_index();
+            ctrl.pause();
+            assert!(ctrl.is_paused());
+            for _ in 0..10 {
+                assert_eq!(ctrl.advance(), index);
+            }
+            assert_eq!(ctrl.frame(), pattern.frame(index));
+        }
+    }
+
+    #[test]
+    fn test_resume_continues_from_paused_step() {
+        let mut ctrl = LedSequenceController::new();
+        ctrl.advance();
+        ctrl.pause();
+        ctrl.advance();
+        ctrl.resume();
+        assert!(!ctrl.is_paused());
+        assert_eq!(ctrl.advance(), 2);
+    }
+
+    #[test]
+    fn test_step_back_wraps_to_last() {
+        for pattern in ALL_PATTERNS {
+            let mut ctrl = LedSequenceController::with_pattern(pattern);
+            assert_eq!(ctrl.step_back(), pattern.cycle_len() - 1);
+        }
+    }
+
+    #[test]
+    fn test_step_back_undoes_step_forward() {
+        for pattern in ALL_PATTERNS {
+            let mut ctrl = LedSequenceController::with_pattern(pattern);
+            for start in 0..pattern.cycle_len() {
+                ctrl.seek(start);
+                ctrl.step_forward();
+                assert_eq!(ctrl.step_back(), start);
+            }
+        }
+    }
+
+    #[test]
+    fn test_manual_steps_ignore_pause() {
+        let mut ctrl = LedSequenceController::new();
+        ctrl.pause();
+        assert_eq!(ctrl.step_forward(), 1);
+        assert_eq!(ctrl.step_forward(), 2);
+        assert_eq!(ctrl.step_back(), 1);
+        assert!(ctrl.is_paused());
+    }
+
+    #[test]
+    fn test_reverse_advance_counts_down() {
+        let mut ctrl = LedSequenceController::new();
+        ctrl.set_direction(Direction::Reverse);
+        assert_eq!(ctrl.direction(), Direction::Reverse);
+        assert_eq!(ctrl.advance(), LED_COUNT - 1);
+        assert_eq!(ctrl.advance(), LED_COUNT - 2);
+    }
+
+    #[test]
+    fn test_reverse_visits_cycle_backwards() {
+        for pattern in ALL_PATTERNS {
+            let cycle_len = pattern.cycle_len();
+            let mut ctrl = LedSequenceController::with_pattern(pattern);
+            ctrl.set_direction(Direction::Reverse);
+            for expected in (0..cycle_len).rev() {
+                assert_eq!(ctrl.advance(), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_reverse_paused_holds() {
+        let mut ctrl = LedSequenceController::new();
+        ctrl.set_direction(Direction::Reverse);
+        ctrl.pause();
+        assert_eq!(ctrl.advance(), 0);
+    }
+
+    #[test]
+    fn test_seek_sets_index() {
+        for pattern in ALL_PATTERNS {
+            let mut ctrl = LedSequenceController::with_pattern(pattern);
+            let last = pattern.cycle_len() - 1;
+            assert_eq!(ctrl.seek(last), last);
+            assert_eq!(ctrl.frame(), pattern.frame(last));
+        }
+    }
+
+    #[test]
+    fn test_seek_wraps() {
+        let mut ctrl = LedSequenceController::with_pattern(Pattern::Blink);
+        assert_eq!(ctrl.seek(5), 1);
+        assert_eq!(ctrl.seek(4), 0);
+    }
+
+    #[test]
+    fn test_seek_keeps_pause() {
+        let mut ctrl = LedSequenceController::new();
+        ctrl.pause();
+        ctrl.seek(3);
+        assert!(ctrl.is_paused());
+        assert_eq!(ctrl.advance(), 3);
+    }
+
+    #[test]
+    fn test_reset_restores_playback() {
+        let mut ctrl = LedSequenceController::with_pattern(Pattern::Fill);
+        ctrl.set_delay_ms(100);
+        ctrl.seek(3);
+        ctrl.set_direction(Direction::Reverse);
+        ctrl.pause();
+        ctrl.reset();
+        assert_eq!(ctrl.current_index(), 0);
+        assert_eq!(ctrl.direction(), Direction::Forward);
+        assert!(!ctrl.is_paused());
+        assert_eq!(ctrl.pattern(), Pattern::Fill);
+        assert_eq!(ctrl.delay_ms(), 100);
+    }
+
+    #[test]
+    fn test_set_pattern_keeps_direction_and_pause() {
+        let mut ctrl = LedSequenceController::new();
+        ctrl.set_direction(Direction::Reverse);
+        ctrl.pause();
+        ctrl.set_pattern(Pattern::Bounce);
+        assert_eq!(ctrl.current_index(), 0);
+        assert_eq!(ctrl.direction(), Direction::Reverse);
+        assert!(ctrl.is_paused());
+    }
+
+    // ==================== set_delay_ms() Tests ====================
+
+    #[test]
+    fn test_set_delay_ms() {
+        let mut ctrl = LedSequenceController::new();
+        ctrl.set_delay_ms(100);
+        assert_eq!(ctrl.delay_ms(), 100);
+    }
+
+    #[test]
+    fn test_set_delay_ms_clamps_low() {
+        let mut ctrl = LedSequenceController::new();
+        ctrl.set_delay_ms(0);
+        assert_eq!(ctrl.delay_ms(), MIN_SEQUENCE_DELAY_MS);
+    }
+
+    #[test]
+    fn test_set_delay_ms_clamps_high() {
+        let mut ctrl = LedSequenceController::new();
+        ctrl.set_delay_ms(u64::MAX);
+        assert_eq!(ctrl.delay_ms(), MAX_SEQUENCE_DELAY_MS);
     }
 
     #[test]
@@ -38,8 +38,35 @@
 //!
 //! AUTHOR: Kevin Thomas
 //! CREATION DATE: December 7, 2025
-//! UPDATE DATE: December 7, 2025
+//! UPDATE DATE: October 18, 2026
 
 #![cfg_attr(not(test), no_std)]
+pub mod bargraph;
+pub mod binclock;
+pub mod clock;
+pub mod compositor;
 pub mod config;
+pub mod console;
+pub mod counter;
+pub mod dmx;
+pub mod effects;
+pub mod frame;
+pub mod fsm;
+pub mod health;
+pub mod idle;
+pub mod jitter;
 pub mod led;
+pub mod midi;
+pub mod morse;
+pub mod pattern;
+pub mod playlist;
+pub mod power;
+pub mod rng;
+pub mod schedule;
+pub mod sequencer;
+pub mod sync;
+pub mod timeline;
+pub mod usage;
+pub mod vu;
+pub mod wallclock;
+pub mod watchdog;
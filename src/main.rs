@@ -35,66 +35,1253 @@
 //! BRIEF:
 //! Main application entry point for RP2350 GPIO LED sequence driver using Embassy.
 //! Implements async sequential LED blinking on GPIO pins 16, 17, 18, 19.
+//! Plays a shuffled kiosk playlist of patterns over a slow breathing background.
+//! Seeds the shuffle from the RP2350 hardware TRNG.
+//! Feeds the watchdog only while frames land on schedule.
+//! Optionally runs the sequencer on core 1 with the control plane on core 0.
+//! Accepts sequencer commands and publishes state changes for other tasks.
+//! Steps on an external clock input and clocks out for daisy-chained boards.
+//! Optionally synchronises a row of boards over UART as one long chase.
+//! Optionally follows a DMX512 lighting desk with fallback on signal loss.
+//! Optionally plays MIDI notes on the LEDs in time with MIDI clock.
+//! Optionally shows a microphone level meter or steps the chase on beats.
+//! Optionally runs a traffic light state machine with a pedestrian button.
+//! Dims to a calm pattern overnight once the wall clock is set.
+//! Keeps LED current within the supply budget.
+//! Optionally sleeps through pauses and long steps for battery builds.
+//! Optionally self-tests the LEDs and steers patterns around faulty ones.
+//! Keeps per-LED usage statistics and saves them to flash hourly.
+//! Measures step jitter and overruns for timing checks on demand.
+//! Takes text commands and prints status changes and reports on a UART console.
+//! Optionally logs pattern changes, overruns, commands and errors over RTT.
 //! Part of the 365 Pico2 RP2350 Project Ideas series.
 //!
 //! AUTHOR: Kevin Thomas
 //! CREATION DATE: December 7, 2025
-//! UPDATE DATE: December 7, 2025
+//! UPDATE DATE: October 18, 2026
 
 #![no_std]
 #![no_main]
 
+#[cfg(all(feature = "dmx", feature = "midi"))]
+compile_error!("the dmx and midi features both use UART1");
+
+#[cfg(all(feature = "vu-meter", feature = "led-health"))]
+compile_error!("the vu-meter and led-health features both use the ADC");
+
+#[macro_use]
+mod log;
+
+mod bargraph;
+mod binclock;
+mod clock;
+mod compositor;
 mod config;
+mod console;
+mod counter;
+#[cfg(feature = "dmx")]
+mod dmx;
+mod effects;
+mod frame;
+mod fsm;
+mod health;
+mod idle;
+mod jitter;
 mod led;
+#[cfg(feature = "midi")]
+mod midi;
+mod morse;
+mod pattern;
+mod playlist;
+mod power;
+mod rng;
+mod schedule;
+mod sequencer;
+#[cfg(feature = "uart-sync")]
+mod sync;
+mod timeline;
+mod usage;
+#[cfg(feature = "vu-meter")]
+mod vu;
+mod wallclock;
+mod watchdog;
 
+use clock::{ClockInput, ClockOut, ClockRatio, ClockSource};
+use compositor::{BlendMode, Compositor, Layer};
+#[cfg(any(feature = "traffic-light", feature = "deep-sleep"))]
+use config::BUTTON_DEBOUNCE_MS;
+#[cfg(not(feature = "uart-sync"))]
+use config::CONSOLE_BAUD;
+#[cfg(feature = "core1-sequencer")]
+use config::CORE1_STACK_SIZE;
+use config::{
+    CLOCK_HOLDOFF_MS, CLOCK_PULSE_MS, CLOCK_TIMEOUT_MS, COMMAND_QUEUE_LEN, FLASH_SIZE_BYTES,
+    FRAME_INTERVAL_MS, IDLE_MAX_SLEEP_MS, LED_CURRENT_MA, MAX_BRIGHTNESS, MAX_STATUS_SUBSCRIBERS,
+    POWER_BUDGET_MA, PWM_TOP, REPORT_QUEUE_LEN, SEQUENCE_DELAY_MS, STATUS_QUEUE_LEN,
+    STEP_TOLERANCE_MS, USAGE_FLASH_OFFSET, USAGE_SAVE_INTERVAL_MS, WATCHDOG_RECOVERY_MS,
+    WATCHDOG_TIMEOUT_MS,
+};
+#[cfg(feature = "dmx")]
+use config::{DMX_BAUD, DMX_START_ADDRESS, DMX_TIMEOUT_MS};
+#[cfg(feature = "led-health")]
+use config::{HEALTH_OPEN_BELOW, HEALTH_SAMPLES, HEALTH_SETTLE_MS, HEALTH_SHORT_ABOVE};
+#[cfg(feature = "midi")]
+use config::{MIDI_BASE_NOTE, MIDI_BAUD, MIDI_CHANNEL, MIDI_CLOCKS_PER_STEP};
+#[cfg(feature = "uart-sync")]
+use config::{
+    SYNC_BAUD, SYNC_BEACON_INTERVAL_MS, SYNC_BOARD_COUNT, SYNC_BOARD_INDEX, SYNC_LATENCY_MS,
+};
+#[cfg(feature = "vu-meter")]
+use config::{VU_BEAT_STEPS, VU_FLOOR_DB, VU_SAMPLE_RATE_HZ};
+#[cfg(not(feature = "uart-sync"))]
+use console::LineReader;
+use console::{Report, ReportLine, status_worth_reporting};
+#[cfg(feature = "defmt")]
+use defmt_rtt as _;
+#[cfg(feature = "dmx")]
+use dmx::{DMX_SLOTS, DmxReceiver, DmxSignal};
+#[cfg(feature = "core1-sequencer")]
+use embassy_executor::Executor;
 use embassy_executor::Spawner;
-use embassy_rp::gpio::{Level, Output};
-use embassy_time::Timer;
-use led::{led_state_to_level, LedSequenceController};
+use embassy_futures::select::{Either, select};
+#[cfg(feature = "vu-meter")]
+use embassy_rp::Peri;
+use embassy_rp::adc;
+#[cfg(any(feature = "vu-meter", feature = "led-health"))]
+use embassy_rp::adc::Adc;
+use embassy_rp::bind_interrupts;
+use embassy_rp::flash::{Blocking, ERASE_SIZE, Flash};
+use embassy_rp::gpio::{Input, Level, Output, Pull};
+#[cfg(feature = "core1-sequencer")]
+use embassy_rp::multicore::{Stack, spawn_core1};
+#[cfg(feature = "deep-sleep")]
+use embassy_rp::pac;
+#[cfg(feature = "vu-meter")]
+use embassy_rp::peripherals::DMA_CH4;
+use embassy_rp::peripherals::{FLASH, TRNG, UART0, UART1};
+use embassy_rp::pwm::{Config as PwmConfig, Pwm};
+use embassy_rp::trng::{self, Trng};
+use embassy_rp::uart;
+#[cfg(not(feature = "uart-sync"))]
+use embassy_rp::uart::Uart;
+use embassy_rp::uart::{Async, Config as UartConfig, UartRx, UartTx};
+#[cfg(feature = "dmx")]
+use embassy_rp::uart::{ReadToBreakError, StopBits};
+use embassy_rp::watchdog::{ResetReason, Watchdog};
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::channel::Channel;
+use embassy_sync::pubsub::PubSubChannel;
+use embassy_sync::signal::Signal;
+#[cfg(feature = "uart-sync")]
+use embassy_time::Ticker;
+use embassy_time::{Duration, Instant, Timer, with_deadline, with_timeout};
+use frame::{Frame, level_to_duty};
+#[cfg(feature = "traffic-light")]
+use fsm::{Fsm, FsmState, FsmTransition, Guard};
+#[cfg(feature = "led-health")]
+use health::SenseThresholds;
+use idle::{IdlePlanner, SleepStrategy};
+use led::LedSequenceController;
+#[cfg(feature = "midi")]
+use midi::{MidiClock, MidiMessage, MidiParser, NoteMap};
 use panic_halt as _;
+use pattern::Pattern;
+use playlist::{EntryLength, Playlist, PlaylistEntry, Transition};
+use power::{LimitMode, PowerLimiter};
+use schedule::{Schedule, ScheduleEntry};
+use sequencer::{Command, Sequencer, Status};
+#[cfg(feature = "core1-sequencer")]
+use static_cell::StaticCell;
+#[cfg(feature = "uart-sync")]
+use sync::{BeaconParser, SyncFollower, SyncLeader, VirtualChase};
+use timeline::{Easing, Keyframe, Playback, Timeline};
+use usage::{USAGE_RECORD_LEN, UsageStats};
+#[cfg(feature = "vu-meter")]
+use vu::VuMeter;
+use wallclock::TimeOfDay;
+use watchdog::{BootRecord, ResetCause, StepSupervisor};
 
-/// Main application entry point.
+/// Kiosk playlist played unattended.
 ///
 /// # Details
-/// Initializes Embassy runtime and runs the main LED sequence loop.
-/// Uses LedSequenceController for state management.
-/// Controls 4 LEDs on GPIO pins 16, 17, 18, 19 in sequence.
+/// Cycles chase, bounce, fill and blink entries with mixed transitions.
+const KIOSK_PLAYLIST: [PlaylistEntry; 4] = [
+    PlaylistEntry::new(Pattern::Chase, SEQUENCE_DELAY_MS, EntryLength::Repeats(8)),
+    PlaylistEntry::new(
+        Pattern::Bounce,
+        SEQUENCE_DELAY_MS / 2,
+        EntryLength::Repeats(6),
+    )
+    .with_transition(Transition::Crossfade(500)),
+    PlaylistEntry::new(Pattern::Fill, SEQUENCE_DELAY_MS, EntryLength::Repeats(4))
+        .with_transition(Transition::Wipe(400)),
+    PlaylistEntry::new(
+        Pattern::Blink,
+        SEQUENCE_DELAY_MS * 2,
+        EntryLength::Duration(4000),
+    )
+    .with_transition(Transition::Crossfade(500)),
+];
+
+/// Overnight schedule.
+///
+/// # Details
+/// From 22:00 to 07:00 the panel breathes slowly at low brightness.
+/// The playlist runs the rest of the day.
+const NIGHT_SCHEDULE: [ScheduleEntry; 1] = [ScheduleEntry::new(
+    TimeOfDay::hm(22, 0),
+    TimeOfDay::hm(7, 0),
+    Pattern::Breathe,
+    MAX_BRIGHTNESS / 8,
+    SEQUENCE_DELAY_MS * 4,
+)];
+
+/// Sunrise timeline length in milliseconds.
+const SUNRISE_MS: u32 = 4000;
+
+/// Sunrise timeline keyframes.
+///
+/// # Details
+/// The LEDs ease up to full brightness one after another, a
+/// second apart. Ping-pong playback dims them again in reverse.
+const SUNRISE_KEYFRAMES: [Keyframe; 8] = [
+    Keyframe::new(0, 0, 0).with_easing(Easing::EaseInOut),
+    Keyframe::new(1000, 0, MAX_BRIGHTNESS),
+    Keyframe::new(1000, 1, 0).with_easing(Easing::EaseInOut),
+    Keyframe::new(2000, 1, MAX_BRIGHTNESS),
+    Keyframe::new(2000, 2, 0).with_easing(Easing::EaseInOut),
+    Keyframe::new(3000, 2, MAX_BRIGHTNESS),
+    Keyframe::new(3000, 3, 0).with_easing(Easing::EaseInOut),
+    Keyframe::new(SUNRISE_MS, 3, MAX_BRIGHTNESS),
+];
+
+/// Steps per accepted clock-in edge.
+const CLOCK_RATIO: ClockRatio = ClockRatio::Divide(1);
+
+/// Pedestrian button input bit.
+#[cfg(feature = "traffic-light")]
+const PEDESTRIAN: u8 = 0b01;
+
+/// Traffic light states.
+///
+/// # Details
+/// LED 0 red, LED 1 amber, LED 2 green and LED 3 the walk signal.
+#[cfg(feature = "traffic-light")]
+const TRAFFIC_STATES: [FsmState; 5] = [
+    FsmState::new(Frame::from_levels([MAX_BRIGHTNESS, 0, 0, 0]), 2000),
+    FsmState::new(
+        Frame::from_levels([MAX_BRIGHTNESS, MAX_BRIGHTNESS, 0, 0]),
+        1000,
+    ),
+    FsmState::new(Frame::from_levels([0, 0, MAX_BRIGHTNESS, 0]), 8000),
+    FsmState::new(Frame::from_levels([0, MAX_BRIGHTNESS, 0, 0]), 2000),
+    FsmState::new(
+        Frame::from_levels([MAX_BRIGHTNESS, 0, 0, MAX_BRIGHTNESS]),
+        6000,
+    ),
+];
+
+/// Traffic light transitions.
+///
+/// # Details
+/// Green holds until the pedestrian button is pressed. Amber then
+/// leads to the walk phase before red.
+#[cfg(feature = "traffic-light")]
+const TRAFFIC_TRANSITIONS: [FsmTransition; 5] = [
+    FsmTransition::new(0, 1, Guard::After),
+    FsmTransition::new(1, 2, Guard::After),
+    FsmTransition::new(2, 3, Guard::AfterWithInput(PEDESTRIAN)),
+    FsmTransition::new(3, 4, Guard::After),
+    FsmTransition::new(4, 0, Guard::After),
+];
+
+/// ADC conversion clock in Hz.
+#[cfg(feature = "vu-meter")]
+const ADC_CLOCK_HZ: u32 = 48_000_000;
+
+/// Microphone samples per frame.
+#[cfg(feature = "vu-meter")]
+const VU_BLOCK_LEN: usize = (VU_SAMPLE_RATE_HZ as u64 * FRAME_INTERVAL_MS / 1000) as usize;
+
+/// Commands from any task to the sequencer.
+///
+/// # Details
+/// Critical-section channels use the RP2350 hardware spinlock,
+/// so they are safe to share between cores.
+static COMMANDS: Channel<CriticalSectionRawMutex, Command, COMMAND_QUEUE_LEN> = Channel::new();
+
+/// Sequencer state changes for input and telemetry tasks.
+///
+/// # Details
+/// The sequencer task is the only publisher.
+static STATUS: PubSubChannel<
+    CriticalSectionRawMutex,
+    Status,
+    STATUS_QUEUE_LEN,
+    MAX_STATUS_SUBSCRIBERS,
+    1,
+> = PubSubChannel::new();
+
+/// Reports waiting to be written to the text console.
+///
+/// # Details
+/// The control task is the only receiver.
+static REPORTS: Channel<CriticalSectionRawMutex, Report, REPORT_QUEUE_LEN> = Channel::new();
+
+/// Usage statistics waiting to be saved to flash.
+static USAGE_SAVE: Signal<CriticalSectionRawMutex, UsageStats> = Signal::new();
+
+/// Flash page written when saving usage statistics.
+const USAGE_PAGE_LEN: usize = 256;
+
+/// On-board flash driver.
+type BoardFlash = Flash<'static, FLASH, Blocking, FLASH_SIZE_BYTES>;
+
+/// Stack for core 1.
+#[cfg(feature = "core1-sequencer")]
+static mut CORE1_STACK: Stack<CORE1_STACK_SIZE> = Stack::new();
+
+/// Executor running the sequencer on core 1.
+#[cfg(feature = "core1-sequencer")]
+static EXECUTOR1: StaticCell<Executor> = StaticCell::new();
+
+bind_interrupts!(struct Irqs {
+    ADC_IRQ_FIFO => adc::InterruptHandler;
+    TRNG_IRQ => trng::InterruptHandler<TRNG>;
+    UART0_IRQ => uart::InterruptHandler<UART0>;
+    UART1_IRQ => uart::InterruptHandler<UART1>;
+});
+/// Reads a random seed from the hardware TRNG.
 ///
 /// # Arguments
-/// * `_spawner` - Embassy task spawner (reserved for future async tasks).
+/// * `trng` - True random number generator peripheral
 ///
 /// # Returns
-/// * `()` - Never returns (infinite loop).
-#[embassy_executor::main]
-async fn main(_spawner: Spawner) {
-    let p = embassy_rp::init(Default::default());
-    let mut led0 = Output::new(p.PIN_16, Level::Low);
-    let mut led1 = Output::new(p.PIN_17, Level::Low);
-    let mut led2 = Output::new(p.PIN_18, Level::Low);
-    let mut led3 = Output::new(p.PIN_19, Level::Low);
-    let mut controller = LedSequenceController::new();
+/// * `u32` - Seed for the library Rng
+async fn read_seed(trng: &mut Trng<'_, TRNG>) -> u32 {
+    let mut bytes = [0u8; 4];
+    trng.fill_bytes(&mut bytes).await;
+    u32::from_le_bytes(bytes)
+}
+
+/// Reads the reset cause and updates the boot record.
+///
+/// # Details
+/// Keeps the boot record in watchdog scratch registers 0 and 1
+/// so watchdog resets are counted across reboots.
+///
+/// # Arguments
+/// * `watchdog` - Watchdog peripheral
+///
+/// # Returns
+/// * `ResetCause` - Cause of the latest reset
+fn record_boot(watchdog: &mut Watchdog) -> ResetCause {
+    let cause = match watchdog.reset_reason() {
+        Some(ResetReason::TimedOut) => ResetCause::Watchdog,
+        _ => ResetCause::PowerOn,
+    };
+    let record = BootRecord::from_scratch(watchdog.get_scratch(0), watchdog.get_scratch(1))
+        .after_reset(cause);
+    let (magic, count) = record.to_scratch();
+    watchdog.set_scratch(0, magic);
+    watchdog.set_scratch(1, count);
+    info!(
+        "boot after {}, {} watchdog resets",
+        cause,
+        record.watchdog_resets()
+    );
+    cause
+}
+
+/// Shows the watchdog recovery pattern.
+///
+/// # Details
+/// Alternates even and odd LEDs so a watchdog reset is easy to
+/// tell apart from a normal power-on.
+///
+/// # Arguments
+/// * `pwm0` - PWM slice driving LEDs 0 and 1
+/// * `pwm1` - PWM slice driving LEDs 2 and 3
+async fn show_recovery(pwm0: &mut Pwm<'_>, pwm1: &mut Pwm<'_>) {
+    let mut controller = LedSequenceController::with_pattern(Pattern::Alternate);
+    controller.set_delay_ms(SEQUENCE_DELAY_MS / 2);
+    for _ in 0..WATCHDOG_RECOVERY_MS / controller.delay_ms() {
+        write_frame(pwm0, pwm1, &controller.frame());
+        Timer::after_millis(controller.delay_ms()).await;
+        controller.advance();
+    }
+}
+
+/// Writes two LED levels to a PWM slice.
+///
+/// # Details
+/// Channel A and channel B drive consecutive LEDs.
+///
+/// # Arguments
+/// * `pwm` - PWM slice driving the LED pair
+/// * `level_a` - Brightness level for channel A
+/// * `level_b` - Brightness level for channel B
+fn write_pair(pwm: &mut Pwm<'_>, level_a: u8, level_b: u8) {
+    let mut cfg = PwmConfig::default();
+    cfg.top = PWM_TOP;
+    cfg.compare_a = level_to_duty(level_a, PWM_TOP);
+    cfg.compare_b = level_to_duty(level_b, PWM_TOP);
+    pwm.set_config(&cfg);
+}
+
+/// Pushes a frame to the LED outputs.
+///
+/// # Arguments
+/// * `pwm0` - PWM slice driving LEDs 0 and 1
+/// * `pwm1` - PWM slice driving LEDs 2 and 3
+/// * `frame` - Frame to display
+fn write_frame(pwm0: &mut Pwm<'_>, pwm1: &mut Pwm<'_>, frame: &Frame) {
+    write_pair(pwm0, frame.level(0), frame.level(1));
+    write_pair(pwm1, frame.level(2), frame.level(3));
+}
+
+/// Reads saved usage statistics from flash.
+///
+/// # Arguments
+/// * `flash` - On-board flash
+///
+/// # Returns
+/// * `UsageStats` - Saved statistics, or empty ones if none were saved
+fn load_usage(flash: &mut BoardFlash) -> UsageStats {
+    let mut record = [0; USAGE_RECORD_LEN];
+    match flash.blocking_read(USAGE_FLASH_OFFSET, &mut record) {
+        Ok(()) => UsageStats::decode(&record).unwrap_or_default(),
+        Err(error) => {
+            error!("usage read failed: {}", error);
+            UsageStats::new()
+        }
+    }
+}
+
+/// Usage statistics store task.
+///
+/// # Details
+/// Erases the usage sector and writes each snapshot the sequencer
+/// sends. Saves are hourly, so the flash stall costs at most one
+/// late frame an hour.
+///
+/// # Arguments
+/// * `flash` - On-board flash
+#[embassy_executor::task]
+async fn usage_store_task(mut flash: BoardFlash) {
     loop {
-        if led_state_to_level(controller.led_state(0)) {
-            led0.set_high();
+        let usage = USAGE_SAVE.wait().await;
+        let mut page = [0xFF; USAGE_PAGE_LEN];
+        page[..USAGE_RECORD_LEN].copy_from_slice(&usage.encode());
+        let end = USAGE_FLASH_OFFSET + ERASE_SIZE as u32;
+        let saved = flash
+            .blocking_erase(USAGE_FLASH_OFFSET, end)
+            .and_then(|()| flash.blocking_write(USAGE_FLASH_OFFSET, &page));
+        match saved {
+            Ok(()) => debug!("usage saved after {} ms", usage.total_ms()),
+            Err(error) => error!("usage save failed: {}", error),
+        }
+    }
+}
+
+/// Render loop sleep strategy.
+///
+/// # Details
+/// The `deep-sleep` feature sleeps until the output next changes,
+/// otherwise the loop wakes every frame.
+#[cfg(feature = "deep-sleep")]
+const SLEEP_STRATEGY: SleepStrategy = SleepStrategy::UntilChange;
+#[cfg(not(feature = "deep-sleep"))]
+const SLEEP_STRATEGY: SleepStrategy = SleepStrategy::EveryFrame;
+
+/// Sequencer task owning the LED outputs.
+///
+/// # Details
+/// Applies queued commands, renders a frame and publishes status
+/// changes. Subscribers that fall behind lose their oldest reports
+/// rather than delaying a frame.
+/// Pulses the clock output on every pattern step.
+/// Sleeps until the next frame the sleep strategy plans or until a
+/// command arrives, and records the time spent asleep.
+/// Replies to usage reports and hands usage statistics to the
+/// store task every USAGE_SAVE_INTERVAL_MS.
+/// Records how late each wake that took a step ran against its
+/// deadline, and replies to jitter reports.
+/// Feeds the watchdog only while frames land on schedule.
+///
+/// # Arguments
+/// * `pwm0` - PWM slice driving LEDs 0 and 1
+/// * `pwm1` - PWM slice driving LEDs 2 and 3
+/// * `clock_pin` - Clock output to the next board
+/// * `watchdog` - Watchdog peripheral
+/// * `sequencer` - Sequencer to run
+#[embassy_executor::task]
+async fn sequencer_task(
+    mut pwm0: Pwm<'static>,
+    mut pwm1: Pwm<'static>,
+    mut clock_pin: Output<'static>,
+    mut watchdog: Watchdog,
+    mut sequencer: Sequencer<'static>,
+) {
+    watchdog.start(Duration::from_millis(WATCHDOG_TIMEOUT_MS));
+    let mut supervisor = StepSupervisor::new(
+        FRAME_INTERVAL_MS,
+        STEP_TOLERANCE_MS,
+        Instant::now().as_millis(),
+    );
+    let mut clock_out = ClockOut::new(CLOCK_PULSE_MS, sequencer.steps());
+    let publisher = STATUS.publisher().unwrap();
+    let mut reported = None;
+    let planner = IdlePlanner::new(SLEEP_STRATEGY, FRAME_INTERVAL_MS, IDLE_MAX_SLEEP_MS).unwrap();
+    let mut last_tick = Instant::now();
+    let mut wake_at = last_tick;
+    let mut saved_at = last_tick;
+    let mut lateness_us = 0;
+    loop {
+        while let Ok(command) = COMMANDS.try_receive() {
+            debug!("command {}", command);
+            sequencer.apply(command);
+        }
+        let awake = Instant::now();
+        let elapsed_ms = (awake - last_tick).as_millis();
+        last_tick += Duration::from_millis(elapsed_ms);
+        let frame = sequencer.tick(elapsed_ms);
+        sequencer.record_step_timing(lateness_us);
+        write_frame(&mut pwm0, &mut pwm1, &frame);
+        let pulse = clock_out.update(sequencer.steps(), awake.as_millis());
+        clock_pin.set_level(Level::from(pulse));
+        let status = sequencer.status();
+        if reported != Some(status) {
+            log_status(reported, &status);
+            publisher.publish_immediate(status);
+            reported = Some(status);
+        }
+        if let Some(usage) = sequencer.take_usage_report() {
+            send_report(Report::Usage(usage));
+        }
+        if let Some(jitter) = sequencer.take_jitter_report() {
+            info!(
+                "step jitter min {} max {} mean {} p99 {} us, {} overruns",
+                jitter.min_us(),
+                jitter.max_us(),
+                jitter.mean_us(),
+                jitter.p99_us(),
+                jitter.overruns()
+            );
+            send_report(Report::Jitter(jitter));
+        }
+        if let Some(power) = sequencer.take_power_report() {
+            info!(
+                "power {} frames, {} scaled, {} refused, peak {} mA",
+                power.frames, power.scaled, power.refused, power.peak_ma
+            );
+            send_report(Report::Power(power));
+        }
+        if let Some(idle) = sequencer.take_idle_report() {
+            info!(
+                "idle {} permille, {} uA average, {} h battery",
+                idle.idle_permille,
+                idle.average_ua,
+                idle.battery_hours.unwrap_or(0)
+            );
+            send_report(Report::Idle(idle));
+        }
+        if (awake - saved_at).as_millis() >= USAGE_SAVE_INTERVAL_MS {
+            USAGE_SAVE.signal(*sequencer.usage());
+            saved_at = awake;
+        }
+        let idle_ms = if clock_out.is_active() {
+            0
         } else {
-            led0.set_low();
+            sequencer.idle_ms()
+        };
+        let sleep_ms = planner.plan(idle_ms);
+        supervisor.set_interval(sleep_ms);
+        wake_at += Duration::from_millis(sleep_ms);
+        let asleep = Instant::now();
+        let _ = with_deadline(wake_at, COMMANDS.ready_to_receive()).await;
+        let now = Instant::now();
+        sequencer.record_idle((asleep - awake).as_micros(), (now - asleep).as_micros());
+        lateness_us = now.as_micros() as i64 - wake_at.as_micros() as i64;
+        wake_at = wake_at.min(now);
+        if supervisor.step_completed(now.as_millis()) {
+            watchdog.feed();
+        } else {
+            warn!("frame overrun, {} late steps", supervisor.late_steps());
         }
-        if led_state_to_level(controller.led_state(1)) {
-            led1.set_high();
+    }
+}
+
+/// Logs the parts of a status change worth a diagnostic.
+///
+/// # Details
+/// Reports pattern and mode changes, frames going over the LED
+/// current budget and self-test results.
+///
+/// # Arguments
+/// * `last` - Status reported before, if any
+/// * `status` - New status
+fn log_status(last: Option<Status>, status: &Status) {
+    if last.is_none_or(|last| last.pattern != status.pattern || last.mode != status.mode) {
+        info!(
+            "{} pattern {} at {} ms per step",
+            status.mode, status.pattern, status.delay_ms
+        );
+    }
+    if status.power_limited && last.is_none_or(|last| !last.power_limited) {
+        warn!("frame over the LED current budget");
+    }
+    if status.self_test.is_none() && last.is_some_and(|last| last.self_test.is_some()) {
+        if status.health.faulty_mask() == 0 {
+            info!("LED self-test passed");
         } else {
-            led1.set_low();
+            warn!("LED self-test found faults: {}", status.health);
+        }
+    }
+}
+
+/// Clock input poll interval while no external clock is followed.
+///
+/// # Details
+/// Edges wake the task on their own, so the `deep-sleep` feature
+/// polls slowly until an external clock is followed.
+#[cfg(feature = "deep-sleep")]
+const CLOCK_IDLE_POLL_MS: u64 = IDLE_MAX_SLEEP_MS;
+#[cfg(not(feature = "deep-sleep"))]
+const CLOCK_IDLE_POLL_MS: u64 = FRAME_INTERVAL_MS;
+
+/// Clock input task.
+///
+/// # Details
+/// Turns rising edges on the clock input into Step commands.
+/// Switches the sequencer to the external clock on the first
+/// accepted edge and back to internal timing on timeout.
+/// Polls every frame only while the external clock is followed.
+///
+/// # Arguments
+/// * `clock_pin` - Clock input from the previous board
+#[embassy_executor::task]
+async fn clock_task(mut clock_pin: Input<'static>) {
+    let mut clock = ClockInput::new(CLOCK_RATIO, CLOCK_HOLDOFF_MS, CLOCK_TIMEOUT_MS).unwrap();
+    let mut source = ClockSource::Internal;
+    loop {
+        let poll_ms = match source {
+            ClockSource::External => FRAME_INTERVAL_MS,
+            ClockSource::Internal => CLOCK_IDLE_POLL_MS,
+        };
+        let poll = Duration::from_millis(poll_ms);
+        let edge = with_timeout(poll, clock_pin.wait_for_rising_edge()).await;
+        let now_ms = Instant::now().as_millis();
+        let mut steps = clock.poll(now_ms);
+        if edge.is_ok() {
+            steps += clock.edge(now_ms);
+        }
+        if clock.source() != source {
+            source = clock.source();
+            COMMANDS.send(Command::SetClock(source)).await;
+        }
+        for _ in 0..steps {
+            COMMANDS.send(Command::Step).await;
         }
-        if led_state_to_level(controller.led_state(2)) {
-            led2.set_high();
+    }
+}
+
+/// Shows a global chase step if it has changed.
+///
+/// # Arguments
+/// * `chase` - This board's place in the global chase
+/// * `step` - Global step to show
+/// * `shown` - Step shown last, updated here
+#[cfg(feature = "uart-sync")]
+async fn show_global_step(chase: &VirtualChase, step: u32, shown: &mut Option<u32>) {
+    if *shown != Some(step) {
+        *shown = Some(step);
+        COMMANDS.send(Command::SetFrame(chase.frame(step))).await;
+    }
+}
+
+/// Sync leader task.
+///
+/// # Details
+/// Runs the global chase on this board's clock and sends sync
+/// beacons to the followers.
+///
+/// # Arguments
+/// * `tx` - UART transmitter wired to every follower
+/// * `chase` - This board's place in the global chase
+#[cfg(feature = "uart-sync")]
+#[embassy_executor::task]
+async fn sync_leader_task(mut tx: UartTx<'static, Async>, chase: VirtualChase) {
+    let mut leader = SyncLeader::new(SEQUENCE_DELAY_MS as u16, SYNC_BEACON_INTERVAL_MS).unwrap();
+    let mut shown = None;
+    let mut ticker = Ticker::every(Duration::from_millis(FRAME_INTERVAL_MS));
+    loop {
+        ticker.next().await;
+        if let Some(beacon) = leader.tick(FRAME_INTERVAL_MS as u32) {
+            if let Err(error) = tx.write(&beacon.encode()).await {
+                warn!("sync beacon write failed: {}", error);
+            }
+        }
+        show_global_step(&chase, leader.step(), &mut shown).await;
+    }
+}
+
+/// Sync follower task.
+///
+/// # Details
+/// Locks to the leader's beacons and shows this board's part of
+/// the global chase once the first beacon has arrived.
+///
+/// # Arguments
+/// * `rx` - UART receiver wired to the leader
+/// * `chase` - This board's place in the global chase
+#[cfg(feature = "uart-sync")]
+#[embassy_executor::task]
+async fn sync_follower_task(mut rx: UartRx<'static, Async>, chase: VirtualChase) {
+    let mut follower = SyncFollower::new(SYNC_LATENCY_MS);
+    let mut parser = BeaconParser::new();
+    let mut shown = None;
+    let mut byte = [0u8; 1];
+    let mut last = Instant::now();
+    let poll = Duration::from_millis(FRAME_INTERVAL_MS);
+    loop {
+        let received = with_timeout(poll, rx.read(&mut byte)).await;
+        let now = Instant::now();
+        follower.tick_us((now - last).as_micros() as u32);
+        last = now;
+        match received {
+            Ok(Ok(())) => {
+                if let Some(beacon) = parser.push(byte[0]) {
+                    follower.on_beacon(&beacon);
+                }
+            }
+            Ok(Err(error)) => warn!("sync read failed: {}", error),
+            Err(_) => {}
+        }
+        if follower.is_synced() {
+            show_global_step(&chase, follower.step(), &mut shown).await;
+        }
+    }
+}
+
+/// DMX512 receiver task.
+///
+/// # Details
+/// Reads packets up to each break and shows every complete frame.
+/// Hands control back to the playlist when the signal is lost.
+///
+/// # Arguments
+/// * `rx` - UART receiver wired to the DMX line driver
+#[cfg(feature = "dmx")]
+#[embassy_executor::task]
+async fn dmx_task(mut rx: UartRx<'static, Async>) {
+    let mut receiver = DmxReceiver::new(DMX_START_ADDRESS, DMX_TIMEOUT_MS).unwrap();
+    let mut buffer = [0u8; DMX_SLOTS + 1];
+    let timeout = Duration::from_millis(DMX_TIMEOUT_MS as u64);
+    let mut live = false;
+    let mut last = Instant::now();
+    loop {
+        let read = with_timeout(timeout, rx.read_to_break(&mut buffer)).await;
+        let (len, ended_by_break) = match read {
+            Ok(Ok(len)) => (len, true),
+            Ok(Err(ReadToBreakError::MissingBreak(len))) => (len, false),
+            Ok(Err(error)) => {
+                warn!("DMX read failed: {}", error);
+                (0, false)
+            }
+            Err(_) => (0, false),
+        };
+        let mut frame = None;
+        for &byte in &buffer[..len] {
+            frame = receiver.push(DmxSignal::Byte(byte)).or(frame);
+        }
+        if ended_by_break {
+            receiver.push(DmxSignal::Break);
+        }
+        let now = Instant::now();
+        let now_live = receiver.tick((now - last).as_millis() as u32);
+        last = now;
+        if let Some(frame) = frame {
+            COMMANDS.send(Command::SetFrame(frame)).await;
+        } else if live && !now_live {
+            COMMANDS.send(Command::RunPlaylist).await;
+        }
+        live = now_live;
+    }
+}
+
+/// MIDI input task.
+///
+/// # Details
+/// Shows held notes on the LEDs with velocity as brightness and
+/// returns to the sequence once every note is released. Locks the
+/// step delay to incoming MIDI clock. Start jumps to the first
+/// step, Stop pauses and Continue resumes.
+///
+/// # Arguments
+/// * `rx` - UART receiver wired to the MIDI opto-isolator
+#[cfg(feature = "midi")]
+#[embassy_executor::task]
+async fn midi_task(mut rx: UartRx<'static, Async>) {
+    let mut parser = MidiParser::new();
+    let mut notes = NoteMap::new(MIDI_BASE_NOTE, MIDI_CHANNEL);
+    let mut clock = MidiClock::new(MIDI_CLOCKS_PER_STEP);
+    let mut sent_delay_ms = None;
+    let mut byte = [0u8; 1];
+    loop {
+        if let Err(error) = rx.read(&mut byte).await {
+            warn!("MIDI read failed: {}", error);
+            continue;
+        }
+        let Some(message) = parser.push(byte[0]) else {
+            continue;
+        };
+        clock.apply(message, Instant::now().as_micros());
+        if let Some(frame) = notes.apply(message) {
+            if notes.is_held() {
+                COMMANDS.send(Command::SetFrame(frame)).await;
+            } else if let Some(delay_ms) = clock.delay_ms() {
+                COMMANDS.send(Command::SetDelay(delay_ms)).await;
+                sent_delay_ms = Some(delay_ms);
+            } else {
+                COMMANDS.send(Command::RunPlaylist).await;
+            }
+        }
+        match message {
+            MidiMessage::Start => COMMANDS.send(Command::Reset).await,
+            MidiMessage::Continue => COMMANDS.send(Command::Resume).await,
+            MidiMessage::Stop => COMMANDS.send(Command::Pause).await,
+            MidiMessage::Clock if !notes.is_held() && clock.delay_ms() != sent_delay_ms => {
+                sent_delay_ms = clock.delay_ms();
+                if let Some(delay_ms) = sent_delay_ms {
+                    COMMANDS.send(Command::SetDelay(delay_ms)).await;
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Microphone VU meter task.
+///
+/// # Details
+/// Samples the microphone one frame at a time by DMA. Shows the
+/// level meter, or with VU_BEAT_STEPS steps the chase on each beat.
+///
+/// # Arguments
+/// * `adc` - ADC driver
+/// * `mic` - ADC channel wired to the microphone amplifier
+/// * `dma` - DMA channel for sample blocks
+#[cfg(feature = "vu-meter")]
+#[embassy_executor::task]
+async fn vu_task(
+    mut adc: Adc<'static, adc::Async>,
+    mut mic: adc::Channel<'static>,
+    mut dma: Peri<'static, DMA_CH4>,
+) {
+    let mut meter = VuMeter::new(VU_SAMPLE_RATE_HZ, VU_FLOOR_DB).unwrap();
+    let mut samples = [0u16; VU_BLOCK_LEN];
+    let div = (ADC_CLOCK_HZ / VU_SAMPLE_RATE_HZ - 1) as u16;
+    if VU_BEAT_STEPS {
+        COMMANDS
+            .send(Command::SetClock(ClockSource::External))
+            .await;
+    }
+    loop {
+        if let Err(error) = adc
+            .read_many(&mut mic, &mut samples, div, dma.reborrow())
+            .await
+        {
+            warn!("microphone read failed: {}", error);
+            continue;
+        }
+        let mut beat = false;
+        for &sample in &samples {
+            beat |= meter.push(sample);
+        }
+        meter.tick(FRAME_INTERVAL_MS as u32);
+        if !VU_BEAT_STEPS {
+            COMMANDS.send(Command::SetFrame(meter.frame())).await;
+        } else if beat {
+            COMMANDS.send(Command::Step).await;
+        }
+    }
+}
+
+/// LED health sense task.
+///
+/// # Details
+/// Starts a self-test at boot. For each self-test step the
+/// sequencer reports, waits for the sense reading to settle and
+/// sends the average of HEALTH_SAMPLES readings. A SelfTest
+/// command from any task runs the test again.
+///
+/// # Arguments
+/// * `adc` - ADC driver
+/// * `sense` - ADC channel across the LED sense resistor
+#[cfg(feature = "led-health")]
+#[embassy_executor::task]
+async fn health_task(mut adc: Adc<'static, adc::Async>, mut sense: adc::Channel<'static>) {
+    let mut subscriber = STATUS.subscriber().unwrap();
+    let mut measured = None;
+    COMMANDS.send(Command::SelfTest).await;
+    loop {
+        let step = subscriber.next_message_pure().await.self_test;
+        if step == measured {
+            continue;
+        }
+        measured = step;
+        if step.is_none() {
+            continue;
+        }
+        Timer::after_millis(HEALTH_SETTLE_MS).await;
+        let mut total = 0;
+        for _ in 0..HEALTH_SAMPLES {
+            match adc.read(&mut sense).await {
+                Ok(sample) => total += u32::from(sample),
+                Err(error) => warn!("sense read failed: {}", error),
+            }
+        }
+        COMMANDS
+            .send(Command::Sense((total / HEALTH_SAMPLES) as u16))
+            .await;
+    }
+}
+
+/// Pedestrian button task.
+///
+/// # Details
+/// Starts the traffic light, then latches the pedestrian input
+/// on each debounced press.
+///
+/// # Arguments
+/// * `button` - Button to ground with the pull-up enabled
+#[cfg(feature = "traffic-light")]
+#[embassy_executor::task]
+async fn button_task(mut button: Input<'static>) {
+    COMMANDS.send(Command::RunFsm).await;
+    loop {
+        button.wait_for_falling_edge().await;
+        COMMANDS.send(Command::Input(PEDESTRIAN)).await;
+        Timer::after_millis(BUTTON_DEBOUNCE_MS).await;
+    }
+}
+
+/// Pause button task.
+///
+/// # Details
+/// Toggles between Pause and Resume on each debounced press so a
+/// battery build can be parked in its deepest idle. The press
+/// interrupt wakes the core from sleep.
+///
+/// # Arguments
+/// * `button` - Button to ground with the pull-up enabled
+#[cfg(feature = "deep-sleep")]
+#[embassy_executor::task]
+async fn pause_button_task(mut button: Input<'static>) {
+    let mut subscriber = STATUS.subscriber().unwrap();
+    let mut paused = false;
+    loop {
+        button.wait_for_falling_edge().await;
+        while let Some(status) = subscriber.try_next_message_pure() {
+            paused = status.paused;
+        }
+        let command = if paused {
+            Command::Resume
         } else {
-            led2.set_low();
+            Command::Pause
+        };
+        COMMANDS.send(command).await;
+        Timer::after_millis(BUTTON_DEBOUNCE_MS).await;
+    }
+}
+
+/// Lets the calling core enter deep sleep when it waits for events.
+///
+/// # Details
+/// Sets SLEEPDEEP so the executor's idle WFE puts the chip to sleep
+/// once every core waits. While asleep only the clocks enabled in
+/// SLEEP_EN0/1 run, so they are cut down to the timer and its ticks,
+/// the watchdog, PWM, GPIO, UART0 and the DMA and SRAM it receives
+/// into, plus the ADC and UART1 clocks of enabled features. The
+/// timer, GPIO, UART and ADC interrupts still wake the chip,
+/// which restores every clock before the core runs.
+#[cfg(feature = "deep-sleep")]
+fn enable_deep_sleep() {
+    pac::CLOCKS.sleep_en0().write(|w| {
+        w.0 = 0;
+        w.set_clk_sys_clocks(true);
+        w.set_clk_sys_busctrl(true);
+        w.set_clk_sys_busfabric(true);
+        w.set_clk_sys_io(true);
+        w.set_clk_sys_pads(true);
+        w.set_clk_sys_pll_sys(true);
+        w.set_clk_sys_psm(true);
+        w.set_clk_sys_pwm(true);
+        w.set_clk_sys_resets(true);
+        w.set_clk_sys_sio(true);
+        #[cfg(any(feature = "vu-meter", feature = "led-health"))]
+        {
+            w.set_clk_adc(true);
+            w.set_clk_sys_adc(true);
+            w.set_clk_sys_pll_usb(true);
+        }
+        w.set_clk_sys_dma(true);
+    });
+    pac::CLOCKS.sleep_en1().write(|w| {
+        w.0 = 0;
+        w.set_clk_ref_ticks(true);
+        w.set_clk_sys_ticks(true);
+        w.set_clk_sys_timer0(true);
+        w.set_clk_sys_watchdog(true);
+        w.set_clk_sys_xosc(true);
+        w.set_clk_peri_uart0(true);
+        w.set_clk_sys_uart0(true);
+        #[cfg(any(feature = "dmx", feature = "midi"))]
+        {
+            w.set_clk_peri_uart1(true);
+            w.set_clk_sys_uart1(true);
+        }
+        w.set_clk_sys_sram0(true);
+        w.set_clk_sys_sram1(true);
+        w.set_clk_sys_sram2(true);
+        w.set_clk_sys_sram3(true);
+        w.set_clk_sys_sram4(true);
+        w.set_clk_sys_sram5(true);
+        w.set_clk_sys_sram6(true);
+        w.set_clk_sys_sram7(true);
+        w.set_clk_sys_sram8(true);
+        w.set_clk_sys_sram9(true);
+    });
+    let mut core = unsafe { cortex_m::Peripherals::steal() };
+    core.SCB.set_sleepdeep();
+}
+
+/// Queues a report for the text console.
+///
+/// # Details
+/// Never waits, so the sequencer can report without delaying a
+/// frame. A report that finds the queue full is dropped.
+///
+/// # Arguments
+/// * `report` - Report to write
+fn send_report(report: Report) {
+    if REPORTS.try_send(report).is_err() {
+        warn!("console report queue full");
+    }
+}
+
+/// Text console receiver task.
+///
+/// # Details
+/// Collects console lines and sends each parsed command to the
+/// sequencer. Lines that fail to parse are reported back.
+///
+/// # Arguments
+/// * `rx` - Console UART receiver
+#[cfg(not(feature = "uart-sync"))]
+#[embassy_executor::task]
+async fn console_task(mut rx: UartRx<'static, Async>) {
+    let mut reader = LineReader::new();
+    let mut byte = [0u8; 1];
+    loop {
+        match rx.read(&mut byte).await {
+            Ok(()) => match reader.push(byte[0]) {
+                Some(Ok(command)) => COMMANDS.send(command).await,
+                Some(Err(error)) => {
+                    warn!("console line rejected: {}", error);
+                    send_report(Report::Error(error));
+                }
+                None => {}
+            },
+            Err(error) => warn!("console read failed: {}", error),
+        }
+    }
+}
+
+/// Control plane task running on core 0.
+///
+/// # Details
+/// Follows sequencer state changes and writes them and queued
+/// reports to the text console, which is absent when the
+/// `uart-sync` feature holds UART0. Status changes that only move
+/// the step are not written. Input, parsing and storage work
+/// belongs here so it never delays a frame. Other tasks control
+/// the sequencer by sending to `COMMANDS`.
+///
+/// # Arguments
+/// * `console` - Console UART transmitter, if any
+#[embassy_executor::task]
+async fn control_task(mut console: Option<UartTx<'static, Async>>) {
+    let mut subscriber = STATUS.subscriber().unwrap();
+    let mut written: Option<Status> = None;
+    loop {
+        let report = match select(subscriber.next_message_pure(), REPORTS.receive()).await {
+            Either::First(status) => {
+                if !status_worth_reporting(written.as_ref(), &status) {
+                    continue;
+                }
+                written = Some(status);
+                Report::Status(status)
+            }
+            Either::Second(report) => report,
+        };
+        let Some(tx) = console.as_mut() else {
+            continue;
+        };
+        if let Err(error) = tx.write(ReportLine::new(&report).as_bytes()).await {
+            warn!("console write failed: {}", error);
         }
-        if led_state_to_level(controller.led_state(3)) {
-            led3.set_high();
+    }
+}
+
+/// Main application entry point.
+///
+/// # Details
+/// Initializes Embassy runtime and starts the sequencer task.
+/// The sequencer plays the shuffled kiosk playlist over a dimmed breathing compositor layer.
+/// Shows the recovery pattern first after a watchdog reset.
+/// Controls 4 LEDs on GPIO pins 16, 17, 18, 19 through PWM slices 0 and 1.
+/// Takes an external step clock on GPIO 20 and clocks out on GPIO 21.
+/// With the `uart-sync` feature board 0 leads a row of boards over
+/// UART0 and every other board follows it as one long chase.
+/// Otherwise UART0 on GPIO 0 and 1 is a text console for commands
+/// and reports, which can also play the loaded sunrise timeline.
+/// With the `dmx` feature a lighting desk on UART1 drives the LEDs
+/// until its signal is lost.
+/// With the `midi` feature notes and clock on UART1 drive the LEDs.
+/// The `dmx` and `midi` features share UART1 and are exclusive.
+/// With the `vu-meter` feature a microphone on GPIO 26 drives a
+/// level meter or steps the chase on beats.
+/// With the `traffic-light` feature the LEDs run a traffic light
+/// with a pedestrian button on GPIO 22.
+/// Once a SetTime command, such as the console's `time` line, sets
+/// the wall clock the panel follows the overnight schedule. Frames are scaled to the LED current budget.
+/// With the `led-health` feature a sense resistor on GPIO 27 checks
+/// each LED at boot and sequences skip faulty ones.
+/// With the `deep-sleep` feature both cores sleep deeply between
+/// output changes and a button on GPIO 14 pauses and resumes.
+/// Usage statistics carry over from flash and are saved every hour.
+/// With the `core1-sequencer` feature the sequencer runs on core 1,
+/// otherwise it shares core 0 with the control plane.
+///
+/// # Arguments
+/// * `spawner` - Embassy task spawner for core 0
+///
+/// # Returns
+/// * `()` - Returns once both tasks are spawned.
+#[embassy_executor::main]
+async fn main(spawner: Spawner) {
+    let p = embassy_rp::init(Default::default());
+    let mut pwm0 = Pwm::new_output_ab(p.PWM_SLICE0, p.PIN_16, p.PIN_17, PwmConfig::default());
+    let mut pwm1 = Pwm::new_output_ab(p.PWM_SLICE1, p.PIN_18, p.PIN_19, PwmConfig::default());
+    let clock_in = Input::new(p.PIN_20, Pull::Down);
+    let clock_out = Output::new(p.PIN_21, Level::Low);
+    let mut watchdog = Watchdog::new(p.WATCHDOG);
+    if record_boot(&mut watchdog) == ResetCause::Watchdog {
+        show_recovery(&mut pwm0, &mut pwm1).await;
+    }
+    let mut background = Compositor::new();
+    let breathe = Layer::new(Pattern::Breathe, SEQUENCE_DELAY_MS, BlendMode::Max)
+        .with_opacity(MAX_BRIGHTNESS / 4);
+    let _ = background.push(breathe);
+    let mut trng = Trng::new(p.TRNG, Irqs, trng::Config::default());
+    let seed = read_seed(&mut trng).await;
+    let playlist = Playlist::new(&KIOSK_PLAYLIST).unwrap().with_shuffle(seed);
+    let schedule = Schedule::new(&NIGHT_SCHEDULE).unwrap();
+    let sunrise = Timeline::new(&SUNRISE_KEYFRAMES, SUNRISE_MS)
+        .unwrap()
+        .with_playback(Playback::PingPong);
+    let limiter = PowerLimiter::new(LED_CURRENT_MA, POWER_BUDGET_MA, LimitMode::Scale).unwrap();
+    let mut flash = BoardFlash::new_blocking(p.FLASH);
+    let usage = load_usage(&mut flash);
+    let effect_seed = read_seed(&mut trng).await;
+    let sequencer = Sequencer::new(background, playlist)
+        .with_seed(effect_seed)
+        .with_schedule(schedule)
+        .with_timeline(sunrise)
+        .with_power_limit(limiter)
+        .with_usage(usage);
+    #[cfg(feature = "led-health")]
+    let sequencer = sequencer
+        .with_self_test(SenseThresholds::new(HEALTH_OPEN_BELOW, HEALTH_SHORT_ABOVE).unwrap());
+    #[cfg(feature = "traffic-light")]
+    let sequencer = sequencer.with_fsm(Fsm::new(&TRAFFIC_STATES, &TRAFFIC_TRANSITIONS, 0).unwrap());
+    #[cfg(feature = "core1-sequencer")]
+    {
+        let stack = unsafe { &mut *core::ptr::addr_of_mut!(CORE1_STACK) };
+        spawn_core1(p.CORE1, stack, move || {
+            #[cfg(feature = "deep-sleep")]
+            enable_deep_sleep();
+            let executor1 = EXECUTOR1.init(Executor::new());
+            executor1.run(|spawner| {
+                spawner.spawn(sequencer_task(pwm0, pwm1, clock_out, watchdog, sequencer).unwrap())
+            })
+        });
+    }
+    #[cfg(not(feature = "core1-sequencer"))]
+    spawner.spawn(sequencer_task(pwm0, pwm1, clock_out, watchdog, sequencer).unwrap());
+    #[cfg(feature = "uart-sync")]
+    {
+        let chase = VirtualChase::new(SYNC_BOARD_COUNT, SYNC_BOARD_INDEX).unwrap();
+        let mut config = UartConfig::default();
+        config.baudrate = SYNC_BAUD;
+        if SYNC_BOARD_INDEX == 0 {
+            let tx = UartTx::new(p.UART0, p.PIN_0, p.DMA_CH0, config);
+            spawner.spawn(sync_leader_task(tx, chase).unwrap());
         } else {
-            led3.set_low();
+            let rx = UartRx::new(p.UART0, p.PIN_1, Irqs, p.DMA_CH1, config);
+            spawner.spawn(sync_follower_task(rx, chase).unwrap());
         }
-        Timer::after_millis(controller.delay_ms()).await;
-        controller.advance();
     }
+    #[cfg(feature = "uart-sync")]
+    let console = None;
+    #[cfg(not(feature = "uart-sync"))]
+    let console = {
+        let mut config = UartConfig::default();
+        config.baudrate = CONSOLE_BAUD;
+        let uart = Uart::new(
+            p.UART0, p.PIN_0, p.PIN_1, Irqs, p.DMA_CH0, p.DMA_CH1, config,
+        );
+        let (tx, rx) = uart.split();
+        spawner.spawn(console_task(rx).unwrap());
+        Some(tx)
+    };
+    #[cfg(feature = "dmx")]
+    {
+        let mut config = UartConfig::default();
+        config.baudrate = DMX_BAUD;
+        config.stop_bits = StopBits::STOP2;
+        let rx = UartRx::new(p.UART1, p.PIN_5, Irqs, p.DMA_CH2, config);
+        spawner.spawn(dmx_task(rx).unwrap());
+    }
+    #[cfg(feature = "midi")]
+    {
+        let mut config = UartConfig::default();
+        config.baudrate = MIDI_BAUD;
+        let rx = UartRx::new(p.UART1, p.PIN_9, Irqs, p.DMA_CH3, config);
+        spawner.spawn(midi_task(rx).unwrap());
+    }
+    #[cfg(feature = "vu-meter")]
+    {
+        let adc = Adc::new(p.ADC, Irqs, adc::Config::default());
+        let mic = adc::Channel::new_pin(p.PIN_26, Pull::None);
+        spawner.spawn(vu_task(adc, mic, p.DMA_CH4).unwrap());
+    }
+    #[cfg(feature = "led-health")]
+    {
+        let adc = Adc::new(p.ADC, Irqs, adc::Config::default());
+        let sense = adc::Channel::new_pin(p.PIN_27, Pull::None);
+        spawner.spawn(health_task(adc, sense).unwrap());
+    }
+    #[cfg(feature = "traffic-light")]
+    spawner.spawn(button_task(Input::new(p.PIN_22, Pull::Up)).unwrap());
+    #[cfg(feature = "deep-sleep")]
+    {
+        enable_deep_sleep();
+        spawner.spawn(pause_button_task(Input::new(p.PIN_14, Pull::Up)).unwrap());
+    }
+    spawner.spawn(usage_store_task(flash).unwrap());
+    spawner.spawn(clock_task(clock_in).unwrap());
+    spawner.spawn(control_task(console).unwrap());
 }
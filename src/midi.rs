@@ -0,0 +1,754 @@
+/*
+ * @file midi.rs
+ * @brief MIDI input parser, note map and clock
+ * @author Kevin Thomas
+ * @date 2025
+ *
+ * MIT License
+ *
+ * Copyright (c) 2025 Kevin Thomas
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+//! FILE: midi.rs
+//!
+//! DESCRIPTION:
+//! MIDI Input for Note-Driven LEDs and Tempo Sync.
+//!
+//! BRIEF:
+//! Parses a MIDI byte stream with running status and realtime messages.
+//! Maps note-on/off onto LEDs with velocity as brightness.
+//! Locks the step delay to incoming 24 PPQN MIDI clock.
+//!
+//! AUTHOR: Kevin Thomas
+//! CREATION DATE: October 18, 2026
+//! UPDATE DATE: October 18, 2026
+
+use crate::config::{LED_COUNT, MAX_BRIGHTNESS};
+use crate::frame::Frame;
+
+/// MIDI clock pulses per quarter note.
+#[allow(dead_code)]
+pub const MIDI_PPQN: u32 = 24;
+
+/// Largest clock interval in microseconds treated as continuous.
+///
+/// # Details
+/// About 10 BPM. Longer gaps restart tempo measurement.
+#[allow(dead_code)]
+pub const MIDI_CLOCK_GAP_US: u64 = 250_000;
+
+/// Parsed MIDI message.
+///
+/// # Variants
+/// * `NoteOn` - Key pressed with channel, note and velocity
+/// * `NoteOff` - Key released with channel and note
+/// * `Clock` - Timing clock, 24 per quarter note
+/// * `Start` - Start from the beginning
+/// * `Continue` - Continue from the current position
+/// * `Stop` - Stop playback
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub enum MidiMessage {
+    NoteOn { channel: u8, note: u8, velocity: u8 },
+    NoteOff { channel: u8, note: u8 },
+    Clock,
+    Start,
+    Continue,
+    Stop,
+}
+
+/// MIDI byte-stream parser.
+///
+/// # Details
+/// Keeps running status for channel messages. Realtime bytes
+/// are returned at once, even between the data bytes of another
+/// message, without disturbing it. System common and exclusive
+/// messages cancel running status and are skipped. A note-on
+/// with velocity 0 is reported as a note-off.
+///
+/// # Fields
+/// * `status` - Running status byte, 0 when none
+/// * `data` - Data bytes collected for the current message
+/// * `len` - Number of data bytes collected
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct MidiParser {
+    status: u8,
+    data: [u8; 2],
+    len: usize,
+}
+
+impl MidiParser {
+    /// Creates new parser without running status.
+    ///
+    /// # Returns
+    /// * `Self` - New MidiParser instance
+    #[allow(dead_code)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feeds one received byte.
+    ///
+    /// # Arguments
+    /// * `byte` - Received byte
+    ///
+    /// # Returns
+    /// * `Option<MidiMessage>` - Message completed by this byte, if any
+    #[allow(dead_code)]
+    pub fn push(&mut self, byte: u8) -> Option<MidiMessage> {
+        if byte >= 0xF8 {
+            return realtime(byte);
+        }
+        if byte >= 0x80 {
+            self.status = if byte < 0xF0 { byte } else { 0 };
+            self.len = 0;
+            return None;
+        }
+        if self.status == 0 {
+            return None;
+        }
+        self.data[self.len] = byte;
+        self.len += 1;
+        if self.len < data_len(self.status) {
+            return None;
+        }
+        self.len = 0;
+        channel_message(self.status, self.data)
+    }
+}
+
+/// Returns message for a realtime byte.
+///
+/// # Arguments
+/// * `byte` - Realtime status byte
+///
+/// # Returns
+/// * `Option<MidiMessage>` - Message, or None for unused realtime bytes
+fn realtime(byte: u8) -> Option<MidiMessage> {
+    match byte {
+        0xF8 => Some(MidiMessage::Clock),
+        0xFA => Some(MidiMessage::Start),
+        0xFB => Some(MidiMessage::Continue),
+        0xFC => Some(MidiMessage::Stop),
+        _ => None,
+    }
+}
+
+/// Returns number of data bytes for a channel status.
+///
+/// # Arguments
+/// * `status` - Channel status byte
+///
+/// # Returns
+/// * `usize` - 1 for program change and channel pressure, 2 otherwise
+fn data_len(status: u8) -> usize {
+    match status & 0xF0 {
+        0xC0 | 0xD0 => 1,
+        _ => 2,
+    }
+}
+
+/// Returns message for a complete channel message.
+///
+/// # Arguments
+/// * `status` - Channel status byte
+/// * `data` - Data bytes
+///
+/// # Returns
+/// * `Option<MidiMessage>` - Note message, or None for other messages
+fn channel_message(status: u8, data: [u8; 2]) -> Option<MidiMessage> {
+    let channel = status & 0x0F;
+    let [note, velocity] = data;
+    match status & 0xF0 {
+        0x90 if velocity > 0 => Some(MidiMessage::NoteOn {
+            channel,
+            note,
+            velocity,
+        }),
+        0x80 | 0x90 => Some(MidiMessage::NoteOff { channel, note }),
+        _ => None,
+    }
+}
+
+/// Converts note velocity to LED brightness.
+///
+/// # Arguments
+/// * `velocity` - Velocity (0 to 127)
+///
+/// # Returns
+/// * `u8` - Brightness (0 to MAX_BRIGHTNESS)
+#[allow(dead_code)]
+pub fn velocity_to_level(velocity: u8) -> u8 {
+    (velocity.min(127) as u32 * MAX_BRIGHTNESS as u32 / 127) as u8
+}
+
+/// Note-to-LED map.
+///
+/// # Details
+/// Notes base_note to base_note + LED_COUNT - 1 drive LEDs 0 to
+/// LED_COUNT - 1. Other notes are ignored.
+///
+/// # Fields
+/// * `base_note` - Note driving LED 0
+/// * `channel` - Channel to follow, None for every channel
+/// * `levels` - Current LED levels
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct NoteMap {
+    base_note: u8,
+    channel: Option<u8>,
+    levels: [u8; LED_COUNT],
+}
+
+impl NoteMap {
+    /// Creates new note map with every LED off.
+    ///
+    /// # Arguments
+    /// * `base_note` - Note driving LED 0
+    /// * `channel` - Channel to follow, None for every channel
+    ///
+    /// # Returns
+    /// * `Self` - New NoteMap instance
+    #[allow(dead_code)]
+    pub fn new(base_note: u8, channel: Option<u8>) -> Self {
+        Self {
+            base_note,
+            channel,
+            levels: [0; LED_COUNT],
+        }
+    }
+
+    /// Returns current frame.
+    ///
+    /// # Returns
+    /// * `Frame` - LED levels from held notes
+    #[allow(dead_code)]
+    pub fn frame(&self) -> Frame {
+        Frame::from_levels(self.levels)
+    }
+
+    /// Returns whether any mapped note is held.
+    ///
+    /// # Returns
+    /// * `bool` - true if any LED is lit by a note
+    #[allow(dead_code)]
+    pub fn is_held(&self) -> bool {
+        self.levels.iter().any(|&level| level > 0)
+    }
+
+    /// Applies a message.
+    ///
+    /// # Arguments
+    /// * `message` - Parsed MIDI message
+    ///
+    /// # Returns
+    /// * `Option<Frame>` - New frame if a mapped note changed
+    #[allow(dead_code)]
+    pub fn apply(&mut self, message: MidiMessage) -> Option<Frame> {
+        let (channel, note, level) = match message {
+            MidiMessage::NoteOn {
+                channel,
+                note,
+                velocity,
+            } => (channel, note, velocity_to_level(velocity)),
+            MidiMessage::NoteOff { channel, note } => (channel, note, 0),
+            _ => return None,
+        };
+        if self.channel.is_some_and(|wanted| wanted != channel) {
+            return None;
+        }
+        let led = note.checked_sub(self.base_note)? as usize;
+        if led >= LED_COUNT {
+            return None;
+        }
+        self.levels[led] = level;
+        Some(self.frame())
+    }
+}
+
+/// MIDI clock follower.
+///
+/// # Details
+/// Smooths the clock interval with a moving average and derives
+/// the step delay from it. Start, Stop and Continue set whether
+/// playback is running.
+///
+/// # Fields
+/// * `clocks_per_step` - Clock pulses per sequence step
+/// * `last_clock_us` - Time of the previous clock, if recent
+/// * `interval_us` - Smoothed clock interval, 0 until measured
+/// * `running` - Whether playback is running
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct MidiClock {
+    clocks_per_step: u8,
+    last_clock_us: Option<u64>,
+    interval_us: u64,
+    running: bool,
+}
+
+impl MidiClock {
+    /// Creates new stopped clock follower.
+    ///
+    /// # Arguments
+    /// * `clocks_per_step` - Clock pulses per step, 6 for sixteenth notes
+    ///
+    /// # Returns
+    /// * `Self` - New MidiClock instance
+    #[allow(dead_code)]
+    pub fn new(clocks_per_step: u8) -> Self {
+        Self {
+            clocks_per_step: clocks_per_step.max(1),
+            last_clock_us: None,
+            interval_us: 0,
+            running: false,
+        }
+    }
+
+    /// Returns whether playback is running.
+    ///
+    /// # Returns
+    /// * `bool` - true between Start or Continue and Stop
+    #[allow(dead_code)]
+    pub fn is_running(&self) -> bool {
+        self.running
+    }
+
+    /// Applies a message.
+    ///
+    /// # Arguments
+    /// * `message` - Parsed MIDI message
+    /// * `now_us` - Time the message arrived in microseconds
+    #[allow(dead_code)]
+    pub fn apply(&mut self, message: MidiMessage, now_us: u64) {
+        match message {
+            MidiMessage::Clock => self.clock(now_us),
+            MidiMessage::Start | MidiMessage::Continue => self.running = true,
+            MidiMessage::Stop => self.running = false,
+            _ => {}
+        }
+    }
+
+    /// Returns tempo.
+    ///
+    /// # Returns
+    /// * `Option<u32>` - Beats per minute, None until measured
+    #[allow(dead_code)]
+    pub fn bpm(&self) -> Option<u32> {
+        (self.interval_us > 0).then(|| (60_000_000 / (self.interval_us * MIDI_PPQN as u64)) as u32)
+    }
+
+    /// Returns step delay locked to the clock.
+    ///
+    /// # Returns
+    /// * `Option<u64>` - Delay in milliseconds, None until measured
+    #[allow(dead_code)]
+    pub fn delay_ms(&self) -> Option<u64> {
+        (self.interval_us > 0)
+            .then(|| (self.interval_us * self.clocks_per_step as u64 + 500) / 1000)
+    }
+
+    /// Measures one clock pulse.
+    ///
+    /// # Arguments
+    /// * `now_us` - Time of the pulse in microseconds
+    fn clock(&mut self, now_us: u64) {
+        if let Some(last) = self.last_clock_us {
+            let interval = now_us.saturating_sub(last);
+            if interval <= MIDI_CLOCK_GAP_US {
+                self.interval_us = if self.interval_us == 0 {
+                    interval
+                } else {
+                    (self.interval_us * 7 + interval) / 8
+                };
+            }
+        }
+        self.last_clock_us = Some(now_us);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(bytes: &[u8]) -> Vec<MidiMessage> {
+        let mut parser = MidiParser::new();
+        bytes.iter().filter_map(|&byte| parser.push(byte)).collect()
+    }
+
+    /// Clock interval for a tempo in microseconds.
+    fn interval_us(bpm: u64) -> u64 {
+        60_000_000 / (bpm * MIDI_PPQN as u64)
+    }
+
+    // ==================== MidiParser Tests ====================
+
+    #[test]
+    fn test_parse_note_on() {
+        assert_eq!(
+            parse(&[0x92, 60, 100]),
+            [MidiMessage::NoteOn {
+                channel: 2,
+                note: 60,
+                velocity: 100
+            }]
+        );
+    }
+
+    #[test]
+    fn test_parse_note_off() {
+        assert_eq!(
+            parse(&[0x80, 61, 64]),
+            [MidiMessage::NoteOff {
+                channel: 0,
+                note: 61
+            }]
+        );
+    }
+
+    #[test]
+    fn test_note_on_zero_velocity_is_off() {
+        assert_eq!(
+            parse(&[0x90, 60, 0]),
+            [MidiMessage::NoteOff {
+                channel: 0,
+                note: 60
+            }]
+        );
+    }
+
+    #[test]
+    fn test_running_status() {
+        let messages = parse(&[0x90, 60, 100, 61, 90, 60, 0]);
+        assert_eq!(messages.len(), 3);
+        assert_eq!(
+            messages[1],
+            MidiMessage::NoteOn {
+                channel: 0,
+                note: 61,
+                velocity: 90
+            }
+        );
+        assert_eq!(
+            messages[2],
+            MidiMessage::NoteOff {
+                channel: 0,
+                note: 60
+            }
+        );
+    }
+
+    #[test]
+    fn test_data_without_status_ignored() {
+        assert!(parse(&[60, 100, 61]).is_empty());
+    }
+
+    #[test]
+    fn test_realtime_messages() {
+        assert_eq!(
+            parse(&[0xF8, 0xFA, 0xFB, 0xFC]),
+            [
+                MidiMessage::Clock,
+                MidiMessage::Start,
+                MidiMessage::Continue,
+                MidiMessage::Stop
+            ]
+        );
+    }
+
+    #[test]
+    fn test_realtime_inside_message() {
+        assert_eq!(
+            parse(&[0x90, 60, 0xF8, 100]),
+            [
+                MidiMessage::Clock,
+                MidiMessage::NoteOn {
+                    channel: 0,
+                    note: 60,
+                    velocity: 100
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn test_realtime_keeps_running_status() {
+        let messages = parse(&[0x90, 60, 100, 0xF8, 62, 50]);
+        assert_eq!(messages.len(), 3);
+        assert_eq!(
+            messages[2],
+            MidiMessage::NoteOn {
+                channel: 0,
+                note: 62,
+                velocity: 50
+            }
+        );
+    }
+
+    #[test]
+    fn test_unused_realtime_ignored() {
+        assert!(parse(&[0xFE, 0xFF]).is_empty());
+    }
+
+    #[test]
+    fn test_system_common_cancels_running_status() {
+        assert!(parse(&[0x90, 60, 100, 0xF3, 1, 62, 50]).len() == 1);
+    }
+
+    #[test]
+    fn test_sysex_skipped() {
+        let messages = parse(&[0xF0, 0x7E, 0x01, 0x02, 0xF7, 0x91, 64, 10]);
+        assert_eq!(
+            messages,
+            [MidiMessage::NoteOn {
+                channel: 1,
+                note: 64,
+                velocity: 10
+            }]
+        );
+    }
+
+    #[test]
+    fn test_two_byte_messages_skipped() {
+        assert_eq!(
+            parse(&[0xC0, 5, 0xD0, 40, 0x90, 60, 1]),
+            [MidiMessage::NoteOn {
+                channel: 0,
+                note: 60,
+                velocity: 1
+            }]
+        );
+    }
+
+    #[test]
+    fn test_control_change_skipped() {
+        assert!(parse(&[0xB0, 7, 100]).is_empty());
+    }
+
+    // ==================== velocity_to_level() Tests ====================
+
+    #[test]
+    fn test_velocity_range() {
+        assert_eq!(velocity_to_level(0), 0);
+        assert_eq!(velocity_to_level(127), MAX_BRIGHTNESS);
+    }
+
+    #[test]
+    fn test_velocity_midpoint() {
+        assert_eq!(velocity_to_level(64), 128);
+    }
+
+    #[test]
+    fn test_velocity_clamped() {
+        assert_eq!(velocity_to_level(200), MAX_BRIGHTNESS);
+    }
+
+    // ==================== NoteMap Tests ====================
+
+    #[test]
+    fn test_note_map_starts_dark() {
+        assert_eq!(NoteMap::new(60, None).frame(), Frame::new());
+    }
+
+    #[test]
+    fn test_note_on_lights_led() {
+        let mut map = NoteMap::new(60, None);
+        let frame = map.apply(MidiMessage::NoteOn {
+            channel: 0,
+            note: 62,
+            velocity: 127,
+        });
+        assert_eq!(frame, Some(Frame::single(2, MAX_BRIGHTNESS)));
+    }
+
+    #[test]
+    fn test_is_held_follows_notes() {
+        let mut map = NoteMap::new(60, None);
+        assert!(!map.is_held());
+        map.apply(MidiMessage::NoteOn {
+            channel: 0,
+            note: 61,
+            velocity: 1,
+        });
+        assert!(map.is_held());
+        map.apply(MidiMessage::NoteOff {
+            channel: 0,
+            note: 61,
+        });
+        assert!(!map.is_held());
+    }
+
+    #[test]
+    fn test_note_off_clears_led() {
+        let mut map = NoteMap::new(60, None);
+        map.apply(MidiMessage::NoteOn {
+            channel: 0,
+            note: 60,
+            velocity: 127,
+        });
+        let frame = map.apply(MidiMessage::NoteOff {
+            channel: 0,
+            note: 60,
+        });
+        assert_eq!(frame, Some(Frame::new()));
+    }
+
+    #[test]
+    fn test_notes_outside_range_ignored() {
+        let mut map = NoteMap::new(60, None);
+        let below = MidiMessage::NoteOn {
+            channel: 0,
+            note: 59,
+            velocity: 100,
+        };
+        let above = MidiMessage::NoteOn {
+            channel: 0,
+            note: 60 + LED_COUNT as u8,
+            velocity: 100,
+        };
+        assert_eq!(map.apply(below), None);
+        assert_eq!(map.apply(above), None);
+    }
+
+    #[test]
+    fn test_channel_filter() {
+        let mut map = NoteMap::new(60, Some(9));
+        let other = MidiMessage::NoteOn {
+            channel: 0,
+            note: 60,
+            velocity: 100,
+        };
+        let wanted = MidiMessage::NoteOn {
+            channel: 9,
+            note: 60,
+            velocity: 100,
+        };
+        assert_eq!(map.apply(other), None);
+        assert!(map.apply(wanted).is_some());
+    }
+
+    #[test]
+    fn test_chord_lights_several() {
+        let mut map = NoteMap::new(60, None);
+        for (note, velocity) in [(60, 127), (61, 64), (63, 1)] {
+            map.apply(MidiMessage::NoteOn {
+                channel: 0,
+                note,
+                velocity,
+            });
+        }
+        assert_eq!(map.frame(), Frame::from_levels([255, 128, 0, 2]));
+    }
+
+    #[test]
+    fn test_clock_ignored_by_note_map() {
+        let mut map = NoteMap::new(60, None);
+        assert_eq!(map.apply(MidiMessage::Clock), None);
+    }
+
+    // ==================== MidiClock Tests ====================
+
+    #[test]
+    fn test_clock_starts_stopped() {
+        let clock = MidiClock::new(6);
+        assert!(!clock.is_running());
+        assert_eq!(clock.delay_ms(), None);
+        assert_eq!(clock.bpm(), None);
+    }
+
+    #[test]
+    fn test_clock_locks_tempo() {
+        let mut clock = MidiClock::new(6);
+        let interval = interval_us(120);
+        for tick in 0..48 {
+            clock.apply(MidiMessage::Clock, tick * interval);
+        }
+        assert_eq!(clock.bpm(), Some(120));
+        assert_eq!(clock.delay_ms(), Some(125));
+    }
+
+    #[test]
+    fn test_clock_per_quarter_delay() {
+        let mut clock = MidiClock::new(24);
+        for tick in 0..10 {
+            clock.apply(MidiMessage::Clock, tick * interval_us(100));
+        }
+        assert_eq!(clock.delay_ms(), Some(600));
+    }
+
+    #[test]
+    fn test_clock_follows_tempo_change() {
+        let mut clock = MidiClock::new(6);
+        let mut now = 0;
+        for _ in 0..48 {
+            now += interval_us(120);
+            clock.apply(MidiMessage::Clock, now);
+        }
+        for _ in 0..96 {
+            now += interval_us(150);
+            clock.apply(MidiMessage::Clock, now);
+        }
+        assert_eq!(clock.bpm(), Some(150));
+        assert_eq!(clock.delay_ms(), Some(100));
+    }
+
+    #[test]
+    fn test_clock_smooths_jitter() {
+        let mut clock = MidiClock::new(6);
+        let mut now = 0;
+        for tick in 0..96 {
+            now += interval_us(120) + if tick % 2 == 0 { 400 } else { 0 };
+            now -= if tick % 2 == 0 { 0 } else { 400 };
+            clock.apply(MidiMessage::Clock, now);
+        }
+        assert_eq!(clock.bpm(), Some(120));
+    }
+
+    #[test]
+    fn test_clock_gap_restarts_measurement() {
+        let mut clock = MidiClock::new(6);
+        clock.apply(MidiMessage::Clock, 0);
+        clock.apply(MidiMessage::Clock, 20_000);
+        clock.apply(MidiMessage::Clock, 5_000_000);
+        clock.apply(MidiMessage::Clock, 5_020_000);
+        assert_eq!(clock.delay_ms(), Some(120));
+    }
+
+    #[test]
+    fn test_start_stop_continue() {
+        let mut clock = MidiClock::new(6);
+        clock.apply(MidiMessage::Start, 0);
+        assert!(clock.is_running());
+        clock.apply(MidiMessage::Stop, 0);
+        assert!(!clock.is_running());
+        clock.apply(MidiMessage::Continue, 0);
+        assert!(clock.is_running());
+    }
+
+    #[test]
+    fn test_zero_clocks_per_step_clamped() {
+        let mut clock = MidiClock::new(0);
+        clock.apply(MidiMessage::Clock, 0);
+        clock.apply(MidiMessage::Clock, 20_000);
+        assert_eq!(clock.delay_ms(), Some(20));
+    }
+}
@@ -0,0 +1,824 @@
+/*
+ * @file morse.rs
+ * @brief Morse code text-to-blink encoder
+ * @author Kevin Thomas
+ * @date 2025
+ *
+ * MIT License
+ *
+ * Copyright (c) 2025 Kevin Thomas
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+//! FILE: morse.rs
+//!
+//! DESCRIPTION:
+//! Morse Code Text-to-Blink Encoder for RP2350.
+//!
+//! BRIEF:
+//! Encodes ASCII text into on/off timing using standard Morse ratios.
+//! Plays queued messages on a single LED or on all LEDs together.
+//!
+//! AUTHOR: Kevin Thomas
+//! CREATION DATE: October 18, 2026
+//! UPDATE DATE: October 18, 2026
+
+use crate::config::{MAX_BRIGHTNESS, MORSE_MESSAGE_LEN, MORSE_QUEUE_LEN};
+use crate::frame::Frame;
+
+/// Length of a dot in units.
+#[allow(dead_code)]
+pub const DOT_UNITS: u8 = 1;
+
+/// Length of a dash in units.
+#[allow(dead_code)]
+pub const DASH_UNITS: u8 = 3;
+
+/// Gap between elements of one character in units.
+#[allow(dead_code)]
+pub const ELEMENT_GAP_UNITS: u8 = 1;
+
+/// Gap between characters of one word in units.
+#[allow(dead_code)]
+pub const LETTER_GAP_UNITS: u8 = 3;
+
+/// Gap between words and after a message in units.
+#[allow(dead_code)]
+pub const WORD_GAP_UNITS: u8 = 7;
+
+/// Morse error enumeration.
+///
+/// # Variants
+/// * `TooLong` - Message exceeds MORSE_MESSAGE_LEN
+/// * `NotAscii` - Message contains non-ASCII bytes
+/// * `QueueFull` - Message queue has no free slot
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[allow(dead_code)]
+pub enum MorseError {
+    TooLong,
+    NotAscii,
+    QueueFull,
+}
+
+/// Morse output target enumeration.
+///
+/// # Variants
+/// * `Led` - Single LED by index
+/// * `All` - Every LED together
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[allow(dead_code)]
+pub enum MorseTarget {
+    Led(usize),
+    All,
+}
+
+/// Single timed on/off symbol.
+///
+/// # Fields
+/// * `on` - Whether the LED is lit
+/// * `units` - Length in dot units
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct Symbol {
+    pub on: bool,
+    pub units: u8,
+}
+
+/// Returns Morse elements for a character.
+///
+/// # Details
+/// Letters are case-insensitive. Unsupported characters return None
+/// and are skipped by the encoder.
+///
+/// # Arguments
+/// * `c` - ASCII character
+///
+/// # Returns
+/// * `Option<&'static [u8]>` - Elements as b'.' and b'-'
+#[allow(dead_code)]
+pub fn morse_code(c: u8) -> Option<&'static [u8]> {
+    let code: &'static [u8] = match c.to_ascii_uppercase() {
+        b'A' => b".-",
+        b'B' => b"-...",
+        b'C' => b"-.-.",
+        b'D' => b"-..",
+        b'E' => b".",
+        b'F' => b"..-.",
+        b'G' => b"--.",
+        b'H' => b"....",
+        b'I' => b"..",
+        b'J' => b".---",
+        b'K' => b"-.-",
+        b'L' => b".-..",
+        b'M' => b"--",
+        b'N' => b"-.",
+        b'O' => b"---",
+        b'P' => b".--.",
+        b'Q' => b"--.-",
+        b'R' => b".-.",
+        b'S' => b"...",
+        b'T' => b"-",
+        b'U' => b"..-",
+        b'V' => b"...-",
+        b'W' => b".--",
+        b'X' => b"-..-",
+        b'Y' => b"-.--",
+        b'Z' => b"--..",
+        b'0' => b"-----",
+        b'1' => b".----",
+        b'2' => b"..---",
+        b'3' => b"...--",
+        b'4' => b"....-",
+        b'5' => b".....",
+        b'6' => b"-....",
+        b'7' => b"--...",
+        b'8' => b"---..",
+        b'9' => b"----.",
+        b'.' => b".-.-.-",
+        b',' => b"--..--",
+        b'?' => b"..--..",
+        b'/' => b"-..-.",
+        b'-' => b"-....-",
+        b'=' => b"-...-",
+        _ => return None,
+    };
+    Some(code)
+}
+
+/// Position within an encoded message.
+///
+/// # Details
+/// Kept separate from the text so an owned message and its
+/// position can live side by side in a player.
+///
+/// # Fields
+/// * `pos` - Index of the current character
+/// * `element` - Index of the next element within the character
+/// * `gap_due` - Whether a gap follows the last emitted element
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct MorseCursor {
+    pos: usize,
+    element: usize,
+    gap_due: bool,
+}
+
+impl MorseCursor {
+    /// Returns next symbol of the text.
+    ///
+    /// # Details
+    /// Emits elements and gaps using standard ratios and ends
+    /// every message with a word gap.
+    ///
+    /// # Arguments
+    /// * `text` - Message being encoded
+    ///
+    /// # Returns
+    /// * `Option<Symbol>` - Next symbol, or None at the end
+    #[allow(dead_code)]
+    pub fn next_symbol(&mut self, text: &[u8]) -> Option<Symbol> {
+        if self.gap_due {
+            self.gap_due = false;
+            return Some(self.gap(text));
+        }
+        while self.pos < text.len() && morse_code(text[self.pos]).is_none() {
+            self.pos += 1;
+        }
+        let code = morse_code(*text.get(self.pos)?)?;
+        let units = if code[self.element] == b'.' {
+            DOT_UNITS
+        } else {
+            DASH_UNITS
+        };
+        self.element += 1;
+        self.gap_due = true;
+        Some(Symbol { on: true, units })
+    }
+
+    /// Returns gap after an element and moves to the next character when due.
+    ///
+    /// # Arguments
+    /// * `text` - Message being encoded
+    ///
+    /// # Returns
+    /// * `Symbol` - Off symbol of the matching gap length
+    fn gap(&mut self, text: &[u8]) -> Symbol {
+        let code = morse_code(text[self.pos]).unwrap_or_default();
+        if self.element < code.len() {
+            return Symbol {
+                on: false,
+                units: ELEMENT_GAP_UNITS,
+            };
+        }
+        self.element = 0;
+        self.pos += 1;
+        let mut word_break = false;
+        while self.pos < text.len() && morse_code(text[self.pos]).is_none() {
+            word_break |= text[self.pos] == b' ';
+            self.pos += 1;
+        }
+        let units = if word_break || self.pos >= text.len() {
+            WORD_GAP_UNITS
+        } else {
+            LETTER_GAP_UNITS
+        };
+        Symbol { on: false, units }
+    }
+}
+
+/// Iterator over the Morse symbols of a text.
+///
+/// # Fields
+/// * `text` - Message being encoded
+/// * `cursor` - Position within the message
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct MorseSymbols<'a> {
+    text: &'a [u8],
+    cursor: MorseCursor,
+}
+
+impl<'a> MorseSymbols<'a> {
+    /// Creates symbol iterator for a text.
+    ///
+    /// # Arguments
+    /// * `text` - ASCII message
+    ///
+    /// # Returns
+    /// * `Self` - New MorseSymbols instance
+    #[allow(dead_code)]
+    pub fn new(text: &'a [u8]) -> Self {
+        Self {
+            text,
+            cursor: MorseCursor::default(),
+        }
+    }
+}
+
+impl Iterator for MorseSymbols<'_> {
+    type Item = Symbol;
+
+    /// Returns next symbol.
+    ///
+    /// # Returns
+    /// * `Option<Symbol>` - Next symbol, or None at the end
+    fn next(&mut self) -> Option<Symbol> {
+        self.cursor.next_symbol(self.text)
+    }
+}
+
+/// Owned Morse message.
+///
+/// # Fields
+/// * `text` - ASCII bytes of the message
+/// * `len` - Bytes in use
+/// * `target` - LED or LEDs to blink
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[allow(dead_code)]
+pub struct MorseMessage {
+    text: [u8; MORSE_MESSAGE_LEN],
+    len: usize,
+    target: MorseTarget,
+}
+
+impl MorseMessage {
+    /// Creates message from text.
+    ///
+    /// # Arguments
+    /// * `text` - ASCII message
+    /// * `target` - LED or LEDs to blink
+    ///
+    /// # Returns
+    /// * `Result<Self, MorseError>` - New message
+    #[allow(dead_code)]
+    pub fn new(text: &str, target: MorseTarget) -> Result<Self, MorseError> {
+        if !text.is_ascii() {
+            return Err(MorseError::NotAscii);
+        }
+        if text.len() > MORSE_MESSAGE_LEN {
+            return Err(MorseError::TooLong);
+        }
+        let mut buf = [0; MORSE_MESSAGE_LEN];
+        buf[..text.len()].copy_from_slice(text.as_bytes());
+        Ok(Self {
+            text: buf,
+            len: text.len(),
+            target,
+        })
+    }
+
+    /// Returns message text.
+    ///
+    /// # Returns
+    /// * `&[u8]` - ASCII bytes of the message
+    #[allow(dead_code)]
+    pub fn text(&self) -> &[u8] {
+        &self.text[..self.len]
+    }
+
+    /// Returns message target.
+    ///
+    /// # Returns
+    /// * `MorseTarget` - LED or LEDs to blink
+    #[allow(dead_code)]
+    pub fn target(&self) -> MorseTarget {
+        self.target
+    }
+}
+
+/// First-in first-out queue of Morse messages.
+///
+/// # Fields
+/// * `slots` - Ring buffer storage
+/// * `head` - Index of the oldest message
+/// * `len` - Messages waiting
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct MorseQueue {
+    slots: [Option<MorseMessage>; MORSE_QUEUE_LEN],
+    head: usize,
+    len: usize,
+}
+
+impl Default for MorseQueue {
+    /// Returns default MorseQueue instance.
+    ///
+    /// # Details
+    /// Delegates to new() for initialization.
+    ///
+    /// # Returns
+    /// * `Self` - New empty MorseQueue
+    #[allow(dead_code)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MorseQueue {
+    /// Creates new empty queue.
+    ///
+    /// # Returns
+    /// * `Self` - New MorseQueue instance
+    #[allow(dead_code)]
+    pub fn new() -> Self {
+        Self {
+            slots: [None; MORSE_QUEUE_LEN],
+            head: 0,
+            len: 0,
+        }
+    }
+
+    /// Adds a message at the back of the queue.
+    ///
+    /// # Arguments
+    /// * `message` - Message to add
+    ///
+    /// # Returns
+    /// * `Result<(), MorseError>` - QueueFull if no slot is free
+    #[allow(dead_code)]
+    pub fn push(&mut self, message: MorseMessage) -> Result<(), MorseError> {
+        if self.len == MORSE_QUEUE_LEN {
+            return Err(MorseError::QueueFull);
+        }
+        self.slots[(self.head + self.len) % MORSE_QUEUE_LEN] = Some(message);
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Removes the message at the front of the queue.
+    ///
+    /// # Returns
+    /// * `Option<MorseMessage>` - Oldest message, if any
+    #[allow(dead_code)]
+    pub fn pop(&mut self) -> Option<MorseMessage> {
+        let message = self.slots[self.head].take()?;
+        self.head = (self.head + 1) % MORSE_QUEUE_LEN;
+        self.len -= 1;
+        Some(message)
+    }
+
+    /// Returns number of waiting messages.
+    ///
+    /// # Returns
+    /// * `usize` - Messages in the queue
+    #[allow(dead_code)]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns whether the queue is empty.
+    ///
+    /// # Returns
+    /// * `bool` - true if no messages wait
+    #[allow(dead_code)]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+/// Morse message player.
+///
+/// # Details
+/// Plays queued messages one after another. The dot unit is
+/// normally the sequence delay so that Morse speed follows the
+/// configured tempo.
+///
+/// # Fields
+/// * `queue` - Messages waiting to play
+/// * `current` - Message being played
+/// * `cursor` - Position within the current message
+/// * `unit_ms` - Dot length in milliseconds
+/// * `remaining_ms` - Time left in the current symbol
+/// * `on` - Whether the current symbol is lit
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct MorsePlayer {
+    queue: MorseQueue,
+    current: Option<MorseMessage>,
+    cursor: MorseCursor,
+    unit_ms: u64,
+    remaining_ms: u64,
+    on: bool,
+}
+
+impl MorsePlayer {
+    /// Creates new idle player.
+    ///
+    /// # Arguments
+    /// * `unit_ms` - Dot length in milliseconds (at least 1)
+    ///
+    /// # Returns
+    /// * `Self` - New MorsePlayer instance
+    #[allow(dead_code)]
+    pub fn new(unit_ms: u64) -> Self {
+        Self {
+            queue: MorseQueue::new(),
+            current: None,
+            cursor: MorseCursor::default(),
+            unit_ms: unit_ms.max(1),
+            remaining_ms: 0,
+            on: false,
+        }
+    }
+
+    /// Returns dot length.
+    ///
+    /// # Returns
+    /// * `u64` - Dot length in milliseconds
+    #[allow(dead_code)]
+    pub fn unit_ms(&self) -> u64 {
+        self.unit_ms
+    }
+
+    /// Sets dot length for following symbols.
+    ///
+    /// # Arguments
+    /// * `unit_ms` - Dot length in milliseconds (at least 1)
+    #[allow(dead_code)]
+    pub fn set_unit_ms(&mut self, unit_ms: u64) {
+        self.unit_ms = unit_ms.max(1);
+    }
+
+    /// Queues a message for playback.
+    ///
+    /// # Arguments
+    /// * `message` - Message to play
+    ///
+    /// # Returns
+    /// * `Result<(), MorseError>` - QueueFull if no slot is free
+    #[allow(dead_code)]
+    pub fn enqueue(&mut self, message: MorseMessage) -> Result<(), MorseError> {
+        self.queue.push(message)
+    }
+
+    /// Returns whether a message is playing or waiting.
+    ///
+    /// # Returns
+    /// * `bool` - true while busy
+    #[allow(dead_code)]
+    pub fn is_active(&self) -> bool {
+        self.current.is_some() || !self.queue.is_empty()
+    }
+
+    /// Advances playback time.
+    ///
+    /// # Arguments
+    /// * `elapsed_ms` - Time since the previous tick
+    ///
+    /// # Returns
+    /// * `Option<Frame>` - Frame to display, or None when idle
+    #[allow(dead_code)]
+    pub fn tick(&mut self, elapsed_ms: u64) -> Option<Frame> {
+        let mut elapsed = elapsed_ms;
+        while elapsed >= self.remaining_ms {
+            elapsed -= self.remaining_ms;
+            if !self.load_symbol() {
+                self.remaining_ms = 0;
+                return None;
+            }
+        }
+        self.remaining_ms -= elapsed;
+        Some(self.frame())
+    }
+
+    /// Loads the next symbol, starting the next message when needed.
+    ///
+    /// # Returns
+    /// * `bool` - false when nothing is left to play
+    fn load_symbol(&mut self) -> bool {
+        loop {
+            if self.current.is_none() {
+                let Some(message) = self.queue.pop() else {
+                    return false;
+                };
+                self.current = Some(message);
+                self.cursor = MorseCursor::default();
+            }
+            let text = self.current.as_ref().map(MorseMessage::text).unwrap_or(&[]);
+            if let Some(symbol) = self.cursor.next_symbol(text) {
+                self.on = symbol.on;
+                self.remaining_ms = symbol.units as u64 * self.unit_ms;
+                return true;
+            }
+            self.current = None;
+        }
+    }
+
+    /// Returns frame for the current symbol.
+    ///
+    /// # Returns
+    /// * `Frame` - Lit target when on, blank otherwise
+    #[allow(dead_code)]
+    pub fn frame(&self) -> Frame {
+        match (self.on, self.current.map(|message| message.target())) {
+            (true, Some(MorseTarget::Led(index))) => Frame::single(index, MAX_BRIGHTNESS),
+            (true, Some(MorseTarget::All)) => Frame::filled(MAX_BRIGHTNESS),
+            _ => Frame::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode(text: &str) -> Vec<(bool, u8)> {
+        MorseSymbols::new(text.as_bytes())
+            .map(|symbol| (symbol.on, symbol.units))
+            .collect()
+    }
+
+    // ==================== morse_code() Tests ====================
+
+    #[test]
+    fn test_code_letters() {
+        assert_eq!(morse_code(b'S'), Some(&b"..."[..]));
+        assert_eq!(morse_code(b'O'), Some(&b"---"[..]));
+    }
+
+    #[test]
+    fn test_code_case_insensitive() {
+        assert_eq!(morse_code(b'a'), morse_code(b'A'));
+    }
+
+    #[test]
+    fn test_code_digits() {
+        assert_eq!(morse_code(b'0'), Some(&b"-----"[..]));
+        assert_eq!(morse_code(b'5'), Some(&b"....."[..]));
+    }
+
+    #[test]
+    fn test_code_unsupported() {
+        assert_eq!(morse_code(b'#'), None);
+        assert_eq!(morse_code(b' '), None);
+    }
+
+    #[test]
+    fn test_code_all_alphanumerics_supported() {
+        for c in (b'A'..=b'Z').chain(b'0'..=b'9') {
+            assert!(morse_code(c).is_some(), "{}", c as char);
+        }
+    }
+
+    // ==================== Encoder Tests ====================
+
+    #[test]
+    fn test_encode_e() {
+        assert_eq!(encode("E"), vec![(true, 1), (false, 7)]);
+    }
+
+    #[test]
+    fn test_encode_a_ratios() {
+        assert_eq!(
+            encode("A"),
+            vec![(true, 1), (false, 1), (true, 3), (false, 7)]
+        );
+    }
+
+    #[test]
+    fn test_encode_letter_gap() {
+        assert_eq!(
+            encode("ET"),
+            vec![(true, 1), (false, 3), (true, 3), (false, 7)]
+        );
+    }
+
+    #[test]
+    fn test_encode_word_gap() {
+        assert_eq!(
+            encode("E T"),
+            vec![(true, 1), (false, 7), (true, 3), (false, 7)]
+        );
+    }
+
+    #[test]
+    fn test_encode_collapses_spaces() {
+        assert_eq!(encode("E   T"), encode("E T"));
+    }
+
+    #[test]
+    fn test_encode_skips_unsupported() {
+        assert_eq!(encode("E#T"), encode("ET"));
+        assert_eq!(encode("#E"), encode("E"));
+    }
+
+    #[test]
+    fn test_encode_empty() {
+        assert!(encode("").is_empty());
+        assert!(encode("   ").is_empty());
+    }
+
+    #[test]
+    fn test_encode_sos_total_units() {
+        let total: u32 = encode("SOS").iter().map(|&(_, units)| units as u32).sum();
+        assert_eq!(total, 5 + 3 + 11 + 3 + 5 + 7);
+    }
+
+    // ==================== MorseMessage Tests ====================
+
+    #[test]
+    fn test_message_new() {
+        let message = MorseMessage::new("SOS", MorseTarget::All).unwrap();
+        assert_eq!(message.text(), b"SOS");
+        assert_eq!(message.target(), MorseTarget::All);
+    }
+
+    #[test]
+    fn test_message_too_long() {
+        let text = "A".repeat(MORSE_MESSAGE_LEN + 1);
+        assert_eq!(
+            MorseMessage::new(&text, MorseTarget::All),
+            Err(MorseError::TooLong)
+        );
+    }
+
+    #[test]
+    fn test_message_not_ascii() {
+        assert_eq!(
+            MorseMessage::new("é", MorseTarget::All),
+            Err(MorseError::NotAscii)
+        );
+    }
+
+    // ==================== MorseQueue Tests ====================
+
+    #[test]
+    fn test_queue_fifo() {
+        let mut queue = MorseQueue::new();
+        let a = MorseMessage::new("A", MorseTarget::All).unwrap();
+        let b = MorseMessage::new("B", MorseTarget::All).unwrap();
+        queue.push(a).unwrap();
+        queue.push(b).unwrap();
+        assert_eq!(queue.pop(), Some(a));
+        assert_eq!(queue.pop(), Some(b));
+        assert_eq!(queue.pop(), None);
+    }
+
+    #[test]
+    fn test_queue_full() {
+        let mut queue = MorseQueue::new();
+        let message = MorseMessage::new("A", MorseTarget::All).unwrap();
+        for _ in 0..MORSE_QUEUE_LEN {
+            queue.push(message).unwrap();
+        }
+        assert_eq!(queue.push(message), Err(MorseError::QueueFull));
+        assert_eq!(queue.len(), MORSE_QUEUE_LEN);
+    }
+
+    #[test]
+    fn test_queue_wraps() {
+        let mut queue = MorseQueue::new();
+        let message = MorseMessage::new("A", MorseTarget::All).unwrap();
+        for _ in 0..MORSE_QUEUE_LEN * 3 {
+            queue.push(message).unwrap();
+            assert_eq!(queue.pop(), Some(message));
+        }
+        assert!(queue.is_empty());
+    }
+
+    // ==================== MorsePlayer Tests ====================
+
+    #[test]
+    fn test_player_idle() {
+        let mut player = MorsePlayer::new(100);
+        assert!(!player.is_active());
+        assert_eq!(player.tick(100), None);
+    }
+
+    #[test]
+    fn test_player_single_led() {
+        let mut player = MorsePlayer::new(100);
+        let message = MorseMessage::new("E", MorseTarget::Led(2)).unwrap();
+        player.enqueue(message).unwrap();
+        assert_eq!(player.tick(0), Some(Frame::single(2, MAX_BRIGHTNESS)));
+        assert_eq!(player.tick(99), Some(Frame::single(2, MAX_BRIGHTNESS)));
+        assert_eq!(player.tick(1), Some(Frame::new()));
+    }
+
+    #[test]
+    fn test_player_frame_follows_tick() {
+        let mut player = MorsePlayer::new(100);
+        assert_eq!(player.frame(), Frame::new());
+        let message = MorseMessage::new("E", MorseTarget::Led(1)).unwrap();
+        player.enqueue(message).unwrap();
+        let frame = player.tick(0);
+        assert_eq!(frame, Some(player.frame()));
+    }
+
+    #[test]
+    fn test_player_all_leds() {
+        let mut player = MorsePlayer::new(100);
+        let message = MorseMessage::new("T", MorseTarget::All).unwrap();
+        player.enqueue(message).unwrap();
+        assert_eq!(player.tick(250), Some(Frame::filled(MAX_BRIGHTNESS)));
+    }
+
+    #[test]
+    fn test_player_finishes_after_word_gap() {
+        let mut player = MorsePlayer::new(10);
+        let message = MorseMessage::new("E", MorseTarget::All).unwrap();
+        player.enqueue(message).unwrap();
+        assert!(player.tick(79).is_some());
+        assert_eq!(player.tick(1), None);
+        assert!(!player.is_active());
+    }
+
+    #[test]
+    fn test_player_plays_queue_in_order() {
+        let mut player = MorsePlayer::new(10);
+        player
+            .enqueue(MorseMessage::new("E", MorseTarget::Led(0)).unwrap())
+            .unwrap();
+        player
+            .enqueue(MorseMessage::new("E", MorseTarget::Led(1)).unwrap())
+            .unwrap();
+        assert_eq!(player.tick(0), Some(Frame::single(0, MAX_BRIGHTNESS)));
+        assert_eq!(player.tick(80), Some(Frame::single(1, MAX_BRIGHTNESS)));
+    }
+
+    #[test]
+    fn test_player_unit_scaling() {
+        let mut player = MorsePlayer::new(250);
+        player
+            .enqueue(MorseMessage::new("T", MorseTarget::All).unwrap())
+            .unwrap();
+        assert!(player.tick(749).unwrap().lit_count() > 0);
+        assert_eq!(player.tick(1), Some(Frame::new()));
+    }
+
+    #[test]
+    fn test_player_unit_minimum() {
+        let player = MorsePlayer::new(0);
+        assert_eq!(player.unit_ms(), 1);
+    }
+
+    #[test]
+    fn test_player_empty_message_skipped() {
+        let mut player = MorsePlayer::new(10);
+        player
+            .enqueue(MorseMessage::new("", MorseTarget::All).unwrap())
+            .unwrap();
+        assert_eq!(player.tick(0), None);
+        assert!(!player.is_active());
+    }
+}
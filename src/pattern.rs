@@ -0,0 +1,452 @@
+/*
+ * @file pattern.rs
+ * @brief LED sequence patterns
+ * @author Kevin Thomas
+ * @date 2025
+ *
+ * MIT License
+ *
+ * Copyright (c) 2025 Kevin Thomas
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+//! FILE: pattern.rs
+//!
+//! DESCRIPTION:
+//! LED Sequence Patterns for RP2350.
+//!
+//! BRIEF:
+//! Provides step-indexed patterns that render LED frames.
+//! Each pattern defines its own cycle length for wrapping.
+//! Patterns can run on the first few LEDs of the row only.
+//!
+//! AUTHOR: Kevin Thomas
+//! CREATION DATE: October 18, 2026
+//! UPDATE DATE: October 18, 2026
+
+use crate::config::{BREATHE_STEPS, LED_COUNT, MAX_BRIGHTNESS};
+use crate::frame::Frame;
+
+/// LED sequence pattern enumeration.
+///
+/// # Details
+/// Describes how a step index maps to a frame.
+/// Patterns are stateless; the caller owns the step position.
+///
+/// # Variants
+/// * `Chase` - Single LED moves from first to last
+/// * `Bounce` - Single LED moves back and forth
+/// * `Fill` - LEDs light up one by one then clear
+/// * `Breathe` - All LEDs ramp up and down together
+/// * `Blink` - All LEDs toggle on and off
+/// * `Alternate` - Even and odd LEDs take turns
+/// * `Solid` - All LEDs fully on
+/// * `Blank` - All LEDs off
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[allow(dead_code)]
+pub enum Pattern {
+    Chase,
+    Bounce,
+    Fill,
+    Breathe,
+    Blink,
+    Alternate,
+    Solid,
+    Blank,
+}
+
+impl Pattern {
+    /// Returns number of steps before the pattern repeats.
+    ///
+    /// # Returns
+    /// * `usize` - Cycle length in steps (at least 1)
+    #[allow(dead_code)]
+    pub fn cycle_len(self) -> usize {
+        self.cycle_len_on(LED_COUNT)
+    }
+
+    /// Returns number of steps before the pattern repeats on a shorter row.
+    ///
+    /// # Arguments
+    /// * `leds` - LEDs the pattern runs on
+    ///
+    /// # Returns
+    /// * `usize` - Cycle length in steps (at least 1)
+    #[allow(dead_code)]
+    pub fn cycle_len_on(self, leds: usize) -> usize {
+        let leds = leds.min(LED_COUNT);
+        match self {
+            Pattern::Chase => leds.max(1),
+            Pattern::Bounce => (2 * leds).saturating_sub(2).max(1),
+            Pattern::Fill => leds + 1,
+            Pattern::Breathe => BREATHE_STEPS,
+            Pattern::Blink | Pattern::Alternate => 2,
+            Pattern::Solid | Pattern::Blank => 1,
+        }
+    }
+
+    /// Renders frame for given step.
+    ///
+    /// # Details
+    /// Step is wrapped to the cycle length, so any value is valid.
+    ///
+    /// # Arguments
+    /// * `step` - Step position within the pattern
+    ///
+    /// # Returns
+    /// * `Frame` - Rendered LED frame
+    #[allow(dead_code)]
+    pub fn frame(self, step: usize) -> Frame {
+        self.frame_on(step, LED_COUNT)
+    }
+
+    /// Renders frame for given step on the first `leds` LEDs.
+    ///
+    /// # Details
+    /// LEDs past the row are left dark. Step is wrapped to the
+    /// cycle length for the row, so any value is valid.
+    ///
+    /// # Arguments
+    /// * `step` - Step position within the pattern
+    /// * `leds` - LEDs the pattern runs on
+    ///
+    /// # Returns
+    /// * `Frame` - Rendered LED frame
+    #[allow(dead_code)]
+    pub fn frame_on(self, step: usize, leds: usize) -> Frame {
+        let leds = leds.min(LED_COUNT);
+        if leds == 0 {
+            return Frame::new();
+        }
+        let step = step % self.cycle_len_on(leds);
+        match self {
+            Pattern::Chase => Frame::single(step, MAX_BRIGHTNESS),
+            Pattern::Bounce => Frame::single(bounce_position(step, leds), MAX_BRIGHTNESS),
+            Pattern::Fill => fill_frame(step, MAX_BRIGHTNESS),
+            Pattern::Breathe => fill_frame(leds, breathe_level(step)),
+            Pattern::Blink if step == 0 => fill_frame(leds, MAX_BRIGHTNESS),
+            Pattern::Blink => Frame::new(),
+            Pattern::Alternate => alternate_frame(step, leds),
+            Pattern::Solid => fill_frame(leds, MAX_BRIGHTNESS),
+            Pattern::Blank => Frame::new(),
+        }
+    }
+}
+
+/// Returns LED index for a bounce step.
+///
+/// # Arguments
+/// * `step` - Step within the bounce cycle
+/// * `leds` - LEDs the pattern runs on
+///
+/// # Returns
+/// * `usize` - LED index to light
+fn bounce_position(step: usize, leds: usize) -> usize {
+    if step < leds {
+        step
+    } else {
+        2 * (leds - 1) - step
+    }
+}
+
+/// Returns frame with the first `count` LEDs lit.
+///
+/// # Arguments
+/// * `count` - Number of LEDs to light
+/// * `level` - Brightness of the lit LEDs
+///
+/// # Returns
+/// * `Frame` - Rendered LED frame
+fn fill_frame(count: usize, level: u8) -> Frame {
+    let mut frame = Frame::new();
+    for index in 0..count {
+        frame.set_level(index, level);
+    }
+    frame
+}
+
+/// Returns frame with every other LED lit.
+///
+/// # Arguments
+/// * `parity` - 0 lights even indices, 1 lights odd indices
+/// * `leds` - LEDs the pattern runs on
+///
+/// # Returns
+/// * `Frame` - Rendered LED frame
+fn alternate_frame(parity: usize, leds: usize) -> Frame {
+    let mut frame = Frame::new();
+    for index in (parity..leds).step_by(2) {
+        frame.set_level(index, MAX_BRIGHTNESS);
+    }
+    frame
+}
+
+/// Returns breathing brightness for a step.
+///
+/// # Details
+/// Rises linearly to full at half cycle then falls back.
+///
+/// # Arguments
+/// * `step` - Step within the breathe cycle
+///
+/// # Returns
+/// * `u8` - Brightness level
+fn breathe_level(step: usize) -> u8 {
+    let half = BREATHE_STEPS / 2;
+    let distance = if step <= half {
+        step
+    } else {
+        BREATHE_STEPS - step
+    };
+    (distance * MAX_BRIGHTNESS as usize / half) as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // ==================== cycle_len() Tests ====================
+
+    #[test]
+    fn test_chase_cycle_is_led_count() {
+        assert_eq!(Pattern::Chase.cycle_len(), LED_COUNT);
+    }
+
+    #[test]
+    fn test_bounce_cycle() {
+        assert_eq!(Pattern::Bounce.cycle_len(), 6);
+    }
+
+    #[test]
+    fn test_fill_cycle() {
+        assert_eq!(Pattern::Fill.cycle_len(), LED_COUNT + 1);
+    }
+
+    #[test]
+    fn test_breathe_cycle() {
+        assert_eq!(Pattern::Breathe.cycle_len(), BREATHE_STEPS);
+    }
+
+    #[test]
+    fn test_static_cycles() {
+        assert_eq!(Pattern::Blink.cycle_len(), 2);
+        assert_eq!(Pattern::Alternate.cycle_len(), 2);
+        assert_eq!(Pattern::Solid.cycle_len(), 1);
+        assert_eq!(Pattern::Blank.cycle_len(), 1);
+    }
+
+    // ==================== Chase Tests ====================
+
+    #[test]
+    fn test_chase_lights_step() {
+        for step in 0..LED_COUNT {
+            assert_eq!(
+                Pattern::Chase.frame(step),
+                Frame::single(step, MAX_BRIGHTNESS)
+            );
+        }
+    }
+
+    #[test]
+    fn test_chase_wraps() {
+        assert_eq!(Pattern::Chase.frame(LED_COUNT), Pattern::Chase.frame(0));
+    }
+
+    // ==================== Bounce Tests ====================
+
+    #[test]
+    fn test_bounce_positions() {
+        let positions: Vec<usize> = (0..Pattern::Bounce.cycle_len())
+            .map(|step| {
+                let frame = Pattern::Bounce.frame(step);
+                (0..LED_COUNT).find(|&i| frame.level(i) > 0).unwrap()
+            })
+            .collect();
+        assert_eq!(positions, vec![0, 1, 2, 3, 2, 1]);
+    }
+
+    #[test]
+    fn test_bounce_single_led() {
+        for step in 0..12 {
+            assert_eq!(Pattern::Bounce.frame(step).lit_count(), 1);
+        }
+    }
+
+    // ==================== Fill Tests ====================
+
+    #[test]
+    fn test_fill_counts() {
+        for step in 0..=LED_COUNT {
+            assert_eq!(Pattern::Fill.frame(step).lit_count(), step);
+        }
+    }
+
+    #[test]
+    fn test_fill_lights_from_start() {
+        let frame = Pattern::Fill.frame(2);
+        assert_eq!(frame.level(0), MAX_BRIGHTNESS);
+        assert_eq!(frame.level(1), MAX_BRIGHTNESS);
+        assert_eq!(frame.level(2), 0);
+    }
+
+    // ==================== Breathe Tests ====================
+
+    #[test]
+    fn test_breathe_starts_dark() {
+        assert_eq!(Pattern::Breathe.frame(0), Frame::new());
+    }
+
+    #[test]
+    fn test_breathe_peaks_at_half() {
+        let frame = Pattern::Breathe.frame(BREATHE_STEPS / 2);
+        assert_eq!(frame, Frame::filled(MAX_BRIGHTNESS));
+    }
+
+    #[test]
+    fn test_breathe_symmetric() {
+        for step in 1..BREATHE_STEPS / 2 {
+            assert_eq!(
+                Pattern::Breathe.frame(step),
+                Pattern::Breathe.frame(BREATHE_STEPS - step)
+            );
+        }
+    }
+
+    #[test]
+    fn test_breathe_all_leds_equal() {
+        let frame = Pattern::Breathe.frame(5);
+        for i in 1..LED_COUNT {
+            assert_eq!(frame.level(i), frame.level(0));
+        }
+    }
+
+    // ==================== Static Pattern Tests ====================
+
+    #[test]
+    fn test_blink_toggles() {
+        assert_eq!(Pattern::Blink.frame(0), Frame::filled(MAX_BRIGHTNESS));
+        assert_eq!(Pattern::Blink.frame(1), Frame::new());
+    }
+
+    #[test]
+    fn test_alternate_even_then_odd() {
+        assert_eq!(
+            Pattern::Alternate.frame(0),
+            Frame::from_levels([255, 0, 255, 0])
+        );
+        assert_eq!(
+            Pattern::Alternate.frame(1),
+            Frame::from_levels([0, 255, 0, 255])
+        );
+    }
+
+    #[test]
+    fn test_solid_all_on() {
+        assert_eq!(Pattern::Solid.frame(3), Frame::filled(MAX_BRIGHTNESS));
+    }
+
+    #[test]
+    fn test_blank_all_off() {
+        assert_eq!(Pattern::Blank.frame(3), Frame::new());
+    }
+
+    // ==================== Shorter Row Tests ====================
+
+    #[test]
+    fn test_full_row_matches_frame() {
+        for step in 0..12 {
+            assert_eq!(
+                Pattern::Bounce.frame_on(step, LED_COUNT),
+                Pattern::Bounce.frame(step)
+            );
+        }
+    }
+
+    #[test]
+    fn test_chase_on_three() {
+        assert_eq!(Pattern::Chase.cycle_len_on(3), 3);
+        assert_eq!(
+            Pattern::Chase.frame_on(2, 3),
+            Frame::single(2, MAX_BRIGHTNESS)
+        );
+        assert_eq!(
+            Pattern::Chase.frame_on(3, 3),
+            Frame::single(0, MAX_BRIGHTNESS)
+        );
+    }
+
+    #[test]
+    fn test_bounce_on_three() {
+        assert_eq!(Pattern::Bounce.cycle_len_on(3), 4);
+        assert_eq!(
+            Pattern::Bounce.frame_on(3, 3),
+            Frame::single(1, MAX_BRIGHTNESS)
+        );
+    }
+
+    #[test]
+    fn test_fill_on_two() {
+        assert_eq!(Pattern::Fill.cycle_len_on(2), 3);
+        assert_eq!(
+            Pattern::Fill.frame_on(2, 2),
+            Frame::from_levels([MAX_BRIGHTNESS, MAX_BRIGHTNESS, 0, 0])
+        );
+    }
+
+    #[test]
+    fn test_solid_on_two_leaves_rest_dark() {
+        assert_eq!(
+            Pattern::Solid.frame_on(0, 2),
+            Frame::from_levels([MAX_BRIGHTNESS, MAX_BRIGHTNESS, 0, 0])
+        );
+    }
+
+    #[test]
+    fn test_alternate_on_three() {
+        assert_eq!(
+            Pattern::Alternate.frame_on(0, 3),
+            Frame::from_levels([MAX_BRIGHTNESS, 0, MAX_BRIGHTNESS, 0])
+        );
+    }
+
+    #[test]
+    fn test_no_leds_dark() {
+        assert_eq!(Pattern::Chase.cycle_len_on(0), 1);
+        assert_eq!(Pattern::Solid.frame_on(0, 0), Frame::new());
+    }
+
+    #[test]
+    fn test_row_clamped_to_led_count() {
+        assert_eq!(Pattern::Chase.cycle_len_on(LED_COUNT + 3), LED_COUNT);
+    }
+
+    // ==================== Trait Implementation Tests ====================
+
+    #[test]
+    fn test_pattern_size() {
+        assert_eq!(core::mem::size_of::<Pattern>(), 1);
+    }
+
+    #[test]
+    fn test_pattern_debug() {
+        assert_eq!(format!("{:?}", Pattern::Chase), "Chase");
+    }
+}
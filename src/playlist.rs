@@ -0,0 +1,848 @@
+/*
+ * @file playlist.rs
+ * @brief Pattern playlist with transitions
+ * @author Kevin Thomas
+ * @date 2025
+ *
+ * MIT License
+ *
+ * Copyright (c) 2025 Kevin Thomas
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+//! FILE: playlist.rs
+//!
+//! DESCRIPTION:
+//! Pattern Playlist with Transitions for RP2350.
+//!
+//! BRIEF:
+//! Cycles through a list of patterns for unattended playback.
+//! Handles entry length, cut/crossfade/wipe transitions, shuffle and loop.
+//!
+//! AUTHOR: Kevin Thomas
+//! CREATION DATE: October 18, 2026
+//! UPDATE DATE: October 18, 2026
+
+use crate::config::{LED_COUNT, MAX_BRIGHTNESS, MAX_PLAYLIST_ENTRIES};
+use crate::frame::Frame;
+use crate::led::LedSequenceController;
+use crate::pattern::Pattern;
+use crate::rng::Rng;
+
+/// Playlist entry length enumeration.
+///
+/// # Variants
+/// * `Duration` - Play for a number of milliseconds
+/// * `Repeats` - Play a number of full pattern cycles
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub enum EntryLength {
+    Duration(u32),
+    Repeats(u16),
+}
+
+/// Transition enumeration.
+///
+/// # Details
+/// Describes how an entry replaces the previous one.
+///
+/// # Variants
+/// * `Cut` - Switch immediately
+/// * `Crossfade` - Fade between entries over a number of milliseconds
+/// * `Wipe` - Replace LEDs from first to last over a number of milliseconds
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub enum Transition {
+    Cut,
+    Crossfade(u32),
+    Wipe(u32),
+}
+
+/// Playlist error enumeration.
+///
+/// # Variants
+/// * `Empty` - Playlist has no entries
+/// * `TooManyEntries` - Playlist exceeds MAX_PLAYLIST_ENTRIES
+/// * `ZeroLength` - An entry has zero duration or repeats
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[allow(dead_code)]
+pub enum PlaylistError {
+    Empty,
+    TooManyEntries,
+    ZeroLength,
+}
+
+/// Single playlist entry.
+///
+/// # Fields
+/// * `pattern` - Pattern to play
+/// * `step_ms` - Delay between pattern steps in milliseconds
+/// * `length` - How long the entry plays
+/// * `transition` - How the entry replaces the previous one
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct PlaylistEntry {
+    pub pattern: Pattern,
+    pub step_ms: u64,
+    pub length: EntryLength,
+    pub transition: Transition,
+}
+
+impl PlaylistEntry {
+    /// Creates new playlist entry entered with a cut.
+    ///
+    /// # Arguments
+    /// * `pattern` - Pattern to play
+    /// * `step_ms` - Delay between pattern steps in milliseconds
+    /// * `length` - How long the entry plays
+    ///
+    /// # Returns
+    /// * `Self` - New PlaylistEntry instance
+    #[allow(dead_code)]
+    pub const fn new(pattern: Pattern, step_ms: u64, length: EntryLength) -> Self {
+        Self {
+            pattern,
+            step_ms,
+            length,
+            transition: Transition::Cut,
+        }
+    }
+
+    /// Returns entry with given transition.
+    ///
+    /// # Arguments
+    /// * `transition` - How the entry replaces the previous one
+    ///
+    /// # Returns
+    /// * `Self` - Updated entry
+    #[allow(dead_code)]
+    pub const fn with_transition(mut self, transition: Transition) -> Self {
+        self.transition = transition;
+        self
+    }
+}
+
+/// Pattern deck with its own step timing.
+///
+/// # Fields
+/// * `controller` - Pattern state and step delay
+/// * `step_elapsed_ms` - Time accumulated toward the next step
+/// * `cycles` - Full pattern cycles completed
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Deck {
+    controller: LedSequenceController,
+    step_elapsed_ms: u64,
+    cycles: u32,
+}
+
+impl Deck {
+    /// Creates deck for a playlist entry.
+    ///
+    /// # Arguments
+    /// * `entry` - Entry to play
+    /// * `led_count` - LEDs the pattern runs on
+    ///
+    /// # Returns
+    /// * `Self` - New Deck at the first step
+    fn new(entry: &PlaylistEntry, led_count: usize) -> Self {
+        let mut controller = LedSequenceController::with_pattern(entry.pattern);
+        controller.set_delay_ms(entry.step_ms);
+        controller.set_led_count(led_count);
+        Self {
+            controller,
+            step_elapsed_ms: 0,
+            cycles: 0,
+        }
+    }
+
+    /// Advances deck time and steps the pattern when due.
+    ///
+    /// # Arguments
+    /// * `elapsed_ms` - Time since the previous tick
+    ///
+    /// # Returns
+    /// * `u32` - Steps taken
+    fn tick(&mut self, elapsed_ms: u64) -> u32 {
+        self.step_elapsed_ms += elapsed_ms;
+        let mut steps = 0;
+        while self.step_elapsed_ms >= self.controller.delay_ms() {
+            self.step_elapsed_ms -= self.controller.delay_ms();
+            if self.controller.advance() == 0 {
+                self.cycles += 1;
+            }
+            steps += 1;
+        }
+        steps
+    }
+}
+
+/// Transition in progress.
+///
+/// # Fields
+/// * `outgoing` - Deck being replaced
+/// * `transition` - Transition style
+/// * `elapsed_ms` - Time since the transition started
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Fade {
+    outgoing: Deck,
+    transition: Transition,
+    elapsed_ms: u32,
+}
+
+/// Playlist player.
+///
+/// # Details
+/// Borrows a list of entries and plays them in sequential or
+/// shuffled order. Entry time includes its incoming transition.
+/// The first entry always starts with a cut. Without looping the
+/// last entry keeps playing once the playlist has finished.
+///
+/// # Fields
+/// * `entries` - Entries to play
+/// * `order` - Play order as entry indices
+/// * `position` - Position within the play order
+/// * `looping` - Whether to restart after the last entry
+/// * `shuffle` - Shuffle generator, None when sequential
+/// * `deck` - Deck for the current entry
+/// * `entry_elapsed_ms` - Time since the current entry started
+/// * `fade` - Transition in progress, if any
+/// * `finished` - Whether one-shot playback has ended
+/// * `steps` - Steps taken by incoming decks, wrapping
+/// * `led_count` - LEDs the patterns run on
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct Playlist<'a> {
+    entries: &'a [PlaylistEntry],
+    order: [u8; MAX_PLAYLIST_ENTRIES],
+    position: usize,
+    looping: bool,
+    shuffle: Option<Rng>,
+    deck: Deck,
+    entry_elapsed_ms: u32,
+    fade: Option<Fade>,
+    finished: bool,
+    steps: u32,
+    led_count: usize,
+}
+
+impl<'a> Playlist<'a> {
+    /// Creates new looping sequential playlist.
+    ///
+    /// # Arguments
+    /// * `entries` - Entries to play
+    ///
+    /// # Returns
+    /// * `Result<Self, PlaylistError>` - Playlist at the first entry
+    #[allow(dead_code)]
+    pub fn new(entries: &'a [PlaylistEntry]) -> Result<Self, PlaylistError> {
+        if entries.is_empty() {
+            return Err(PlaylistError::Empty);
+        }
+        if entries.len() > MAX_PLAYLIST_ENTRIES {
+            return Err(PlaylistError::TooManyEntries);
+        }
+        if entries.iter().any(|entry| {
+            matches!(
+                entry.length,
+                EntryLength::Duration(0) | EntryLength::Repeats(0)
+            )
+        }) {
+            return Err(PlaylistError::ZeroLength);
+        }
+        let mut order = [0; MAX_PLAYLIST_ENTRIES];
+        for (index, slot) in order.iter_mut().enumerate() {
+            *slot = index as u8;
+        }
+        let led_count = LED_COUNT;
+        Ok(Self {
+            entries,
+            order,
+            position: 0,
+            looping: true,
+            shuffle: None,
+            deck: Deck::new(&entries[0], led_count),
+            entry_elapsed_ms: 0,
+            fade: None,
+            finished: false,
+            steps: 0,
+            led_count,
+        })
+    }
+
+    /// Returns playlist with looping enabled or disabled.
+    ///
+    /// # Arguments
+    /// * `looping` - true to restart after the last entry
+    ///
+    /// # Returns
+    /// * `Self` - Updated playlist
+    #[allow(dead_code)]
+    pub fn with_loop(mut self, looping: bool) -> Self {
+        self.looping = looping;
+        self
+    }
+
+    /// Returns playlist with shuffled order.
+    ///
+    /// # Details
+    /// The order is reshuffled on every pass. A given seed
+    /// always produces the same order.
+    ///
+    /// # Arguments
+    /// * `seed` - Shuffle seed
+    ///
+    /// # Returns
+    /// * `Self` - Updated playlist restarted at its first entry
+    #[allow(dead_code)]
+    pub fn with_shuffle(mut self, seed: u32) -> Self {
+        self.shuffle = Some(Rng::new(seed));
+        self.shuffle();
+        self.position = 0;
+        self.deck = Deck::new(self.current_entry(), self.led_count);
+        self
+    }
+
+    /// Sets number of LEDs the patterns run on.
+    ///
+    /// # Details
+    /// Applies to the playing decks and every later entry.
+    ///
+    /// # Arguments
+    /// * `led_count` - LEDs in the sequence
+    #[allow(dead_code)]
+    pub fn set_led_count(&mut self, led_count: usize) {
+        self.led_count = led_count;
+        self.deck.controller.set_led_count(led_count);
+        if let Some(fade) = self.fade.as_mut() {
+            fade.outgoing.controller.set_led_count(led_count);
+        }
+    }
+
+    /// Returns index of the entry being played.
+    ///
+    /// # Returns
+    /// * `usize` - Index into the entry list
+    #[allow(dead_code)]
+    pub fn current_index(&self) -> usize {
+        self.order[self.position] as usize
+    }
+
+    /// Returns entry being played.
+    ///
+    /// # Returns
+    /// * `&PlaylistEntry` - Current entry
+    #[allow(dead_code)]
+    pub fn current_entry(&self) -> &PlaylistEntry {
+        &self.entries[self.current_index()]
+    }
+
+    /// Returns controller of the entry being played.
+    ///
+    /// # Returns
+    /// * `&LedSequenceController` - Current pattern state and step delay
+    #[allow(dead_code)]
+    pub fn controller(&self) -> &LedSequenceController {
+        &self.deck.controller
+    }
+
+    /// Returns steps taken so far.
+    ///
+    /// # Details
+    /// Counts steps of the entry being played, not of an outgoing
+    /// entry during a transition. Wraps on overflow.
+    ///
+    /// # Returns
+    /// * `u32` - Step count
+    #[allow(dead_code)]
+    pub fn steps(&self) -> u32 {
+        self.steps
+    }
+
+    /// Returns whether a transition is in progress.
+    ///
+    /// # Returns
+    /// * `bool` - true while transitioning
+    #[allow(dead_code)]
+    pub fn is_transitioning(&self) -> bool {
+        self.fade.is_some()
+    }
+
+    /// Returns whether one-shot playback has ended.
+    ///
+    /// # Returns
+    /// * `bool` - true once the last entry has completed without looping
+    #[allow(dead_code)]
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Moves to the next entry using its transition.
+    ///
+    /// # Details
+    /// Does nothing once one-shot playback has finished.
+    #[allow(dead_code)]
+    pub fn skip(&mut self) {
+        if self.finished {
+            return;
+        }
+        if self.position + 1 < self.entries.len() {
+            self.position += 1;
+        } else if self.looping {
+            let last = self.current_index();
+            if self.shuffle.is_some() {
+                self.shuffle();
+                self.avoid_repeat(last);
+            }
+            self.position = 0;
+        } else {
+            self.finished = true;
+            return;
+        }
+        let outgoing = self.deck;
+        let entry = *self.current_entry();
+        self.deck = Deck::new(&entry, self.led_count);
+        self.entry_elapsed_ms = 0;
+        self.fade = match entry.transition {
+            Transition::Cut => None,
+            transition => Some(Fade {
+                outgoing,
+                transition,
+                elapsed_ms: 0,
+            }),
+        };
+    }
+
+    /// Advances playback and returns the output frame.
+    ///
+    /// # Arguments
+    /// * `elapsed_ms` - Time since the previous tick
+    ///
+    /// # Returns
+    /// * `Frame` - Frame to display
+    #[allow(dead_code)]
+    pub fn tick(&mut self, elapsed_ms: u32) -> Frame {
+        let steps = self.deck.tick(elapsed_ms as u64);
+        self.steps = self.steps.wrapping_add(steps);
+        self.entry_elapsed_ms = self.entry_elapsed_ms.saturating_add(elapsed_ms);
+        if let Some(fade) = self.fade.as_mut() {
+            fade.outgoing.tick(elapsed_ms as u64);
+            fade.elapsed_ms = fade.elapsed_ms.saturating_add(elapsed_ms);
+            if fade.elapsed_ms >= transition_ms(fade.transition) {
+                self.fade = None;
+            }
+        }
+        if self.entry_done() {
+            self.skip();
+        }
+        self.frame()
+    }
+
+    /// Returns output frame without advancing time.
+    ///
+    /// # Returns
+    /// * `Frame` - Frame to display
+    #[allow(dead_code)]
+    pub fn frame(&self) -> Frame {
+        let incoming = self.deck.controller.frame();
+        let Some(fade) = self.fade else {
+            return incoming;
+        };
+        let outgoing = fade.outgoing.controller.frame();
+        let total = transition_ms(fade.transition).max(1) as u64;
+        let elapsed = fade.elapsed_ms as u64;
+        match fade.transition {
+            Transition::Cut => incoming,
+            Transition::Crossfade(_) => {
+                let amount = elapsed * MAX_BRIGHTNESS as u64 / total;
+                outgoing.mix(&incoming, amount as u8)
+            }
+            Transition::Wipe(_) => {
+                let edge = (elapsed * self.led_count as u64 / total) as usize;
+                let mut out = outgoing;
+                for index in 0..edge {
+                    out.set_level(index, incoming.level(index));
+                }
+                out
+            }
+        }
+    }
+
+    /// Returns whether the current entry has played its length.
+    ///
+    /// # Returns
+    /// * `bool` - true when the entry should end
+    fn entry_done(&self) -> bool {
+        match self.current_entry().length {
+            EntryLength::Duration(ms) => self.entry_elapsed_ms >= ms,
+            EntryLength::Repeats(count) => self.deck.cycles >= count as u32,
+        }
+    }
+
+    /// Shuffles the play order.
+    ///
+    /// # Details
+    /// Fisher-Yates shuffle; does nothing when sequential.
+    fn shuffle(&mut self) {
+        let Some(rng) = self.shuffle.as_mut() else {
+            return;
+        };
+        for index in (1..self.entries.len()).rev() {
+            let pick = rng.below(index as u32 + 1) as usize;
+            self.order.swap(index, pick);
+        }
+    }
+
+    /// Keeps a new pass from starting with the entry just played.
+    ///
+    /// # Arguments
+    /// * `last` - Entry index that ended the previous pass
+    fn avoid_repeat(&mut self, last: usize) {
+        let count = self.entries.len();
+        if count > 1 && self.order[0] as usize == last {
+            self.order.swap(0, count - 1);
+        }
+    }
+}
+
+/// Returns transition length.
+///
+/// # Arguments
+/// * `transition` - Transition style
+///
+/// # Returns
+/// * `u32` - Length in milliseconds, 0 for a cut
+fn transition_ms(transition: Transition) -> u32 {
+    match transition {
+        Transition::Cut => 0,
+        Transition::Crossfade(ms) | Transition::Wipe(ms) => ms,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHASE_TWICE: PlaylistEntry =
+        PlaylistEntry::new(Pattern::Chase, 100, EntryLength::Repeats(2));
+    const SOLID_SECOND: PlaylistEntry =
+        PlaylistEntry::new(Pattern::Solid, 100, EntryLength::Duration(1000));
+    const BLANK_SECOND: PlaylistEntry =
+        PlaylistEntry::new(Pattern::Blank, 100, EntryLength::Duration(1000));
+
+    fn pass_order(playlist: &mut Playlist, count: usize) -> Vec<usize> {
+        (0..count)
+            .map(|_| {
+                let index = playlist.current_index();
+                playlist.skip();
+                index
+            })
+            .collect()
+    }
+
+    // ==================== Validation Tests ====================
+
+    #[test]
+    fn test_new_empty() {
+        assert_eq!(Playlist::new(&[]).err(), Some(PlaylistError::Empty));
+    }
+
+    #[test]
+    fn test_new_too_many() {
+        let entries = [SOLID_SECOND; MAX_PLAYLIST_ENTRIES + 1];
+        assert_eq!(
+            Playlist::new(&entries).err(),
+            Some(PlaylistError::TooManyEntries)
+        );
+    }
+
+    #[test]
+    fn test_new_zero_duration() {
+        let entries = [PlaylistEntry::new(
+            Pattern::Solid,
+            100,
+            EntryLength::Duration(0),
+        )];
+        assert_eq!(
+            Playlist::new(&entries).err(),
+            Some(PlaylistError::ZeroLength)
+        );
+    }
+
+    #[test]
+    fn test_new_zero_repeats() {
+        let entries = [PlaylistEntry::new(
+            Pattern::Solid,
+            100,
+            EntryLength::Repeats(0),
+        )];
+        assert_eq!(
+            Playlist::new(&entries).err(),
+            Some(PlaylistError::ZeroLength)
+        );
+    }
+
+    #[test]
+    fn test_new_starts_at_first() {
+        let entries = [SOLID_SECOND, BLANK_SECOND];
+        let playlist = Playlist::new(&entries).unwrap();
+        assert_eq!(playlist.current_index(), 0);
+        assert_eq!(playlist.frame(), Pattern::Solid.frame(0));
+    }
+
+    #[test]
+    fn test_controller_follows_entry() {
+        let entries = [CHASE_TWICE, BLANK_SECOND];
+        let mut playlist = Playlist::new(&entries).unwrap();
+        playlist.tick(100);
+        assert_eq!(playlist.controller().pattern(), Pattern::Chase);
+        assert_eq!(playlist.controller().current_index(), 1);
+        playlist.skip();
+        assert_eq!(playlist.controller().pattern(), Pattern::Blank);
+    }
+
+    #[test]
+    fn test_steps_counts_incoming_deck() {
+        let entries = [CHASE_TWICE, BLANK_SECOND];
+        let mut playlist = Playlist::new(&entries).unwrap();
+        playlist.tick(250);
+        assert_eq!(playlist.steps(), 2);
+        playlist.tick(50);
+        assert_eq!(playlist.steps(), 3);
+    }
+
+    // ==================== Entry Length Tests ====================
+
+    #[test]
+    fn test_duration_entry_ends() {
+        let entries = [SOLID_SECOND, BLANK_SECOND];
+        let mut playlist = Playlist::new(&entries).unwrap();
+        playlist.tick(990);
+        assert_eq!(playlist.current_index(), 0);
+        playlist.tick(10);
+        assert_eq!(playlist.current_index(), 1);
+    }
+
+    #[test]
+    fn test_repeats_entry_ends() {
+        let entries = [CHASE_TWICE, BLANK_SECOND];
+        let mut playlist = Playlist::new(&entries).unwrap();
+        for _ in 0..(2 * LED_COUNT - 1) {
+            playlist.tick(100);
+        }
+        assert_eq!(playlist.current_index(), 0);
+        playlist.tick(100);
+        assert_eq!(playlist.current_index(), 1);
+    }
+
+    #[test]
+    fn test_entry_uses_own_tempo() {
+        let entries = [PlaylistEntry::new(
+            Pattern::Chase,
+            50,
+            EntryLength::Duration(1000),
+        )];
+        let mut playlist = Playlist::new(&entries).unwrap();
+        assert_eq!(playlist.tick(100), Pattern::Chase.frame(2));
+    }
+
+    // ==================== Loop Tests ====================
+
+    #[test]
+    fn test_loop_wraps() {
+        let entries = [SOLID_SECOND, BLANK_SECOND];
+        let mut playlist = Playlist::new(&entries).unwrap();
+        playlist.tick(1000);
+        playlist.tick(1000);
+        assert_eq!(playlist.current_index(), 0);
+        assert!(!playlist.is_finished());
+    }
+
+    #[test]
+    fn test_once_finishes_on_last() {
+        let entries = [SOLID_SECOND, BLANK_SECOND];
+        let mut playlist = Playlist::new(&entries).unwrap().with_loop(false);
+        playlist.tick(1000);
+        playlist.tick(1000);
+        assert!(playlist.is_finished());
+        assert_eq!(playlist.current_index(), 1);
+        assert_eq!(playlist.tick(5000), Frame::new());
+    }
+
+    #[test]
+    fn test_skip_after_finish_ignored() {
+        let entries = [SOLID_SECOND];
+        let mut playlist = Playlist::new(&entries).unwrap().with_loop(false);
+        playlist.skip();
+        playlist.skip();
+        assert!(playlist.is_finished());
+        assert_eq!(playlist.current_index(), 0);
+    }
+
+    // ==================== Transition Tests ====================
+
+    #[test]
+    fn test_cut_switches_immediately() {
+        let entries = [SOLID_SECOND, BLANK_SECOND];
+        let mut playlist = Playlist::new(&entries).unwrap();
+        assert_eq!(playlist.tick(1000), Frame::new());
+        assert!(!playlist.is_transitioning());
+    }
+
+    #[test]
+    fn test_crossfade() {
+        let entries = [
+            SOLID_SECOND,
+            BLANK_SECOND.with_transition(Transition::Crossfade(200)),
+        ];
+        let mut playlist = Playlist::new(&entries).unwrap();
+        let start = playlist.tick(1000);
+        assert_eq!(start, Frame::filled(MAX_BRIGHTNESS));
+        assert!(playlist.is_transitioning());
+        let middle = playlist.tick(100);
+        assert_eq!(middle.level(0), 128);
+        let end = playlist.tick(100);
+        assert_eq!(end, Frame::new());
+        assert!(!playlist.is_transitioning());
+    }
+
+    #[test]
+    fn test_wipe() {
+        let entries = [
+            SOLID_SECOND,
+            BLANK_SECOND.with_transition(Transition::Wipe(400)),
+        ];
+        let mut playlist = Playlist::new(&entries).unwrap();
+        playlist.tick(1000);
+        let frame = playlist.tick(200);
+        assert_eq!(frame.level(0), 0);
+        assert_eq!(frame.level(1), 0);
+        assert_eq!(frame.level(2), MAX_BRIGHTNESS);
+        assert_eq!(frame.level(3), MAX_BRIGHTNESS);
+    }
+
+    #[test]
+    fn test_transition_counts_toward_entry_time() {
+        let entries = [
+            SOLID_SECOND,
+            BLANK_SECOND.with_transition(Transition::Crossfade(200)),
+        ];
+        let mut playlist = Playlist::new(&entries).unwrap();
+        playlist.tick(1000);
+        playlist.tick(1000);
+        assert_eq!(playlist.current_index(), 0);
+    }
+
+    #[test]
+    fn test_first_entry_transition_ignored() {
+        let entries = [SOLID_SECOND.with_transition(Transition::Crossfade(500))];
+        let playlist = Playlist::new(&entries).unwrap();
+        assert!(!playlist.is_transitioning());
+    }
+
+    // ==================== Shuffle Tests ====================
+
+    #[test]
+    fn test_shuffle_plays_every_entry() {
+        let entries = [SOLID_SECOND; 8];
+        let mut playlist = Playlist::new(&entries).unwrap().with_shuffle(42);
+        let mut order = pass_order(&mut playlist, 8);
+        order.sort();
+        assert_eq!(order, (0..8).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_shuffle_reproducible() {
+        let entries = [SOLID_SECOND; 8];
+        let mut a = Playlist::new(&entries).unwrap().with_shuffle(7);
+        let mut b = Playlist::new(&entries).unwrap().with_shuffle(7);
+        assert_eq!(pass_order(&mut a, 24), pass_order(&mut b, 24));
+    }
+
+    #[test]
+    fn test_shuffle_changes_order() {
+        let entries = [SOLID_SECOND; 8];
+        let mut playlist = Playlist::new(&entries).unwrap().with_shuffle(42);
+        assert_ne!(pass_order(&mut playlist, 8), (0..8).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_shuffle_no_repeat_across_passes() {
+        let entries = [SOLID_SECOND; 4];
+        let mut playlist = Playlist::new(&entries).unwrap().with_shuffle(3);
+        let order = pass_order(&mut playlist, 40);
+        for pair in order.windows(2) {
+            assert_ne!(pair[0], pair[1]);
+        }
+    }
+
+    #[test]
+    fn test_shuffle_zero_seed_still_shuffles() {
+        let entries = [SOLID_SECOND; 8];
+        let mut playlist = Playlist::new(&entries).unwrap().with_shuffle(0);
+        assert_ne!(pass_order(&mut playlist, 8), (0..8).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_sequential_order() {
+        let entries = [SOLID_SECOND; 3];
+        let mut playlist = Playlist::new(&entries).unwrap();
+        assert_eq!(pass_order(&mut playlist, 6), vec![0, 1, 2, 0, 1, 2]);
+    }
+
+    // ==================== LED Count Tests ====================
+
+    #[test]
+    fn test_set_led_count_shortens_repeats() {
+        let entries = [CHASE_TWICE, BLANK_SECOND];
+        let mut playlist = Playlist::new(&entries).unwrap();
+        playlist.set_led_count(3);
+        for _ in 0..5 {
+            playlist.tick(100);
+        }
+        assert_eq!(playlist.current_index(), 0);
+        playlist.tick(100);
+        assert_eq!(playlist.current_index(), 1);
+    }
+
+    #[test]
+    fn test_set_led_count_applies_to_later_entries() {
+        let entries = [SOLID_SECOND, SOLID_SECOND];
+        let mut playlist = Playlist::new(&entries).unwrap();
+        playlist.set_led_count(2);
+        playlist.skip();
+        assert_eq!(playlist.controller().led_count(), 2);
+        assert_eq!(playlist.frame().lit_count(), 2);
+    }
+
+    #[test]
+    fn test_wipe_spans_led_count() {
+        let entries = [
+            SOLID_SECOND,
+            BLANK_SECOND.with_transition(Transition::Wipe(400)),
+        ];
+        let mut playlist = Playlist::new(&entries).unwrap();
+        playlist.set_led_count(2);
+        playlist.tick(1000);
+        let frame = playlist.tick(200);
+        assert_eq!(frame.level(0), 0);
+        assert_eq!(frame.level(1), MAX_BRIGHTNESS);
+    }
+}
@@ -0,0 +1,335 @@
+/*
+ * @file power.rs
+ * @brief LED current budget limiter
+ * @author Kevin Thomas
+ * @date 2025
+ *
+ * MIT License
+ *
+ * Copyright (c) 2025 Kevin Thomas
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+//! FILE: power.rs
+//!
+//! DESCRIPTION:
+//! LED Current Budget Limiter for the Output Stage.
+//!
+//! BRIEF:
+//! Estimates the current a frame draws from the per-LED current.
+//! Scales frames over budget down proportionally or refuses them.
+//! Counts how often limiting happened.
+//!
+//! AUTHOR: Kevin Thomas
+//! CREATION DATE: October 18, 2026
+//! UPDATE DATE: October 18, 2026
+
+use crate::config::MAX_BRIGHTNESS;
+use crate::frame::Frame;
+
+/// Limit mode enumeration.
+///
+/// # Variants
+/// * `Scale` - Dim every LED by the same factor to fit the budget
+/// * `Refuse` - Drop the frame so the previous one stays shown
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub enum LimitMode {
+    Scale,
+    Refuse,
+}
+
+/// Power limiter error enumeration.
+///
+/// # Variants
+/// * `ZeroCurrent` - Per-LED current of zero
+/// * `ZeroBudget` - Current budget of zero
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[allow(dead_code)]
+pub enum PowerError {
+    ZeroCurrent,
+    ZeroBudget,
+}
+
+/// Power limiter statistics.
+///
+/// # Fields
+/// * `frames` - Frames checked
+/// * `scaled` - Frames scaled down
+/// * `refused` - Frames refused
+/// * `peak_ma` - Highest current requested in milliamps
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct PowerStats {
+    pub frames: u32,
+    pub scaled: u32,
+    pub refused: u32,
+    pub peak_ma: u32,
+}
+
+impl PowerStats {
+    /// Returns frames limited in either way.
+    ///
+    /// # Returns
+    /// * `u32` - Scaled plus refused frames
+    #[allow(dead_code)]
+    pub fn limited(&self) -> u32 {
+        self.scaled.saturating_add(self.refused)
+    }
+}
+
+/// LED current budget limiter.
+///
+/// # Details
+/// LED current is taken as proportional to brightness, as PWM duty
+/// is. Currents are in microamps internally to keep precision.
+///
+/// # Fields
+/// * `led_current_ua` - Current of one LED at full brightness
+/// * `budget_ua` - Total current budget
+/// * `mode` - What to do with frames over budget
+/// * `stats` - Counts of checked and limited frames
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct PowerLimiter {
+    led_current_ua: u32,
+    budget_ua: u32,
+    mode: LimitMode,
+    stats: PowerStats,
+}
+
+impl PowerLimiter {
+    /// Creates new power limiter.
+    ///
+    /// # Arguments
+    /// * `led_current_ma` - Current of one LED at full brightness
+    /// * `budget_ma` - Total current budget
+    /// * `mode` - What to do with frames over budget
+    ///
+    /// # Returns
+    /// * `Result<Self, PowerError>` - New PowerLimiter
+    #[allow(dead_code)]
+    pub fn new(led_current_ma: u32, budget_ma: u32, mode: LimitMode) -> Result<Self, PowerError> {
+        if led_current_ma == 0 {
+            return Err(PowerError::ZeroCurrent);
+        }
+        if budget_ma == 0 {
+            return Err(PowerError::ZeroBudget);
+        }
+        Ok(Self {
+            led_current_ua: led_current_ma.saturating_mul(1000),
+            budget_ua: budget_ma.saturating_mul(1000),
+            mode,
+            stats: PowerStats::default(),
+        })
+    }
+
+    /// Returns statistics.
+    ///
+    /// # Returns
+    /// * `PowerStats` - Counts of checked and limited frames
+    #[allow(dead_code)]
+    pub fn stats(&self) -> PowerStats {
+        self.stats
+    }
+
+    /// Clears statistics.
+    #[allow(dead_code)]
+    pub fn reset_stats(&mut self) {
+        self.stats = PowerStats::default();
+    }
+
+    /// Returns current a frame draws.
+    ///
+    /// # Arguments
+    /// * `frame` - Frame to estimate
+    ///
+    /// # Returns
+    /// * `u32` - Current in microamps
+    #[allow(dead_code)]
+    pub fn current_ua(&self, frame: &Frame) -> u32 {
+        let total: u64 = frame.levels().iter().map(|&level| level as u64).sum();
+        (total * self.led_current_ua as u64 / MAX_BRIGHTNESS as u64) as u32
+    }
+
+    /// Checks a frame against the budget.
+    ///
+    /// # Arguments
+    /// * `frame` - Frame to show
+    ///
+    /// # Returns
+    /// * `Option<Frame>` - Frame within budget, or None if refused
+    #[allow(dead_code)]
+    pub fn limit(&mut self, frame: &Frame) -> Option<Frame> {
+        let current = self.current_ua(frame);
+        self.stats.frames = self.stats.frames.wrapping_add(1);
+        self.stats.peak_ma = self.stats.peak_ma.max(current / 1000);
+        if current <= self.budget_ua {
+            return Some(*frame);
+        }
+        match self.mode {
+            LimitMode::Scale => {
+                self.stats.scaled = self.stats.scaled.wrapping_add(1);
+                let mut out = *frame;
+                for index in 0..frame.levels().len() {
+                    let level = frame.level(index) as u64 * self.budget_ua as u64 / current as u64;
+                    out.set_level(index, level as u8);
+                }
+                Some(out)
+            }
+            LimitMode::Refuse => {
+                self.stats.refused = self.stats.refused.wrapping_add(1);
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::LED_COUNT;
+
+    fn limiter(budget_ma: u32, mode: LimitMode) -> PowerLimiter {
+        PowerLimiter::new(20, budget_ma, mode).unwrap()
+    }
+
+    // ==================== Construction Tests ====================
+
+    #[test]
+    fn test_new_zero_current() {
+        assert_eq!(
+            PowerLimiter::new(0, 100, LimitMode::Scale),
+            Err(PowerError::ZeroCurrent)
+        );
+    }
+
+    #[test]
+    fn test_new_zero_budget() {
+        assert_eq!(
+            PowerLimiter::new(20, 0, LimitMode::Scale),
+            Err(PowerError::ZeroBudget)
+        );
+    }
+
+    #[test]
+    fn test_new_stats_empty() {
+        assert_eq!(
+            limiter(100, LimitMode::Scale).stats(),
+            PowerStats::default()
+        );
+    }
+
+    // ==================== Current Tests ====================
+
+    #[test]
+    fn test_current_dark() {
+        assert_eq!(limiter(100, LimitMode::Scale).current_ua(&Frame::new()), 0);
+    }
+
+    #[test]
+    fn test_current_full() {
+        let limiter = limiter(100, LimitMode::Scale);
+        let current = limiter.current_ua(&Frame::filled(MAX_BRIGHTNESS));
+        assert_eq!(current, 20_000 * LED_COUNT as u32);
+    }
+
+    #[test]
+    fn test_current_proportional() {
+        let limiter = limiter(100, LimitMode::Scale);
+        assert_eq!(limiter.current_ua(&Frame::single(0, 51)), 4000);
+    }
+
+    // ==================== Scale Tests ====================
+
+    #[test]
+    fn test_within_budget_unchanged() {
+        let mut limiter = limiter(80, LimitMode::Scale);
+        let frame = Frame::filled(MAX_BRIGHTNESS);
+        assert_eq!(limiter.limit(&frame), Some(frame));
+        assert_eq!(limiter.stats().limited(), 0);
+    }
+
+    #[test]
+    fn test_scale_halves() {
+        let mut limiter = limiter(40, LimitMode::Scale);
+        let out = limiter.limit(&Frame::filled(MAX_BRIGHTNESS)).unwrap();
+        assert_eq!(out, Frame::filled(127));
+        assert_eq!(limiter.stats().scaled, 1);
+    }
+
+    #[test]
+    fn test_scale_keeps_proportions() {
+        let mut limiter = limiter(20, LimitMode::Scale);
+        let out = limiter
+            .limit(&Frame::from_levels([255, 255, 128, 0]))
+            .unwrap();
+        assert_eq!(out, Frame::from_levels([101, 101, 51, 0]));
+    }
+
+    #[test]
+    fn test_scaled_frame_within_budget() {
+        for budget in 1..=80 {
+            let mut limiter = limiter(budget, LimitMode::Scale);
+            let frame = Frame::from_levels([255, 200, 77, 3]);
+            let out = limiter.limit(&frame).unwrap();
+            assert!(limiter.current_ua(&out) <= budget * 1000);
+        }
+    }
+
+    // ==================== Refuse Tests ====================
+
+    #[test]
+    fn test_refuse_over_budget() {
+        let mut limiter = limiter(40, LimitMode::Refuse);
+        assert_eq!(limiter.limit(&Frame::filled(MAX_BRIGHTNESS)), None);
+        assert_eq!(limiter.stats().refused, 1);
+    }
+
+    #[test]
+    fn test_refuse_passes_within_budget() {
+        let mut limiter = limiter(40, LimitMode::Refuse);
+        let frame = Frame::from_levels([255, 255, 0, 0]);
+        assert_eq!(limiter.limit(&frame), Some(frame));
+    }
+
+    // ==================== Stats Tests ====================
+
+    #[test]
+    fn test_stats_count() {
+        let mut limiter = limiter(40, LimitMode::Scale);
+        limiter.limit(&Frame::new());
+        limiter.limit(&Frame::filled(MAX_BRIGHTNESS));
+        limiter.limit(&Frame::filled(MAX_BRIGHTNESS));
+        let stats = limiter.stats();
+        assert_eq!(stats.frames, 3);
+        assert_eq!(stats.limited(), 2);
+        assert_eq!(stats.peak_ma, 80);
+    }
+
+    #[test]
+    fn test_reset_stats() {
+        let mut limiter = limiter(40, LimitMode::Refuse);
+        limiter.limit(&Frame::filled(MAX_BRIGHTNESS));
+        limiter.reset_stats();
+        assert_eq!(limiter.stats(), PowerStats::default());
+    }
+}
@@ -0,0 +1,249 @@
+/*
+ * @file rng.rs
+ * @brief Seedable pseudo-random number generator
+ * @author Kevin Thomas
+ * @date 2025
+ *
+ * MIT License
+ *
+ * Copyright (c) 2025 Kevin Thomas
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+//! FILE: rng.rs
+//!
+//! DESCRIPTION:
+//! Seedable Pseudo-Random Number Generator for RP2350.
+//!
+//! BRIEF:
+//! Provides a small xorshift32 generator for no_std effects.
+//! A given seed always yields the same sequence for host testing.
+//!
+//! AUTHOR: Kevin Thomas
+//! CREATION DATE: October 18, 2026
+//! UPDATE DATE: October 18, 2026
+
+/// Seed used in place of zero.
+///
+/// # Details
+/// Xorshift generators stay at zero forever, so a zero seed is replaced.
+///
+/// # Value
+/// 0x9E37_79B9
+#[allow(dead_code)]
+pub const FALLBACK_SEED: u32 = 0x9E37_79B9;
+
+/// Xorshift32 pseudo-random number generator.
+///
+/// # Details
+/// Deterministic for a given seed. Not suitable for cryptography.
+///
+/// # Fields
+/// * `state` - Non-zero generator state
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct Rng {
+    state: u32,
+}
+
+impl Rng {
+    /// Creates new generator from a seed.
+    ///
+    /// # Arguments
+    /// * `seed` - Initial state, 0 is replaced with FALLBACK_SEED
+    ///
+    /// # Returns
+    /// * `Self` - New Rng instance
+    #[allow(dead_code)]
+    pub const fn new(seed: u32) -> Self {
+        Self {
+            state: if seed == 0 { FALLBACK_SEED } else { seed },
+        }
+    }
+
+    /// Returns next 32-bit value.
+    ///
+    /// # Returns
+    /// * `u32` - Pseudo-random value
+    #[allow(dead_code)]
+    pub fn next_u32(&mut self) -> u32 {
+        let mut x = self.state;
+        x ^= x << 13;
+        x ^= x >> 17;
+        x ^= x << 5;
+        self.state = x;
+        x
+    }
+
+    /// Returns next 8-bit value.
+    ///
+    /// # Returns
+    /// * `u8` - Pseudo-random value
+    #[allow(dead_code)]
+    pub fn next_u8(&mut self) -> u8 {
+        (self.next_u32() >> 24) as u8
+    }
+
+    /// Returns value below a bound.
+    ///
+    /// # Arguments
+    /// * `bound` - Exclusive upper bound
+    ///
+    /// # Returns
+    /// * `u32` - Value in 0..bound, or 0 if bound is 0
+    #[allow(dead_code)]
+    pub fn below(&mut self, bound: u32) -> u32 {
+        if bound == 0 {
+            return 0;
+        }
+        ((self.next_u32() as u64 * bound as u64) >> 32) as u32
+    }
+
+    /// Returns value within an inclusive range.
+    ///
+    /// # Arguments
+    /// * `low` - Lowest value
+    /// * `high` - Highest value
+    ///
+    /// # Returns
+    /// * `u8` - Value in low..=high, or low if high < low
+    #[allow(dead_code)]
+    pub fn range_u8(&mut self, low: u8, high: u8) -> u8 {
+        if high <= low {
+            return low;
+        }
+        low + self.below((high - low) as u32 + 1) as u8
+    }
+
+    /// Returns true with a given probability.
+    ///
+    /// # Arguments
+    /// * `chance` - Probability out of 256
+    ///
+    /// # Returns
+    /// * `bool` - true with probability chance / 256
+    #[allow(dead_code)]
+    pub fn chance(&mut self, chance: u8) -> bool {
+        self.next_u8() < chance
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // ==================== Determinism Tests ====================
+
+    #[test]
+    fn test_same_seed_same_sequence() {
+        let mut a = Rng::new(1234);
+        let mut b = Rng::new(1234);
+        for _ in 0..100 {
+            assert_eq!(a.next_u32(), b.next_u32());
+        }
+    }
+
+    #[test]
+    fn test_different_seed_different_sequence() {
+        let mut a = Rng::new(1);
+        let mut b = Rng::new(2);
+        assert_ne!(a.next_u32(), b.next_u32());
+    }
+
+    #[test]
+    fn test_known_first_value() {
+        let mut rng = Rng::new(1);
+        assert_eq!(rng.next_u32(), 270_369);
+    }
+
+    #[test]
+    fn test_zero_seed_replaced() {
+        assert_eq!(Rng::new(0), Rng::new(FALLBACK_SEED));
+    }
+
+    #[test]
+    fn test_never_zero() {
+        let mut rng = Rng::new(7);
+        for _ in 0..10_000 {
+            assert_ne!(rng.next_u32(), 0);
+        }
+    }
+
+    // ==================== Range Tests ====================
+
+    #[test]
+    fn test_below_in_range() {
+        let mut rng = Rng::new(99);
+        for _ in 0..1000 {
+            assert!(rng.below(7) < 7);
+        }
+    }
+
+    #[test]
+    fn test_below_zero() {
+        let mut rng = Rng::new(99);
+        assert_eq!(rng.below(0), 0);
+    }
+
+    #[test]
+    fn test_below_covers_range() {
+        let mut rng = Rng::new(5);
+        let mut seen = [false; 4];
+        for _ in 0..200 {
+            seen[rng.below(4) as usize] = true;
+        }
+        assert!(seen.iter().all(|&s| s));
+    }
+
+    #[test]
+    fn test_range_u8_inclusive() {
+        let mut rng = Rng::new(11);
+        let mut seen_low = false;
+        let mut seen_high = false;
+        for _ in 0..1000 {
+            let value = rng.range_u8(10, 12);
+            assert!((10..=12).contains(&value));
+            seen_low |= value == 10;
+            seen_high |= value == 12;
+        }
+        assert!(seen_low && seen_high);
+    }
+
+    #[test]
+    fn test_range_u8_empty() {
+        let mut rng = Rng::new(11);
+        assert_eq!(rng.range_u8(50, 40), 50);
+    }
+
+    #[test]
+    fn test_chance_extremes() {
+        let mut rng = Rng::new(3);
+        for _ in 0..100 {
+            assert!(!rng.chance(0));
+        }
+    }
+
+    #[test]
+    fn test_chance_roughly_half() {
+        let mut rng = Rng::new(3);
+        let hits = (0..10_000).filter(|_| rng.chance(128)).count();
+        assert!((4500..5500).contains(&hits));
+    }
+}
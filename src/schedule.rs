@@ -0,0 +1,322 @@
+/*
+ * @file schedule.rs
+ * @brief Time-of-day schedules
+ * @author Kevin Thomas
+ * @date 2025
+ *
+ * MIT License
+ *
+ * Copyright (c) 2025 Kevin Thomas
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+//! FILE: schedule.rs
+//!
+//! DESCRIPTION:
+//! Time-of-Day Schedules for Unattended Installations.
+//!
+//! BRIEF:
+//! Maps time windows to a pattern, brightness and speed.
+//! Windows may wrap past midnight and may overlap.
+//! Lets the panel go dim and calm overnight on its own.
+//!
+//! AUTHOR: Kevin Thomas
+//! CREATION DATE: October 18, 2026
+//! UPDATE DATE: October 18, 2026
+
+use crate::config::MAX_SCHEDULE_ENTRIES;
+use crate::pattern::Pattern;
+use crate::wallclock::TimeOfDay;
+
+/// Schedule error enumeration.
+///
+/// # Variants
+/// * `Empty` - Schedule has no entries
+/// * `TooManyEntries` - Schedule exceeds MAX_SCHEDULE_ENTRIES
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[allow(dead_code)]
+pub enum ScheduleError {
+    Empty,
+    TooManyEntries,
+}
+
+/// Single schedule entry.
+///
+/// # Details
+/// The window runs from start up to but not including end. An end
+/// before the start wraps past midnight and an end equal to the
+/// start covers the whole day.
+///
+/// # Fields
+/// * `start` - Time the window opens
+/// * `end` - Time the window closes
+/// * `pattern` - Pattern shown in the window
+/// * `brightness` - Output brightness (0 to MAX_BRIGHTNESS)
+/// * `delay_ms` - Delay between steps in milliseconds
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct ScheduleEntry {
+    pub start: TimeOfDay,
+    pub end: TimeOfDay,
+    pub pattern: Pattern,
+    pub brightness: u8,
+    pub delay_ms: u64,
+}
+
+impl ScheduleEntry {
+    /// Creates new schedule entry.
+    ///
+    /// # Arguments
+    /// * `start` - Time the window opens
+    /// * `end` - Time the window closes
+    /// * `pattern` - Pattern shown in the window
+    /// * `brightness` - Output brightness (0 to MAX_BRIGHTNESS)
+    /// * `delay_ms` - Delay between steps in milliseconds
+    ///
+    /// # Returns
+    /// * `Self` - New ScheduleEntry instance
+    #[allow(dead_code)]
+    pub const fn new(
+        start: TimeOfDay,
+        end: TimeOfDay,
+        pattern: Pattern,
+        brightness: u8,
+        delay_ms: u64,
+    ) -> Self {
+        Self {
+            start,
+            end,
+            pattern,
+            brightness,
+            delay_ms,
+        }
+    }
+
+    /// Returns whether the window contains a time.
+    ///
+    /// # Arguments
+    /// * `time` - Time of day
+    ///
+    /// # Returns
+    /// * `bool` - true inside the window
+    #[allow(dead_code)]
+    pub fn contains(&self, time: TimeOfDay) -> bool {
+        if self.start == self.end {
+            true
+        } else if self.start < self.end {
+            self.start <= time && time < self.end
+        } else {
+            time >= self.start || time < self.end
+        }
+    }
+}
+
+/// Time-of-day schedule.
+///
+/// # Details
+/// Where windows overlap, the later entry in the table wins, so a
+/// table can start with a daytime default and add overrides.
+///
+/// # Fields
+/// * `entries` - Entries in priority order, lowest first
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct Schedule<'a> {
+    entries: &'a [ScheduleEntry],
+}
+
+impl<'a> Schedule<'a> {
+    /// Creates new schedule.
+    ///
+    /// # Arguments
+    /// * `entries` - Entries in priority order, lowest first
+    ///
+    /// # Returns
+    /// * `Result<Self, ScheduleError>` - New Schedule
+    #[allow(dead_code)]
+    pub fn new(entries: &'a [ScheduleEntry]) -> Result<Self, ScheduleError> {
+        if entries.is_empty() {
+            return Err(ScheduleError::Empty);
+        }
+        if entries.len() > MAX_SCHEDULE_ENTRIES {
+            return Err(ScheduleError::TooManyEntries);
+        }
+        Ok(Self { entries })
+    }
+
+    /// Returns entries.
+    ///
+    /// # Returns
+    /// * `&[ScheduleEntry]` - Entries in priority order
+    #[allow(dead_code)]
+    pub fn entries(&self) -> &'a [ScheduleEntry] {
+        self.entries
+    }
+
+    /// Returns index of the entry in force at a time.
+    ///
+    /// # Arguments
+    /// * `time` - Time of day
+    ///
+    /// # Returns
+    /// * `Option<usize>` - Last entry containing the time, if any
+    #[allow(dead_code)]
+    pub fn active_index(&self, time: TimeOfDay) -> Option<usize> {
+        self.entries.iter().rposition(|entry| entry.contains(time))
+    }
+
+    /// Returns entry in force at a time.
+    ///
+    /// # Arguments
+    /// * `time` - Time of day
+    ///
+    /// # Returns
+    /// * `Option<&ScheduleEntry>` - Last entry containing the time, if any
+    #[allow(dead_code)]
+    pub fn active(&self, time: TimeOfDay) -> Option<&'a ScheduleEntry> {
+        self.active_index(time).map(|index| &self.entries[index])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: ScheduleEntry = ScheduleEntry::new(
+        TimeOfDay::MIDNIGHT,
+        TimeOfDay::MIDNIGHT,
+        Pattern::Chase,
+        255,
+        250,
+    );
+
+    const EVENING: ScheduleEntry = ScheduleEntry::new(
+        TimeOfDay::hm(18, 0),
+        TimeOfDay::hm(23, 0),
+        Pattern::Bounce,
+        160,
+        400,
+    );
+
+    const NIGHT: ScheduleEntry = ScheduleEntry::new(
+        TimeOfDay::hm(22, 0),
+        TimeOfDay::hm(7, 0),
+        Pattern::Breathe,
+        32,
+        1000,
+    );
+
+    const ENTRIES: [ScheduleEntry; 3] = [DAY, EVENING, NIGHT];
+
+    fn schedule() -> Schedule<'static> {
+        Schedule::new(&ENTRIES).unwrap()
+    }
+
+    // ==================== Construction Tests ====================
+
+    #[test]
+    fn test_new_empty() {
+        assert_eq!(Schedule::new(&[]), Err(ScheduleError::Empty));
+    }
+
+    #[test]
+    fn test_new_too_many() {
+        let entries = [DAY; MAX_SCHEDULE_ENTRIES + 1];
+        assert_eq!(Schedule::new(&entries), Err(ScheduleError::TooManyEntries));
+    }
+
+    #[test]
+    fn test_entries() {
+        assert_eq!(schedule().entries().len(), 3);
+    }
+
+    // ==================== Window Tests ====================
+
+    #[test]
+    fn test_window_contains() {
+        assert!(EVENING.contains(TimeOfDay::hm(18, 0)));
+        assert!(EVENING.contains(TimeOfDay::hm(20, 30)));
+        assert!(!EVENING.contains(TimeOfDay::hm(23, 0)));
+        assert!(!EVENING.contains(TimeOfDay::hm(17, 59)));
+    }
+
+    #[test]
+    fn test_window_wraps_midnight() {
+        assert!(NIGHT.contains(TimeOfDay::hm(23, 59)));
+        assert!(NIGHT.contains(TimeOfDay::MIDNIGHT));
+        assert!(NIGHT.contains(TimeOfDay::hm(6, 59)));
+        assert!(!NIGHT.contains(TimeOfDay::hm(7, 0)));
+        assert!(!NIGHT.contains(TimeOfDay::hm(12, 0)));
+    }
+
+    #[test]
+    fn test_equal_ends_cover_day() {
+        for hour in 0..24 {
+            assert!(DAY.contains(TimeOfDay::hm(hour, 30)));
+        }
+    }
+
+    // ==================== Active Entry Tests ====================
+
+    #[test]
+    fn test_daytime_default() {
+        assert_eq!(schedule().active(TimeOfDay::hm(12, 0)), Some(&DAY));
+    }
+
+    #[test]
+    fn test_evening_overrides_day() {
+        assert_eq!(schedule().active(TimeOfDay::hm(19, 0)), Some(&EVENING));
+    }
+
+    #[test]
+    fn test_later_entry_wins_overlap() {
+        assert_eq!(schedule().active_index(TimeOfDay::hm(22, 30)), Some(2));
+    }
+
+    #[test]
+    fn test_night_after_midnight() {
+        assert_eq!(schedule().active(TimeOfDay::hm(3, 0)), Some(&NIGHT));
+        assert_eq!(schedule().active(TimeOfDay::hm(7, 0)), Some(&DAY));
+    }
+
+    #[test]
+    fn test_gap_has_no_entry() {
+        let entries = [NIGHT];
+        let schedule = Schedule::new(&entries).unwrap();
+        assert_eq!(schedule.active(TimeOfDay::hm(12, 0)), None);
+    }
+
+    #[test]
+    fn test_whole_day_walk() {
+        let schedule = schedule();
+        let mut changes = 0;
+        let mut previous = schedule.active_index(TimeOfDay::MIDNIGHT);
+        for minute in 1..24 * 60 {
+            let active = schedule.active_index(TimeOfDay::from_seconds(minute * 60));
+            assert!(active.is_some());
+            if active != previous {
+                changes += 1;
+                previous = active;
+            }
+        }
+        assert_eq!(changes, 3);
+    }
+}
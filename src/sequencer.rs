@@ -0,0 +1,2360 @@
+/*
+ * @file sequencer.rs
+ * @brief LED sequencer driven by commands
+ * @author Kevin Thomas
+ * @date 2025
+ *
+ * MIT License
+ *
+ * Copyright (c) 2025 Kevin Thomas
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+//! FILE: sequencer.rs
+//!
+//! DESCRIPTION:
+//! Command-Driven LED Sequencer for the RP2350 Render Loop.
+//!
+//! BRIEF:
+//! Combines the background compositor, kiosk playlist and a manual controller.
+//! Applies commands sent by other tasks and reports its status.
+//! Supports pausing, single steps both ways, jumps, direction, reset,
+//! fixed frames and a bar graph.
+//! Optionally runs a table-driven state machine fed by input commands.
+//! Keeps a settable wall clock and shows it as a binary or BCD clock.
+//! Plays keyframe timelines sampled at the output refresh rate.
+//! Blinks queued Morse messages at a dot unit of the manual step delay.
+//! Counts in binary or Gray code at the manual step delay.
+//! Runs seeded twinkle, random walk and candle effects at the manual step delay.
+//! Optionally follows a time-of-day schedule of pattern, speed and brightness.
+//! Optionally keeps output frames within an LED current budget.
+//! Reports how long the output stays unchanged so the loop can sleep.
+//! Keeps the loop's awake and asleep time for a battery life report.
+//! Runs an LED health self-test and steers patterns around faulty LEDs.
+//! Accounts per-LED on-time, switch-ons and duty of every output frame.
+//! Holds no hardware so it runs on either core and on the host.
+//!
+//! AUTHOR: Kevin Thomas
+//! CREATION DATE: October 18, 2026
+//! UPDATE DATE: October 18, 2026
+
+use crate::bargraph::{BarGraph, FillDirection};
+use crate::binclock::ClockEncoding;
+use crate::clock::ClockSource;
+use crate::compositor::{BlendMode, Compositor, Layer, blend_frames};
+use crate::config::{
+    ACTIVE_CURRENT_MA, BAR_BLINK_MS, BAR_SMOOTHING_MS, BATTERY_CAPACITY_MAH, JITTER_OVERRUN_US,
+    MAX_BRIGHTNESS, SLEEP_CURRENT_MA,
+};
+use crate::counter::Counter;
+use crate::effects::{Effect, EffectKind};
+use crate::frame::Frame;
+use crate::fsm::Fsm;
+use crate::health::{HealthReport, SelfTest, SenseThresholds, TestStep};
+use crate::idle::{IdleBudget, IdleReport};
+use crate::jitter::JitterHistogram;
+use crate::led::Direction;
+use crate::morse::{MorseMessage, MorsePlayer};
+use crate::pattern::Pattern;
+use crate::playlist::Playlist;
+use crate::power::{PowerLimiter, PowerStats};
+use crate::rng::{FALLBACK_SEED, Rng};
+use crate::schedule::Schedule;
+use crate::timeline::{Playback, Timeline};
+use crate::usage::UsageStats;
+use crate::wallclock::{TimeOfDay, WallClock};
+
+/// Sequencer command enumeration.
+///
+/// # Variants
+/// * `SetPattern` - Play a pattern manually from its first step
+/// * `SetDelay` - Set the manual step delay in milliseconds
+/// * `Pause` - Freeze the output
+/// * `Resume` - Continue after a pause
+/// * `Step` - Advance the manual pattern by one step
+/// * `StepBack` - Move the manual pattern back by one step
+/// * `Jump` - Move the manual pattern to a step
+/// * `SetDirection` - Set the manual pattern's playback direction
+/// * `Reset` - Restart the manual pattern forward and unpaused
+/// * `SetFrame` - Show a fixed frame
+/// * `SetClock` - Step on time or only on Step commands
+/// * `RunPlaylist` - Return to the playlist
+/// * `SetBar` - Show the bar graph at a value in tenths of a percent
+/// * `SetBarDirection` - Set the bar graph fill direction
+/// * `RunFsm` - Run the state machine from its initial state
+/// * `Input` - Latch state machine inputs
+/// * `SetTime` - Set the wall clock time of day
+/// * `ShowClock` - Show the wall clock in an encoding
+/// * `LoadTimeline` - Play a keyframe timeline from its start
+/// * `PlayTimeline` - Show the loaded timeline from where it was
+/// * `SeekTimeline` - Move the loaded timeline to a position in milliseconds
+/// * `SetPlayback` - Set the loaded timeline's playback mode
+/// * `Morse` - Queue a Morse message and show the Morse player
+/// * `ShowCounter` - Show a counter from its first value
+/// * `ShowEffect` - Start a random effect with a fresh seed
+/// * `SelfTest` - Run the LED health self-test
+/// * `Sense` - Settled sense reading for the self-test step
+/// * `ReportUsage` - Request a copy of the usage statistics
+/// * `ResetUsage` - Clear the usage statistics
+/// * `ReportJitter` - Request a copy of the step timing histogram
+/// * `ResetJitter` - Clear the step timing histogram
+/// * `ReportPower` - Request a copy of the power limiter statistics
+/// * `ResetPower` - Clear the power limiter statistics
+/// * `ReportIdle` - Request the idle time and battery estimate
+/// * `ResetIdle` - Clear the idle time totals
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[allow(dead_code)]
+pub enum Command {
+    SetPattern(Pattern),
+    SetDelay(u64),
+    Pause,
+    Resume,
+    Step,
+    StepBack,
+    Jump(usize),
+    SetDirection(Direction),
+    Reset,
+    SetFrame(Frame),
+    SetClock(ClockSource),
+    RunPlaylist,
+    SetBar(u16),
+    SetBarDirection(FillDirection),
+    RunFsm,
+    Input(u8),
+    SetTime(TimeOfDay),
+    ShowClock(ClockEncoding),
+    LoadTimeline(Timeline<'static>),
+    PlayTimeline,
+    SeekTimeline(u32),
+    SetPlayback(Playback),
+    Morse(MorseMessage),
+    ShowCounter(Counter),
+    ShowEffect(EffectKind),
+    SelfTest,
+    Sense(u16),
+    ReportUsage,
+    ResetUsage,
+    ReportJitter,
+    ResetJitter,
+    ReportPower,
+    ResetPower,
+    ReportIdle,
+    ResetIdle,
+}
+
+/// Sequencer mode enumeration.
+///
+/// # Variants
+/// * `Playlist` - Playlist chooses the pattern
+/// * `Manual` - Commands choose the pattern
+/// * `Frame` - A fixed frame set by command
+/// * `Bar` - The bar graph
+/// * `Fsm` - The state machine
+/// * `Clock` - The wall clock
+/// * `Timeline` - The loaded keyframe timeline
+/// * `Morse` - Queued Morse messages
+/// * `Counter` - A binary or Gray-code counter
+/// * `Effect` - A random effect
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[allow(dead_code)]
+pub enum Mode {
+    Playlist,
+    Manual,
+    Frame,
+    Bar,
+    Fsm,
+    Clock,
+    Timeline,
+    Morse,
+    Counter,
+    Effect,
+}
+
+/// Sequencer status report.
+///
+/// # Details
+/// In the other modes the pattern fields describe the manual pattern
+/// that Step and Jump resume from.
+///
+/// # Fields
+/// * `mode` - Who chooses the output
+/// * `paused` - Whether the output is frozen
+/// * `clock` - Source of pattern steps
+/// * `pattern` - Pattern on the top layer
+/// * `step` - Step within the pattern cycle
+/// * `delay_ms` - Delay between steps in milliseconds
+/// * `power_limited` - Whether the last output frame was over budget
+/// * `health` - Per-LED health from the last self-test
+/// * `self_test` - Self-test step awaiting a sense reading, if testing
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[allow(dead_code)]
+pub struct Status {
+    pub mode: Mode,
+    pub paused: bool,
+    pub clock: ClockSource,
+    pub pattern: Pattern,
+    pub step: usize,
+    pub delay_ms: u64,
+    pub power_limited: bool,
+    pub health: HealthReport,
+    pub self_test: Option<TestStep>,
+}
+
+/// LED sequencer.
+///
+/// # Details
+/// The top layer is the playlist, the manual layer or a fixed
+/// frame and overrides the background compositor. The playlist
+/// pauses while it is not shown. Taking manual control starts
+/// from the pattern, step and delay the playlist was showing.
+/// Pausing freezes every layer but still accepts Step and Jump.
+/// On an external clock the top layer only moves on Step commands
+/// while the background keeps its own time.
+///
+/// # Fields
+/// * `background` - Layers drawn under the top layer
+/// * `playlist` - Playlist shown in playlist mode
+/// * `manual` - Layer shown in manual mode
+/// * `still` - Frame shown in frame mode
+/// * `bar` - Bar graph shown in bar mode
+/// * `fsm` - State machine shown in state machine mode, if any
+/// * `wall` - Wall clock
+/// * `encoding` - Encoding of the wall clock in clock mode
+/// * `timeline` - Timeline shown in timeline mode, if loaded
+/// * `morse` - Morse player shown in Morse mode
+/// * `counter` - Counter shown in counter mode
+/// * `rng` - Seeds for new effects
+/// * `effect` - Effect shown in effect mode
+/// * `now_ms` - Time ticked since creation, paused or not
+/// * `schedule` - Time-of-day schedule, if any
+/// * `scheduled` - Schedule entry in force, if any
+/// * `brightness` - Output brightness set by the schedule
+/// * `limiter` - LED current limiter, if any
+/// * `output` - Frame last returned by tick
+/// * `power_limited` - Whether the last output frame was over budget
+/// * `power_requested` - Whether a power report is waiting
+/// * `thresholds` - Self-test sense thresholds, if any
+/// * `self_test` - Self-test in progress, if any
+/// * `health` - Per-LED health from the last self-test
+/// * `usage` - Per-LED usage statistics of the output
+/// * `usage_requested` - Whether a usage report is waiting
+/// * `jitter` - Lateness of timed steps
+/// * `jitter_requested` - Whether a jitter report is waiting
+/// * `idle` - Awake and asleep time of the render loop
+/// * `idle_requested` - Whether an idle report is waiting
+/// * `stepped` - Whether the last tick took a timed step
+/// * `mode` - Who chooses the output
+/// * `clock` - Source of pattern steps
+/// * `steps` - Top layer steps taken, wrapping
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct Sequencer<'a> {
+    background: Compositor,
+    playlist: Playlist<'a>,
+    manual: Layer,
+    still: Frame,
+    bar: BarGraph,
+    fsm: Option<Fsm<'a>>,
+    wall: WallClock,
+    encoding: ClockEncoding,
+    timeline: Option<Timeline<'a>>,
+    morse: MorsePlayer,
+    counter: Counter,
+    rng: Rng,
+    effect: Effect,
+    now_ms: u64,
+    schedule: Option<Schedule<'a>>,
+    scheduled: Option<usize>,
+    brightness: u8,
+    limiter: Option<PowerLimiter>,
+    output: Frame,
+    power_limited: bool,
+    power_requested: bool,
+    thresholds: Option<SenseThresholds>,
+    self_test: Option<SelfTest>,
+    health: HealthReport,
+    usage: UsageStats,
+    usage_requested: bool,
+    jitter: JitterHistogram,
+    jitter_requested: bool,
+    idle: IdleBudget,
+    idle_requested: bool,
+    stepped: bool,
+    mode: Mode,
+    clock: ClockSource,
+    steps: u32,
+}
+
+impl<'a> Sequencer<'a> {
+    /// Creates new sequencer in playlist mode.
+    ///
+    /// # Arguments
+    /// * `background` - Layers drawn under the top layer
+    /// * `playlist` - Playlist to show
+    ///
+    /// # Returns
+    /// * `Self` - New Sequencer instance
+    #[allow(dead_code)]
+    pub fn new(background: Compositor, playlist: Playlist<'a>) -> Self {
+        let mut manual = Layer::new(Pattern::Chase, 0, BlendMode::Override);
+        *manual.controller_mut() = *playlist.controller();
+        Self {
+            background,
+            playlist,
+            manual,
+            still: Frame::new(),
+            bar: BarGraph::new(FillDirection::Forward, BAR_SMOOTHING_MS)
+                .with_overflow_blink(BAR_BLINK_MS),
+            fsm: None,
+            wall: WallClock::new(),
+            encoding: ClockEncoding::Binary,
+            timeline: None,
+            morse: MorsePlayer::new(manual.controller().delay_ms()),
+            counter: Counter::full_range(),
+            rng: Rng::new(FALLBACK_SEED),
+            effect: Effect::new(EffectKind::Twinkle, FALLBACK_SEED),
+            now_ms: 0,
+            schedule: None,
+            scheduled: None,
+            brightness: MAX_BRIGHTNESS,
+            limiter: None,
+            output: Frame::new(),
+            power_limited: false,
+            power_requested: false,
+            thresholds: None,
+            self_test: None,
+            health: HealthReport::new(),
+            usage: UsageStats::new(),
+            usage_requested: false,
+            jitter: JitterHistogram::new(JITTER_OVERRUN_US),
+            jitter_requested: false,
+            idle: IdleBudget::new(),
+            idle_requested: false,
+            stepped: false,
+            mode: Mode::Playlist,
+            clock: ClockSource::Internal,
+            steps: 0,
+        }
+    }
+
+    /// Returns sequencer able to run the LED health self-test.
+    ///
+    /// # Details
+    /// The self-test starts on a SelfTest command.
+    ///
+    /// # Arguments
+    /// * `thresholds` - Sense reading thresholds
+    ///
+    /// # Returns
+    /// * `Self` - Updated sequencer
+    #[allow(dead_code)]
+    pub fn with_self_test(mut self, thresholds: SenseThresholds) -> Self {
+        self.thresholds = Some(thresholds);
+        self
+    }
+
+    /// Returns per-LED health.
+    ///
+    /// # Returns
+    /// * `HealthReport` - Health from the last self-test
+    #[allow(dead_code)]
+    pub fn health(&self) -> HealthReport {
+        self.health
+    }
+
+    /// Returns sequencer continuing from saved usage statistics.
+    ///
+    /// # Arguments
+    /// * `usage` - Statistics read back from storage
+    ///
+    /// # Returns
+    /// * `Self` - Updated sequencer
+    #[allow(dead_code)]
+    pub fn with_usage(mut self, usage: UsageStats) -> Self {
+        self.usage = usage;
+        self
+    }
+
+    /// Returns per-LED usage statistics.
+    ///
+    /// # Returns
+    /// * `&UsageStats` - Statistics of every output frame so far
+    #[allow(dead_code)]
+    pub fn usage(&self) -> &UsageStats {
+        &self.usage
+    }
+
+    /// Returns the usage statistics once after a ReportUsage command.
+    ///
+    /// # Returns
+    /// * `Option<UsageStats>` - Statistics if a report was requested
+    #[allow(dead_code)]
+    pub fn take_usage_report(&mut self) -> Option<UsageStats> {
+        if !self.usage_requested {
+            return None;
+        }
+        self.usage_requested = false;
+        Some(self.usage)
+    }
+
+    /// Records how late the last tick ran.
+    ///
+    /// # Details
+    /// Only ticks that took a timed step are recorded, so pauses,
+    /// an external clock and Step commands leave the histogram
+    /// alone.
+    ///
+    /// # Arguments
+    /// * `lateness_us` - Actual minus scheduled time of the tick
+    #[allow(dead_code)]
+    pub fn record_step_timing(&mut self, lateness_us: i64) {
+        if self.stepped {
+            self.jitter.record(lateness_us);
+        }
+    }
+
+    /// Returns the step timing histogram.
+    ///
+    /// # Returns
+    /// * `&JitterHistogram` - Lateness of every timed step so far
+    #[allow(dead_code)]
+    pub fn jitter(&self) -> &JitterHistogram {
+        &self.jitter
+    }
+
+    /// Returns the step timing histogram once after a ReportJitter command.
+    ///
+    /// # Returns
+    /// * `Option<JitterHistogram>` - Histogram if a report was requested
+    #[allow(dead_code)]
+    pub fn take_jitter_report(&mut self) -> Option<JitterHistogram> {
+        if !self.jitter_requested {
+            return None;
+        }
+        self.jitter_requested = false;
+        Some(self.jitter)
+    }
+
+    /// Records one awake and sleep cycle of the render loop.
+    ///
+    /// # Arguments
+    /// * `awake_us` - Time spent rendering in microseconds
+    /// * `asleep_us` - Time spent sleeping in microseconds
+    #[allow(dead_code)]
+    pub fn record_idle(&mut self, awake_us: u64, asleep_us: u64) {
+        self.idle.record(awake_us, asleep_us);
+    }
+
+    /// Returns awake and asleep time totals.
+    ///
+    /// # Returns
+    /// * `&IdleBudget` - Time recorded by record_idle
+    #[allow(dead_code)]
+    pub fn idle(&self) -> &IdleBudget {
+        &self.idle
+    }
+
+    /// Returns the idle report once after a ReportIdle command.
+    ///
+    /// # Details
+    /// Estimates current and run time from ACTIVE_CURRENT_MA,
+    /// SLEEP_CURRENT_MA and BATTERY_CAPACITY_MAH.
+    ///
+    /// # Returns
+    /// * `Option<IdleReport>` - Report if one was requested
+    #[allow(dead_code)]
+    pub fn take_idle_report(&mut self) -> Option<IdleReport> {
+        if !self.idle_requested {
+            return None;
+        }
+        self.idle_requested = false;
+        Some(
+            self.idle
+                .report(ACTIVE_CURRENT_MA, SLEEP_CURRENT_MA, BATTERY_CAPACITY_MAH),
+        )
+    }
+
+    /// Returns sequencer with a state machine.
+    ///
+    /// # Details
+    /// The state machine is shown after a RunFsm command.
+    ///
+    /// # Arguments
+    /// * `fsm` - State machine
+    ///
+    /// # Returns
+    /// * `Self` - Updated sequencer
+    #[allow(dead_code)]
+    pub fn with_fsm(mut self, fsm: Fsm<'a>) -> Self {
+        self.fsm = Some(fsm);
+        self
+    }
+
+    /// Returns state machine.
+    ///
+    /// # Returns
+    /// * `Option<&Fsm<'a>>` - State machine, if any
+    #[allow(dead_code)]
+    pub fn fsm(&self) -> Option<&Fsm<'a>> {
+        self.fsm.as_ref()
+    }
+
+    /// Returns sequencer with a timeline loaded.
+    ///
+    /// # Details
+    /// The timeline is shown after a PlayTimeline or SeekTimeline
+    /// command, and a LoadTimeline command replaces it.
+    ///
+    /// # Arguments
+    /// * `timeline` - Keyframe timeline
+    ///
+    /// # Returns
+    /// * `Self` - Updated sequencer
+    #[allow(dead_code)]
+    pub fn with_timeline(mut self, timeline: Timeline<'a>) -> Self {
+        self.timeline = Some(timeline);
+        self
+    }
+
+    /// Returns loaded timeline.
+    ///
+    /// # Returns
+    /// * `Option<&Timeline<'a>>` - Timeline, if one was loaded
+    #[allow(dead_code)]
+    pub fn timeline(&self) -> Option<&Timeline<'a>> {
+        self.timeline.as_ref()
+    }
+
+    /// Returns Morse player.
+    ///
+    /// # Returns
+    /// * `&MorsePlayer` - Player and its message queue
+    #[allow(dead_code)]
+    pub fn morse(&self) -> &MorsePlayer {
+        &self.morse
+    }
+
+    /// Returns sequencer seeding effects from a random seed.
+    ///
+    /// # Details
+    /// Each ShowEffect command draws the effect's seed from a
+    /// generator started with this seed, so a seed gives the same
+    /// effects in the same order.
+    ///
+    /// # Arguments
+    /// * `seed` - Random seed
+    ///
+    /// # Returns
+    /// * `Self` - Updated sequencer
+    #[allow(dead_code)]
+    pub fn with_seed(mut self, seed: u32) -> Self {
+        self.rng = Rng::new(seed);
+        self
+    }
+
+    /// Returns effect.
+    ///
+    /// # Returns
+    /// * `&Effect` - Effect shown in effect mode
+    #[allow(dead_code)]
+    pub fn effect(&self) -> &Effect {
+        &self.effect
+    }
+
+    /// Returns counter.
+    ///
+    /// # Returns
+    /// * `&Counter` - Counter shown in counter mode
+    #[allow(dead_code)]
+    pub fn counter(&self) -> &Counter {
+        &self.counter
+    }
+
+    /// Returns sequencer with a time-of-day schedule.
+    ///
+    /// # Details
+    /// Once the wall clock is set, entering a schedule window plays
+    /// its pattern manually at its speed and brightness, unless a
+    /// frame, bar, state machine or clock is shown. Leaving every
+    /// window restores full brightness and the playlist.
+    ///
+    /// # Arguments
+    /// * `schedule` - Time-of-day schedule
+    ///
+    /// # Returns
+    /// * `Self` - Updated sequencer
+    #[allow(dead_code)]
+    pub fn with_schedule(mut self, schedule: Schedule<'a>) -> Self {
+        self.schedule = Some(schedule);
+        self
+    }
+
+    /// Returns sequencer with an LED current limiter.
+    ///
+    /// # Details
+    /// Frames over budget are scaled down, or refused so the
+    /// previous output frame stays shown, as the limiter is set up.
+    ///
+    /// # Arguments
+    /// * `limiter` - LED current limiter
+    ///
+    /// # Returns
+    /// * `Self` - Updated sequencer
+    #[allow(dead_code)]
+    pub fn with_power_limit(mut self, limiter: PowerLimiter) -> Self {
+        self.limiter = Some(limiter);
+        self
+    }
+
+    /// Returns power limiter statistics.
+    ///
+    /// # Returns
+    /// * `Option<PowerStats>` - Counts of limited frames, None without a limiter
+    #[allow(dead_code)]
+    pub fn power_stats(&self) -> Option<PowerStats> {
+        self.limiter.map(|limiter| limiter.stats())
+    }
+
+    /// Returns the power limiter statistics once after a ReportPower command.
+    ///
+    /// # Details
+    /// A request without a limiter is dropped.
+    ///
+    /// # Returns
+    /// * `Option<PowerStats>` - Statistics if a report was requested
+    #[allow(dead_code)]
+    pub fn take_power_report(&mut self) -> Option<PowerStats> {
+        if !self.power_requested {
+            return None;
+        }
+        self.power_requested = false;
+        self.power_stats()
+    }
+
+    /// Returns schedule entry in force.
+    ///
+    /// # Returns
+    /// * `Option<usize>` - Index into the schedule, if any
+    #[allow(dead_code)]
+    pub fn scheduled(&self) -> Option<usize> {
+        self.scheduled
+    }
+
+    /// Returns output brightness.
+    ///
+    /// # Returns
+    /// * `u8` - Brightness (0 to MAX_BRIGHTNESS)
+    #[allow(dead_code)]
+    pub fn brightness(&self) -> u8 {
+        self.brightness
+    }
+
+    /// Returns time of day.
+    ///
+    /// # Details
+    /// The wall clock advances with tick time, which the firmware
+    /// measures with the embassy-time monotonic clock.
+    ///
+    /// # Returns
+    /// * `Option<TimeOfDay>` - Time of day, None until set
+    #[allow(dead_code)]
+    pub fn time(&self) -> Option<TimeOfDay> {
+        self.wall.time(self.now_ms)
+    }
+
+    /// Returns current mode.
+    ///
+    /// # Returns
+    /// * `Mode` - Who chooses the output
+    #[allow(dead_code)]
+    pub fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    /// Returns whether the output is frozen.
+    ///
+    /// # Details
+    /// The manual controller's pause flag freezes the whole output.
+    ///
+    /// # Returns
+    /// * `bool` - true while paused
+    #[allow(dead_code)]
+    pub fn is_paused(&self) -> bool {
+        self.manual.controller().is_paused()
+    }
+
+    /// Returns top layer steps taken so far.
+    ///
+    /// # Details
+    /// Counts timed steps and Step commands. Wraps on overflow.
+    ///
+    /// # Returns
+    /// * `u32` - Step count
+    #[allow(dead_code)]
+    pub fn steps(&self) -> u32 {
+        self.steps
+    }
+
+    /// Applies a command.
+    ///
+    /// # Details
+    /// Pattern, delay, step, jump, direction and reset commands take
+    /// manual control,
+    /// except that Step advances the counter or effect in those
+    /// modes. StepBack moves the counter back, is ignored by effects
+    /// and Morse, and is not counted as a step for the clock output. Jump wraps the index to the pattern cycle. RunFsm and Input
+    /// are ignored without a state machine. PlayTimeline,
+    /// SeekTimeline and SetPlayback are ignored until a timeline is
+    /// loaded. Morse messages are dropped while the queue is full.
+    /// SelfTest is ignored without thresholds and Sense outside a
+    /// self-test.
+    ///
+    /// # Arguments
+    /// * `command` - Command to apply
+    #[allow(dead_code)]
+    pub fn apply(&mut self, command: Command) {
+        match command {
+            Command::SetPattern(pattern) => {
+                self.take_manual();
+                self.manual.controller_mut().set_pattern(pattern);
+            }
+            Command::SetDelay(delay_ms) => {
+                self.take_manual();
+                self.manual.controller_mut().set_delay_ms(delay_ms);
+            }
+            Command::Pause => self.manual.controller_mut().pause(),
+            Command::Resume => self.manual.controller_mut().resume(),
+            Command::Step => {
+                match self.mode {
+                    Mode::Counter => {
+                        self.counter.advance();
+                    }
+                    Mode::Effect => {
+                        self.effect.step();
+                    }
+                    _ => {
+                        self.take_manual();
+                        self.manual.controller_mut().step_forward();
+                    }
+                }
+                self.steps = self.steps.wrapping_add(1);
+            }
+            Command::StepBack => match self.mode {
+                Mode::Counter => {
+                    self.counter.retreat();
+                }
+                Mode::Effect | Mode::Morse => {}
+                _ => {
+                    self.take_manual();
+                    self.manual.controller_mut().step_back();
+                }
+            },
+            Command::Jump(index) => {
+                self.take_manual();
+                self.manual.controller_mut().seek(index);
+            }
+            Command::SetDirection(direction) => {
+                self.take_manual();
+                self.manual.controller_mut().set_direction(direction);
+            }
+            Command::Reset => {
+                self.take_manual();
+                self.manual.controller_mut().reset();
+            }
+            Command::SetFrame(frame) => {
+                self.still = frame;
+                self.mode = Mode::Frame;
+            }
+            Command::SetClock(clock) => self.clock = clock,
+            Command::RunPlaylist => self.mode = Mode::Playlist,
+            Command::SetBar(value) => {
+                self.bar.set_value(value);
+                self.mode = Mode::Bar;
+            }
+            Command::SetBarDirection(direction) => self.bar.set_direction(direction),
+            Command::RunFsm => {
+                if let Some(fsm) = self.fsm.as_mut() {
+                    fsm.reset();
+                    self.mode = Mode::Fsm;
+                }
+            }
+            Command::Input(mask) => {
+                if let Some(fsm) = self.fsm.as_mut() {
+                    fsm.input(mask);
+                }
+            }
+            Command::SetTime(time) => self.wall.set(self.now_ms, time),
+            Command::ShowClock(encoding) => {
+                self.encoding = encoding;
+                self.mode = Mode::Clock;
+            }
+            Command::LoadTimeline(timeline) => {
+                let mut timeline = timeline;
+                timeline.seek(0);
+                self.timeline = Some(timeline);
+                self.mode = Mode::Timeline;
+            }
+            Command::PlayTimeline => {
+                if self.timeline.is_some() {
+                    self.mode = Mode::Timeline;
+                }
+            }
+            Command::SeekTimeline(position_ms) => {
+                if let Some(timeline) = self.timeline.as_mut() {
+                    timeline.seek(position_ms);
+                    self.mode = Mode::Timeline;
+                }
+            }
+            Command::SetPlayback(playback) => {
+                if let Some(timeline) = self.timeline.as_mut() {
+                    timeline.set_playback(playback);
+                }
+            }
+            Command::Morse(message) => {
+                if self.morse.enqueue(message).is_ok() {
+                    self.mode = Mode::Morse;
+                }
+            }
+            Command::ShowCounter(counter) => {
+                self.counter = counter;
+                self.counter.reset();
+                self.mode = Mode::Counter;
+            }
+            Command::ShowEffect(kind) => {
+                self.effect = Effect::new(kind, self.rng.next_u32());
+                self.mode = Mode::Effect;
+            }
+            Command::SelfTest => self.self_test = self.thresholds.map(SelfTest::new),
+            Command::Sense(sample) => {
+                if let Some(report) = self.self_test.as_mut().and_then(|test| test.record(sample)) {
+                    self.self_test = None;
+                    self.set_health(report);
+                }
+            }
+            Command::ReportUsage => self.usage_requested = true,
+            Command::ResetUsage => self.usage.reset(),
+            Command::ReportJitter => self.jitter_requested = true,
+            Command::ResetJitter => self.jitter.reset(),
+            Command::ReportPower => self.power_requested = true,
+            Command::ResetPower => {
+                if let Some(limiter) = self.limiter.as_mut() {
+                    limiter.reset_stats();
+                }
+            }
+            Command::ReportIdle => self.idle_requested = true,
+            Command::ResetIdle => self.idle.reset(),
+        }
+    }
+
+    /// Advances time and returns the output frame.
+    ///
+    /// # Details
+    /// Time does not advance while paused, except for the wall
+    /// clock. The top layer does not step with time on an external
+    /// clock. The frame is kept within the current budget if a
+    /// limiter is set. The previous output is recorded in the usage
+    /// statistics for the elapsed time.
+    ///
+    /// # Arguments
+    /// * `elapsed_ms` - Time since the previous tick
+    ///
+    /// # Returns
+    /// * `Frame` - Frame to display
+    #[allow(dead_code)]
+    pub fn tick(&mut self, elapsed_ms: u64) -> Frame {
+        self.usage.record(&self.output, elapsed_ms);
+        self.advance(elapsed_ms);
+        let frame = self.frame();
+        let limited = match self.limiter.as_mut() {
+            Some(limiter) => limiter.limit(&frame),
+            None => Some(frame),
+        };
+        self.power_limited = limited != Some(frame);
+        if let Some(frame) = limited {
+            self.output = frame;
+        }
+        self.output
+    }
+
+    /// Advances every time-driven part by one tick.
+    ///
+    /// # Arguments
+    /// * `elapsed_ms` - Time since the previous tick
+    fn advance(&mut self, elapsed_ms: u64) {
+        self.now_ms = self.now_ms.wrapping_add(elapsed_ms);
+        self.stepped = false;
+        self.follow_schedule();
+        if self.is_paused() {
+            return;
+        }
+        self.background.tick(elapsed_ms);
+        self.bar.tick(elapsed_ms as u32);
+        if self.clock == ClockSource::External {
+            return;
+        }
+        let steps = match self.mode {
+            Mode::Playlist => {
+                let before = self.playlist.steps();
+                self.playlist.tick(elapsed_ms as u32);
+                self.playlist.steps().wrapping_sub(before)
+            }
+            Mode::Manual => self.manual.tick(elapsed_ms),
+            Mode::Fsm => self
+                .fsm
+                .as_mut()
+                .map_or(0, |fsm| fsm.tick(elapsed_ms as u32) as u32),
+            Mode::Timeline => {
+                if let Some(timeline) = self.timeline.as_mut() {
+                    timeline.tick(elapsed_ms as u32);
+                }
+                0
+            }
+            Mode::Morse => {
+                self.morse.set_unit_ms(self.manual.controller().delay_ms());
+                if self.morse.tick(elapsed_ms).is_none() {
+                    self.mode = Mode::Playlist;
+                }
+                0
+            }
+            Mode::Counter => {
+                let steps = self.manual.tick(elapsed_ms);
+                for _ in 0..steps {
+                    self.counter.advance();
+                }
+                steps
+            }
+            Mode::Effect => {
+                let steps = self.manual.tick(elapsed_ms);
+                for _ in 0..steps {
+                    self.effect.step();
+                }
+                steps
+            }
+            Mode::Frame | Mode::Bar | Mode::Clock => 0,
+        };
+        self.steps = self.steps.wrapping_add(steps);
+        self.stepped = steps > 0;
+    }
+
+    /// Returns how long the output is certain to stay unchanged.
+    ///
+    /// # Details
+    /// Lets the render loop sleep through long steps and pauses.
+    /// The playlist, bar graph and state machine change on timers
+    /// not exposed here, so they report no idle time. Commands may
+    /// change the output sooner.
+    ///
+    /// # Returns
+    /// * `u64` - Milliseconds until the next change, u64::MAX if never
+    #[allow(dead_code)]
+    pub fn idle_ms(&self) -> u64 {
+        if self.self_test.is_some() {
+            return u64::MAX;
+        }
+        let second = self.ms_to_next_second();
+        let top = match self.mode {
+            Mode::Clock => second,
+            _ if self.is_paused() => u64::MAX,
+            Mode::Bar => 0,
+            _ if self.clock == ClockSource::External => u64::MAX,
+            Mode::Playlist | Mode::Fsm | Mode::Morse => 0,
+            Mode::Manual | Mode::Counter | Mode::Effect => self.manual.ms_until_step(),
+            Mode::Timeline => match self.timeline {
+                Some(timeline) if !timeline.is_finished() => 0,
+                _ => u64::MAX,
+            },
+            Mode::Frame => u64::MAX,
+        };
+        let background = if self.is_paused() {
+            u64::MAX
+        } else {
+            self.background.ms_until_step()
+        };
+        let schedule = if self.schedule.is_some() && self.wall.is_set() {
+            second
+        } else {
+            u64::MAX
+        };
+        top.min(background).min(schedule)
+    }
+
+    /// Returns time until the wall clock or uptime next ticks a second.
+    ///
+    /// # Returns
+    /// * `u64` - Milliseconds until the next whole second
+    fn ms_to_next_second(&self) -> u64 {
+        let ms = match self.wall.ms_of_day(self.now_ms) {
+            Some(ms) => u64::from(ms),
+            None => self.now_ms,
+        };
+        1000 - ms % 1000
+    }
+
+    /// Returns output frame without advancing time.
+    ///
+    /// # Details
+    /// The frame is as rendered, before any current limiting.
+    /// Patterns run on the usable LEDs only and faulty LEDs stay
+    /// dark. A self-test frame replaces the output unscaled.
+    ///
+    /// # Returns
+    /// * `Frame` - Frame to display
+    #[allow(dead_code)]
+    pub fn frame(&self) -> Frame {
+        if let Some(test) = self.self_test.as_ref() {
+            return test.frame();
+        }
+        let health = &self.health;
+        let top = match self.mode {
+            Mode::Playlist => health.spread(&self.playlist.frame()),
+            Mode::Manual => health.spread(&self.manual.frame()),
+            Mode::Frame => health.mask(&self.still),
+            Mode::Bar => health.mask(&self.bar.frame()),
+            Mode::Fsm => health.mask(&self.fsm.as_ref().map_or(Frame::new(), Fsm::frame)),
+            Mode::Timeline => health.mask(&self.timeline.map_or(Frame::new(), |timeline| {
+                timeline.sample(timeline.position_ms())
+            })),
+            Mode::Morse => health.mask(&self.morse.frame()),
+            Mode::Counter => health.mask(&self.counter.frame()),
+            Mode::Effect => health.mask(&self.effect.frame()),
+            Mode::Clock => {
+                let uptime = TimeOfDay::from_seconds((self.now_ms / 1000) as u32);
+                health.mask(&self.encoding.frame(self.time().unwrap_or(uptime)))
+            }
+        };
+        let background = health.spread(&self.background.frame());
+        blend_frames(&background, &top, BlendMode::Override).scaled(self.brightness)
+    }
+
+    /// Returns status report.
+    ///
+    /// # Returns
+    /// * `Status` - Current mode, pause state, pattern, step and delay
+    #[allow(dead_code)]
+    pub fn status(&self) -> Status {
+        let controller = match self.mode {
+            Mode::Playlist => self.playlist.controller(),
+            _ => self.manual.controller(),
+        };
+        Status {
+            mode: self.mode,
+            paused: self.is_paused(),
+            clock: self.clock,
+            pattern: controller.pattern(),
+            step: controller.current_index(),
+            delay_ms: controller.delay_ms(),
+            power_limited: self.power_limited,
+            health: self.health,
+            self_test: self.self_test.as_ref().map(SelfTest::step),
+        }
+    }
+
+    /// Applies the schedule entry in force when it changes.
+    fn follow_schedule(&mut self) {
+        let (Some(schedule), Some(time)) = (self.schedule, self.time()) else {
+            return;
+        };
+        let active = schedule.active_index(time);
+        if active == self.scheduled {
+            return;
+        }
+        self.scheduled = active;
+        let follows = matches!(self.mode, Mode::Playlist | Mode::Manual);
+        match active {
+            Some(index) => {
+                let entry = schedule.entries()[index];
+                self.brightness = entry.brightness;
+                if follows {
+                    self.take_manual();
+                    let controller = self.manual.controller_mut();
+                    controller.set_pattern(entry.pattern);
+                    controller.set_delay_ms(entry.delay_ms);
+                }
+            }
+            None => {
+                self.brightness = MAX_BRIGHTNESS;
+                if follows {
+                    self.mode = Mode::Playlist;
+                }
+            }
+        }
+    }
+
+    /// Stores self-test results and runs patterns on the usable LEDs.
+    ///
+    /// # Arguments
+    /// * `report` - Per-LED health
+    fn set_health(&mut self, report: HealthReport) {
+        let usable = report.usable_count();
+        self.health = report;
+        self.manual.controller_mut().set_led_count(usable);
+        self.playlist.set_led_count(usable);
+        self.background.set_led_count(usable);
+    }
+
+    /// Switches to manual mode.
+    ///
+    /// # Details
+    /// Leaving the playlist copies its position into the manual layer,
+    /// keeping the pause state.
+    fn take_manual(&mut self) {
+        if self.mode == Mode::Playlist {
+            let paused = self.is_paused();
+            *self.manual.controller_mut() = *self.playlist.controller();
+            if paused {
+                self.manual.controller_mut().pause();
+            } else {
+                self.manual.controller_mut().resume();
+            }
+        }
+        self.mode = Mode::Manual;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bargraph::BAR_FULL;
+    use crate::config::{FRAME_INTERVAL_MS, LED_COUNT, MORSE_QUEUE_LEN};
+    use crate::counter::{CountDirection, CounterEncoding, bits_frame, gray_encode};
+    use crate::effects::CANDLE_MAX;
+    use crate::fsm::{FsmState, FsmTransition, Guard};
+    use crate::health::LedHealth;
+    use crate::morse::MorseTarget;
+    use crate::playlist::{EntryLength, PlaylistEntry};
+    use crate::power::LimitMode;
+    use crate::schedule::ScheduleEntry;
+    use crate::timeline::Keyframe;
+
+    const STATES: [FsmState; 2] = [
+        FsmState::new(Frame::from_levels([255, 0, 0, 0]), 100),
+        FsmState::new(Frame::from_levels([0, 255, 0, 0]), 100),
+    ];
+
+    const TRANSITIONS: [FsmTransition; 2] = [
+        FsmTransition::new(0, 1, Guard::AfterWithInput(1)),
+        FsmTransition::new(1, 0, Guard::After),
+    ];
+
+    const ENTRIES: [PlaylistEntry; 2] = [
+        PlaylistEntry::new(Pattern::Chase, 100, EntryLength::Repeats(2)),
+        PlaylistEntry::new(Pattern::Solid, 100, EntryLength::Duration(1000)),
+    ];
+
+    fn sequencer() -> Sequencer<'static> {
+        Sequencer::new(Compositor::new(), Playlist::new(&ENTRIES).unwrap())
+    }
+
+    // ==================== Construction Tests ====================
+
+    #[test]
+    fn test_new_plays_playlist() {
+        let sequencer = sequencer();
+        assert_eq!(sequencer.mode(), Mode::Playlist);
+        assert_eq!(sequencer.frame(), Pattern::Chase.frame(0));
+    }
+
+    #[test]
+    fn test_new_status() {
+        let status = sequencer().status();
+        assert_eq!(status.mode, Mode::Playlist);
+        assert!(!status.paused);
+        assert_eq!(status.pattern, Pattern::Chase);
+        assert_eq!(status.step, 0);
+        assert_eq!(status.delay_ms, 100);
+    }
+
+    // ==================== Command Tests ====================
+
+    #[test]
+    fn test_set_pattern_takes_manual() {
+        let mut sequencer = sequencer();
+        sequencer.apply(Command::SetPattern(Pattern::Fill));
+        assert_eq!(sequencer.mode(), Mode::Manual);
+        assert_eq!(sequencer.status().pattern, Pattern::Fill);
+        assert_eq!(sequencer.frame(), Pattern::Fill.frame(0));
+    }
+
+    #[test]
+    fn test_set_delay_keeps_playlist_pattern() {
+        let mut sequencer = sequencer();
+        sequencer.tick(100);
+        sequencer.apply(Command::SetDelay(200));
+        let status = sequencer.status();
+        assert_eq!(status.mode, Mode::Manual);
+        assert_eq!(status.pattern, Pattern::Chase);
+        assert_eq!(status.step, 1);
+        assert_eq!(status.delay_ms, 200);
+    }
+
+    #[test]
+    fn test_manual_uses_own_delay() {
+        let mut sequencer = sequencer();
+        sequencer.apply(Command::SetDelay(200));
+        sequencer.tick(100);
+        assert_eq!(sequencer.status().step, 0);
+        sequencer.tick(100);
+        assert_eq!(sequencer.status().step, 1);
+    }
+
+    #[test]
+    fn test_run_playlist_resumes() {
+        let mut sequencer = sequencer();
+        sequencer.tick(100);
+        sequencer.apply(Command::SetPattern(Pattern::Blank));
+        sequencer.tick(500);
+        sequencer.apply(Command::RunPlaylist);
+        assert_eq!(sequencer.mode(), Mode::Playlist);
+        assert_eq!(sequencer.frame(), Pattern::Chase.frame(1));
+    }
+
+    #[test]
+    fn test_manual_pauses_playlist() {
+        let mut sequencer = sequencer();
+        sequencer.apply(Command::SetPattern(Pattern::Blank));
+        sequencer.tick(5000);
+        sequencer.apply(Command::RunPlaylist);
+        assert_eq!(sequencer.status().pattern, Pattern::Chase);
+        assert_eq!(sequencer.status().step, 0);
+    }
+
+    #[test]
+    fn test_pause_freezes_output() {
+        let mut sequencer = sequencer();
+        sequencer.apply(Command::Pause);
+        assert!(sequencer.is_paused());
+        assert_eq!(sequencer.tick(500), Pattern::Chase.frame(0));
+        assert!(sequencer.status().paused);
+    }
+
+    #[test]
+    fn test_resume_continues() {
+        let mut sequencer = sequencer();
+        sequencer.apply(Command::Pause);
+        sequencer.tick(500);
+        sequencer.apply(Command::Resume);
+        assert!(!sequencer.is_paused());
+        assert_eq!(sequencer.tick(100), Pattern::Chase.frame(1));
+    }
+
+    #[test]
+    fn test_step_advances_once() {
+        let mut sequencer = sequencer();
+        sequencer.apply(Command::Step);
+        assert_eq!(sequencer.mode(), Mode::Manual);
+        assert_eq!(sequencer.frame(), Pattern::Chase.frame(1));
+    }
+
+    #[test]
+    fn test_step_while_paused() {
+        let mut sequencer = sequencer();
+        sequencer.apply(Command::Pause);
+        sequencer.apply(Command::Step);
+        sequencer.apply(Command::Step);
+        assert_eq!(sequencer.tick(1000), Pattern::Chase.frame(2));
+    }
+
+    #[test]
+    fn test_pause_kept_taking_manual() {
+        let mut sequencer = sequencer();
+        sequencer.apply(Command::Pause);
+        sequencer.apply(Command::SetDelay(100));
+        assert_eq!(sequencer.mode(), Mode::Manual);
+        assert!(sequencer.is_paused());
+        assert_eq!(sequencer.tick(500), Pattern::Chase.frame(0));
+    }
+
+    #[test]
+    fn test_step_back_wraps() {
+        let mut sequencer = sequencer();
+        sequencer.apply(Command::StepBack);
+        let last = Pattern::Chase.cycle_len_on(LED_COUNT) - 1;
+        assert_eq!(sequencer.mode(), Mode::Manual);
+        assert_eq!(sequencer.status().step, last);
+        assert_eq!(sequencer.steps(), 0);
+    }
+
+    #[test]
+    fn test_set_direction_reverses() {
+        let mut sequencer = sequencer();
+        sequencer.apply(Command::SetDelay(100));
+        sequencer.apply(Command::Jump(2));
+        sequencer.apply(Command::SetDirection(Direction::Reverse));
+        assert_eq!(sequencer.tick(100), Pattern::Chase.frame(1));
+    }
+
+    #[test]
+    fn test_reset_restarts_unpaused() {
+        let mut sequencer = sequencer();
+        sequencer.apply(Command::SetDelay(100));
+        sequencer.apply(Command::Jump(2));
+        sequencer.apply(Command::SetDirection(Direction::Reverse));
+        sequencer.apply(Command::Pause);
+        sequencer.apply(Command::Reset);
+        assert!(!sequencer.is_paused());
+        assert_eq!(sequencer.status().step, 0);
+        assert_eq!(sequencer.tick(100), Pattern::Chase.frame(1));
+    }
+
+    #[test]
+    fn test_jump_sets_step() {
+        let mut sequencer = sequencer();
+        sequencer.apply(Command::Jump(3));
+        assert_eq!(sequencer.status().step, 3);
+        assert_eq!(sequencer.frame(), Pattern::Chase.frame(3));
+    }
+
+    #[test]
+    fn test_jump_wraps() {
+        let mut sequencer = sequencer();
+        sequencer.apply(Command::SetPattern(Pattern::Blink));
+        sequencer.apply(Command::Jump(5));
+        assert_eq!(sequencer.status().step, 1);
+    }
+
+    #[test]
+    fn test_set_frame_shows_frame() {
+        let mut sequencer = sequencer();
+        let still = Frame::from_levels([1, 2, 3, 4]);
+        sequencer.apply(Command::SetFrame(still));
+        assert_eq!(sequencer.mode(), Mode::Frame);
+        assert_eq!(sequencer.tick(1000), still);
+    }
+
+    #[test]
+    fn test_step_leaves_frame_mode() {
+        let mut sequencer = sequencer();
+        sequencer.apply(Command::SetPattern(Pattern::Fill));
+        sequencer.apply(Command::SetFrame(Frame::filled(1)));
+        sequencer.apply(Command::Step);
+        assert_eq!(sequencer.mode(), Mode::Manual);
+        assert_eq!(sequencer.frame(), Pattern::Fill.frame(1));
+    }
+
+    #[test]
+    fn test_run_playlist_leaves_frame_mode() {
+        let mut sequencer = sequencer();
+        sequencer.apply(Command::SetFrame(Frame::filled(1)));
+        sequencer.apply(Command::RunPlaylist);
+        assert_eq!(sequencer.frame(), Pattern::Chase.frame(0));
+    }
+
+    // ==================== Clock Tests ====================
+
+    #[test]
+    fn test_new_uses_internal_clock() {
+        let sequencer = sequencer();
+        assert_eq!(sequencer.status().clock, ClockSource::Internal);
+        assert_eq!(sequencer.steps(), 0);
+    }
+
+    #[test]
+    fn test_external_clock_holds_pattern() {
+        let mut sequencer = sequencer();
+        sequencer.apply(Command::SetClock(ClockSource::External));
+        assert_eq!(sequencer.tick(1000), Pattern::Chase.frame(0));
+        assert_eq!(sequencer.steps(), 0);
+        assert_eq!(sequencer.status().clock, ClockSource::External);
+    }
+
+    #[test]
+    fn test_external_clock_steps_on_command() {
+        let mut sequencer = sequencer();
+        sequencer.apply(Command::SetClock(ClockSource::External));
+        sequencer.apply(Command::Step);
+        assert_eq!(sequencer.tick(1000), Pattern::Chase.frame(1));
+        assert_eq!(sequencer.steps(), 1);
+    }
+
+    #[test]
+    fn test_internal_clock_resumes_timing() {
+        let mut sequencer = sequencer();
+        sequencer.apply(Command::SetClock(ClockSource::External));
+        sequencer.tick(1000);
+        sequencer.apply(Command::SetClock(ClockSource::Internal));
+        assert_eq!(sequencer.tick(100), Pattern::Chase.frame(1));
+    }
+
+    #[test]
+    fn test_steps_count_playlist_and_manual() {
+        let mut sequencer = sequencer();
+        sequencer.tick(200);
+        assert_eq!(sequencer.steps(), 2);
+        sequencer.apply(Command::SetDelay(100));
+        sequencer.tick(300);
+        assert_eq!(sequencer.steps(), 5);
+    }
+
+    #[test]
+    fn test_steps_ignore_jump() {
+        let mut sequencer = sequencer();
+        sequencer.apply(Command::Jump(2));
+        assert_eq!(sequencer.steps(), 0);
+    }
+
+    // ==================== Bar Tests ====================
+
+    #[test]
+    fn test_set_bar_shows_bar() {
+        let mut sequencer = sequencer();
+        sequencer.apply(Command::SetBar(500));
+        assert_eq!(sequencer.mode(), Mode::Bar);
+        assert_eq!(sequencer.tick(10_000), Frame::from_levels([255, 255, 0, 0]));
+    }
+
+    #[test]
+    fn test_bar_is_smoothed() {
+        let mut sequencer = sequencer();
+        sequencer.apply(Command::SetBar(BAR_FULL));
+        let frame = sequencer.tick(FRAME_INTERVAL_MS);
+        assert!(frame.lit_count() < LED_COUNT);
+        for _ in 0..300 {
+            sequencer.tick(FRAME_INTERVAL_MS);
+        }
+        assert_eq!(sequencer.frame(), Frame::filled(MAX_BRIGHTNESS));
+    }
+
+    #[test]
+    fn test_bar_direction() {
+        let mut sequencer = sequencer();
+        sequencer.apply(Command::SetBarDirection(FillDirection::Reverse));
+        sequencer.apply(Command::SetBar(500));
+        assert_eq!(sequencer.tick(10_000), Frame::from_levels([0, 0, 255, 255]));
+    }
+
+    #[test]
+    fn test_bar_overflow_blinks() {
+        let mut sequencer = sequencer();
+        sequencer.apply(Command::SetBar(BAR_FULL + 1));
+        assert_eq!(sequencer.tick(10_000), Frame::filled(MAX_BRIGHTNESS));
+        assert_eq!(sequencer.tick(BAR_BLINK_MS as u64 / 2), Frame::new());
+    }
+
+    #[test]
+    fn test_bar_keeps_manual_position() {
+        let mut sequencer = sequencer();
+        sequencer.apply(Command::Jump(2));
+        sequencer.apply(Command::SetBar(100));
+        sequencer.tick(1000);
+        assert_eq!(sequencer.status().mode, Mode::Bar);
+        assert_eq!(sequencer.status().step, 2);
+        assert_eq!(sequencer.steps(), 0);
+    }
+
+    #[test]
+    fn test_run_playlist_leaves_bar() {
+        let mut sequencer = sequencer();
+        sequencer.apply(Command::SetBar(100));
+        sequencer.apply(Command::RunPlaylist);
+        assert_eq!(sequencer.frame(), Pattern::Chase.frame(0));
+    }
+
+    // ==================== State Machine Tests ====================
+
+    fn with_fsm() -> Sequencer<'static> {
+        sequencer().with_fsm(Fsm::new(&STATES, &TRANSITIONS, 0).unwrap())
+    }
+
+    #[test]
+    fn test_run_fsm_without_fsm_ignored() {
+        let mut sequencer = sequencer();
+        sequencer.apply(Command::RunFsm);
+        sequencer.apply(Command::Input(1));
+        assert_eq!(sequencer.mode(), Mode::Playlist);
+        assert!(sequencer.fsm().is_none());
+    }
+
+    #[test]
+    fn test_run_fsm_shows_initial_state() {
+        let mut sequencer = with_fsm();
+        sequencer.apply(Command::RunFsm);
+        assert_eq!(sequencer.mode(), Mode::Fsm);
+        assert_eq!(sequencer.frame(), STATES[0].frame);
+    }
+
+    #[test]
+    fn test_fsm_waits_for_input() {
+        let mut sequencer = with_fsm();
+        sequencer.apply(Command::RunFsm);
+        assert_eq!(sequencer.tick(500), STATES[0].frame);
+        sequencer.apply(Command::Input(1));
+        assert_eq!(sequencer.tick(10), STATES[1].frame);
+        assert_eq!(sequencer.steps(), 1);
+    }
+
+    #[test]
+    fn test_fsm_input_latched_before_run() {
+        let mut sequencer = with_fsm();
+        sequencer.apply(Command::Input(1));
+        sequencer.apply(Command::RunFsm);
+        assert_eq!(sequencer.fsm().unwrap().inputs(), 0);
+    }
+
+    #[test]
+    fn test_fsm_paused() {
+        let mut sequencer = with_fsm();
+        sequencer.apply(Command::RunFsm);
+        sequencer.apply(Command::Input(1));
+        sequencer.apply(Command::Pause);
+        assert_eq!(sequencer.tick(500), STATES[0].frame);
+    }
+
+    #[test]
+    fn test_fsm_keeps_state_while_hidden() {
+        let mut sequencer = with_fsm();
+        sequencer.apply(Command::RunFsm);
+        sequencer.apply(Command::Input(1));
+        sequencer.tick(100);
+        sequencer.apply(Command::RunPlaylist);
+        sequencer.tick(1000);
+        assert_eq!(sequencer.fsm().unwrap().state(), 1);
+    }
+
+    // ==================== Wall Clock Tests ====================
+
+    fn time(hours: u8, minutes: u8, seconds: u8) -> TimeOfDay {
+        TimeOfDay::new(hours, minutes, seconds).unwrap()
+    }
+
+    #[test]
+    fn test_time_unset() {
+        assert_eq!(sequencer().time(), None);
+    }
+
+    #[test]
+    fn test_set_time_keeps_mode() {
+        let mut sequencer = sequencer();
+        sequencer.apply(Command::SetTime(time(8, 30, 0)));
+        assert_eq!(sequencer.mode(), Mode::Playlist);
+        assert_eq!(sequencer.time(), Some(time(8, 30, 0)));
+    }
+
+    #[test]
+    fn test_time_advances_with_ticks() {
+        let mut sequencer = sequencer();
+        sequencer.tick(250);
+        sequencer.apply(Command::SetTime(time(8, 30, 0)));
+        sequencer.tick(1000);
+        assert_eq!(sequencer.time(), Some(time(8, 30, 1)));
+    }
+
+    #[test]
+    fn test_time_advances_while_paused() {
+        let mut sequencer = sequencer();
+        sequencer.apply(Command::SetTime(time(8, 30, 0)));
+        sequencer.apply(Command::Pause);
+        sequencer.tick(60_000);
+        assert_eq!(sequencer.time(), Some(time(8, 31, 0)));
+    }
+
+    #[test]
+    fn test_show_clock() {
+        let mut sequencer = sequencer();
+        sequencer.apply(Command::SetTime(time(8, 30, 5)));
+        sequencer.apply(Command::ShowClock(ClockEncoding::Binary));
+        assert_eq!(sequencer.mode(), Mode::Clock);
+        assert_eq!(
+            sequencer.frame(),
+            ClockEncoding::Binary.frame(time(8, 30, 5))
+        );
+        sequencer.tick(1000);
+        assert_eq!(
+            sequencer.frame(),
+            ClockEncoding::Binary.frame(time(8, 30, 6))
+        );
+    }
+
+    #[test]
+    fn test_show_clock_bcd() {
+        let mut sequencer = sequencer();
+        sequencer.apply(Command::SetTime(time(8, 30, 19)));
+        sequencer.apply(Command::ShowClock(ClockEncoding::Bcd));
+        assert_eq!(sequencer.frame(), ClockEncoding::Bcd.frame(time(8, 30, 19)));
+    }
+
+    #[test]
+    fn test_unset_clock_shows_uptime() {
+        let mut sequencer = sequencer();
+        sequencer.apply(Command::ShowClock(ClockEncoding::Binary));
+        sequencer.tick(3000);
+        assert_eq!(
+            sequencer.frame(),
+            ClockEncoding::Binary.frame(time(0, 0, 3))
+        );
+    }
+
+    // ==================== Timeline Tests ====================
+
+    static RAMP: [Keyframe; 2] = [Keyframe::new(0, 0, 0), Keyframe::new(1000, 0, 200)];
+
+    fn ramp() -> Timeline<'static> {
+        Timeline::new(&RAMP, 1000).unwrap()
+    }
+
+    #[test]
+    fn test_load_timeline_plays_from_start() {
+        let mut sequencer = sequencer();
+        let mut timeline = ramp();
+        timeline.seek(600);
+        sequencer.apply(Command::LoadTimeline(timeline));
+        assert_eq!(sequencer.mode(), Mode::Timeline);
+        assert_eq!(sequencer.timeline().unwrap().position_ms(), 0);
+        assert_eq!(sequencer.frame().level(0), 0);
+    }
+
+    #[test]
+    fn test_timeline_sampled_every_frame() {
+        let mut sequencer = sequencer();
+        sequencer.apply(Command::LoadTimeline(ramp()));
+        sequencer.tick(FRAME_INTERVAL_MS);
+        assert_eq!(sequencer.frame().level(0), 1);
+        sequencer.tick(490);
+        assert_eq!(sequencer.frame().level(0), 100);
+    }
+
+    #[test]
+    fn test_timeline_commands_ignored_until_loaded() {
+        let mut sequencer = sequencer();
+        sequencer.apply(Command::PlayTimeline);
+        sequencer.apply(Command::SeekTimeline(500));
+        sequencer.apply(Command::SetPlayback(Playback::Loop));
+        assert_eq!(sequencer.mode(), Mode::Playlist);
+        assert_eq!(sequencer.timeline(), None);
+    }
+
+    #[test]
+    fn test_with_timeline_waits_for_play() {
+        let mut sequencer = sequencer().with_timeline(ramp());
+        assert_eq!(sequencer.mode(), Mode::Playlist);
+        sequencer.apply(Command::PlayTimeline);
+        assert_eq!(sequencer.mode(), Mode::Timeline);
+        assert_eq!(sequencer.timeline().unwrap().position_ms(), 0);
+    }
+
+    #[test]
+    fn test_seek_timeline() {
+        let mut sequencer = sequencer();
+        sequencer.apply(Command::LoadTimeline(ramp()));
+        sequencer.apply(Command::RunPlaylist);
+        sequencer.apply(Command::SeekTimeline(500));
+        assert_eq!(sequencer.mode(), Mode::Timeline);
+        assert_eq!(sequencer.frame().level(0), 100);
+    }
+
+    #[test]
+    fn test_play_timeline_resumes_position() {
+        let mut sequencer = sequencer();
+        sequencer.apply(Command::LoadTimeline(ramp()));
+        sequencer.tick(250);
+        sequencer.apply(Command::RunPlaylist);
+        sequencer.tick(250);
+        sequencer.apply(Command::PlayTimeline);
+        assert_eq!(sequencer.timeline().unwrap().position_ms(), 250);
+    }
+
+    #[test]
+    fn test_set_playback_loops_timeline() {
+        let mut sequencer = sequencer();
+        sequencer.apply(Command::LoadTimeline(ramp()));
+        sequencer.apply(Command::SetPlayback(Playback::Loop));
+        sequencer.tick(1250);
+        assert_eq!(sequencer.timeline().unwrap().position_ms(), 250);
+    }
+
+    #[test]
+    fn test_timeline_frozen_while_paused() {
+        let mut sequencer = sequencer();
+        sequencer.apply(Command::LoadTimeline(ramp()));
+        sequencer.apply(Command::Pause);
+        sequencer.tick(500);
+        assert_eq!(sequencer.timeline().unwrap().position_ms(), 0);
+    }
+
+    // ==================== Morse Tests ====================
+
+    fn morse(text: &str) -> Command {
+        Command::Morse(MorseMessage::new(text, MorseTarget::All).unwrap())
+    }
+
+    #[test]
+    fn test_morse_shows_player() {
+        let mut sequencer = sequencer();
+        sequencer.apply(morse("E"));
+        assert_eq!(sequencer.mode(), Mode::Morse);
+        sequencer.tick(0);
+        assert_eq!(sequencer.frame(), Frame::filled(MAX_BRIGHTNESS));
+    }
+
+    #[test]
+    fn test_morse_unit_follows_manual_delay() {
+        let mut sequencer = sequencer();
+        sequencer.apply(Command::SetDelay(50));
+        sequencer.apply(morse("T"));
+        sequencer.tick(0);
+        assert_eq!(sequencer.morse().unit_ms(), 50);
+        sequencer.tick(149);
+        assert_eq!(sequencer.frame(), Frame::filled(MAX_BRIGHTNESS));
+        sequencer.tick(1);
+        assert_eq!(sequencer.frame(), Frame::new());
+    }
+
+    #[test]
+    fn test_morse_queue_plays_in_order() {
+        let mut sequencer = sequencer();
+        sequencer.apply(Command::SetDelay(10));
+        sequencer.apply(morse("E"));
+        sequencer.apply(morse("E"));
+        sequencer.tick(0);
+        sequencer.tick(80);
+        assert_eq!(sequencer.mode(), Mode::Morse);
+        assert_eq!(sequencer.frame(), Frame::filled(MAX_BRIGHTNESS));
+    }
+
+    #[test]
+    fn test_morse_returns_to_playlist() {
+        let mut sequencer = sequencer();
+        sequencer.apply(Command::SetDelay(10));
+        sequencer.apply(morse("E"));
+        sequencer.tick(0);
+        sequencer.tick(80);
+        assert_eq!(sequencer.mode(), Mode::Playlist);
+    }
+
+    #[test]
+    fn test_step_back_ignored_by_morse() {
+        let mut sequencer = sequencer();
+        sequencer.apply(morse("E"));
+        sequencer.apply(Command::StepBack);
+        assert_eq!(sequencer.mode(), Mode::Morse);
+        assert_eq!(sequencer.steps(), 0);
+    }
+
+    #[test]
+    fn test_morse_dropped_when_queue_full() {
+        let mut sequencer = sequencer();
+        for _ in 0..MORSE_QUEUE_LEN {
+            sequencer.apply(morse("E"));
+        }
+        sequencer.apply(Command::RunPlaylist);
+        sequencer.apply(morse("E"));
+        assert_eq!(sequencer.mode(), Mode::Playlist);
+    }
+
+    // ==================== Counter Tests ====================
+
+    #[test]
+    fn test_show_counter_from_first_value() {
+        let mut sequencer = sequencer();
+        let mut counter = Counter::new(3, 9).unwrap();
+        counter.advance();
+        sequencer.apply(Command::ShowCounter(counter));
+        assert_eq!(sequencer.mode(), Mode::Counter);
+        assert_eq!(sequencer.counter().value(), 3);
+        assert_eq!(sequencer.frame(), bits_frame(3));
+    }
+
+    #[test]
+    fn test_counter_steps_at_manual_delay() {
+        let mut sequencer = sequencer();
+        sequencer.apply(Command::SetDelay(100));
+        let counter = Counter::new(0, 15).unwrap().with_step(2).unwrap();
+        sequencer.apply(Command::ShowCounter(counter));
+        sequencer.tick(250);
+        assert_eq!(sequencer.counter().value(), 4);
+        assert_eq!(sequencer.steps(), 2);
+        assert_eq!(sequencer.idle_ms(), 50);
+    }
+
+    #[test]
+    fn test_counter_counts_down_in_gray() {
+        let mut sequencer = sequencer();
+        sequencer.apply(Command::SetDelay(100));
+        let counter = Counter::full_range()
+            .with_direction(CountDirection::Down)
+            .with_encoding(CounterEncoding::Gray);
+        sequencer.apply(Command::ShowCounter(counter));
+        sequencer.tick(100);
+        assert_eq!(sequencer.counter().value(), 14);
+        assert_eq!(sequencer.frame(), bits_frame(gray_encode(14)));
+    }
+
+    #[test]
+    fn test_step_advances_counter() {
+        let mut sequencer = sequencer();
+        sequencer.apply(Command::SetClock(ClockSource::External));
+        sequencer.apply(Command::ShowCounter(Counter::full_range()));
+        sequencer.apply(Command::Step);
+        sequencer.tick(1000);
+        assert_eq!(sequencer.mode(), Mode::Counter);
+        assert_eq!(sequencer.counter().value(), 1);
+    }
+
+    #[test]
+    fn test_step_back_moves_counter_back() {
+        let mut sequencer = sequencer();
+        sequencer.apply(Command::ShowCounter(Counter::new(3, 9).unwrap()));
+        sequencer.apply(Command::StepBack);
+        assert_eq!(sequencer.mode(), Mode::Counter);
+        assert_eq!(sequencer.counter().value(), 9);
+        assert_eq!(sequencer.steps(), 0);
+    }
+
+    // ==================== Effect Tests ====================
+
+    #[test]
+    fn test_show_effect() {
+        let mut sequencer = sequencer();
+        sequencer.apply(Command::ShowEffect(EffectKind::Candle));
+        assert_eq!(sequencer.mode(), Mode::Effect);
+        assert_eq!(sequencer.effect().kind(), EffectKind::Candle);
+        assert_eq!(sequencer.frame(), Frame::filled(CANDLE_MAX));
+    }
+
+    #[test]
+    fn test_effect_seeded_reproducibly() {
+        let mut first = sequencer().with_seed(42);
+        let mut second = sequencer().with_seed(42);
+        first.apply(Command::SetDelay(100));
+        second.apply(Command::SetDelay(100));
+        first.apply(Command::ShowEffect(EffectKind::Twinkle));
+        second.apply(Command::ShowEffect(EffectKind::Twinkle));
+        for _ in 0..20 {
+            assert_eq!(first.tick(100), second.tick(100));
+        }
+    }
+
+    #[test]
+    fn test_effect_steps_at_manual_delay() {
+        let mut sequencer = sequencer().with_seed(7);
+        sequencer.apply(Command::SetDelay(100));
+        sequencer.apply(Command::ShowEffect(EffectKind::RandomWalk));
+        let mut walk = *sequencer.effect();
+        sequencer.tick(250);
+        walk.step();
+        walk.step();
+        assert_eq!(*sequencer.effect(), walk);
+    }
+
+    #[test]
+    fn test_step_advances_effect() {
+        let mut sequencer = sequencer();
+        sequencer.apply(Command::ShowEffect(EffectKind::RandomWalk));
+        let mut walk = *sequencer.effect();
+        sequencer.apply(Command::Step);
+        walk.step();
+        assert_eq!(sequencer.mode(), Mode::Effect);
+        assert_eq!(*sequencer.effect(), walk);
+    }
+
+    #[test]
+    fn test_step_back_ignored_by_effect() {
+        let mut sequencer = sequencer();
+        sequencer.apply(Command::ShowEffect(EffectKind::RandomWalk));
+        let walk = *sequencer.effect();
+        sequencer.apply(Command::StepBack);
+        assert_eq!(sequencer.mode(), Mode::Effect);
+        assert_eq!(*sequencer.effect(), walk);
+        assert_eq!(sequencer.steps(), 0);
+    }
+
+    // ==================== Schedule Tests ====================
+
+    const SCHEDULE: [ScheduleEntry; 1] = [ScheduleEntry::new(
+        TimeOfDay::hm(22, 0),
+        TimeOfDay::hm(7, 0),
+        Pattern::Solid,
+        64,
+        1000,
+    )];
+
+    fn scheduled_at(hours: u8, minutes: u8) -> Sequencer<'static> {
+        let mut sequencer = sequencer().with_schedule(Schedule::new(&SCHEDULE).unwrap());
+        sequencer.apply(Command::SetTime(TimeOfDay::hm(hours, minutes)));
+        sequencer
+    }
+
+    #[test]
+    fn test_schedule_waits_for_time() {
+        let mut sequencer = sequencer().with_schedule(Schedule::new(&SCHEDULE).unwrap());
+        sequencer.tick(10);
+        assert_eq!(sequencer.scheduled(), None);
+        assert_eq!(sequencer.mode(), Mode::Playlist);
+    }
+
+    #[test]
+    fn test_schedule_outside_windows() {
+        let mut sequencer = scheduled_at(12, 0);
+        sequencer.tick(10);
+        assert_eq!(sequencer.scheduled(), None);
+        assert_eq!(sequencer.brightness(), MAX_BRIGHTNESS);
+    }
+
+    #[test]
+    fn test_schedule_dims_at_night() {
+        let mut sequencer = scheduled_at(23, 0);
+        let frame = sequencer.tick(10);
+        assert_eq!(sequencer.scheduled(), Some(0));
+        assert_eq!(sequencer.brightness(), 64);
+        assert_eq!(sequencer.status().pattern, Pattern::Solid);
+        assert_eq!(sequencer.status().delay_ms, 1000);
+        assert_eq!(frame, Frame::filled(MAX_BRIGHTNESS).scaled(64));
+    }
+
+    #[test]
+    fn test_schedule_enters_at_window_start() {
+        let mut sequencer = scheduled_at(21, 59);
+        sequencer.tick(59_000);
+        assert_eq!(sequencer.scheduled(), None);
+        sequencer.tick(1000);
+        assert_eq!(sequencer.scheduled(), Some(0));
+    }
+
+    #[test]
+    fn test_schedule_wraps_midnight_and_ends() {
+        let mut sequencer = scheduled_at(23, 30);
+        sequencer.tick(10);
+        sequencer.tick(60 * 60 * 1000);
+        assert_eq!(sequencer.scheduled(), Some(0));
+        sequencer.tick(7 * 60 * 60 * 1000);
+        assert_eq!(sequencer.scheduled(), None);
+        assert_eq!(sequencer.brightness(), MAX_BRIGHTNESS);
+        assert_eq!(sequencer.mode(), Mode::Playlist);
+    }
+
+    #[test]
+    fn test_schedule_leaves_frame_mode_alone() {
+        let mut sequencer = scheduled_at(23, 0);
+        sequencer.apply(Command::SetFrame(Frame::filled(MAX_BRIGHTNESS)));
+        sequencer.tick(10);
+        assert_eq!(sequencer.mode(), Mode::Frame);
+        assert_eq!(sequencer.frame(), Frame::filled(64));
+    }
+
+    #[test]
+    fn test_manual_choice_kept_within_window() {
+        let mut sequencer = scheduled_at(23, 0);
+        sequencer.tick(10);
+        sequencer.apply(Command::SetPattern(Pattern::Chase));
+        sequencer.tick(60_000);
+        assert_eq!(sequencer.status().pattern, Pattern::Chase);
+    }
+
+    // ==================== Power Tests ====================
+
+    fn limited(mode: LimitMode) -> Sequencer<'static> {
+        let limiter = PowerLimiter::new(20, 40, mode).unwrap();
+        let mut sequencer = sequencer().with_power_limit(limiter);
+        sequencer.apply(Command::SetPattern(Pattern::Solid));
+        sequencer
+    }
+
+    #[test]
+    fn test_no_limiter_no_stats() {
+        let mut sequencer = sequencer();
+        sequencer.tick(10);
+        assert_eq!(sequencer.power_stats(), None);
+        assert!(!sequencer.status().power_limited);
+    }
+
+    #[test]
+    fn test_within_budget_not_limited() {
+        let limiter = PowerLimiter::new(20, 40, LimitMode::Scale).unwrap();
+        let mut sequencer = sequencer().with_power_limit(limiter);
+        assert_eq!(sequencer.tick(10), Pattern::Chase.frame(0));
+        assert!(!sequencer.status().power_limited);
+        assert_eq!(sequencer.power_stats().unwrap().frames, 1);
+    }
+
+    #[test]
+    fn test_scale_limits_output() {
+        let mut sequencer = limited(LimitMode::Scale);
+        assert_eq!(sequencer.tick(10), Frame::filled(127));
+        assert_eq!(sequencer.frame(), Frame::filled(MAX_BRIGHTNESS));
+        assert!(sequencer.status().power_limited);
+        assert_eq!(sequencer.power_stats().unwrap().scaled, 1);
+    }
+
+    #[test]
+    fn test_refuse_keeps_previous_output() {
+        let limiter = PowerLimiter::new(20, 40, LimitMode::Refuse).unwrap();
+        let mut sequencer = sequencer().with_power_limit(limiter);
+        let before = sequencer.tick(10);
+        sequencer.apply(Command::SetPattern(Pattern::Solid));
+        assert_eq!(sequencer.tick(10), before);
+        assert_eq!(sequencer.power_stats().unwrap().refused, 1);
+    }
+
+    #[test]
+    fn test_limit_clears_when_within_budget() {
+        let mut sequencer = limited(LimitMode::Refuse);
+        sequencer.tick(10);
+        sequencer.apply(Command::SetPattern(Pattern::Chase));
+        sequencer.tick(10);
+        assert!(!sequencer.status().power_limited);
+        assert_eq!(sequencer.power_stats().unwrap().limited(), 1);
+    }
+
+    #[test]
+    fn test_power_report_once_per_request() {
+        let mut sequencer = limited(LimitMode::Scale);
+        sequencer.tick(10);
+        assert_eq!(sequencer.take_power_report(), None);
+        sequencer.apply(Command::ReportPower);
+        let report = sequencer.take_power_report().unwrap();
+        assert_eq!(report.scaled, 1);
+        assert_eq!(report.peak_ma, 80);
+        assert_eq!(sequencer.take_power_report(), None);
+    }
+
+    #[test]
+    fn test_power_report_without_limiter() {
+        let mut sequencer = sequencer();
+        sequencer.apply(Command::ReportPower);
+        assert_eq!(sequencer.take_power_report(), None);
+    }
+
+    #[test]
+    fn test_reset_power() {
+        let mut sequencer = limited(LimitMode::Refuse);
+        sequencer.tick(10);
+        sequencer.apply(Command::ResetPower);
+        assert_eq!(sequencer.power_stats(), Some(PowerStats::default()));
+    }
+
+    // ==================== Health Tests ====================
+
+    fn tested() -> Sequencer<'static> {
+        let thresholds = SenseThresholds::new(60, 600).unwrap();
+        sequencer().with_self_test(thresholds)
+    }
+
+    fn run_self_test(sequencer: &mut Sequencer, samples: [u16; LED_COUNT]) {
+        sequencer.apply(Command::SelfTest);
+        sequencer.apply(Command::Sense(0));
+        for sample in samples {
+            sequencer.apply(Command::Sense(sample));
+        }
+    }
+
+    #[test]
+    fn test_self_test_needs_thresholds() {
+        let mut sequencer = sequencer();
+        sequencer.apply(Command::SelfTest);
+        assert_eq!(sequencer.status().self_test, None);
+    }
+
+    #[test]
+    fn test_sense_outside_self_test_ignored() {
+        let mut sequencer = tested();
+        sequencer.apply(Command::Sense(300));
+        assert_eq!(sequencer.health(), HealthReport::new());
+    }
+
+    #[test]
+    fn test_self_test_shows_test_frames() {
+        let mut sequencer = tested();
+        sequencer.apply(Command::SelfTest);
+        assert_eq!(sequencer.status().self_test, Some(TestStep::Baseline));
+        assert_eq!(sequencer.tick(10), Frame::new());
+        sequencer.apply(Command::Sense(0));
+        assert_eq!(sequencer.status().self_test, Some(TestStep::Led(0)));
+        assert_eq!(sequencer.tick(10), Frame::single(0, MAX_BRIGHTNESS));
+    }
+
+    #[test]
+    fn test_self_test_reports_health() {
+        let mut sequencer = tested();
+        run_self_test(&mut sequencer, [300, 10, 300, 900]);
+        let status = sequencer.status();
+        assert_eq!(status.self_test, None);
+        assert_eq!(status.health.get(0), LedHealth::Ok);
+        assert_eq!(status.health.get(1), LedHealth::Open);
+        assert_eq!(status.health.get(3), LedHealth::Short);
+    }
+
+    #[test]
+    fn test_chase_skips_faulty_led() {
+        let mut sequencer = tested();
+        run_self_test(&mut sequencer, [300, 10, 300, 300]);
+        sequencer.apply(Command::SetPattern(Pattern::Chase));
+        let lit: Vec<Frame> = (0..3).map(|_| sequencer.tick(100)).collect();
+        assert_eq!(
+            lit,
+            vec![
+                Frame::single(2, MAX_BRIGHTNESS),
+                Frame::single(3, MAX_BRIGHTNESS),
+                Frame::single(0, MAX_BRIGHTNESS),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_fixed_frame_masks_faulty_led() {
+        let mut sequencer = tested();
+        run_self_test(&mut sequencer, [300, 300, 900, 300]);
+        sequencer.apply(Command::SetFrame(Frame::filled(MAX_BRIGHTNESS)));
+        assert_eq!(sequencer.frame().level(2), 0);
+        assert_eq!(sequencer.frame().lit_count(), 3);
+    }
+
+    #[test]
+    fn test_self_test_idle_until_sense() {
+        let mut sequencer = tested();
+        sequencer.apply(Command::SelfTest);
+        assert_eq!(sequencer.idle_ms(), u64::MAX);
+    }
+
+    // ==================== Usage Tests ====================
+
+    #[test]
+    fn test_usage_records_shown_frames() {
+        let mut sequencer = sequencer();
+        sequencer.apply(Command::SetPattern(Pattern::Chase));
+        sequencer.apply(Command::SetDelay(100));
+        sequencer.tick(0);
+        sequencer.tick(100);
+        sequencer.tick(100);
+        let usage = sequencer.usage();
+        assert_eq!(usage.total_ms(), 200);
+        assert_eq!(usage.led(0).on_ms, 100);
+        assert_eq!(usage.led(1).on_ms, 100);
+        assert_eq!(usage.led(1).switches, 1);
+    }
+
+    #[test]
+    fn test_usage_report_once_per_request() {
+        let mut sequencer = sequencer();
+        assert_eq!(sequencer.take_usage_report(), None);
+        sequencer.apply(Command::ReportUsage);
+        assert_eq!(sequencer.take_usage_report(), Some(*sequencer.usage()));
+        assert_eq!(sequencer.take_usage_report(), None);
+    }
+
+    #[test]
+    fn test_reset_usage() {
+        let mut sequencer = sequencer();
+        sequencer.tick(10);
+        sequencer.tick(10);
+        sequencer.apply(Command::ResetUsage);
+        assert_eq!(*sequencer.usage(), UsageStats::new());
+    }
+
+    #[test]
+    fn test_with_usage_continues_totals() {
+        let mut saved = UsageStats::new();
+        saved.record(&Frame::filled(MAX_BRIGHTNESS), 1000);
+        let mut sequencer = sequencer().with_usage(saved);
+        sequencer.tick(10);
+        sequencer.tick(10);
+        assert_eq!(sequencer.usage().total_ms(), 1020);
+    }
+
+    // ==================== Jitter Tests ====================
+
+    #[test]
+    fn test_jitter_records_timed_steps() {
+        let mut sequencer = sequencer();
+        sequencer.apply(Command::SetPattern(Pattern::Chase));
+        sequencer.apply(Command::SetDelay(100));
+        sequencer.tick(100);
+        sequencer.record_step_timing(300);
+        assert_eq!(sequencer.jitter().count(), 1);
+        assert_eq!(sequencer.jitter().max_us(), Some(300));
+    }
+
+    #[test]
+    fn test_jitter_skips_ticks_without_step() {
+        let mut sequencer = sequencer();
+        sequencer.apply(Command::SetPattern(Pattern::Chase));
+        sequencer.apply(Command::SetDelay(100));
+        sequencer.tick(10);
+        sequencer.record_step_timing(300);
+        assert_eq!(sequencer.jitter().count(), 0);
+    }
+
+    #[test]
+    fn test_jitter_skips_step_commands() {
+        let mut sequencer = sequencer();
+        sequencer.apply(Command::SetClock(ClockSource::External));
+        sequencer.apply(Command::Step);
+        sequencer.tick(100);
+        sequencer.record_step_timing(300);
+        assert_eq!(sequencer.jitter().count(), 0);
+    }
+
+    #[test]
+    fn test_jitter_overrun_limit_from_config() {
+        assert_eq!(sequencer().jitter().overrun_us(), JITTER_OVERRUN_US);
+    }
+
+    #[test]
+    fn test_jitter_report_once_per_request() {
+        let mut sequencer = sequencer();
+        assert_eq!(sequencer.take_jitter_report(), None);
+        sequencer.apply(Command::ReportJitter);
+        assert_eq!(sequencer.take_jitter_report(), Some(*sequencer.jitter()));
+        assert_eq!(sequencer.take_jitter_report(), None);
+    }
+
+    #[test]
+    fn test_reset_jitter() {
+        let mut sequencer = sequencer();
+        sequencer.apply(Command::SetDelay(100));
+        sequencer.tick(100);
+        sequencer.record_step_timing(20_000);
+        sequencer.apply(Command::ResetJitter);
+        assert_eq!(*sequencer.jitter(), JitterHistogram::new(JITTER_OVERRUN_US));
+    }
+
+    // ==================== Idle Tests ====================
+
+    #[test]
+    fn test_idle_report_once_per_request() {
+        let mut sequencer = sequencer();
+        sequencer.record_idle(100, 900);
+        assert_eq!(sequencer.take_idle_report(), None);
+        sequencer.apply(Command::ReportIdle);
+        let report = sequencer.take_idle_report().unwrap();
+        assert_eq!(report.idle_permille, 900);
+        assert_eq!(
+            report.average_ua,
+            sequencer
+                .idle()
+                .average_current_ua(ACTIVE_CURRENT_MA, SLEEP_CURRENT_MA)
+        );
+        assert_eq!(sequencer.take_idle_report(), None);
+    }
+
+    #[test]
+    fn test_reset_idle() {
+        let mut sequencer = sequencer();
+        sequencer.record_idle(100, 900);
+        sequencer.apply(Command::ResetIdle);
+        assert_eq!(*sequencer.idle(), IdleBudget::new());
+    }
+
+    #[test]
+    fn test_idle_playlist_never_idle() {
+        assert_eq!(sequencer().idle_ms(), 0);
+    }
+
+    #[test]
+    fn test_idle_manual_until_step() {
+        let mut sequencer = sequencer();
+        sequencer.apply(Command::SetDelay(1000));
+        sequencer.tick(300);
+        assert_eq!(sequencer.idle_ms(), 700);
+    }
+
+    #[test]
+    fn test_idle_timeline_until_finished() {
+        let mut sequencer = sequencer();
+        sequencer.apply(Command::LoadTimeline(ramp()));
+        assert_eq!(sequencer.idle_ms(), 0);
+        sequencer.tick(1000);
+        assert_eq!(sequencer.idle_ms(), u64::MAX);
+    }
+
+    #[test]
+    fn test_idle_paused_forever() {
+        let mut sequencer = sequencer();
+        sequencer.apply(Command::Pause);
+        assert_eq!(sequencer.idle_ms(), u64::MAX);
+    }
+
+    #[test]
+    fn test_idle_frame_forever() {
+        let mut sequencer = sequencer();
+        sequencer.apply(Command::SetFrame(Frame::filled(MAX_BRIGHTNESS)));
+        assert_eq!(sequencer.idle_ms(), u64::MAX);
+    }
+
+    #[test]
+    fn test_idle_external_clock_forever() {
+        let mut sequencer = sequencer();
+        sequencer.apply(Command::SetClock(ClockSource::External));
+        assert_eq!(sequencer.idle_ms(), u64::MAX);
+    }
+
+    #[test]
+    fn test_idle_bar_never_idle() {
+        let mut sequencer = sequencer();
+        sequencer.apply(Command::SetBar(BAR_FULL));
+        sequencer.apply(Command::SetClock(ClockSource::External));
+        assert_eq!(sequencer.idle_ms(), 0);
+    }
+
+    #[test]
+    fn test_idle_clock_until_next_second() {
+        let mut sequencer = sequencer();
+        sequencer.apply(Command::ShowClock(ClockEncoding::Binary));
+        sequencer.apply(Command::Pause);
+        sequencer.tick(1250);
+        assert_eq!(sequencer.idle_ms(), 750);
+    }
+
+    #[test]
+    fn test_idle_background_limits() {
+        let mut background = Compositor::new();
+        let _ = background.push(Layer::new(Pattern::Chase, 200, BlendMode::Max));
+        let mut sequencer = Sequencer::new(background, Playlist::new(&ENTRIES).unwrap());
+        sequencer.apply(Command::SetFrame(Frame::new()));
+        sequencer.tick(50);
+        assert_eq!(sequencer.idle_ms(), 150);
+    }
+
+    #[test]
+    fn test_idle_schedule_checks_each_second() {
+        let mut sequencer = scheduled_at(12, 0);
+        sequencer.apply(Command::SetFrame(Frame::new()));
+        sequencer.tick(400);
+        assert_eq!(sequencer.idle_ms(), 600);
+    }
+
+    // ==================== Frame Tests ====================
+
+    #[test]
+    fn test_playlist_advances() {
+        let mut sequencer = sequencer();
+        assert_eq!(sequencer.tick(100), Pattern::Chase.frame(1));
+        assert_eq!(sequencer.tick(100), Pattern::Chase.frame(2));
+    }
+
+    #[test]
+    fn test_background_shows_under_blank() {
+        let mut background = Compositor::new();
+        let _ = background.push(Layer::new(Pattern::Solid, 100, BlendMode::Max));
+        let mut sequencer = Sequencer::new(background, Playlist::new(&ENTRIES).unwrap());
+        sequencer.apply(Command::SetPattern(Pattern::Blank));
+        assert_eq!(sequencer.tick(10), Frame::filled(MAX_BRIGHTNESS));
+    }
+
+    #[test]
+    fn test_frame_does_not_advance() {
+        let mut sequencer = sequencer();
+        sequencer.tick(50);
+        let frame = sequencer.frame();
+        assert_eq!(sequencer.frame(), frame);
+        assert_eq!(frame.lit_count(), 1);
+    }
+}
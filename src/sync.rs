@@ -0,0 +1,998 @@
+/*
+ * @file sync.rs
+ * @brief Multi-board sync protocol
+ * @author Kevin Thomas
+ * @date 2025
+ *
+ * MIT License
+ *
+ * Copyright (c) 2025 Kevin Thomas
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+//! FILE: sync.rs
+//!
+//! DESCRIPTION:
+//! Leader/Follower Synchronisation Protocol for Chained RP2350 Boards.
+//!
+//! BRIEF:
+//! Encodes and parses sync beacons carrying step index and timestamp.
+//! Locks follower phase to the leader with a PLL-like correction loop.
+//! Maps each board's LEDs onto a global virtual chase by board index.
+//! Holds no hardware so several boards can be simulated on the host.
+//!
+//! AUTHOR: Kevin Thomas
+//! CREATION DATE: October 18, 2026
+//! UPDATE DATE: October 18, 2026
+
+use crate::config::{LED_COUNT, MAX_BRIGHTNESS, MIN_SEQUENCE_DELAY_MS};
+use crate::frame::Frame;
+
+/// First byte of every beacon.
+#[allow(dead_code)]
+pub const BEACON_MAGIC: u8 = 0xA5;
+
+/// Encoded beacon length in bytes.
+///
+/// # Details
+/// Magic, step, timestamp, step delay and checksum.
+#[allow(dead_code)]
+pub const BEACON_LEN: usize = 12;
+
+/// Phase correction applied per beacon as a right shift of the error.
+#[allow(dead_code)]
+pub const PHASE_GAIN_SHIFT: u32 = 2;
+
+/// Frequency correction applied per beacon as a right shift of the error.
+#[allow(dead_code)]
+pub const FREQ_GAIN_SHIFT: u32 = 3;
+
+/// Largest frequency correction in parts per million.
+#[allow(dead_code)]
+pub const MAX_RATE_PPM: i64 = 5000;
+
+/// Phase error above which the follower jumps instead of slewing.
+#[allow(dead_code)]
+pub const SNAP_ERROR_MS: i64 = 500;
+
+/// Phase error below which the follower counts as locked.
+#[allow(dead_code)]
+pub const LOCK_ERROR_MS: i64 = 2;
+
+/// Sync error enumeration.
+///
+/// # Variants
+/// * `NoBoards` - Board count of zero
+/// * `InvalidBoard` - Board index not below the board count
+/// * `ZeroInterval` - Beacon interval of zero
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[allow(dead_code)]
+pub enum SyncError {
+    NoBoards,
+    InvalidBoard,
+    ZeroInterval,
+}
+
+/// Sync beacon sent by the leader.
+///
+/// # Fields
+/// * `step` - Global step index at the timestamp
+/// * `timestamp_ms` - Leader time when the beacon was sent, wrapping
+/// * `step_ms` - Delay between global steps in milliseconds
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct Beacon {
+    pub step: u32,
+    pub timestamp_ms: u32,
+    pub step_ms: u16,
+}
+
+impl Beacon {
+    /// Encodes beacon for the wire.
+    ///
+    /// # Details
+    /// Fields are little-endian after the magic byte and are
+    /// followed by an XOR checksum of the fields.
+    ///
+    /// # Returns
+    /// * `[u8; BEACON_LEN]` - Encoded beacon
+    #[allow(dead_code)]
+    pub fn encode(&self) -> [u8; BEACON_LEN] {
+        let mut bytes = [0; BEACON_LEN];
+        bytes[0] = BEACON_MAGIC;
+        bytes[1..5].copy_from_slice(&self.step.to_le_bytes());
+        bytes[5..9].copy_from_slice(&self.timestamp_ms.to_le_bytes());
+        bytes[9..11].copy_from_slice(&self.step_ms.to_le_bytes());
+        bytes[11] = checksum(&bytes[1..11]);
+        bytes
+    }
+
+    /// Decodes beacon from the wire.
+    ///
+    /// # Arguments
+    /// * `bytes` - Encoded beacon
+    ///
+    /// # Returns
+    /// * `Option<Self>` - Beacon, or None if the magic or checksum is wrong
+    #[allow(dead_code)]
+    pub fn decode(bytes: &[u8; BEACON_LEN]) -> Option<Self> {
+        if bytes[0] != BEACON_MAGIC || bytes[11] != checksum(&bytes[1..11]) {
+            return None;
+        }
+        Some(Self {
+            step: u32::from_le_bytes([bytes[1], bytes[2], bytes[3], bytes[4]]),
+            timestamp_ms: u32::from_le_bytes([bytes[5], bytes[6], bytes[7], bytes[8]]),
+            step_ms: u16::from_le_bytes([bytes[9], bytes[10]]),
+        })
+    }
+}
+
+/// Returns XOR checksum of beacon fields.
+///
+/// # Arguments
+/// * `bytes` - Encoded fields
+///
+/// # Returns
+/// * `u8` - Checksum
+fn checksum(bytes: &[u8]) -> u8 {
+    bytes.iter().fold(0, |sum, byte| sum ^ byte)
+}
+
+/// Byte-stream beacon parser.
+///
+/// # Details
+/// Waits for the magic byte, then collects a full beacon. On a
+/// bad checksum it rescans the collected bytes for the next
+/// magic byte so one corrupt byte loses at most one beacon.
+///
+/// # Fields
+/// * `buffer` - Bytes collected so far
+/// * `len` - Number of bytes collected
+/// * `rejected` - Beacons dropped on a bad checksum
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct BeaconParser {
+    buffer: [u8; BEACON_LEN],
+    len: usize,
+    rejected: u32,
+}
+
+impl Default for BeaconParser {
+    /// Returns default BeaconParser instance.
+    ///
+    /// # Details
+    /// Delegates to new() for initialization.
+    ///
+    /// # Returns
+    /// * `Self` - New empty BeaconParser
+    #[allow(dead_code)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BeaconParser {
+    /// Creates new empty parser.
+    ///
+    /// # Returns
+    /// * `Self` - New BeaconParser instance
+    #[allow(dead_code)]
+    pub fn new() -> Self {
+        Self {
+            buffer: [0; BEACON_LEN],
+            len: 0,
+            rejected: 0,
+        }
+    }
+
+    /// Returns number of beacons dropped on a bad checksum.
+    ///
+    /// # Returns
+    /// * `u32` - Rejected beacons
+    #[allow(dead_code)]
+    pub fn rejected(&self) -> u32 {
+        self.rejected
+    }
+
+    /// Feeds one received byte.
+    ///
+    /// # Arguments
+    /// * `byte` - Received byte
+    ///
+    /// # Returns
+    /// * `Option<Beacon>` - Beacon completed by this byte, if any
+    #[allow(dead_code)]
+    pub fn push(&mut self, byte: u8) -> Option<Beacon> {
+        if self.len == 0 && byte != BEACON_MAGIC {
+            return None;
+        }
+        self.buffer[self.len] = byte;
+        self.len += 1;
+        if self.len < BEACON_LEN {
+            return None;
+        }
+        if let Some(beacon) = Beacon::decode(&self.buffer) {
+            self.len = 0;
+            return Some(beacon);
+        }
+        self.rejected = self.rejected.saturating_add(1);
+        let restart = self.buffer[1..]
+            .iter()
+            .position(|&byte| byte == BEACON_MAGIC)
+            .map_or(BEACON_LEN, |offset| offset + 1);
+        self.buffer.copy_within(restart.., 0);
+        self.len = BEACON_LEN - restart;
+        None
+    }
+}
+
+/// Global step position anchored at a point in time.
+///
+/// # Fields
+/// * `step` - Global step at the anchor time
+/// * `time_ms` - Anchor time, wrapping
+/// * `step_ms` - Delay between steps in milliseconds
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Anchor {
+    step: u32,
+    time_ms: u32,
+    step_ms: u16,
+}
+
+impl Anchor {
+    /// Returns global step at a time.
+    ///
+    /// # Arguments
+    /// * `time_ms` - Time on the anchor clock, wrapping
+    ///
+    /// # Returns
+    /// * `u32` - Global step, wrapping
+    fn step_at(&self, time_ms: u32) -> u32 {
+        let offset = time_ms.wrapping_sub(self.time_ms) as i32 as i64;
+        let steps = offset.div_euclid(self.step_ms.max(1) as i64);
+        self.step.wrapping_add(steps as u32)
+    }
+}
+
+/// Sync leader.
+///
+/// # Details
+/// Owns the global time base and sends a beacon every interval.
+///
+/// # Fields
+/// * `anchor` - Global step position
+/// * `time_ms` - Leader time, wrapping
+/// * `interval_ms` - Time between beacons
+/// * `since_beacon_ms` - Time since the last beacon
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct SyncLeader {
+    anchor: Anchor,
+    time_ms: u32,
+    interval_ms: u32,
+    since_beacon_ms: u32,
+}
+
+impl SyncLeader {
+    /// Creates new leader at step 0.
+    ///
+    /// # Details
+    /// The first tick sends a beacon.
+    ///
+    /// # Arguments
+    /// * `step_ms` - Delay between global steps in milliseconds
+    /// * `interval_ms` - Time between beacons in milliseconds
+    ///
+    /// # Returns
+    /// * `Result<Self, SyncError>` - New SyncLeader
+    #[allow(dead_code)]
+    pub fn new(step_ms: u16, interval_ms: u32) -> Result<Self, SyncError> {
+        if interval_ms == 0 {
+            return Err(SyncError::ZeroInterval);
+        }
+        Ok(Self {
+            anchor: Anchor {
+                step: 0,
+                time_ms: 0,
+                step_ms: clamp_step_ms(step_ms),
+            },
+            time_ms: 0,
+            interval_ms,
+            since_beacon_ms: interval_ms,
+        })
+    }
+
+    /// Returns current global step.
+    ///
+    /// # Returns
+    /// * `u32` - Global step, wrapping
+    #[allow(dead_code)]
+    pub fn step(&self) -> u32 {
+        self.anchor.step_at(self.time_ms)
+    }
+
+    /// Changes the global step delay from the current step.
+    ///
+    /// # Arguments
+    /// * `step_ms` - Delay between global steps in milliseconds
+    #[allow(dead_code)]
+    pub fn set_step_ms(&mut self, step_ms: u16) {
+        self.anchor = Anchor {
+            step: self.step(),
+            time_ms: self.time_ms,
+            step_ms: clamp_step_ms(step_ms),
+        };
+    }
+
+    /// Advances leader time.
+    ///
+    /// # Arguments
+    /// * `elapsed_ms` - Time since the previous tick
+    ///
+    /// # Returns
+    /// * `Option<Beacon>` - Beacon to send, if one is due
+    #[allow(dead_code)]
+    pub fn tick(&mut self, elapsed_ms: u32) -> Option<Beacon> {
+        self.time_ms = self.time_ms.wrapping_add(elapsed_ms);
+        self.since_beacon_ms = self.since_beacon_ms.saturating_add(elapsed_ms);
+        if self.since_beacon_ms < self.interval_ms {
+            return None;
+        }
+        self.since_beacon_ms = 0;
+        Some(Beacon {
+            step: self.step(),
+            timestamp_ms: self.time_ms,
+            step_ms: self.anchor.step_ms,
+        })
+    }
+}
+
+/// Sync follower.
+///
+/// # Details
+/// Keeps an estimate of leader time that runs at a corrected rate.
+/// Each beacon measures the phase error; a fraction of it is
+/// applied to the phase and a fraction of the implied frequency
+/// error to the rate, like a proportional-integral PLL. Errors
+/// above SNAP_ERROR_MS are treated as a restart and jumped over.
+///
+/// # Fields
+/// * `anchor` - Global step position from the last beacon
+/// * `phase_us` - Estimated leader time in microseconds
+/// * `rate_ppm` - Frequency correction in parts per million
+/// * `latency_ms` - Beacon transmit delay added to timestamps
+/// * `since_beacon_us` - Local time since the last beacon
+/// * `error_us` - Phase error at the last beacon
+/// * `synced` - Whether a beacon has been received
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct SyncFollower {
+    anchor: Anchor,
+    phase_us: i64,
+    rate_ppm: i64,
+    latency_ms: u32,
+    since_beacon_us: u32,
+    error_us: i64,
+    synced: bool,
+}
+
+impl SyncFollower {
+    /// Creates new follower waiting for its first beacon.
+    ///
+    /// # Arguments
+    /// * `latency_ms` - Beacon transmit delay added to timestamps
+    ///
+    /// # Returns
+    /// * `Self` - New SyncFollower
+    #[allow(dead_code)]
+    pub fn new(latency_ms: u32) -> Self {
+        Self {
+            anchor: Anchor {
+                step: 0,
+                time_ms: 0,
+                step_ms: MIN_SEQUENCE_DELAY_MS as u16,
+            },
+            phase_us: 0,
+            rate_ppm: 0,
+            latency_ms,
+            since_beacon_us: 0,
+            error_us: 0,
+            synced: false,
+        }
+    }
+
+    /// Returns whether a beacon has been received.
+    ///
+    /// # Returns
+    /// * `bool` - true once synced
+    #[allow(dead_code)]
+    pub fn is_synced(&self) -> bool {
+        self.synced
+    }
+
+    /// Returns whether the phase error is within LOCK_ERROR_MS.
+    ///
+    /// # Returns
+    /// * `bool` - true when locked
+    #[allow(dead_code)]
+    pub fn is_locked(&self) -> bool {
+        self.synced && self.error_us.abs() < LOCK_ERROR_MS * 1000
+    }
+
+    /// Returns phase error measured at the last beacon.
+    ///
+    /// # Returns
+    /// * `i64` - Leader minus follower time in microseconds
+    #[allow(dead_code)]
+    pub fn error_us(&self) -> i64 {
+        self.error_us
+    }
+
+    /// Returns frequency correction.
+    ///
+    /// # Returns
+    /// * `i64` - Correction in parts per million
+    #[allow(dead_code)]
+    pub fn rate_ppm(&self) -> i64 {
+        self.rate_ppm
+    }
+
+    /// Returns current global step.
+    ///
+    /// # Returns
+    /// * `u32` - Global step, wrapping
+    #[allow(dead_code)]
+    pub fn step(&self) -> u32 {
+        self.anchor.step_at(self.time_ms())
+    }
+
+    /// Advances follower time at the corrected rate.
+    ///
+    /// # Details
+    /// Takes microseconds so small rate corrections are not lost
+    /// to rounding.
+    ///
+    /// # Arguments
+    /// * `elapsed_us` - Local time since the previous tick in microseconds
+    #[allow(dead_code)]
+    pub fn tick_us(&mut self, elapsed_us: u32) {
+        self.phase_us += elapsed_us as i64 * (1_000_000 + self.rate_ppm) / 1_000_000;
+        self.since_beacon_us = self.since_beacon_us.saturating_add(elapsed_us);
+    }
+
+    /// Corrects phase and rate from a beacon.
+    ///
+    /// # Arguments
+    /// * `beacon` - Beacon received from the leader
+    #[allow(dead_code)]
+    pub fn on_beacon(&mut self, beacon: &Beacon) {
+        let leader_ms = beacon.timestamp_ms.wrapping_add(self.latency_ms);
+        let local_ms = self.time_ms();
+        let fraction_us = self.phase_us.rem_euclid(1000);
+        let error_us = leader_ms.wrapping_sub(local_ms) as i32 as i64 * 1000 - fraction_us;
+        let interval_us = self.since_beacon_us as i64;
+        self.anchor = Anchor {
+            step: beacon.step,
+            time_ms: beacon.timestamp_ms,
+            step_ms: clamp_step_ms(beacon.step_ms),
+        };
+        self.since_beacon_us = 0;
+        if !self.synced || error_us.abs() > SNAP_ERROR_MS * 1000 {
+            self.phase_us += error_us;
+            self.rate_ppm = 0;
+            self.error_us = 0;
+            self.synced = true;
+            return;
+        }
+        self.error_us = error_us;
+        self.phase_us += error_us >> PHASE_GAIN_SHIFT;
+        if interval_us > 0 {
+            let freq_error_ppm = error_us * 1_000_000 / interval_us;
+            self.rate_ppm = (self.rate_ppm + (freq_error_ppm >> FREQ_GAIN_SHIFT))
+                .clamp(-MAX_RATE_PPM, MAX_RATE_PPM);
+        }
+    }
+
+    /// Returns estimated leader time.
+    ///
+    /// # Returns
+    /// * `u32` - Leader time in milliseconds, wrapping
+    fn time_ms(&self) -> u32 {
+        self.phase_us.div_euclid(1000) as u32
+    }
+}
+
+/// Board position within a global virtual chase.
+///
+/// # Details
+/// Board k shows global LEDs k * LED_COUNT to k * LED_COUNT +
+/// LED_COUNT - 1, so the boards together run one long chase.
+///
+/// # Fields
+/// * `boards` - Number of boards in the row
+/// * `index` - Position of this board, 0 being the leader
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct VirtualChase {
+    boards: u8,
+    index: u8,
+}
+
+impl VirtualChase {
+    /// Creates new board position.
+    ///
+    /// # Arguments
+    /// * `boards` - Number of boards in the row
+    /// * `index` - Position of this board
+    ///
+    /// # Returns
+    /// * `Result<Self, SyncError>` - New VirtualChase
+    #[allow(dead_code)]
+    pub fn new(boards: u8, index: u8) -> Result<Self, SyncError> {
+        if boards == 0 {
+            return Err(SyncError::NoBoards);
+        }
+        if index >= boards {
+            return Err(SyncError::InvalidBoard);
+        }
+        Ok(Self { boards, index })
+    }
+
+    /// Returns number of LEDs across all boards.
+    ///
+    /// # Returns
+    /// * `usize` - Global LED count
+    #[allow(dead_code)]
+    pub fn global_len(&self) -> usize {
+        self.boards as usize * LED_COUNT
+    }
+
+    /// Returns global index of a local LED.
+    ///
+    /// # Arguments
+    /// * `led` - Local LED index
+    ///
+    /// # Returns
+    /// * `usize` - Global LED index
+    #[allow(dead_code)]
+    pub fn global_index(&self, led: usize) -> usize {
+        self.index as usize * LED_COUNT + led
+    }
+
+    /// Returns local frame for a global step.
+    ///
+    /// # Arguments
+    /// * `step` - Global step
+    ///
+    /// # Returns
+    /// * `Frame` - Frame with the lit LED, blank if it is on another board
+    #[allow(dead_code)]
+    pub fn frame(&self, step: u32) -> Frame {
+        let lit = step as usize % self.global_len();
+        (0..LED_COUNT)
+            .find(|&led| self.global_index(led) == lit)
+            .map_or(Frame::new(), |led| Frame::single(led, MAX_BRIGHTNESS))
+    }
+}
+
+/// Clamps a step delay to the supported range.
+///
+/// # Arguments
+/// * `step_ms` - Requested delay in milliseconds
+///
+/// # Returns
+/// * `u16` - Delay of at least MIN_SEQUENCE_DELAY_MS
+fn clamp_step_ms(step_ms: u16) -> u16 {
+    step_ms.max(MIN_SEQUENCE_DELAY_MS as u16)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BEACON: Beacon = Beacon {
+        step: 0x0102_0304,
+        timestamp_ms: 0xA0B0_C0D0,
+        step_ms: 250,
+    };
+
+    /// Virtual board with a drifting local clock.
+    struct Board {
+        follower: SyncFollower,
+        chase: VirtualChase,
+        drift_ppm: i64,
+    }
+
+    impl Board {
+        fn new(index: u8, boards: u8, drift_ppm: i64) -> Self {
+            Self {
+                follower: SyncFollower::new(0),
+                chase: VirtualChase::new(boards, index).unwrap(),
+                drift_ppm,
+            }
+        }
+
+        fn tick(&mut self, elapsed_ms: u32) {
+            let elapsed_us = elapsed_ms as i64 * 1000 + elapsed_ms as i64 * self.drift_ppm / 1000;
+            self.follower.tick_us(elapsed_us as u32);
+        }
+    }
+
+    fn simulate(leader: &mut SyncLeader, boards: &mut [Board], ticks: u32) {
+        for _ in 0..ticks {
+            let beacon = leader.tick(10);
+            for board in boards.iter_mut() {
+                board.tick(10);
+                if let Some(beacon) = beacon {
+                    let mut parser = BeaconParser::new();
+                    let received = beacon.encode().iter().find_map(|&byte| parser.push(byte));
+                    board.follower.on_beacon(&received.unwrap());
+                }
+            }
+        }
+    }
+
+    // ==================== Beacon Tests ====================
+
+    #[test]
+    fn test_beacon_round_trip() {
+        assert_eq!(Beacon::decode(&BEACON.encode()), Some(BEACON));
+    }
+
+    #[test]
+    fn test_beacon_starts_with_magic() {
+        assert_eq!(BEACON.encode()[0], BEACON_MAGIC);
+    }
+
+    #[test]
+    fn test_beacon_little_endian() {
+        let bytes = BEACON.encode();
+        assert_eq!(&bytes[1..5], &[0x04, 0x03, 0x02, 0x01]);
+        assert_eq!(&bytes[9..11], &[250, 0]);
+    }
+
+    #[test]
+    fn test_decode_bad_checksum() {
+        let mut bytes = BEACON.encode();
+        bytes[3] ^= 0x10;
+        assert_eq!(Beacon::decode(&bytes), None);
+    }
+
+    #[test]
+    fn test_decode_bad_magic() {
+        let mut bytes = BEACON.encode();
+        bytes[0] = 0;
+        assert_eq!(Beacon::decode(&bytes), None);
+    }
+
+    // ==================== BeaconParser Tests ====================
+
+    #[test]
+    fn test_parser_reads_beacon() {
+        let mut parser = BeaconParser::new();
+        let bytes = BEACON.encode();
+        for &byte in &bytes[..BEACON_LEN - 1] {
+            assert_eq!(parser.push(byte), None);
+        }
+        assert_eq!(parser.push(bytes[BEACON_LEN - 1]), Some(BEACON));
+    }
+
+    #[test]
+    fn test_parser_skips_noise() {
+        let mut parser = BeaconParser::new();
+        for byte in [0x00, 0xFF, 0x13] {
+            assert_eq!(parser.push(byte), None);
+        }
+        let received = BEACON.encode().iter().find_map(|&byte| parser.push(byte));
+        assert_eq!(received, Some(BEACON));
+    }
+
+    #[test]
+    fn test_parser_back_to_back() {
+        let mut parser = BeaconParser::new();
+        let count = BEACON
+            .encode()
+            .iter()
+            .chain(BEACON.encode().iter())
+            .filter_map(|&byte| parser.push(byte))
+            .count();
+        assert_eq!(count, 2);
+    }
+
+    #[test]
+    fn test_parser_recovers_after_corruption() {
+        let mut parser = BeaconParser::new();
+        let mut corrupt = BEACON.encode();
+        corrupt[6] ^= 0x01;
+        let received: Vec<Beacon> = corrupt
+            .iter()
+            .chain(BEACON.encode().iter())
+            .filter_map(|&byte| parser.push(byte))
+            .collect();
+        assert_eq!(received, [BEACON]);
+        assert_eq!(parser.rejected(), 1);
+    }
+
+    #[test]
+    fn test_parser_resyncs_on_truncated_beacon() {
+        let mut parser = BeaconParser::new();
+        let bytes = BEACON.encode();
+        let received: Vec<Beacon> = bytes[..5]
+            .iter()
+            .chain(bytes.iter())
+            .filter_map(|&byte| parser.push(byte))
+            .collect();
+        assert_eq!(received, [BEACON]);
+    }
+
+    // ==================== SyncLeader Tests ====================
+
+    #[test]
+    fn test_leader_zero_interval() {
+        assert_eq!(SyncLeader::new(100, 0), Err(SyncError::ZeroInterval));
+    }
+
+    #[test]
+    fn test_leader_first_tick_sends_beacon() {
+        let mut leader = SyncLeader::new(100, 500).unwrap();
+        assert!(leader.tick(10).is_some());
+        assert!(leader.tick(10).is_none());
+    }
+
+    #[test]
+    fn test_leader_beacon_interval() {
+        let mut leader = SyncLeader::new(100, 500).unwrap();
+        leader.tick(10);
+        let sent = (0..100).filter_map(|_| leader.tick(10)).count();
+        assert_eq!(sent, 2);
+    }
+
+    #[test]
+    fn test_leader_beacon_contents() {
+        let mut leader = SyncLeader::new(100, 50).unwrap();
+        let beacon = (0..31).filter_map(|_| leader.tick(10)).last().unwrap();
+        assert_eq!(beacon.timestamp_ms, 310);
+        assert_eq!(beacon.step, 3);
+        assert_eq!(beacon.step_ms, 100);
+    }
+
+    #[test]
+    fn test_leader_steps_with_time() {
+        let mut leader = SyncLeader::new(100, 1000).unwrap();
+        leader.tick(99);
+        assert_eq!(leader.step(), 0);
+        leader.tick(1);
+        assert_eq!(leader.step(), 1);
+    }
+
+    #[test]
+    fn test_leader_tempo_change_keeps_step() {
+        let mut leader = SyncLeader::new(100, 1000).unwrap();
+        leader.tick(250);
+        leader.set_step_ms(50);
+        assert_eq!(leader.step(), 2);
+        leader.tick(50);
+        assert_eq!(leader.step(), 3);
+    }
+
+    #[test]
+    fn test_leader_clamps_step_ms() {
+        let mut leader = SyncLeader::new(0, 10).unwrap();
+        assert_eq!(
+            leader.tick(10).unwrap().step_ms,
+            MIN_SEQUENCE_DELAY_MS as u16
+        );
+    }
+
+    // ==================== SyncFollower Tests ====================
+
+    #[test]
+    fn test_follower_starts_unsynced() {
+        let follower = SyncFollower::new(0);
+        assert!(!follower.is_synced());
+        assert!(!follower.is_locked());
+    }
+
+    #[test]
+    fn test_follower_snaps_to_first_beacon() {
+        let mut follower = SyncFollower::new(0);
+        follower.tick_us(12_345_000);
+        follower.on_beacon(&Beacon {
+            step: 7,
+            timestamp_ms: 700,
+            step_ms: 100,
+        });
+        assert!(follower.is_synced());
+        assert_eq!(follower.step(), 7);
+        follower.tick_us(100_000);
+        assert_eq!(follower.step(), 8);
+    }
+
+    #[test]
+    fn test_follower_adds_latency() {
+        let mut follower = SyncFollower::new(5);
+        follower.on_beacon(&Beacon {
+            step: 0,
+            timestamp_ms: 0,
+            step_ms: 100,
+        });
+        follower.tick_us(95_000);
+        assert_eq!(follower.step(), 1);
+    }
+
+    #[test]
+    fn test_follower_slews_small_error() {
+        let mut follower = SyncFollower::new(0);
+        let mut beacon = Beacon {
+            step: 0,
+            timestamp_ms: 0,
+            step_ms: 100,
+        };
+        follower.on_beacon(&beacon);
+        follower.tick_us(100_000);
+        beacon.timestamp_ms = 108;
+        follower.on_beacon(&beacon);
+        assert_eq!(follower.error_us(), 8000);
+        assert_eq!(follower.time_ms(), 102);
+        assert!(follower.rate_ppm() > 0);
+    }
+
+    #[test]
+    fn test_follower_snaps_large_error() {
+        let mut follower = SyncFollower::new(0);
+        let mut beacon = Beacon {
+            step: 0,
+            timestamp_ms: 0,
+            step_ms: 100,
+        };
+        follower.on_beacon(&beacon);
+        follower.tick_us(100_000);
+        beacon.timestamp_ms = 5000;
+        follower.on_beacon(&beacon);
+        assert_eq!(follower.time_ms(), 5000);
+        assert_eq!(follower.rate_ppm(), 0);
+    }
+
+    #[test]
+    fn test_follower_handles_timestamp_wrap() {
+        let mut follower = SyncFollower::new(0);
+        let mut beacon = Beacon {
+            step: 0,
+            timestamp_ms: u32::MAX - 49,
+            step_ms: 100,
+        };
+        follower.on_beacon(&beacon);
+        follower.tick_us(100_000);
+        beacon.timestamp_ms = 50;
+        follower.on_beacon(&beacon);
+        assert_eq!(follower.error_us(), 0);
+        assert_eq!(follower.step(), 0);
+    }
+
+    // ==================== VirtualChase Tests ====================
+
+    #[test]
+    fn test_chase_no_boards() {
+        assert_eq!(VirtualChase::new(0, 0), Err(SyncError::NoBoards));
+    }
+
+    #[test]
+    fn test_chase_invalid_board() {
+        assert_eq!(VirtualChase::new(3, 3), Err(SyncError::InvalidBoard));
+    }
+
+    #[test]
+    fn test_chase_global_index() {
+        let chase = VirtualChase::new(3, 2).unwrap();
+        assert_eq!(chase.global_len(), 3 * LED_COUNT);
+        assert_eq!(chase.global_index(1), 2 * LED_COUNT + 1);
+    }
+
+    #[test]
+    fn test_chase_lights_own_led() {
+        let chase = VirtualChase::new(3, 1).unwrap();
+        let step = (LED_COUNT + 2) as u32;
+        assert_eq!(chase.frame(step), Frame::single(2, MAX_BRIGHTNESS));
+    }
+
+    #[test]
+    fn test_chase_blank_on_other_board() {
+        let chase = VirtualChase::new(3, 1).unwrap();
+        assert_eq!(chase.frame(0), Frame::new());
+    }
+
+    #[test]
+    fn test_chase_wraps_global_length() {
+        let chase = VirtualChase::new(2, 0).unwrap();
+        assert_eq!(
+            chase.frame(2 * LED_COUNT as u32),
+            Frame::single(0, MAX_BRIGHTNESS)
+        );
+    }
+
+    #[test]
+    fn test_chase_single_board_is_local_chase() {
+        let chase = VirtualChase::new(1, 0).unwrap();
+        for step in 0..LED_COUNT as u32 {
+            assert_eq!(
+                chase.frame(step),
+                Frame::single(step as usize, MAX_BRIGHTNESS)
+            );
+        }
+    }
+
+    // ==================== Simulation Tests ====================
+
+    #[test]
+    fn test_sim_exactly_one_led_lit() {
+        let mut leader = SyncLeader::new(100, 200).unwrap();
+        let mut boards: Vec<Board> = (0..4).map(|index| Board::new(index, 4, 0)).collect();
+        for _ in 0..200 {
+            simulate(&mut leader, &mut boards, 5);
+            let lit: usize = boards
+                .iter()
+                .map(|board| board.chase.frame(board.follower.step()).lit_count())
+                .sum();
+            assert_eq!(lit, 1);
+        }
+    }
+
+    #[test]
+    fn test_sim_drifting_boards_lock() {
+        let mut leader = SyncLeader::new(100, 200).unwrap();
+        let mut boards = [
+            Board::new(0, 3, 800),
+            Board::new(1, 3, -1200),
+            Board::new(2, 3, 300),
+        ];
+        simulate(&mut leader, &mut boards, 6000);
+        for board in &boards {
+            assert!(board.follower.is_locked());
+            assert!((board.follower.rate_ppm() + board.drift_ppm).abs() < 100);
+        }
+    }
+
+    #[test]
+    fn test_sim_drifting_boards_follow_leader_step() {
+        let mut leader = SyncLeader::new(100, 200).unwrap();
+        let mut boards = [Board::new(0, 2, 1500), Board::new(1, 2, -1500)];
+        simulate(&mut leader, &mut boards, 3000);
+        for _ in 0..100 {
+            simulate(&mut leader, &mut boards, 10);
+            simulate(&mut leader, &mut boards, 5);
+            for board in &boards {
+                assert_eq!(board.follower.step(), leader.step());
+            }
+            simulate(&mut leader, &mut boards, 5);
+        }
+    }
+
+    #[test]
+    fn test_sim_follower_tracks_tempo_change() {
+        let mut leader = SyncLeader::new(100, 200).unwrap();
+        let mut boards = [Board::new(0, 1, 500)];
+        simulate(&mut leader, &mut boards, 1000);
+        leader.set_step_ms(40);
+        simulate(&mut leader, &mut boards, 1000);
+        simulate(&mut leader, &mut boards, 2);
+        assert_eq!(boards[0].follower.step(), leader.step());
+    }
+}
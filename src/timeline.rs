@@ -0,0 +1,713 @@
+/*
+ * @file timeline.rs
+ * @brief Keyframe timeline with easing
+ * @author Kevin Thomas
+ * @date 2025
+ *
+ * MIT License
+ *
+ * Copyright (c) 2025 Kevin Thomas
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+//! FILE: timeline.rs
+//!
+//! DESCRIPTION:
+//! Keyframe Timeline with Easing Curves for RP2350.
+//!
+//! BRIEF:
+//! Interpolates authored per-LED keyframes into frames.
+//! Sampled at the output refresh rate with loop, ping-pong and seek.
+//!
+//! AUTHOR: Kevin Thomas
+//! CREATION DATE: October 18, 2026
+//! UPDATE DATE: October 18, 2026
+
+use crate::config::LED_COUNT;
+use crate::frame::Frame;
+
+/// Fixed-point scale for easing progress.
+///
+/// # Details
+/// Progress values run from 0 (segment start) to this value (segment end).
+///
+/// # Value
+/// 1024
+#[allow(dead_code)]
+pub const EASE_SCALE: u32 = 1024;
+
+/// Easing curve enumeration.
+///
+/// # Details
+/// Shapes the interpolation between a keyframe and the next
+/// keyframe for the same LED.
+///
+/// # Variants
+/// * `Linear` - Constant rate of change
+/// * `EaseIn` - Quadratic, slow start
+/// * `EaseOut` - Quadratic, slow finish
+/// * `EaseInOut` - Quadratic, slow start and finish
+/// * `Cubic` - Cubic, slow start and finish
+/// * `Step` - Hold the start level until the next keyframe
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[allow(dead_code)]
+pub enum Easing {
+    Linear,
+    EaseIn,
+    EaseOut,
+    EaseInOut,
+    Cubic,
+    Step,
+}
+
+/// Timeline playback mode enumeration.
+///
+/// # Variants
+/// * `Once` - Play to the end and hold the last frame
+/// * `Loop` - Restart from the beginning after the end
+/// * `PingPong` - Alternate forward and backward playback
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[allow(dead_code)]
+pub enum Playback {
+    Once,
+    Loop,
+    PingPong,
+}
+
+/// Timeline error enumeration.
+///
+/// # Variants
+/// * `ZeroDuration` - Timeline duration is zero
+/// * `Unsorted` - Keyframe times are not in ascending order
+/// * `InvalidLed` - Keyframe LED index is out of range
+/// * `PastEnd` - Keyframe time is after the timeline duration
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[allow(dead_code)]
+pub enum TimelineError {
+    ZeroDuration,
+    Unsorted,
+    InvalidLed,
+    PastEnd,
+}
+
+/// Single authored keyframe.
+///
+/// # Fields
+/// * `time_ms` - Time of the keyframe from the timeline start
+/// * `led` - LED index the keyframe applies to
+/// * `level` - Brightness level at the keyframe
+/// * `easing` - Curve used toward the next keyframe for the LED
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[allow(dead_code)]
+pub struct Keyframe {
+    pub time_ms: u32,
+    pub led: usize,
+    pub level: u8,
+    pub easing: Easing,
+}
+
+impl Keyframe {
+    /// Creates new keyframe with linear easing.
+    ///
+    /// # Arguments
+    /// * `time_ms` - Time of the keyframe from the timeline start
+    /// * `led` - LED index the keyframe applies to
+    /// * `level` - Brightness level at the keyframe
+    ///
+    /// # Returns
+    /// * `Self` - New Keyframe instance
+    #[allow(dead_code)]
+    pub const fn new(time_ms: u32, led: usize, level: u8) -> Self {
+        Self {
+            time_ms,
+            led,
+            level,
+            easing: Easing::Linear,
+        }
+    }
+
+    /// Returns keyframe with given easing.
+    ///
+    /// # Arguments
+    /// * `easing` - Curve used toward the next keyframe
+    ///
+    /// # Returns
+    /// * `Self` - Updated keyframe
+    #[allow(dead_code)]
+    pub const fn with_easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+}
+
+/// Applies an easing curve to linear progress.
+///
+/// # Arguments
+/// * `easing` - Curve to apply
+/// * `progress` - Linear progress (0 to EASE_SCALE)
+///
+/// # Returns
+/// * `u32` - Eased progress (0 to EASE_SCALE)
+#[allow(dead_code)]
+pub fn ease(easing: Easing, progress: u32) -> u32 {
+    let s = EASE_SCALE as u64;
+    let p = progress.min(EASE_SCALE) as u64;
+    let q = s - p;
+    let eased = match easing {
+        Easing::Linear => p,
+        Easing::EaseIn => p * p / s,
+        Easing::EaseOut => s - q * q / s,
+        Easing::EaseInOut if p < s / 2 => 2 * p * p / s,
+        Easing::EaseInOut => s - 2 * q * q / s,
+        Easing::Cubic if p < s / 2 => 4 * p * p * p / (s * s),
+        Easing::Cubic => s - 4 * q * q * q / (s * s),
+        Easing::Step if p < s => 0,
+        Easing::Step => s,
+    };
+    eased as u32
+}
+
+/// Interpolates between two levels.
+///
+/// # Arguments
+/// * `from` - Level at progress 0
+/// * `to` - Level at progress EASE_SCALE
+/// * `progress` - Eased progress (0 to EASE_SCALE)
+///
+/// # Returns
+/// * `u8` - Interpolated level
+fn lerp(from: u8, to: u8, progress: u32) -> u8 {
+    let delta = to as i32 - from as i32;
+    (from as i32 + delta * progress as i32 / EASE_SCALE as i32) as u8
+}
+
+/// Keyframe timeline player.
+///
+/// # Details
+/// Borrows a keyframe table sorted by time and tracks a playback
+/// position. Before its first keyframe an LED holds that keyframe's
+/// level; after its last keyframe it holds the last level. LEDs with
+/// no keyframes stay off.
+///
+/// # Fields
+/// * `keyframes` - Keyframes sorted by time
+/// * `duration_ms` - Timeline length in milliseconds
+/// * `playback` - Behaviour at the end of the timeline
+/// * `position_ms` - Current playback position
+/// * `forward` - Direction of travel for ping-pong playback
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[allow(dead_code)]
+pub struct Timeline<'a> {
+    keyframes: &'a [Keyframe],
+    duration_ms: u32,
+    playback: Playback,
+    position_ms: u32,
+    forward: bool,
+}
+
+impl<'a> Timeline<'a> {
+    /// Creates new timeline after validating keyframes.
+    ///
+    /// # Arguments
+    /// * `keyframes` - Keyframes sorted by time
+    /// * `duration_ms` - Timeline length in milliseconds
+    ///
+    /// # Returns
+    /// * `Result<Self, TimelineError>` - Timeline playing once from 0
+    #[allow(dead_code)]
+    pub fn new(keyframes: &'a [Keyframe], duration_ms: u32) -> Result<Self, TimelineError> {
+        if duration_ms == 0 {
+            return Err(TimelineError::ZeroDuration);
+        }
+        if keyframes
+            .windows(2)
+            .any(|pair| pair[1].time_ms < pair[0].time_ms)
+        {
+            return Err(TimelineError::Unsorted);
+        }
+        if keyframes.iter().any(|key| key.led >= LED_COUNT) {
+            return Err(TimelineError::InvalidLed);
+        }
+        if keyframes.iter().any(|key| key.time_ms > duration_ms) {
+            return Err(TimelineError::PastEnd);
+        }
+        Ok(Self {
+            keyframes,
+            duration_ms,
+            playback: Playback::Once,
+            position_ms: 0,
+            forward: true,
+        })
+    }
+
+    /// Returns timeline with given playback mode.
+    ///
+    /// # Arguments
+    /// * `playback` - Behaviour at the end of the timeline
+    ///
+    /// # Returns
+    /// * `Self` - Updated timeline
+    #[allow(dead_code)]
+    pub fn with_playback(mut self, playback: Playback) -> Self {
+        self.playback = playback;
+        self
+    }
+
+    /// Sets playback mode.
+    ///
+    /// # Details
+    /// Keeps the position. Ping-pong playback continues forward.
+    ///
+    /// # Arguments
+    /// * `playback` - Behaviour at the end of the timeline
+    #[allow(dead_code)]
+    pub fn set_playback(&mut self, playback: Playback) {
+        self.playback = playback;
+        self.forward = true;
+    }
+
+    /// Returns playback mode.
+    ///
+    /// # Returns
+    /// * `Playback` - Behaviour at the end of the timeline
+    #[allow(dead_code)]
+    pub fn playback(&self) -> Playback {
+        self.playback
+    }
+
+    /// Returns timeline length.
+    ///
+    /// # Returns
+    /// * `u32` - Duration in milliseconds
+    #[allow(dead_code)]
+    pub fn duration_ms(&self) -> u32 {
+        self.duration_ms
+    }
+
+    /// Returns current playback position.
+    ///
+    /// # Returns
+    /// * `u32` - Position in milliseconds
+    #[allow(dead_code)]
+    pub fn position_ms(&self) -> u32 {
+        self.position_ms
+    }
+
+    /// Returns whether one-shot playback reached the end.
+    ///
+    /// # Returns
+    /// * `bool` - true if finished, always false when looping
+    #[allow(dead_code)]
+    pub fn is_finished(&self) -> bool {
+        self.playback == Playback::Once && self.position_ms == self.duration_ms
+    }
+
+    /// Moves playback to a position.
+    ///
+    /// # Details
+    /// Clamps to the duration and resumes forward travel.
+    ///
+    /// # Arguments
+    /// * `position_ms` - New position in milliseconds
+    #[allow(dead_code)]
+    pub fn seek(&mut self, position_ms: u32) {
+        self.position_ms = position_ms.min(self.duration_ms);
+        self.forward = true;
+    }
+
+    /// Advances playback and returns the frame at the new position.
+    ///
+    /// # Arguments
+    /// * `elapsed_ms` - Time since the previous tick
+    ///
+    /// # Returns
+    /// * `Frame` - Interpolated frame
+    #[allow(dead_code)]
+    pub fn tick(&mut self, elapsed_ms: u32) -> Frame {
+        match self.playback {
+            Playback::Once => {
+                self.position_ms = self
+                    .position_ms
+                    .saturating_add(elapsed_ms)
+                    .min(self.duration_ms);
+            }
+            Playback::Loop => {
+                let total = self.position_ms as u64 + elapsed_ms as u64;
+                self.position_ms = (total % self.duration_ms as u64) as u32;
+            }
+            Playback::PingPong => self.bounce(elapsed_ms),
+        }
+        self.sample(self.position_ms)
+    }
+
+    /// Advances ping-pong playback.
+    ///
+    /// # Details
+    /// Reflects the position at both ends of the timeline.
+    ///
+    /// # Arguments
+    /// * `elapsed_ms` - Time since the previous tick
+    fn bounce(&mut self, elapsed_ms: u32) {
+        let period = 2 * self.duration_ms as u64;
+        let unfolded = if self.forward {
+            self.position_ms as u64
+        } else {
+            period - self.position_ms as u64
+        };
+        let phase = (unfolded + elapsed_ms as u64) % period;
+        self.forward = phase < self.duration_ms as u64;
+        self.position_ms = if self.forward {
+            phase as u32
+        } else {
+            (period - phase) as u32
+        };
+    }
+
+    /// Returns frame at a position without moving playback.
+    ///
+    /// # Arguments
+    /// * `time_ms` - Position to sample in milliseconds
+    ///
+    /// # Returns
+    /// * `Frame` - Interpolated frame
+    #[allow(dead_code)]
+    pub fn sample(&self, time_ms: u32) -> Frame {
+        let mut frame = Frame::new();
+        for led in 0..LED_COUNT {
+            frame.set_level(led, self.sample_led(led, time_ms));
+        }
+        frame
+    }
+
+    /// Returns level of one LED at a position.
+    ///
+    /// # Arguments
+    /// * `led` - LED index
+    /// * `time_ms` - Position to sample in milliseconds
+    ///
+    /// # Returns
+    /// * `u8` - Interpolated level
+    fn sample_led(&self, led: usize, time_ms: u32) -> u8 {
+        let mut keys = self.keyframes.iter().filter(|key| key.led == led);
+        let Some(mut prev) = keys.next() else {
+            return 0;
+        };
+        if time_ms < prev.time_ms {
+            return prev.level;
+        }
+        for next in keys {
+            if time_ms < next.time_ms {
+                let span = next.time_ms - prev.time_ms;
+                let progress = (time_ms - prev.time_ms) as u64 * EASE_SCALE as u64 / span as u64;
+                return lerp(prev.level, next.level, ease(prev.easing, progress as u32));
+            }
+            prev = next;
+        }
+        prev.level
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RAMP: [Keyframe; 2] = [Keyframe::new(0, 0, 0), Keyframe::new(1000, 0, 200)];
+
+    // ==================== ease() Tests ====================
+
+    #[test]
+    fn test_ease_endpoints() {
+        for easing in [
+            Easing::Linear,
+            Easing::EaseIn,
+            Easing::EaseOut,
+            Easing::EaseInOut,
+            Easing::Cubic,
+            Easing::Step,
+        ] {
+            assert_eq!(ease(easing, 0), 0, "{:?}", easing);
+            assert_eq!(ease(easing, EASE_SCALE), EASE_SCALE, "{:?}", easing);
+        }
+    }
+
+    #[test]
+    fn test_ease_linear_midpoint() {
+        assert_eq!(ease(Easing::Linear, 512), 512);
+    }
+
+    #[test]
+    fn test_ease_in_below_linear() {
+        assert_eq!(ease(Easing::EaseIn, 512), 256);
+    }
+
+    #[test]
+    fn test_ease_out_above_linear() {
+        assert_eq!(ease(Easing::EaseOut, 512), 768);
+    }
+
+    #[test]
+    fn test_ease_in_out_symmetric() {
+        assert_eq!(ease(Easing::EaseInOut, 512), 512);
+        assert!(ease(Easing::EaseInOut, 256) < 256);
+        assert!(ease(Easing::EaseInOut, 768) > 768);
+    }
+
+    #[test]
+    fn test_cubic_flatter_than_quadratic() {
+        assert!(ease(Easing::Cubic, 256) < ease(Easing::EaseInOut, 256));
+    }
+
+    #[test]
+    fn test_step_holds() {
+        assert_eq!(ease(Easing::Step, EASE_SCALE - 1), 0);
+    }
+
+    #[test]
+    fn test_ease_clamps_progress() {
+        assert_eq!(ease(Easing::Linear, EASE_SCALE * 2), EASE_SCALE);
+    }
+
+    #[test]
+    fn test_ease_monotonic() {
+        for easing in [
+            Easing::EaseIn,
+            Easing::EaseOut,
+            Easing::EaseInOut,
+            Easing::Cubic,
+        ] {
+            let mut last = 0;
+            for p in 0..=EASE_SCALE {
+                let value = ease(easing, p);
+                assert!(value >= last, "{:?} at {}", easing, p);
+                last = value;
+            }
+        }
+    }
+
+    // ==================== Validation Tests ====================
+
+    #[test]
+    fn test_new_valid() {
+        assert!(Timeline::new(&RAMP, 1000).is_ok());
+    }
+
+    #[test]
+    fn test_new_zero_duration() {
+        assert_eq!(Timeline::new(&RAMP, 0), Err(TimelineError::ZeroDuration));
+    }
+
+    #[test]
+    fn test_new_unsorted() {
+        let keys = [Keyframe::new(500, 0, 0), Keyframe::new(100, 1, 0)];
+        assert_eq!(Timeline::new(&keys, 1000), Err(TimelineError::Unsorted));
+    }
+
+    #[test]
+    fn test_new_invalid_led() {
+        let keys = [Keyframe::new(0, LED_COUNT, 0)];
+        assert_eq!(Timeline::new(&keys, 1000), Err(TimelineError::InvalidLed));
+    }
+
+    #[test]
+    fn test_new_past_end() {
+        assert_eq!(Timeline::new(&RAMP, 999), Err(TimelineError::PastEnd));
+    }
+
+    // ==================== sample() Tests ====================
+
+    #[test]
+    fn test_sample_linear() {
+        let timeline = Timeline::new(&RAMP, 1000).unwrap();
+        assert_eq!(timeline.sample(0).level(0), 0);
+        assert_eq!(timeline.sample(500).level(0), 100);
+        assert_eq!(timeline.sample(1000).level(0), 200);
+    }
+
+    #[test]
+    fn test_sample_descending() {
+        let keys = [Keyframe::new(0, 1, 200), Keyframe::new(100, 1, 0)];
+        let timeline = Timeline::new(&keys, 100).unwrap();
+        assert_eq!(timeline.sample(50).level(1), 100);
+    }
+
+    #[test]
+    fn test_sample_uses_start_easing() {
+        let keys = [
+            Keyframe::new(0, 0, 0).with_easing(Easing::Step),
+            Keyframe::new(100, 0, 255),
+        ];
+        let timeline = Timeline::new(&keys, 100).unwrap();
+        assert_eq!(timeline.sample(99).level(0), 0);
+        assert_eq!(timeline.sample(100).level(0), 255);
+    }
+
+    #[test]
+    fn test_sample_holds_before_first() {
+        let keys = [Keyframe::new(200, 2, 80), Keyframe::new(400, 2, 0)];
+        let timeline = Timeline::new(&keys, 500).unwrap();
+        assert_eq!(timeline.sample(0).level(2), 80);
+    }
+
+    #[test]
+    fn test_sample_holds_after_last() {
+        let keys = [Keyframe::new(0, 2, 0), Keyframe::new(200, 2, 90)];
+        let timeline = Timeline::new(&keys, 500).unwrap();
+        assert_eq!(timeline.sample(400).level(2), 90);
+    }
+
+    #[test]
+    fn test_sample_unkeyed_led_off() {
+        let timeline = Timeline::new(&RAMP, 1000).unwrap();
+        assert_eq!(timeline.sample(500).level(3), 0);
+    }
+
+    #[test]
+    fn test_sample_interleaved_leds() {
+        let keys = [
+            Keyframe::new(0, 0, 0),
+            Keyframe::new(0, 1, 100),
+            Keyframe::new(100, 0, 100),
+            Keyframe::new(100, 1, 0),
+        ];
+        let timeline = Timeline::new(&keys, 100).unwrap();
+        let frame = timeline.sample(50);
+        assert_eq!(frame.level(0), 50);
+        assert_eq!(frame.level(1), 50);
+    }
+
+    #[test]
+    fn test_sample_empty_timeline_blank() {
+        let timeline = Timeline::new(&[], 100).unwrap();
+        assert_eq!(timeline.sample(50), Frame::new());
+    }
+
+    // ==================== Playback Tests ====================
+
+    #[test]
+    fn test_once_stops_at_end() {
+        let mut timeline = Timeline::new(&RAMP, 1000).unwrap();
+        timeline.tick(600);
+        assert!(!timeline.is_finished());
+        let frame = timeline.tick(600);
+        assert!(timeline.is_finished());
+        assert_eq!(timeline.position_ms(), 1000);
+        assert_eq!(frame.level(0), 200);
+    }
+
+    #[test]
+    fn test_loop_wraps() {
+        let mut timeline = Timeline::new(&RAMP, 1000)
+            .unwrap()
+            .with_playback(Playback::Loop);
+        timeline.tick(1250);
+        assert_eq!(timeline.position_ms(), 250);
+        assert!(!timeline.is_finished());
+    }
+
+    #[test]
+    fn test_ping_pong_reverses() {
+        let mut timeline = Timeline::new(&RAMP, 1000)
+            .unwrap()
+            .with_playback(Playback::PingPong);
+        timeline.tick(800);
+        assert_eq!(timeline.position_ms(), 800);
+        timeline.tick(400);
+        assert_eq!(timeline.position_ms(), 800);
+        timeline.tick(300);
+        assert_eq!(timeline.position_ms(), 500);
+    }
+
+    #[test]
+    fn test_ping_pong_turns_at_start() {
+        let mut timeline = Timeline::new(&RAMP, 1000)
+            .unwrap()
+            .with_playback(Playback::PingPong);
+        timeline.tick(1900);
+        assert_eq!(timeline.position_ms(), 100);
+        timeline.tick(200);
+        assert_eq!(timeline.position_ms(), 100);
+        timeline.tick(100);
+        assert_eq!(timeline.position_ms(), 200);
+    }
+
+    #[test]
+    fn test_set_playback_keeps_position() {
+        let mut timeline = Timeline::new(&RAMP, 1000).unwrap();
+        timeline.tick(1000);
+        assert!(timeline.is_finished());
+        timeline.set_playback(Playback::Loop);
+        assert_eq!(timeline.position_ms(), 1000);
+        timeline.tick(250);
+        assert_eq!(timeline.position_ms(), 250);
+    }
+
+    #[test]
+    fn test_refresh_rate_sampling() {
+        let mut timeline = Timeline::new(&RAMP, 1000).unwrap();
+        let mut last = 0;
+        for _ in 0..100 {
+            let level = timeline.tick(10).level(0);
+            assert!(level >= last);
+            last = level;
+        }
+        assert_eq!(last, 200);
+    }
+
+    // ==================== seek() Tests ====================
+
+    #[test]
+    fn test_seek() {
+        let mut timeline = Timeline::new(&RAMP, 1000).unwrap();
+        timeline.seek(250);
+        assert_eq!(timeline.position_ms(), 250);
+        assert_eq!(timeline.tick(0).level(0), 50);
+    }
+
+    #[test]
+    fn test_seek_clamps() {
+        let mut timeline = Timeline::new(&RAMP, 1000).unwrap();
+        timeline.seek(5000);
+        assert_eq!(timeline.position_ms(), 1000);
+    }
+
+    #[test]
+    fn test_seek_restarts_finished() {
+        let mut timeline = Timeline::new(&RAMP, 1000).unwrap();
+        timeline.tick(2000);
+        timeline.seek(0);
+        assert!(!timeline.is_finished());
+    }
+
+    #[test]
+    fn test_seek_resets_ping_pong_direction() {
+        let mut timeline = Timeline::new(&RAMP, 1000)
+            .unwrap()
+            .with_playback(Playback::PingPong);
+        timeline.tick(1200);
+        timeline.seek(100);
+        timeline.tick(100);
+        assert_eq!(timeline.position_ms(), 200);
+    }
+}
@@ -0,0 +1,425 @@
+/*
+ * @file usage.rs
+ * @brief Per-LED on-time and usage statistics
+ * @author Kevin Thomas
+ * @date 2025
+ *
+ * MIT License
+ *
+ * Copyright (c) 2025 Kevin Thomas
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+//! FILE: usage.rs
+//!
+//! DESCRIPTION:
+//! Per-LED On-Time Accounting and Usage Statistics.
+//!
+//! BRIEF:
+//! Accumulates on-time, switch-on counts and brightness-weighted time per LED.
+//! Reports average duty and how evenly load spreads across the LEDs.
+//! Encodes the totals as a checksummed record for flash storage.
+//!
+//! AUTHOR: Kevin Thomas
+//! CREATION DATE: October 18, 2026
+//! UPDATE DATE: October 18, 2026
+
+use crate::config::{LED_COUNT, MAX_BRIGHTNESS};
+use crate::frame::Frame;
+
+/// First four bytes of every usage record.
+#[allow(dead_code)]
+pub const USAGE_MAGIC: u32 = 0x4547_5355;
+
+/// Encoded bytes per LED.
+///
+/// # Details
+/// On-time, brightness-weighted time and switch-on count.
+#[allow(dead_code)]
+pub const USAGE_LED_LEN: usize = 20;
+
+/// Encoded usage record length in bytes.
+///
+/// # Details
+/// Magic, total time, the per-LED fields and checksum.
+#[allow(dead_code)]
+pub const USAGE_RECORD_LEN: usize = 13 + USAGE_LED_LEN * LED_COUNT;
+
+/// Usage totals for one LED.
+///
+/// # Fields
+/// * `on_ms` - Time spent lit at any brightness
+/// * `level_ms` - Sum of brightness level times milliseconds shown
+/// * `switches` - Times the LED went from dark to lit
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct LedUsage {
+    pub on_ms: u64,
+    pub level_ms: u64,
+    pub switches: u32,
+}
+
+/// Usage statistics for every LED.
+///
+/// # Details
+/// Each frame is recorded for the time it was shown. The last
+/// frame is kept to count switch-ons and is not stored.
+///
+/// # Fields
+/// * `leds` - Totals per LED
+/// * `total_ms` - Time recorded
+/// * `last` - Frame recorded last
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct UsageStats {
+    leds: [LedUsage; LED_COUNT],
+    total_ms: u64,
+    last: Frame,
+}
+
+impl UsageStats {
+    /// Creates new empty statistics.
+    ///
+    /// # Returns
+    /// * `Self` - New UsageStats instance
+    #[allow(dead_code)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records a frame shown for a time.
+    ///
+    /// # Arguments
+    /// * `frame` - Frame that was shown
+    /// * `elapsed_ms` - Time it was shown
+    #[allow(dead_code)]
+    pub fn record(&mut self, frame: &Frame, elapsed_ms: u64) {
+        self.total_ms = self.total_ms.saturating_add(elapsed_ms);
+        for (index, led) in self.leds.iter_mut().enumerate() {
+            let level = frame.level(index);
+            if level == 0 {
+                continue;
+            }
+            if self.last.level(index) == 0 {
+                led.switches = led.switches.saturating_add(1);
+            }
+            led.on_ms = led.on_ms.saturating_add(elapsed_ms);
+            led.level_ms = led.level_ms.saturating_add(u64::from(level) * elapsed_ms);
+        }
+        self.last = *frame;
+    }
+
+    /// Clears every total.
+    #[allow(dead_code)]
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Returns time recorded.
+    ///
+    /// # Returns
+    /// * `u64` - Recorded time in milliseconds
+    #[allow(dead_code)]
+    pub fn total_ms(&self) -> u64 {
+        self.total_ms
+    }
+
+    /// Returns totals for one LED.
+    ///
+    /// # Arguments
+    /// * `index` - LED index
+    ///
+    /// # Returns
+    /// * `LedUsage` - LED totals, zero if out of range
+    #[allow(dead_code)]
+    pub fn led(&self, index: usize) -> LedUsage {
+        self.leds.get(index).copied().unwrap_or_default()
+    }
+
+    /// Returns average duty of one LED.
+    ///
+    /// # Details
+    /// Brightness-weighted time over recorded time, so an LED held
+    /// at half brightness all the time reads about 500.
+    ///
+    /// # Arguments
+    /// * `index` - LED index
+    ///
+    /// # Returns
+    /// * `u16` - Duty in tenths of a percent, 0 if nothing recorded
+    #[allow(dead_code)]
+    pub fn duty_permille(&self, index: usize) -> u16 {
+        let full = u128::from(self.total_ms) * u128::from(MAX_BRIGHTNESS);
+        if full == 0 {
+            return 0;
+        }
+        (u128::from(self.led(index).level_ms) * 1000 / full) as u16
+    }
+
+    /// Returns how evenly load spreads across the LEDs.
+    ///
+    /// # Details
+    /// The least used LED's weighted time over the most used one's.
+    ///
+    /// # Returns
+    /// * `u16` - Balance in tenths of a percent, 1000 if no LED was lit
+    #[allow(dead_code)]
+    pub fn balance_permille(&self) -> u16 {
+        let most = self.leds.iter().map(|led| led.level_ms).max().unwrap_or(0);
+        let least = self.leds.iter().map(|led| led.level_ms).min().unwrap_or(0);
+        if most == 0 {
+            return 1000;
+        }
+        (u128::from(least) * 1000 / u128::from(most)) as u16
+    }
+
+    /// Encodes the totals for flash storage.
+    ///
+    /// # Details
+    /// Fields are little-endian after the magic and are followed
+    /// by an XOR checksum of the fields.
+    ///
+    /// # Returns
+    /// * `[u8; USAGE_RECORD_LEN]` - Encoded record
+    #[allow(dead_code)]
+    pub fn encode(&self) -> [u8; USAGE_RECORD_LEN] {
+        let mut bytes = [0; USAGE_RECORD_LEN];
+        bytes[0..4].copy_from_slice(&USAGE_MAGIC.to_le_bytes());
+        bytes[4..12].copy_from_slice(&self.total_ms.to_le_bytes());
+        for (led, chunk) in self
+            .leds
+            .iter()
+            .zip(bytes[12..USAGE_RECORD_LEN - 1].chunks_exact_mut(USAGE_LED_LEN))
+        {
+            chunk[0..8].copy_from_slice(&led.on_ms.to_le_bytes());
+            chunk[8..16].copy_from_slice(&led.level_ms.to_le_bytes());
+            chunk[16..20].copy_from_slice(&led.switches.to_le_bytes());
+        }
+        bytes[USAGE_RECORD_LEN - 1] = checksum(&bytes[4..USAGE_RECORD_LEN - 1]);
+        bytes
+    }
+
+    /// Decodes totals read back from flash.
+    ///
+    /// # Arguments
+    /// * `bytes` - Encoded record
+    ///
+    /// # Returns
+    /// * `Option<Self>` - Totals, or None if the magic or checksum is wrong
+    #[allow(dead_code)]
+    pub fn decode(bytes: &[u8; USAGE_RECORD_LEN]) -> Option<Self> {
+        let magic = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+        if magic != USAGE_MAGIC
+            || bytes[USAGE_RECORD_LEN - 1] != checksum(&bytes[4..USAGE_RECORD_LEN - 1])
+        {
+            return None;
+        }
+        let mut stats = Self::new();
+        stats.total_ms = read_u64(&bytes[4..12]);
+        for (led, chunk) in stats
+            .leds
+            .iter_mut()
+            .zip(bytes[12..USAGE_RECORD_LEN - 1].chunks_exact(USAGE_LED_LEN))
+        {
+            led.on_ms = read_u64(&chunk[0..8]);
+            led.level_ms = read_u64(&chunk[8..16]);
+            led.switches = u32::from_le_bytes([chunk[16], chunk[17], chunk[18], chunk[19]]);
+        }
+        Some(stats)
+    }
+}
+
+/// Reads a little-endian u64.
+///
+/// # Arguments
+/// * `bytes` - Eight encoded bytes
+///
+/// # Returns
+/// * `u64` - Decoded value
+fn read_u64(bytes: &[u8]) -> u64 {
+    let mut word = [0; 8];
+    word.copy_from_slice(&bytes[..8]);
+    u64::from_le_bytes(word)
+}
+
+/// Returns XOR checksum of record fields.
+///
+/// # Arguments
+/// * `bytes` - Encoded fields
+///
+/// # Returns
+/// * `u8` - Checksum
+fn checksum(bytes: &[u8]) -> u8 {
+    bytes.iter().fold(0, |sum, byte| sum ^ byte)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // ==================== record() Tests ====================
+
+    #[test]
+    fn test_new_empty() {
+        let stats = UsageStats::new();
+        assert_eq!(stats.total_ms(), 0);
+        assert_eq!(stats.led(0), LedUsage::default());
+        assert_eq!(stats.duty_permille(0), 0);
+    }
+
+    #[test]
+    fn test_record_on_time() {
+        let mut stats = UsageStats::new();
+        stats.record(&Frame::single(1, MAX_BRIGHTNESS), 100);
+        assert_eq!(stats.total_ms(), 100);
+        assert_eq!(stats.led(1).on_ms, 100);
+        assert_eq!(stats.led(0).on_ms, 0);
+    }
+
+    #[test]
+    fn test_record_counts_switch_ons() {
+        let mut stats = UsageStats::new();
+        let lit = Frame::single(0, MAX_BRIGHTNESS);
+        stats.record(&lit, 10);
+        stats.record(&lit, 10);
+        stats.record(&Frame::new(), 10);
+        stats.record(&lit, 10);
+        assert_eq!(stats.led(0).switches, 2);
+    }
+
+    #[test]
+    fn test_dimming_is_not_a_switch() {
+        let mut stats = UsageStats::new();
+        stats.record(&Frame::single(0, MAX_BRIGHTNESS), 10);
+        stats.record(&Frame::single(0, 10), 10);
+        assert_eq!(stats.led(0).switches, 1);
+    }
+
+    #[test]
+    fn test_out_of_range_led_zero() {
+        assert_eq!(UsageStats::new().led(LED_COUNT), LedUsage::default());
+    }
+
+    #[test]
+    fn test_reset_clears() {
+        let mut stats = UsageStats::new();
+        stats.record(&Frame::filled(MAX_BRIGHTNESS), 10);
+        stats.reset();
+        assert_eq!(stats, UsageStats::new());
+    }
+
+    // ==================== Duty Tests ====================
+
+    #[test]
+    fn test_duty_full() {
+        let mut stats = UsageStats::new();
+        stats.record(&Frame::filled(MAX_BRIGHTNESS), 100);
+        assert_eq!(stats.duty_permille(0), 1000);
+    }
+
+    #[test]
+    fn test_duty_weights_brightness_and_time() {
+        let mut stats = UsageStats::new();
+        stats.record(&Frame::filled(MAX_BRIGHTNESS), 100);
+        stats.record(&Frame::new(), 300);
+        assert_eq!(stats.duty_permille(0), 250);
+    }
+
+    #[test]
+    fn test_duty_half_brightness() {
+        let mut stats = UsageStats::new();
+        stats.record(&Frame::filled(MAX_BRIGHTNESS / 2), 100);
+        assert_eq!(stats.duty_permille(0), 498);
+    }
+
+    // ==================== balance_permille() Tests ====================
+
+    #[test]
+    fn test_balance_nothing_lit() {
+        assert_eq!(UsageStats::new().balance_permille(), 1000);
+    }
+
+    #[test]
+    fn test_balance_even_chase() {
+        let mut stats = UsageStats::new();
+        for index in 0..LED_COUNT {
+            stats.record(&Frame::single(index, MAX_BRIGHTNESS), 100);
+        }
+        assert_eq!(stats.balance_permille(), 1000);
+    }
+
+    #[test]
+    fn test_balance_uneven() {
+        let mut stats = UsageStats::new();
+        stats.record(&Frame::filled(MAX_BRIGHTNESS), 100);
+        stats.record(&Frame::single(0, MAX_BRIGHTNESS), 100);
+        assert_eq!(stats.balance_permille(), 500);
+    }
+
+    // ==================== Encoding Tests ====================
+
+    fn sample() -> UsageStats {
+        let mut stats = UsageStats::new();
+        stats.record(&Frame::from_levels([255, 0, 128, 1]), 1234);
+        stats.record(&Frame::new(), 10);
+        stats.record(&Frame::single(1, 200), 99);
+        stats
+    }
+
+    #[test]
+    fn test_record_len() {
+        assert_eq!(USAGE_RECORD_LEN, 93);
+    }
+
+    #[test]
+    fn test_encode_starts_with_magic() {
+        assert_eq!(sample().encode()[0..4], USAGE_MAGIC.to_le_bytes());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let stats = sample();
+        let decoded = UsageStats::decode(&stats.encode()).unwrap();
+        assert_eq!(decoded.total_ms(), stats.total_ms());
+        for index in 0..LED_COUNT {
+            assert_eq!(decoded.led(index), stats.led(index));
+        }
+    }
+
+    #[test]
+    fn test_decode_forgets_last_frame() {
+        let decoded = UsageStats::decode(&sample().encode()).unwrap();
+        let mut resumed = decoded;
+        resumed.record(&Frame::single(1, 200), 1);
+        assert_eq!(resumed.led(1).switches, decoded.led(1).switches + 1);
+    }
+
+    #[test]
+    fn test_decode_erased_flash() {
+        assert_eq!(UsageStats::decode(&[0xFF; USAGE_RECORD_LEN]), None);
+    }
+
+    #[test]
+    fn test_decode_bad_checksum() {
+        let mut bytes = sample().encode();
+        bytes[20] ^= 0x01;
+        assert_eq!(UsageStats::decode(&bytes), None);
+    }
+}
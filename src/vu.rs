@@ -0,0 +1,1006 @@
+/*
+ * @file vu.rs
+ * @brief Audio-reactive VU meter
+ * @author Kevin Thomas
+ * @date 2025
+ *
+ * MIT License
+ *
+ * Copyright (c) 2025 Kevin Thomas
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+//! FILE: vu.rs
+//!
+//! DESCRIPTION:
+//! Audio-Reactive VU Meter for Microphone Input.
+//!
+//! BRIEF:
+//! Removes DC from ADC microphone samples and follows their level.
+//! Maps the level in decibels onto a bar of LEDs with peak hold.
+//! Detects beats so the chase can step in time with the music.
+//!
+//! AUTHOR: Kevin Thomas
+//! CREATION DATE: October 18, 2026
+//! UPDATE DATE: October 18, 2026
+
+use crate::config::{LED_COUNT, MAX_BRIGHTNESS};
+use crate::frame::Frame;
+
+/// ADC reading of a silent microphone.
+///
+/// # Details
+/// Mid-scale of the 12-bit ADC, where the DC blocker starts.
+#[allow(dead_code)]
+pub const ADC_MIDPOINT: u16 = 2048;
+
+/// Full-scale amplitude after DC removal, the 0 dB reference.
+#[allow(dead_code)]
+pub const FULL_SCALE: u32 = 2048;
+
+/// DC blocker pole in Q15, about 0.995.
+#[allow(dead_code)]
+pub const DC_POLE_Q15: i64 = 32_604;
+
+/// RMS averaging time constant in milliseconds.
+///
+/// # Details
+/// Several periods of the lowest tones of interest.
+#[allow(dead_code)]
+pub const RMS_WINDOW_MS: u32 = 20;
+
+/// Default envelope attack time in milliseconds.
+#[allow(dead_code)]
+pub const DEFAULT_ATTACK_MS: u32 = 5;
+
+/// Default envelope release time in milliseconds.
+#[allow(dead_code)]
+pub const DEFAULT_RELEASE_MS: u32 = 300;
+
+/// Default peak hold time in milliseconds.
+#[allow(dead_code)]
+pub const DEFAULT_PEAK_HOLD_MS: u32 = 1000;
+
+/// Peak fall rate after the hold time, in tenths of a dB per second.
+#[allow(dead_code)]
+pub const PEAK_DECAY_DB10_PER_S: i32 = 200;
+
+/// Short-term to long-term energy ratio counted as a beat, in percent.
+#[allow(dead_code)]
+pub const BEAT_THRESHOLD_PCT: i64 = 200;
+
+/// Smallest short-term energy counted as a beat.
+///
+/// # Details
+/// An amplitude of 64, about -30 dB, so room noise never beats.
+#[allow(dead_code)]
+pub const BEAT_MIN_ENERGY: i64 = 64 * 64;
+
+/// Shortest time between beats in milliseconds.
+#[allow(dead_code)]
+pub const BEAT_REFRACTORY_MS: u32 = 250;
+
+/// Short-term beat energy time constant in milliseconds.
+#[allow(dead_code)]
+pub const BEAT_SHORT_MS: u32 = 10;
+
+/// Long-term beat energy time constant in milliseconds.
+#[allow(dead_code)]
+pub const BEAT_LONG_MS: u32 = 1000;
+
+/// Envelope follower mode.
+///
+/// # Variants
+/// * `Peak` - Follows the rectified signal, quick to show transients
+/// * `Rms` - Follows signal power, closer to perceived loudness
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub enum EnvelopeMode {
+    Peak,
+    Rms,
+}
+
+/// VU meter error enumeration.
+///
+/// # Variants
+/// * `ZeroSampleRate` - Sample rate of zero
+/// * `InvalidFloor` - Floor at or above 0 dB
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[allow(dead_code)]
+pub enum VuError {
+    ZeroSampleRate,
+    InvalidFloor,
+}
+
+/// Converts a time constant to a smoothing shift.
+///
+/// # Details
+/// A one-pole filter with shift s settles in about 2^s samples.
+///
+/// # Arguments
+/// * `ms` - Time constant in milliseconds
+/// * `sample_rate_hz` - Samples per second
+///
+/// # Returns
+/// * `u32` - Shift, 0 for instant response
+#[allow(dead_code)]
+pub fn time_shift(ms: u32, sample_rate_hz: u32) -> u32 {
+    let samples = ms as u64 * sample_rate_hz as u64 / 1000;
+    if samples < 2 {
+        0
+    } else {
+        (63 - samples.leading_zeros()).min(24)
+    }
+}
+
+/// Returns base-2 logarithm with 8 fractional bits.
+///
+/// # Arguments
+/// * `value` - Value greater than zero
+///
+/// # Returns
+/// * `i32` - log2(value) * 256
+fn log2_q8(value: u32) -> i32 {
+    let whole = 31 - value.leading_zeros() as i32;
+    let mut x = ((value as u64) << 30) >> whole;
+    let mut fraction = 0;
+    for bit in (0..8).rev() {
+        x = (x * x) >> 30;
+        if x >= 2 << 30 {
+            x >>= 1;
+            fraction |= 1 << bit;
+        }
+    }
+    whole * 256 + fraction
+}
+
+/// Returns integer square root.
+///
+/// # Arguments
+/// * `value` - Value
+///
+/// # Returns
+/// * `u32` - Largest root whose square does not exceed value
+fn isqrt(value: u64) -> u32 {
+    let mut root = 0u64;
+    let mut bit = 1u64 << 62;
+    let mut rest = value;
+    while bit > value {
+        bit >>= 2;
+    }
+    while bit != 0 {
+        if rest >= root + bit {
+            rest -= root + bit;
+            root = (root >> 1) + bit;
+        } else {
+            root >>= 1;
+        }
+        bit >>= 2;
+    }
+    root as u32
+}
+
+/// Converts an amplitude to decibels relative to full scale.
+///
+/// # Arguments
+/// * `amplitude` - Amplitude after DC removal
+///
+/// # Returns
+/// * `i32` - Level in tenths of a dB, i32::MIN for silence
+#[allow(dead_code)]
+pub fn amplitude_to_db10(amplitude: u32) -> i32 {
+    if amplitude == 0 {
+        return i32::MIN;
+    }
+    let octaves_q8 = log2_q8(amplitude) - log2_q8(FULL_SCALE);
+    (octaves_q8 as i64 * 60_206 / 256_000) as i32
+}
+
+/// Returns bar fill for a level.
+///
+/// # Arguments
+/// * `db10` - Level in tenths of a dB
+/// * `floor_db10` - Level shown as an empty bar
+///
+/// # Returns
+/// * `u32` - Fill in brightness steps, 0 to LED_COUNT * MAX_BRIGHTNESS
+fn fill(db10: i32, floor_db10: i32) -> u32 {
+    let full = LED_COUNT as i64 * MAX_BRIGHTNESS as i64;
+    let above = db10.max(floor_db10) as i64 - floor_db10 as i64;
+    (above * full / -(floor_db10 as i64)).min(full) as u32
+}
+
+/// Converts a level to a bar frame.
+///
+/// # Details
+/// LEDs below the level are full and the last lit LED shows the
+/// fraction it covers.
+///
+/// # Arguments
+/// * `db10` - Level in tenths of a dB
+/// * `floor_db10` - Level shown as an empty bar, below 0
+///
+/// # Returns
+/// * `Frame` - Bar frame
+#[allow(dead_code)]
+pub fn db10_to_frame(db10: i32, floor_db10: i32) -> Frame {
+    let fill = fill(db10, floor_db10);
+    let mut frame = Frame::new();
+    for led in 0..LED_COUNT {
+        let covered = fill.saturating_sub(led as u32 * MAX_BRIGHTNESS as u32);
+        frame.set_level(led, covered.min(MAX_BRIGHTNESS as u32) as u8);
+    }
+    frame
+}
+
+/// One-pole DC blocking filter.
+///
+/// # Fields
+/// * `x` - Previous input in Q8
+/// * `y` - Previous output in Q8
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct DcBlocker {
+    x: i64,
+    y: i64,
+}
+
+impl Default for DcBlocker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DcBlocker {
+    /// Creates new DC blocker settled at ADC_MIDPOINT.
+    ///
+    /// # Returns
+    /// * `Self` - New DcBlocker instance
+    #[allow(dead_code)]
+    pub fn new() -> Self {
+        Self {
+            x: (ADC_MIDPOINT as i64) << 8,
+            y: 0,
+        }
+    }
+
+    /// Filters one sample.
+    ///
+    /// # Arguments
+    /// * `sample` - Raw ADC sample
+    ///
+    /// # Returns
+    /// * `i32` - Sample with DC removed
+    #[allow(dead_code)]
+    pub fn filter(&mut self, sample: u16) -> i32 {
+        let x = (sample as i64) << 8;
+        self.y = x - self.x + ((self.y * DC_POLE_Q15) >> 15);
+        self.x = x;
+        (self.y >> 8) as i32
+    }
+}
+
+/// Attack/release envelope follower.
+///
+/// # Details
+/// In RMS mode the power is averaged over RMS_WINDOW_MS before
+/// attack and release are applied to its square root.
+///
+/// # Fields
+/// * `mode` - Peak or RMS following
+/// * `attack_shift` - Smoothing shift while rising
+/// * `release_shift` - Smoothing shift while falling
+/// * `power_shift` - RMS power averaging shift
+/// * `power` - Averaged power in Q8
+/// * `level` - Amplitude in Q8
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct Envelope {
+    mode: EnvelopeMode,
+    attack_shift: u32,
+    release_shift: u32,
+    power_shift: u32,
+    power: i64,
+    level: i64,
+}
+
+impl Envelope {
+    /// Creates new envelope at silence.
+    ///
+    /// # Arguments
+    /// * `mode` - Peak or RMS following
+    /// * `attack_shift` - Smoothing shift while rising
+    /// * `release_shift` - Smoothing shift while falling
+    /// * `power_shift` - RMS power averaging shift
+    ///
+    /// # Returns
+    /// * `Self` - New Envelope instance
+    #[allow(dead_code)]
+    pub fn new(
+        mode: EnvelopeMode,
+        attack_shift: u32,
+        release_shift: u32,
+        power_shift: u32,
+    ) -> Self {
+        Self {
+            mode,
+            attack_shift,
+            release_shift,
+            power_shift,
+            power: 0,
+            level: 0,
+        }
+    }
+
+    /// Follows one sample.
+    ///
+    /// # Arguments
+    /// * `sample` - Sample with DC removed
+    #[allow(dead_code)]
+    pub fn push(&mut self, sample: i32) {
+        let sample = sample as i64;
+        let target = match self.mode {
+            EnvelopeMode::Peak => sample.abs() << 8,
+            EnvelopeMode::Rms => {
+                self.power += (((sample * sample) << 8) - self.power) >> self.power_shift;
+                (isqrt((self.power.max(0) >> 8) as u64) as i64) << 8
+            }
+        };
+        let shift = if target > self.level {
+            self.attack_shift
+        } else {
+            self.release_shift
+        };
+        self.level += (target - self.level) >> shift;
+    }
+
+    /// Returns current amplitude.
+    ///
+    /// # Returns
+    /// * `u32` - Peak or RMS amplitude
+    #[allow(dead_code)]
+    pub fn amplitude(&self) -> u32 {
+        (self.level.max(0) >> 8) as u32
+    }
+}
+
+/// Peak level hold with slow fall.
+///
+/// # Fields
+/// * `hold_ms` - Time the peak stays before falling
+/// * `floor_db10` - Lowest level the peak falls to
+/// * `peak_db10` - Held peak in tenths of a dB
+/// * `held_ms` - Time since the peak was set
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct PeakHold {
+    hold_ms: u32,
+    floor_db10: i32,
+    peak_db10: i32,
+    held_ms: u32,
+}
+
+impl PeakHold {
+    /// Creates new peak hold at the floor.
+    ///
+    /// # Arguments
+    /// * `hold_ms` - Time the peak stays before falling
+    /// * `floor_db10` - Lowest level the peak falls to
+    ///
+    /// # Returns
+    /// * `Self` - New PeakHold instance
+    #[allow(dead_code)]
+    pub fn new(hold_ms: u32, floor_db10: i32) -> Self {
+        Self {
+            hold_ms,
+            floor_db10,
+            peak_db10: floor_db10,
+            held_ms: 0,
+        }
+    }
+
+    /// Returns held peak.
+    ///
+    /// # Returns
+    /// * `i32` - Peak in tenths of a dB
+    #[allow(dead_code)]
+    pub fn peak_db10(&self) -> i32 {
+        self.peak_db10
+    }
+
+    /// Offers a level.
+    ///
+    /// # Arguments
+    /// * `db10` - Current level in tenths of a dB
+    #[allow(dead_code)]
+    pub fn update(&mut self, db10: i32) {
+        if db10 >= self.peak_db10 {
+            self.peak_db10 = db10;
+            self.held_ms = 0;
+        }
+    }
+
+    /// Advances time, letting the peak fall after the hold time.
+    ///
+    /// # Arguments
+    /// * `elapsed_ms` - Time since the previous tick
+    #[allow(dead_code)]
+    pub fn tick(&mut self, elapsed_ms: u32) {
+        let before = self.held_ms;
+        self.held_ms = self.held_ms.saturating_add(elapsed_ms);
+        let falling_ms = self.held_ms - before.max(self.hold_ms).min(self.held_ms);
+        let fall = (falling_ms as i64 * PEAK_DECAY_DB10_PER_S as i64 / 1000) as i32;
+        self.peak_db10 = (self.peak_db10 - fall).max(self.floor_db10);
+    }
+}
+
+/// Energy-ratio beat detector.
+///
+/// # Details
+/// Compares short-term and long-term signal energy. A beat is a
+/// jump in short-term energy well above the running average,
+/// no sooner than BEAT_REFRACTORY_MS after the previous one.
+///
+/// # Fields
+/// * `short_shift` - Short-term smoothing shift
+/// * `long_shift` - Long-term smoothing shift
+/// * `refractory` - Samples between beats
+/// * `short` - Short-term energy in Q8
+/// * `long` - Long-term energy in Q8
+/// * `since_beat` - Samples since the previous beat
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct BeatDetector {
+    short_shift: u32,
+    long_shift: u32,
+    refractory: u32,
+    short: i64,
+    long: i64,
+    since_beat: u32,
+}
+
+impl BeatDetector {
+    /// Creates new beat detector.
+    ///
+    /// # Arguments
+    /// * `sample_rate_hz` - Samples per second
+    ///
+    /// # Returns
+    /// * `Self` - New BeatDetector instance
+    #[allow(dead_code)]
+    pub fn new(sample_rate_hz: u32) -> Self {
+        let refractory = (BEAT_REFRACTORY_MS as u64 * sample_rate_hz as u64 / 1000) as u32;
+        Self {
+            short_shift: time_shift(BEAT_SHORT_MS, sample_rate_hz),
+            long_shift: time_shift(BEAT_LONG_MS, sample_rate_hz),
+            refractory,
+            short: 0,
+            long: 0,
+            since_beat: refractory,
+        }
+    }
+
+    /// Follows one sample.
+    ///
+    /// # Arguments
+    /// * `sample` - Sample with DC removed
+    ///
+    /// # Returns
+    /// * `bool` - true if this sample completes a beat
+    #[allow(dead_code)]
+    pub fn push(&mut self, sample: i32) -> bool {
+        let energy = ((sample as i64) * (sample as i64)) << 8;
+        self.short += (energy - self.short) >> self.short_shift;
+        self.long += (energy - self.long) >> self.long_shift;
+        self.since_beat = self.since_beat.saturating_add(1);
+        let loud = self.short >> 8 >= BEAT_MIN_ENERGY;
+        let jump = self.short * 100 > self.long * BEAT_THRESHOLD_PCT;
+        if loud && jump && self.since_beat >= self.refractory {
+            self.since_beat = 0;
+            return true;
+        }
+        false
+    }
+}
+
+/// Microphone VU meter.
+///
+/// # Details
+/// Feed every ADC sample to `push` and call `tick` once per frame.
+/// The frame shows the level as a bar from `floor_db` to 0 dB with
+/// the held peak at full brightness.
+///
+/// # Fields
+/// * `sample_rate_hz` - Samples per second
+/// * `floor_db10` - Level shown as an empty bar
+/// * `dc` - DC blocker
+/// * `envelope` - Level follower
+/// * `peak` - Peak hold
+/// * `beat` - Beat detector
+/// * `beats` - Beats detected so far
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct VuMeter {
+    sample_rate_hz: u32,
+    floor_db10: i32,
+    dc: DcBlocker,
+    envelope: Envelope,
+    peak: PeakHold,
+    beat: BeatDetector,
+    beats: u32,
+}
+
+impl VuMeter {
+    /// Creates new peak-following VU meter with default timing.
+    ///
+    /// # Arguments
+    /// * `sample_rate_hz` - Samples per second
+    /// * `floor_db` - Level in dB shown as an empty bar
+    ///
+    /// # Returns
+    /// * `Result<Self, VuError>` - New VuMeter
+    #[allow(dead_code)]
+    pub fn new(sample_rate_hz: u32, floor_db: i16) -> Result<Self, VuError> {
+        if sample_rate_hz == 0 {
+            return Err(VuError::ZeroSampleRate);
+        }
+        if floor_db >= 0 {
+            return Err(VuError::InvalidFloor);
+        }
+        let floor_db10 = floor_db as i32 * 10;
+        Ok(Self {
+            sample_rate_hz,
+            floor_db10,
+            dc: DcBlocker::new(),
+            envelope: Envelope::new(
+                EnvelopeMode::Peak,
+                time_shift(DEFAULT_ATTACK_MS, sample_rate_hz),
+                time_shift(DEFAULT_RELEASE_MS, sample_rate_hz),
+                time_shift(RMS_WINDOW_MS, sample_rate_hz),
+            ),
+            peak: PeakHold::new(DEFAULT_PEAK_HOLD_MS, floor_db10),
+            beat: BeatDetector::new(sample_rate_hz),
+            beats: 0,
+        })
+    }
+
+    /// Returns meter with the given envelope timing.
+    ///
+    /// # Arguments
+    /// * `mode` - Peak or RMS following
+    /// * `attack_ms` - Rise time constant in milliseconds
+    /// * `release_ms` - Fall time constant in milliseconds
+    ///
+    /// # Returns
+    /// * `Self` - Updated meter restarted at silence
+    #[allow(dead_code)]
+    pub fn with_envelope(mut self, mode: EnvelopeMode, attack_ms: u32, release_ms: u32) -> Self {
+        self.envelope = Envelope::new(
+            mode,
+            time_shift(attack_ms, self.sample_rate_hz),
+            time_shift(release_ms, self.sample_rate_hz),
+            time_shift(RMS_WINDOW_MS, self.sample_rate_hz),
+        );
+        self
+    }
+
+    /// Returns meter with the given peak hold time.
+    ///
+    /// # Arguments
+    /// * `hold_ms` - Time the peak stays before falling
+    ///
+    /// # Returns
+    /// * `Self` - Updated meter
+    #[allow(dead_code)]
+    pub fn with_peak_hold(mut self, hold_ms: u32) -> Self {
+        self.peak = PeakHold::new(hold_ms, self.floor_db10);
+        self
+    }
+
+    /// Returns number of beats detected.
+    ///
+    /// # Returns
+    /// * `u32` - Beat count
+    #[allow(dead_code)]
+    pub fn beats(&self) -> u32 {
+        self.beats
+    }
+
+    /// Feeds one ADC sample.
+    ///
+    /// # Arguments
+    /// * `sample` - Raw 12-bit ADC sample
+    ///
+    /// # Returns
+    /// * `bool` - true if a beat was detected
+    #[allow(dead_code)]
+    pub fn push(&mut self, sample: u16) -> bool {
+        let sample = self.dc.filter(sample);
+        self.envelope.push(sample);
+        let beat = self.beat.push(sample);
+        if beat {
+            self.beats = self.beats.wrapping_add(1);
+        }
+        beat
+    }
+
+    /// Advances peak hold time and takes the current level.
+    ///
+    /// # Arguments
+    /// * `elapsed_ms` - Time since the previous tick
+    #[allow(dead_code)]
+    pub fn tick(&mut self, elapsed_ms: u32) {
+        self.peak.tick(elapsed_ms);
+        self.peak.update(self.level_db10());
+    }
+
+    /// Returns current level.
+    ///
+    /// # Returns
+    /// * `i32` - Level in tenths of a dB, clamped to the floor
+    #[allow(dead_code)]
+    pub fn level_db10(&self) -> i32 {
+        amplitude_to_db10(self.envelope.amplitude()).max(self.floor_db10)
+    }
+
+    /// Returns held peak.
+    ///
+    /// # Returns
+    /// * `i32` - Peak in tenths of a dB
+    #[allow(dead_code)]
+    pub fn peak_db10(&self) -> i32 {
+        self.peak.peak_db10()
+    }
+
+    /// Returns meter frame.
+    ///
+    /// # Returns
+    /// * `Frame` - Level bar with the held peak LED at full brightness
+    #[allow(dead_code)]
+    pub fn frame(&self) -> Frame {
+        let mut frame = db10_to_frame(self.level_db10(), self.floor_db10);
+        let peak_fill = fill(self.peak_db10(), self.floor_db10);
+        if peak_fill > 0 {
+            let led = ((peak_fill - 1) / MAX_BRIGHTNESS as u32) as usize;
+            frame.set_level(led, MAX_BRIGHTNESS);
+        }
+        frame
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RATE: u32 = 8000;
+
+    /// Returns sine sample around the ADC midpoint.
+    fn sine(index: u32, amplitude: f64, hz: f64) -> u16 {
+        let phase = 2.0 * std::f64::consts::PI * hz * index as f64 / RATE as f64;
+        (ADC_MIDPOINT as f64 + amplitude * phase.sin()).round() as u16
+    }
+
+    fn meter() -> VuMeter {
+        VuMeter::new(RATE, -48).unwrap()
+    }
+
+    /// Feeds a tone for a time and ticks every 10 ms.
+    fn play(meter: &mut VuMeter, ms: u32, amplitude: f64) -> u32 {
+        let mut beats = 0;
+        for block in 0..ms / 10 {
+            for i in 0..RATE / 100 {
+                let index = block * RATE / 100 + i;
+                beats += meter.push(sine(index, amplitude, 440.0)) as u32;
+            }
+            meter.tick(10);
+        }
+        beats
+    }
+
+    // ==================== Helper Tests ====================
+
+    #[test]
+    fn test_time_shift() {
+        assert_eq!(time_shift(0, RATE), 0);
+        assert_eq!(time_shift(1, RATE), 3);
+        assert_eq!(time_shift(1000, RATE), 12);
+    }
+
+    #[test]
+    fn test_time_shift_capped() {
+        assert_eq!(time_shift(u32::MAX, u32::MAX), 24);
+    }
+
+    #[test]
+    fn test_log2_q8_powers() {
+        assert_eq!(log2_q8(1), 0);
+        assert_eq!(log2_q8(2), 256);
+        assert_eq!(log2_q8(1024), 2560);
+    }
+
+    #[test]
+    fn test_log2_q8_fraction() {
+        assert!((log2_q8(3) - 406).abs() <= 1);
+    }
+
+    #[test]
+    fn test_isqrt() {
+        assert_eq!(isqrt(0), 0);
+        assert_eq!(isqrt(15), 3);
+        assert_eq!(isqrt(16), 4);
+        assert_eq!(isqrt(4_194_304), 2048);
+    }
+
+    // ==================== dB Mapping Tests ====================
+
+    #[test]
+    fn test_full_scale_is_zero_db() {
+        assert_eq!(amplitude_to_db10(FULL_SCALE), 0);
+    }
+
+    #[test]
+    fn test_half_scale_is_minus_six_db() {
+        assert_eq!(amplitude_to_db10(FULL_SCALE / 2), -60);
+    }
+
+    #[test]
+    fn test_silence_is_minimum() {
+        assert_eq!(amplitude_to_db10(0), i32::MIN);
+    }
+
+    #[test]
+    fn test_db_is_logarithmic() {
+        let step = amplitude_to_db10(200) - amplitude_to_db10(100);
+        assert_eq!(step, amplitude_to_db10(20) - amplitude_to_db10(10));
+    }
+
+    #[test]
+    fn test_frame_at_floor_is_dark() {
+        assert_eq!(db10_to_frame(-480, -480), Frame::new());
+        assert_eq!(db10_to_frame(i32::MIN, -480), Frame::new());
+    }
+
+    #[test]
+    fn test_frame_at_full_scale_is_full() {
+        assert_eq!(db10_to_frame(0, -480), Frame::filled(MAX_BRIGHTNESS));
+        assert_eq!(db10_to_frame(60, -480), Frame::filled(MAX_BRIGHTNESS));
+    }
+
+    #[test]
+    fn test_frame_fractional_last_led() {
+        let frame = db10_to_frame(-300, -480);
+        assert_eq!(frame, Frame::from_levels([255, 127, 0, 0]));
+    }
+
+    // ==================== DcBlocker Tests ====================
+
+    #[test]
+    fn test_dc_removed() {
+        let mut dc = DcBlocker::new();
+        let mut out = 0;
+        for _ in 0..4000 {
+            out = dc.filter(3000);
+        }
+        assert!(out.abs() <= 1);
+    }
+
+    #[test]
+    fn test_midpoint_is_silent() {
+        let mut dc = DcBlocker::new();
+        assert_eq!(dc.filter(ADC_MIDPOINT), 0);
+    }
+
+    #[test]
+    fn test_ac_passes() {
+        let mut dc = DcBlocker::new();
+        let peak = (0..4000)
+            .map(|i| dc.filter(sine(i, 1000.0, 440.0) + 500).abs())
+            .skip(3000)
+            .max()
+            .unwrap();
+        assert!((950..=1050).contains(&peak));
+    }
+
+    // ==================== Envelope Tests ====================
+
+    #[test]
+    fn test_peak_envelope_of_sine() {
+        let mut meter = meter();
+        play(&mut meter, 200, 2047.0);
+        assert!(meter.level_db10() >= -10);
+    }
+
+    #[test]
+    fn test_rms_envelope_of_sine() {
+        let mut meter = meter().with_envelope(EnvelopeMode::Rms, 5, 300);
+        play(&mut meter, 500, 2047.0);
+        assert!((-40..=-20).contains(&meter.level_db10()));
+    }
+
+    #[test]
+    fn test_envelope_tracks_amplitude() {
+        let mut loud = meter();
+        let mut quiet = meter();
+        play(&mut loud, 200, 1600.0);
+        play(&mut quiet, 200, 400.0);
+        let difference = loud.level_db10() - quiet.level_db10();
+        assert!((100..=140).contains(&difference));
+    }
+
+    #[test]
+    fn test_envelope_releases() {
+        let mut meter = meter();
+        play(&mut meter, 200, 2000.0);
+        let loud = meter.level_db10();
+        play(&mut meter, 300, 0.0);
+        assert!(meter.level_db10() < loud - 100);
+    }
+
+    #[test]
+    fn test_attack_faster_than_release() {
+        let mut envelope = Envelope::new(EnvelopeMode::Peak, 2, 10, 0);
+        for _ in 0..20 {
+            envelope.push(1000);
+        }
+        assert!(envelope.amplitude() > 990);
+        for _ in 0..20 {
+            envelope.push(0);
+        }
+        assert!(envelope.amplitude() > 950);
+    }
+
+    #[test]
+    fn test_silence_reads_floor() {
+        let mut meter = meter();
+        play(&mut meter, 100, 0.0);
+        assert_eq!(meter.level_db10(), -480);
+        assert_eq!(meter.frame(), Frame::new());
+    }
+
+    // ==================== PeakHold Tests ====================
+
+    #[test]
+    fn test_peak_holds() {
+        let mut peak = PeakHold::new(1000, -480);
+        peak.update(-100);
+        peak.tick(1000);
+        peak.update(-300);
+        assert_eq!(peak.peak_db10(), -100);
+    }
+
+    #[test]
+    fn test_peak_falls_after_hold() {
+        let mut peak = PeakHold::new(1000, -480);
+        peak.update(-100);
+        peak.tick(900);
+        peak.tick(600);
+        assert_eq!(peak.peak_db10(), -200);
+    }
+
+    #[test]
+    fn test_peak_stops_at_floor() {
+        let mut peak = PeakHold::new(0, -480);
+        peak.update(0);
+        peak.tick(60_000);
+        assert_eq!(peak.peak_db10(), -480);
+    }
+
+    #[test]
+    fn test_higher_level_resets_hold() {
+        let mut peak = PeakHold::new(1000, -480);
+        peak.update(-200);
+        peak.tick(900);
+        peak.update(-100);
+        peak.tick(900);
+        assert_eq!(peak.peak_db10(), -100);
+    }
+
+    #[test]
+    fn test_peak_led_lit_after_level_falls() {
+        let mut meter = meter().with_envelope(EnvelopeMode::Peak, 1, 10);
+        play(&mut meter, 100, 2000.0);
+        play(&mut meter, 200, 0.0);
+        assert_eq!(meter.frame(), Frame::single(LED_COUNT - 1, MAX_BRIGHTNESS));
+    }
+
+    // ==================== BeatDetector Tests ====================
+
+    #[test]
+    fn test_onset_is_a_beat() {
+        let mut meter = meter();
+        play(&mut meter, 100, 1000.0);
+        assert_eq!(meter.beats(), 1);
+    }
+
+    #[test]
+    fn test_settled_tone_has_no_beats() {
+        let mut meter = meter();
+        play(&mut meter, 3000, 1000.0);
+        assert_eq!(play(&mut meter, 3000, 1000.0), 0);
+    }
+
+    #[test]
+    fn test_silence_has_no_beats() {
+        let mut meter = meter();
+        assert_eq!(play(&mut meter, 1000, 0.0), 0);
+    }
+
+    #[test]
+    fn test_quiet_noise_has_no_beats() {
+        let mut meter = meter();
+        assert_eq!(play(&mut meter, 1000, 20.0), 0);
+    }
+
+    #[test]
+    fn test_bursts_are_beats() {
+        let mut meter = meter();
+        for _ in 0..8 {
+            play(&mut meter, 100, 1500.0);
+            play(&mut meter, 400, 30.0);
+        }
+        assert_eq!(meter.beats(), 8);
+    }
+
+    #[test]
+    fn test_refractory_period() {
+        let mut detector = BeatDetector::new(RATE);
+        let mut beats = 0;
+        for block in 0..20 {
+            let level = if block % 2 == 0 { 2000 } else { 0 };
+            for _ in 0..RATE / 100 {
+                beats += detector.push(level) as u32;
+            }
+        }
+        assert_eq!(beats, 1);
+    }
+
+    // ==================== VuMeter Tests ====================
+
+    #[test]
+    fn test_new_zero_rate() {
+        assert_eq!(VuMeter::new(0, -48), Err(VuError::ZeroSampleRate));
+    }
+
+    #[test]
+    fn test_new_invalid_floor() {
+        assert_eq!(VuMeter::new(RATE, 0), Err(VuError::InvalidFloor));
+        assert_eq!(VuMeter::new(RATE, 6), Err(VuError::InvalidFloor));
+    }
+
+    #[test]
+    fn test_meter_bar_grows_with_level() {
+        let mut quiet = meter();
+        let mut loud = meter();
+        play(&mut quiet, 200, 100.0);
+        play(&mut loud, 200, 1500.0);
+        assert!(loud.frame().lit_count() > quiet.frame().lit_count());
+    }
+
+    #[test]
+    fn test_with_peak_hold_zero_falls() {
+        let mut meter = meter()
+            .with_peak_hold(0)
+            .with_envelope(EnvelopeMode::Peak, 1, 1);
+        play(&mut meter, 100, 2000.0);
+        play(&mut meter, 5000, 0.0);
+        assert_eq!(meter.peak_db10(), -480);
+    }
+}
@@ -0,0 +1,339 @@
+/*
+ * @file wallclock.rs
+ * @brief Wall-clock time of day
+ * @author Kevin Thomas
+ * @date 2025
+ *
+ * MIT License
+ *
+ * Copyright (c) 2025 Kevin Thomas
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+//! FILE: wallclock.rs
+//!
+//! DESCRIPTION:
+//! Wall-Clock Time of Day over the Monotonic Timer.
+//!
+//! BRIEF:
+//! Keeps the time of day as an offset from monotonic milliseconds.
+//! The monotonic clock comes from embassy-time on the target.
+//! Holds times of day as hours, minutes and seconds.
+//!
+//! AUTHOR: Kevin Thomas
+//! CREATION DATE: October 18, 2026
+//! UPDATE DATE: October 18, 2026
+
+/// Seconds in a day.
+#[allow(dead_code)]
+pub const SECONDS_PER_DAY: u32 = 86_400;
+
+/// Milliseconds in a day.
+const MS_PER_DAY: u64 = SECONDS_PER_DAY as u64 * 1000;
+
+/// Time of day.
+///
+/// # Fields
+/// * `hours` - Hours (0 to 23)
+/// * `minutes` - Minutes (0 to 59)
+/// * `seconds` - Seconds (0 to 59)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[allow(dead_code)]
+pub struct TimeOfDay {
+    hours: u8,
+    minutes: u8,
+    seconds: u8,
+}
+
+impl TimeOfDay {
+    /// Midnight.
+    #[allow(dead_code)]
+    pub const MIDNIGHT: TimeOfDay = TimeOfDay {
+        hours: 0,
+        minutes: 0,
+        seconds: 0,
+    };
+
+    /// Creates new time of day.
+    ///
+    /// # Arguments
+    /// * `hours` - Hours (0 to 23)
+    /// * `minutes` - Minutes (0 to 59)
+    /// * `seconds` - Seconds (0 to 59)
+    ///
+    /// # Returns
+    /// * `Option<Self>` - Time of day, or None if a field is out of range
+    #[allow(dead_code)]
+    pub const fn new(hours: u8, minutes: u8, seconds: u8) -> Option<Self> {
+        if hours > 23 || minutes > 59 || seconds > 59 {
+            return None;
+        }
+        Some(Self {
+            hours,
+            minutes,
+            seconds,
+        })
+    }
+
+    /// Creates time of day on the minute.
+    ///
+    /// # Details
+    /// Out-of-range fields wrap, so this works in const tables.
+    ///
+    /// # Arguments
+    /// * `hours` - Hours (0 to 23)
+    /// * `minutes` - Minutes (0 to 59)
+    ///
+    /// # Returns
+    /// * `Self` - Time of day
+    #[allow(dead_code)]
+    pub const fn hm(hours: u8, minutes: u8) -> Self {
+        Self::from_seconds(hours as u32 * 3600 + minutes as u32 * 60)
+    }
+
+    /// Creates time of day from seconds since midnight.
+    ///
+    /// # Arguments
+    /// * `seconds` - Seconds since midnight, wrapped to one day
+    ///
+    /// # Returns
+    /// * `Self` - Time of day
+    #[allow(dead_code)]
+    pub const fn from_seconds(seconds: u32) -> Self {
+        let seconds = seconds % SECONDS_PER_DAY;
+        Self {
+            hours: (seconds / 3600) as u8,
+            minutes: (seconds / 60 % 60) as u8,
+            seconds: (seconds % 60) as u8,
+        }
+    }
+
+    /// Returns hours.
+    ///
+    /// # Returns
+    /// * `u8` - Hours (0 to 23)
+    #[allow(dead_code)]
+    pub const fn hours(&self) -> u8 {
+        self.hours
+    }
+
+    /// Returns minutes.
+    ///
+    /// # Returns
+    /// * `u8` - Minutes (0 to 59)
+    #[allow(dead_code)]
+    pub const fn minutes(&self) -> u8 {
+        self.minutes
+    }
+
+    /// Returns seconds.
+    ///
+    /// # Returns
+    /// * `u8` - Seconds (0 to 59)
+    #[allow(dead_code)]
+    pub const fn seconds(&self) -> u8 {
+        self.seconds
+    }
+
+    /// Returns seconds since midnight.
+    ///
+    /// # Returns
+    /// * `u32` - Seconds (0 to 86399)
+    #[allow(dead_code)]
+    pub const fn seconds_of_day(&self) -> u32 {
+        self.hours as u32 * 3600 + self.minutes as u32 * 60 + self.seconds as u32
+    }
+}
+
+/// Wall clock kept as an offset from monotonic time.
+///
+/// # Fields
+/// * `offset_ms` - Time of day at monotonic zero, None until set
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct WallClock {
+    offset_ms: Option<u64>,
+}
+
+impl WallClock {
+    /// Creates new unset wall clock.
+    ///
+    /// # Returns
+    /// * `Self` - New WallClock instance
+    #[allow(dead_code)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns whether the time has been set.
+    ///
+    /// # Returns
+    /// * `bool` - true once set
+    #[allow(dead_code)]
+    pub fn is_set(&self) -> bool {
+        self.offset_ms.is_some()
+    }
+
+    /// Sets the time of day.
+    ///
+    /// # Arguments
+    /// * `now_ms` - Monotonic time in milliseconds
+    /// * `time` - Time of day at now_ms
+    #[allow(dead_code)]
+    pub fn set(&mut self, now_ms: u64, time: TimeOfDay) {
+        let since_zero = now_ms % MS_PER_DAY;
+        let at_now = time.seconds_of_day() as u64 * 1000;
+        self.offset_ms = Some((at_now + MS_PER_DAY - since_zero) % MS_PER_DAY);
+    }
+
+    /// Returns milliseconds since midnight.
+    ///
+    /// # Arguments
+    /// * `now_ms` - Monotonic time in milliseconds
+    ///
+    /// # Returns
+    /// * `Option<u32>` - Milliseconds since midnight, None until set
+    #[allow(dead_code)]
+    pub fn ms_of_day(&self, now_ms: u64) -> Option<u32> {
+        self.offset_ms
+            .map(|offset| ((offset + now_ms % MS_PER_DAY) % MS_PER_DAY) as u32)
+    }
+
+    /// Returns time of day.
+    ///
+    /// # Arguments
+    /// * `now_ms` - Monotonic time in milliseconds
+    ///
+    /// # Returns
+    /// * `Option<TimeOfDay>` - Time of day, None until set
+    #[allow(dead_code)]
+    pub fn time(&self, now_ms: u64) -> Option<TimeOfDay> {
+        self.ms_of_day(now_ms)
+            .map(|ms| TimeOfDay::from_seconds(ms / 1000))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(hours: u8, minutes: u8, seconds: u8) -> TimeOfDay {
+        TimeOfDay::new(hours, minutes, seconds).unwrap()
+    }
+
+    // ==================== TimeOfDay Tests ====================
+
+    #[test]
+    fn test_new_valid() {
+        let time = time(23, 59, 58);
+        assert_eq!(time.hours(), 23);
+        assert_eq!(time.minutes(), 59);
+        assert_eq!(time.seconds(), 58);
+    }
+
+    #[test]
+    fn test_new_out_of_range() {
+        assert_eq!(TimeOfDay::new(24, 0, 0), None);
+        assert_eq!(TimeOfDay::new(0, 60, 0), None);
+        assert_eq!(TimeOfDay::new(0, 0, 60), None);
+    }
+
+    #[test]
+    fn test_seconds_of_day() {
+        assert_eq!(TimeOfDay::MIDNIGHT.seconds_of_day(), 0);
+        assert_eq!(time(1, 2, 3).seconds_of_day(), 3723);
+        assert_eq!(time(23, 59, 59).seconds_of_day(), SECONDS_PER_DAY - 1);
+    }
+
+    #[test]
+    fn test_from_seconds_round_trip() {
+        for seconds in [0, 59, 60, 3599, 3600, 45_296, SECONDS_PER_DAY - 1] {
+            assert_eq!(TimeOfDay::from_seconds(seconds).seconds_of_day(), seconds);
+        }
+    }
+
+    #[test]
+    fn test_hm() {
+        assert_eq!(TimeOfDay::hm(22, 30), time(22, 30, 0));
+        assert_eq!(TimeOfDay::hm(24, 0), TimeOfDay::MIDNIGHT);
+    }
+
+    #[test]
+    fn test_from_seconds_wraps() {
+        assert_eq!(TimeOfDay::from_seconds(SECONDS_PER_DAY + 61), time(0, 1, 1));
+    }
+
+    #[test]
+    fn test_ordering() {
+        assert!(time(7, 0, 0) < time(22, 30, 0));
+        assert!(time(7, 0, 1) > time(7, 0, 0));
+    }
+
+    // ==================== WallClock Tests ====================
+
+    #[test]
+    fn test_unset() {
+        let clock = WallClock::new();
+        assert!(!clock.is_set());
+        assert_eq!(clock.time(1000), None);
+    }
+
+    #[test]
+    fn test_set_reads_back() {
+        let mut clock = WallClock::new();
+        clock.set(5000, time(12, 34, 56));
+        assert!(clock.is_set());
+        assert_eq!(clock.time(5000), Some(time(12, 34, 56)));
+    }
+
+    #[test]
+    fn test_time_advances() {
+        let mut clock = WallClock::new();
+        clock.set(5000, time(12, 34, 56));
+        assert_eq!(clock.time(5999), Some(time(12, 34, 56)));
+        assert_eq!(clock.time(6000), Some(time(12, 34, 57)));
+        assert_eq!(clock.time(5000 + 3_600_000), Some(time(13, 34, 56)));
+    }
+
+    #[test]
+    fn test_midnight_wrap() {
+        let mut clock = WallClock::new();
+        clock.set(0, time(23, 59, 59));
+        assert_eq!(clock.time(1000), Some(TimeOfDay::MIDNIGHT));
+        assert_eq!(clock.ms_of_day(1500), Some(500));
+    }
+
+    #[test]
+    fn test_set_late_in_uptime() {
+        let mut clock = WallClock::new();
+        let now = 40 * MS_PER_DAY + 12_345;
+        clock.set(now, time(6, 0, 0));
+        assert_eq!(clock.time(now + MS_PER_DAY), Some(time(6, 0, 0)));
+    }
+
+    #[test]
+    fn test_reset_time() {
+        let mut clock = WallClock::new();
+        clock.set(0, time(10, 0, 0));
+        clock.set(1000, time(8, 0, 0));
+        assert_eq!(clock.time(2000), Some(time(8, 0, 1)));
+    }
+}
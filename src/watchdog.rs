@@ -0,0 +1,318 @@
+/*
+ * @file watchdog.rs
+ * @brief Watchdog supervision and boot record
+ * @author Kevin Thomas
+ * @date 2025
+ *
+ * MIT License
+ *
+ * Copyright (c) 2025 Kevin Thomas
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+//! FILE: watchdog.rs
+//!
+//! DESCRIPTION:
+//! Watchdog Supervision for the RP2350 Sequencer Loop.
+//!
+//! BRIEF:
+//! Decides when the sequencer is on schedule and may feed the watchdog.
+//! Tracks watchdog resets in a record kept in watchdog scratch registers.
+//!
+//! AUTHOR: Kevin Thomas
+//! CREATION DATE: October 18, 2026
+//! UPDATE DATE: October 18, 2026
+
+/// Marker identifying a valid boot record in scratch registers.
+///
+/// # Value
+/// 0xB007_D002
+#[allow(dead_code)]
+pub const BOOT_RECORD_MAGIC: u32 = 0xB007_D002;
+
+/// Reset cause enumeration.
+///
+/// # Variants
+/// * `PowerOn` - Power-on, pin or debugger reset
+/// * `Watchdog` - Watchdog timer expired
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[allow(dead_code)]
+pub enum ResetCause {
+    PowerOn,
+    Watchdog,
+}
+
+/// Reset history kept across watchdog resets.
+///
+/// # Details
+/// Stored as a magic word and a count in two watchdog scratch
+/// registers, which keep their contents across watchdog resets
+/// but not across power cycles.
+///
+/// # Fields
+/// * `watchdog_resets` - Watchdog resets since power-on
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct BootRecord {
+    watchdog_resets: u32,
+}
+
+impl BootRecord {
+    /// Restores record from scratch register values.
+    ///
+    /// # Details
+    /// Returns an empty record if the magic word does not match.
+    ///
+    /// # Arguments
+    /// * `magic` - First scratch register value
+    /// * `count` - Second scratch register value
+    ///
+    /// # Returns
+    /// * `Self` - Restored BootRecord
+    #[allow(dead_code)]
+    pub fn from_scratch(magic: u32, count: u32) -> Self {
+        if magic == BOOT_RECORD_MAGIC {
+            Self {
+                watchdog_resets: count,
+            }
+        } else {
+            Self::default()
+        }
+    }
+
+    /// Returns scratch register values for this record.
+    ///
+    /// # Returns
+    /// * `(u32, u32)` - Magic word and reset count
+    #[allow(dead_code)]
+    pub fn to_scratch(&self) -> (u32, u32) {
+        (BOOT_RECORD_MAGIC, self.watchdog_resets)
+    }
+
+    /// Returns record updated for the latest reset.
+    ///
+    /// # Details
+    /// Counts a watchdog reset and clears the count otherwise.
+    ///
+    /// # Arguments
+    /// * `cause` - Cause of the latest reset
+    ///
+    /// # Returns
+    /// * `Self` - Updated BootRecord
+    #[allow(dead_code)]
+    pub fn after_reset(self, cause: ResetCause) -> Self {
+        match cause {
+            ResetCause::Watchdog => Self {
+                watchdog_resets: self.watchdog_resets.saturating_add(1),
+            },
+            ResetCause::PowerOn => Self::default(),
+        }
+    }
+
+    /// Returns watchdog reset count.
+    ///
+    /// # Returns
+    /// * `u32` - Watchdog resets since power-on
+    #[allow(dead_code)]
+    pub fn watchdog_resets(&self) -> u32 {
+        self.watchdog_resets
+    }
+}
+
+/// Step schedule supervisor.
+///
+/// # Details
+/// Reports whether each completed step landed within its interval
+/// plus a tolerance. The watchdog is only fed for on-schedule
+/// steps, so a stalled or overloaded loop lets it expire.
+///
+/// # Fields
+/// * `interval_ms` - Expected time between steps
+/// * `tolerance_ms` - Allowed lateness per step
+/// * `last_step_ms` - Time of the previous step
+/// * `late_steps` - Steps that missed their schedule
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct StepSupervisor {
+    interval_ms: u64,
+    tolerance_ms: u64,
+    last_step_ms: u64,
+    late_steps: u32,
+}
+
+impl StepSupervisor {
+    /// Creates new supervisor.
+    ///
+    /// # Arguments
+    /// * `interval_ms` - Expected time between steps
+    /// * `tolerance_ms` - Allowed lateness per step
+    /// * `now_ms` - Current time
+    ///
+    /// # Returns
+    /// * `Self` - New StepSupervisor instance
+    #[allow(dead_code)]
+    pub fn new(interval_ms: u64, tolerance_ms: u64, now_ms: u64) -> Self {
+        Self {
+            interval_ms,
+            tolerance_ms,
+            last_step_ms: now_ms,
+            late_steps: 0,
+        }
+    }
+
+    /// Sets the expected time until the next step.
+    ///
+    /// # Details
+    /// Lets a loop that sleeps through idle periods announce a
+    /// longer step without it being counted as late.
+    ///
+    /// # Arguments
+    /// * `interval_ms` - Expected time between steps
+    #[allow(dead_code)]
+    pub fn set_interval(&mut self, interval_ms: u64) {
+        self.interval_ms = interval_ms;
+    }
+
+    /// Records a completed step.
+    ///
+    /// # Arguments
+    /// * `now_ms` - Time the step completed
+    ///
+    /// # Returns
+    /// * `bool` - true if on schedule and the watchdog may be fed
+    #[allow(dead_code)]
+    pub fn step_completed(&mut self, now_ms: u64) -> bool {
+        let gap = now_ms.saturating_sub(self.last_step_ms);
+        self.last_step_ms = now_ms;
+        let on_schedule = gap <= self.interval_ms + self.tolerance_ms;
+        if !on_schedule {
+            self.late_steps = self.late_steps.saturating_add(1);
+        }
+        on_schedule
+    }
+
+    /// Returns number of late steps.
+    ///
+    /// # Returns
+    /// * `u32` - Steps that missed their schedule
+    #[allow(dead_code)]
+    pub fn late_steps(&self) -> u32 {
+        self.late_steps
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // ==================== BootRecord Tests ====================
+
+    #[test]
+    fn test_from_scratch_valid() {
+        let record = BootRecord::from_scratch(BOOT_RECORD_MAGIC, 3);
+        assert_eq!(record.watchdog_resets(), 3);
+    }
+
+    #[test]
+    fn test_from_scratch_invalid_magic() {
+        let record = BootRecord::from_scratch(0, 3);
+        assert_eq!(record, BootRecord::default());
+    }
+
+    #[test]
+    fn test_scratch_round_trip() {
+        let record = BootRecord::default().after_reset(ResetCause::Watchdog);
+        let (magic, count) = record.to_scratch();
+        assert_eq!(BootRecord::from_scratch(magic, count), record);
+    }
+
+    #[test]
+    fn test_watchdog_reset_counts() {
+        let record = BootRecord::default()
+            .after_reset(ResetCause::Watchdog)
+            .after_reset(ResetCause::Watchdog);
+        assert_eq!(record.watchdog_resets(), 2);
+    }
+
+    #[test]
+    fn test_power_on_clears() {
+        let record =
+            BootRecord::from_scratch(BOOT_RECORD_MAGIC, 5).after_reset(ResetCause::PowerOn);
+        assert_eq!(record.watchdog_resets(), 0);
+    }
+
+    #[test]
+    fn test_count_saturates() {
+        let record =
+            BootRecord::from_scratch(BOOT_RECORD_MAGIC, u32::MAX).after_reset(ResetCause::Watchdog);
+        assert_eq!(record.watchdog_resets(), u32::MAX);
+    }
+
+    // ==================== StepSupervisor Tests ====================
+
+    #[test]
+    fn test_on_time_step_feeds() {
+        let mut supervisor = StepSupervisor::new(10, 5, 0);
+        assert!(supervisor.step_completed(10));
+        assert_eq!(supervisor.late_steps(), 0);
+    }
+
+    #[test]
+    fn test_step_within_tolerance_feeds() {
+        let mut supervisor = StepSupervisor::new(10, 5, 0);
+        assert!(supervisor.step_completed(15));
+    }
+
+    #[test]
+    fn test_late_step_does_not_feed() {
+        let mut supervisor = StepSupervisor::new(10, 5, 0);
+        assert!(!supervisor.step_completed(16));
+        assert_eq!(supervisor.late_steps(), 1);
+    }
+
+    #[test]
+    fn test_recovers_after_late_step() {
+        let mut supervisor = StepSupervisor::new(10, 5, 0);
+        supervisor.step_completed(100);
+        assert!(supervisor.step_completed(110));
+        assert_eq!(supervisor.late_steps(), 1);
+    }
+
+    #[test]
+    fn test_early_step_feeds() {
+        let mut supervisor = StepSupervisor::new(10, 5, 0);
+        assert!(supervisor.step_completed(2));
+    }
+
+    #[test]
+    fn test_set_interval_allows_longer_step() {
+        let mut supervisor = StepSupervisor::new(10, 5, 0);
+        supervisor.set_interval(200);
+        assert!(supervisor.step_completed(205));
+        assert!(!supervisor.step_completed(411));
+    }
+
+    #[test]
+    fn test_clock_going_backwards_treated_as_on_time() {
+        let mut supervisor = StepSupervisor::new(10, 5, 100);
+        assert!(supervisor.step_completed(50));
+    }
+}